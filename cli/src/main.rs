@@ -28,6 +28,7 @@ mod export;
 mod model;
 mod params;
 mod profile;
+mod quantize;
 mod run;
 #[cfg(feature = "pulse")]
 mod stream_check;
@@ -67,6 +68,8 @@ pub const STAGES: &'static [&'static str] = &[
     "pulse-declutter",
     "nnef-cycle",
     "nnef-cycle-declutter",
+    "dynamic-quantize",
+    "dynamic-quantize-declutter",
     "before-optimize",
     "optimize",
 ];
@@ -143,9 +146,12 @@ fn main() -> tract_core::anyhow::Result<()> {
     (@arg extract_decluttered_sub: --("extract-decluttered-sub") +takes_value "Zoom on a subgraph after decluttering by parent node name")
 
     (@arg nnef_cycle: --("nnef-cycle") "Perform NNEF dump and reload before optimizing")
+    (@arg dynamic_quantize: --("dynamic-quantize") "Quantize constant matmul weights to i8, activations being quantized at runtime")
     (@arg nnef_tract_core: --("nnef-tract-core") "Allow usage of tract-core extension in NNEF dump and load")
     (@arg nnef_tract_onnx: --("nnef-tract-onnx") "Allow usage of tract-onnx extension in NNEF dump and load")
     (@arg nnef_tract_pulse: --("nnef-tract-pulse") "Allow usage of tract-pulse extension in NNEF dump and load")
+    (@arg nnef_tract_lir: --("nnef-tract-lir") "Allow usage of tract-lir extension (optimized operators) in NNEF dump and load")
+    (@arg nnef_mmap: --("nnef-mmap") "Memory-map tensors when loading a NNEF directory")
 
     (@arg optimize: -O --optimize "Optimize before running")
     (@arg pulse: --pulse +takes_value "Translate to pulse network")
@@ -340,6 +346,28 @@ fn main() -> tract_core::anyhow::Result<()> {
     let optimize = clap::SubCommand::with_name("optimize").help("Optimize the graph");
     app = app.subcommand(output_options(optimize));
 
+    let quantize = clap::SubCommand::with_name("quantize")
+        .long_about("Calibrates the model on its inputs (--input-bundle) and dumps an i8 quantized version of it in NNEF format.")
+        .arg(
+            Arg::with_name("nnef-dir")
+                .takes_value(true)
+                .long("nnef-dir")
+                .help("Dump the quantized network in NNEF format (as a directory)"),
+        )
+        .arg(
+            Arg::with_name("nnef-tar")
+                .takes_value(true)
+                .long("nnef-tar")
+                .help("Dump the quantized network in NNEF format (as a tar file)"),
+        )
+        .arg(
+            Arg::with_name("nnef")
+                .takes_value(true)
+                .long("nnef")
+                .help("Dump the quantized network in NNEF format (as a tar.gz file)"),
+        );
+    app = app.subcommand(quantize);
+
     let stream_check = clap::SubCommand::with_name("stream-check")
         .long_about("Compare output of streamed and regular exec");
     app = app.subcommand(output_options(stream_check));
@@ -513,6 +541,8 @@ fn handle(matches: clap::ArgMatches, probe: Option<&Probe>) -> CliResult<()> {
 
         ("run", Some(m)) => run::handle(&params, m),
 
+        ("quantize", Some(m)) => quantize::handle(&params, &matches, m),
+
         #[cfg(feature = "pulse")]
         ("stream-check", Some(m)) => {
             stream_check::handle(&params, &display_params_from_clap(&matches, m)?)
@@ -585,5 +615,11 @@ fn nnef(matches: &clap::ArgMatches) -> tract_nnef::internal::Nnef {
     if matches.is_present("nnef_tract_core") {
         fw = fw.with_tract_core();
     }
+    if matches.is_present("nnef_tract_lir") {
+        fw = fw.with_tract_lir();
+    }
+    if matches.is_present("nnef_mmap") {
+        fw = fw.with_mmap();
+    }
     fw
 }
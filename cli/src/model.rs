@@ -66,6 +66,14 @@ pub trait Model:
             self.node_op(id).downcast_ref::<tract_hir::ops::scan::InferenceScan>()
         {
             vec![("loop".into(), &hir.body)]
+        } else if let Some(op) =
+            self.node_op(id).downcast_ref::<tract_core::ops::control_flow::If>()
+        {
+            vec![("then".into(), op.then_body()), ("else".into(), op.else_body())]
+        } else if let Some(op) =
+            self.node_op(id).downcast_ref::<tract_core::ops::control_flow::Loop>()
+        {
+            vec![("loop".into(), op.body())]
         } else {
             vec![]
         }
@@ -82,6 +90,13 @@ pub trait Model:
         {
             // if we have typefact, we hopefully have type ops
             unreachable!();
+        } else if let Some(_) = self.node_op(id).downcast_ref::<tract_core::ops::control_flow::If>()
+        {
+            vec![None, None]
+        } else if let Some(_) =
+            self.node_op(id).downcast_ref::<tract_core::ops::control_flow::Loop>()
+        {
+            vec![None]
         } else {
             vec![]
         }
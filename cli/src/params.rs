@@ -216,7 +216,11 @@ impl Parameters {
                 info_usage("loaded framework (onnx)", probe);
                 let graph = onnx.proto_model_for_read(&mut *location.read()?)?;
                 info_usage("proto model loaded", probe);
-                let parsed = onnx.parse(&graph)?;
+                let model_dir = match location {
+                    ModelLocation::Fs(p) => p.parent(),
+                    ModelLocation::Http(_) => None,
+                };
+                let parsed = onnx.parse_with_model_dir(&graph, model_dir)?;
                 if need_graph {
                     (
                         SomeGraphDef::Onnx(graph, parsed.clone()),
@@ -665,6 +669,10 @@ impl Parameters {
             });
             stage!("nnef-declutter", typed_model -> typed_model, |m:TypedModel| Ok(m.declutter()?));
         }
+        if matches.is_present("dynamic_quantize") {
+            stage!("dynamic-quantize", typed_model -> typed_model, |m:TypedModel| tract_core::quantization::quantize_dynamic(&m));
+            stage!("dynamic-quantize-declutter", typed_model -> typed_model, |m:TypedModel| Ok(m.declutter()?));
+        }
         if let Some(sub) = matches.value_of("extract_decluttered_sub") {
             stage!("extract", typed_model -> typed_model, |m:TypedModel| {
                 let node = m.node_id_by_name(sub)?;
@@ -0,0 +1,39 @@
+use crate::tensor::retrieve_or_make_inputs;
+use crate::CliResult;
+use crate::Parameters;
+use tract_core::quantization::{quantize, Calibration};
+use tract_hir::internal::*;
+
+pub fn handle(
+    params: &Parameters,
+    matches: &clap::ArgMatches,
+    sub_matches: &clap::ArgMatches,
+) -> CliResult<()> {
+    let model = params
+        .tract_model
+        .downcast_ref::<TypedModel>()
+        .context("Can only quantize typed models")?;
+    let samples = retrieve_or_make_inputs(model, params)?;
+    info!("Calibrating on {} sample(s)", samples.len());
+    let calibration = Calibration::new(model, samples)?;
+    let quantized = quantize(model, &calibration)?;
+
+    // casts from and to quantized types are tract_core operators
+    let mut nnef = super::nnef(matches);
+    if !matches.is_present("nnef_tract_core") {
+        nnef = nnef.with_tract_core();
+    }
+    if let Some(path) = sub_matches.value_of("nnef") {
+        let file = std::fs::File::create(path)?;
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        nnef.write_to_tar(&quantized, encoder)?;
+    } else if let Some(path) = sub_matches.value_of("nnef-tar") {
+        let file = std::fs::File::create(path)?;
+        nnef.write_to_tar(&quantized, file)?;
+    } else if let Some(path) = sub_matches.value_of("nnef-dir") {
+        nnef.write_to_dir(&quantized, path)?;
+    } else {
+        bail!("No output specified for the quantized model (--nnef, --nnef-tar or --nnef-dir)")
+    }
+    Ok(())
+}
@@ -72,9 +72,11 @@ pub mod broadcast;
 pub mod framework;
 mod hash;
 mod late_bind;
+pub mod memory;
 pub mod model;
 pub mod optim;
 pub mod plan;
+pub mod quantization;
 
 pub use dyn_clone;
 
@@ -85,7 +87,7 @@ pub type TractResult<T> = Result<T, anyhow::Error>;
 pub mod prelude {
     pub use crate::framework::Framework;
     pub use crate::model::*;
-    pub use crate::plan::{SimplePlan, SimpleState};
+    pub use crate::plan::{PlanExecution, SimplePlan, SimpleState};
     pub use crate::{TractError, TractResult};
     pub use std::sync::Arc;
     pub use tract_data::prelude::*;
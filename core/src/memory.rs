@@ -0,0 +1,244 @@
+//! Static memory planning: placing the intermediate values of a plan in a single arena.
+use std::fmt::{Debug, Display};
+
+use crate::internal::*;
+use crate::model::{Fact, Graph, OutletId};
+
+/// Alignment of the arena, and of every value placed in it.
+pub const ARENA_ALIGNMENT: usize = 64;
+
+fn aligned(bytes: usize) -> usize {
+    (bytes + ARENA_ALIGNMENT - 1) / ARENA_ALIGNMENT * ARENA_ALIGNMENT
+}
+
+/// Position of a value in the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaSlot {
+    pub offset: usize,
+    pub bytes: usize,
+}
+
+/// Placement of the intermediate values of a plan in a preallocated arena.
+///
+/// Only the outputs of stateless nodes with concrete shapes and plain datum types, consumed only
+/// by stateless nodes and not exported as plan outputs get a slot. A slot is available for
+/// reuse as soon as the plan flushes the value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MemoryPlan {
+    /// Slots, by node and output slot.
+    pub slots: Vec<TVec<Option<ArenaSlot>>>,
+    pub arena_size: usize,
+}
+
+impl MemoryPlan {
+    pub fn new<F, O>(
+        model: &Graph<F, O>,
+        order: &[usize],
+        flush_lists: &[TVec<usize>],
+        outputs: &[OutletId],
+    ) -> TractResult<MemoryPlan>
+    where
+        F: Fact + Hash + Clone + 'static,
+        O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
+    {
+        let mut session = SessionState::default();
+        let mut eligible = vec![true; model.nodes().len()];
+        for &n in order {
+            let node = model.node(n);
+            if node.op().state(&mut session, n)?.is_some() {
+                eligible[n] = false;
+                for i in &node.inputs {
+                    eligible[i.node] = false;
+                }
+            }
+        }
+        let mut free: Vec<(usize, usize)> = vec![];
+        let mut arena_size = 0;
+        let mut slots: Vec<TVec<Option<ArenaSlot>>> = vec![tvec!(); model.nodes().len()];
+        for (step, &n) in order.iter().enumerate() {
+            let node = model.node(n);
+            if eligible[n] && node.inputs.len() > 0 {
+                for (ix, output) in node.outputs.iter().enumerate() {
+                    let fact = output.fact.to_typed_fact()?;
+                    let bytes = match fact.shape.as_concrete() {
+                        Some(shape) if fact.datum_type.is_copy() => {
+                            shape.iter().product::<usize>() * fact.datum_type.size_of()
+                        }
+                        _ => 0,
+                    };
+                    if bytes == 0 || outputs.contains(&OutletId::new(n, ix)) {
+                        slots[n].push(None);
+                        continue;
+                    }
+                    let rounded = aligned(bytes);
+                    let offset = if let Some(ix) = free.iter().position(|f| f.1 >= rounded) {
+                        let (offset, size) = free[ix];
+                        if size == rounded {
+                            free.remove(ix);
+                        } else {
+                            free[ix] = (offset + rounded, size - rounded);
+                        }
+                        offset
+                    } else if free.last().map(|f| f.0 + f.1 == arena_size).unwrap_or(false) {
+                        let (offset, _) = free.pop().unwrap();
+                        arena_size = offset + rounded;
+                        offset
+                    } else {
+                        arena_size += rounded;
+                        arena_size - rounded
+                    };
+                    slots[n].push(Some(ArenaSlot { offset, bytes }));
+                }
+            }
+            for &flush in &flush_lists[step] {
+                for slot in slots[flush].iter().flatten() {
+                    Self::release(&mut free, slot.offset, aligned(slot.bytes));
+                }
+            }
+        }
+        Ok(MemoryPlan { slots, arena_size })
+    }
+
+    fn release(free: &mut Vec<(usize, usize)>, offset: usize, size: usize) {
+        let ix = free.iter().position(|f| f.0 > offset).unwrap_or(free.len());
+        free.insert(ix, (offset, size));
+        if ix + 1 < free.len() && free[ix].0 + free[ix].1 == free[ix + 1].0 {
+            free[ix].1 += free[ix + 1].1;
+            free.remove(ix + 1);
+        }
+        if ix > 0 && free[ix - 1].0 + free[ix - 1].1 == free[ix].0 {
+            free[ix - 1].1 += free[ix].1;
+            free.remove(ix);
+        }
+    }
+
+    /// Slot of a value, if it is placed in the arena.
+    pub fn slot(&self, outlet: OutletId) -> Option<ArenaSlot> {
+        self.slots.get(outlet.node).and_then(|s| s.get(outlet.slot)).and_then(|s| *s)
+    }
+}
+
+/// Serves the allocations of a node outputs from their arena slots.
+///
+/// Only the outputs reserved by the plan are served, and only to the allocations made for them
+/// through `Tensor::uninitialized_output_dt`. Op temporaries go to the heap.
+#[derive(Debug)]
+pub(crate) struct ArenaAllocator {
+    pub arena: Arc<Tensor>,
+    pub node: usize,
+    pub pending: TVec<(OutletId, ArenaSlot)>,
+}
+
+impl ArenaAllocator {
+    pub fn new(arena: Arc<Tensor>) -> ArenaAllocator {
+        ArenaAllocator { arena, node: 0, pending: tvec!() }
+    }
+
+    /// Reserve the arena slots of `node` outputs for its evaluation.
+    pub fn reserve(&mut self, node: usize, slots: &[Option<ArenaSlot>]) {
+        self.node = node;
+        self.pending = slots
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, slot)| slot.map(|s| (OutletId::new(node, ix), s)))
+            .collect();
+    }
+
+    fn base(&self) -> *mut u8 {
+        unsafe { self.arena.as_ptr_unchecked::<u8>() as *mut u8 }
+    }
+
+    /// Is `tensor` data the arena slot `slot` ?
+    pub fn is_in_slot(&self, tensor: &Tensor, slot: Option<ArenaSlot>) -> bool {
+        let ptr = unsafe { tensor.as_ptr_unchecked::<u8>() };
+        slot.map(|s| ptr == self.base().wrapping_add(s.offset) as *const u8).unwrap_or(false)
+    }
+
+    /// Does `tensor` data live somewhere in the arena ?
+    pub fn is_in_arena(&self, tensor: &Tensor) -> bool {
+        let ptr = unsafe { tensor.as_ptr_unchecked::<u8>() };
+        tensor.is_foreign()
+            && ptr >= self.base() as *const u8
+            && ptr < self.base().wrapping_add(self.arena.len()) as *const u8
+    }
+}
+
+impl TensorAllocator for ArenaAllocator {
+    fn allocate(
+        &mut self,
+        output: usize,
+        bytes: usize,
+        alignment: usize,
+    ) -> Option<(*mut u8, Arc<dyn std::any::Any + Send + Sync>)> {
+        if alignment > ARENA_ALIGNMENT {
+            return None;
+        }
+        let outlet = OutletId::new(self.node, output);
+        let ix = self.pending.iter().position(|(o, s)| *o == outlet && s.bytes == bytes)?;
+        let (_, slot) = self.pending.remove(ix);
+        Some((self.base().wrapping_add(slot.offset), self.arena.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+
+    #[test]
+    fn reuse_flushed_slots() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let mut wire = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[16]))?;
+        for i in 0..4 {
+            wire = model.wire_node(format!("exp{}", i), math::exp(), &[wire])?[0];
+        }
+        model.set_output_outlets(&[wire])?;
+        let plan = SimplePlan::new(&model)?;
+        let memory = MemoryPlan::new(&model, &plan.order, &plan.flush_lists, &plan.outputs)?;
+        assert_eq!(memory.arena_size, 2 * ARENA_ALIGNMENT);
+        assert!(memory.slot(OutletId::new(0, 0)).is_none());
+        assert!(memory.slot(OutletId::new(4, 0)).is_none());
+        assert_ne!(memory.slot(OutletId::new(1, 0)), memory.slot(OutletId::new(2, 0)));
+        assert_eq!(memory.slot(OutletId::new(1, 0)), memory.slot(OutletId::new(3, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn run_in_arena() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[3]))?;
+        let two = model.add_const("two", tensor1(&[2f32]))?;
+        let mut wire = source;
+        for i in 0..4 {
+            wire = model.wire_node(format!("mul{}", i), math::mul::bin_typed(), &[wire, two])?[0];
+            wire = model.wire_node(format!("reshape{}", i), AxisOp::Add(0), &[wire])?[0];
+            wire = model.wire_node(format!("rm{}", i), AxisOp::Rm(0), &[wire])?[0];
+        }
+        model.set_output_outlets(&[wire])?;
+        let plan = model.into_runnable_with_execution(PlanExecution::Arena)?;
+        let mut state = SimpleState::new(&plan)?;
+        for _ in 0..3 {
+            let result = state.run(tvec!(tensor1(&[1f32, 2., 3.])))?;
+            assert_eq!(result[0], rctensor1(&[16f32, 32., 48.]));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn only_reserved_outputs_in_arena() -> TractResult<()> {
+        let arena = unsafe { Tensor::uninitialized_aligned::<u8>(&[128], ARENA_ALIGNMENT)? };
+        let mut allocator = ArenaAllocator::new(arena.into_arc_tensor());
+        allocator.reserve(3, &[None, Some(ArenaSlot { offset: 64, bytes: 16 })]);
+        let (temp, first, second) = with_tensor_allocator(&mut allocator, || unsafe {
+            TractResult::Ok((
+                Tensor::uninitialized::<f32>(&[4])?,
+                Tensor::uninitialized_output_dt(0, f32::datum_type(), &[4])?,
+                Tensor::uninitialized_output_dt(1, f32::datum_type(), &[4])?,
+            ))
+        })?;
+        assert!(!allocator.is_in_arena(&temp));
+        assert!(!allocator.is_in_arena(&first));
+        assert!(allocator.is_in_slot(&second, Some(ArenaSlot { offset: 64, bytes: 16 })));
+        Ok(())
+    }
+}
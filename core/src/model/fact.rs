@@ -145,6 +145,8 @@ pub struct TypedFact {
     pub konst: Option<Arc<Tensor>>,
     /// optional uniform value
     pub uniform: Option<Arc<Tensor>>,
+    /// optional per-axis quantization parameters
+    pub axis_qparams: Option<Arc<AxisQParams>>,
 }
 
 impl_dyn_hash!(TypedFact);
@@ -168,14 +170,26 @@ impl TypedFact {
 
     pub fn dt_scalar(datum_type: DatumType) -> TypedFact {
         let foo: &[usize] = &[];
-        TypedFact { datum_type, shape: ShapeFact::from(foo), konst: None, uniform: None }
+        TypedFact {
+            datum_type,
+            shape: ShapeFact::from(foo),
+            konst: None,
+            uniform: None,
+            axis_qparams: None,
+        }
     }
 
     pub fn dt_shape<S>(datum_type: DatumType, shape: S) -> TypedFact
     where
         S: Into<ShapeFact>,
     {
-        TypedFact { datum_type, shape: shape.into(), konst: None, uniform: None }
+        TypedFact {
+            datum_type,
+            shape: shape.into(),
+            konst: None,
+            uniform: None,
+            axis_qparams: None,
+        }
     }
 
     pub fn rank(&self) -> usize {
@@ -220,12 +234,30 @@ impl TypedFact {
                 bail!("Fact said to be uniform ({:?}) and equal to {:?} which is not.", u, k);
             }
         }
+        if let Some(qp) = &self.axis_qparams {
+            if qp.axis >= self.shape.rank() {
+                bail!(
+                    "Per-axis quantization on axis {} of a rank {} fact",
+                    qp.axis,
+                    self.shape.rank()
+                )
+            }
+            if qp.zero_points.len() != qp.scales.len()
+                || self.shape[qp.axis].to_usize().map(|d| d != qp.scales.len()).unwrap_or(false)
+            {
+                bail!("Per-axis quantization {:?} does not match shape {:?}", qp, self.shape);
+            }
+        }
         Ok(())
     }
 
     pub fn without_value(&self) -> Self {
         Self::dt_shape(self.datum_type, self.shape.clone())
     }
+
+    pub fn with_axis_qparams(self, axis_qparams: AxisQParams) -> Self {
+        TypedFact { axis_qparams: Some(Arc::new(axis_qparams)), ..self }
+    }
 }
 
 impl Fact for TypedFact {
@@ -289,6 +321,7 @@ impl From<Arc<Tensor>> for TypedFact {
             shape: ShapeFact::from_dims(t.shape().iter().map(TDim::from)),
             uniform: t.as_uniform().map(Arc::new),
             konst: Some(t),
+            axis_qparams: None,
         }
     }
 }
@@ -41,6 +41,10 @@ where
     /// model properties
     #[educe(Hash(method = "hash_properties"))]
     pub properties: HashMap<String, Arc<Tensor>>,
+    /// register constants and pre-packed weights in the process-wide `TensorStore`, so that
+    /// models loaded several times share them
+    #[educe(Hash(ignore))]
+    pub share_tensors: bool,
 }
 
 fn hash_outlet_labels<H: std::hash::Hasher>(it: &HashMap<OutletId, String>, state: &mut H) {
@@ -73,6 +77,7 @@ where
             outputs: vec![],
             outlet_labels: HashMap::new(),
             properties: HashMap::new(),
+            share_tensors: false,
         }
     }
 }
@@ -113,6 +118,15 @@ where
         Ok(id)
     }
 
+    /// Go through the process-wide `TensorStore` if the model shares its tensors.
+    pub fn share_tensor(&self, tensor: Arc<Tensor>) -> Arc<Tensor> {
+        if self.share_tensors {
+            TensorStore::global().share(tensor)
+        } else {
+            tensor
+        }
+    }
+
     /// Connect a node outlet to a node inlet.
     pub fn add_edge(&mut self, outlet: OutletId, inlet: InletId) -> TractResult<()> {
         if let Some(previous) = self.nodes[inlet.node].inputs.get(inlet.slot).cloned() {
@@ -481,6 +495,14 @@ where
         crate::plan::SimplePlan::new(self)
     }
 
+    /// Converts the model into a `RunnableModel`, picking how its nodes will be scheduled.
+    pub fn into_runnable_with_execution(
+        self,
+        execution: crate::plan::PlanExecution,
+    ) -> TractResult<RunnableModel<F, O, Self>> {
+        crate::plan::SimplePlan::new(self)?.with_execution(execution)
+    }
+
     pub fn single_prec(&self, id: usize) -> TractResult<Option<&Node<F, O>>> {
         let node = &self.nodes()[id];
         if node.inputs.len() != 1 {
@@ -552,7 +574,7 @@ where
         name: impl Into<String>,
         v: impl IntoArcTensor,
     ) -> TractResult<OutletId> {
-        let v = v.into_arc_tensor();
+        let v = self.share_tensor(v.into_arc_tensor());
         let fact = F::from(v.clone());
         let name = name.into();
         self.add_node(name, crate::ops::konst::Const::new(v), tvec!(fact)).map(|id| id.into())
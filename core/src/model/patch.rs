@@ -224,6 +224,8 @@ where
     }
 
     /// Apply all changes in the patch to the target model.
+    ///
+    /// Constants from the patch go through the `TensorStore` if the target shares its tensors.
     pub fn apply(self, target: &mut Graph<F, O>) -> TractResult<()> {
         let prior_target_inputs = target.input_outlets()?.len();
         let prior_target_outputs = target.output_outlets()?.len();
@@ -244,7 +246,16 @@ where
                     continue;
                 }
             }
-            let Node { id, name, inputs, op, outputs } = node;
+            let Node { id, name, inputs, mut op, mut outputs } = node;
+            if target.share_tensors {
+                if let Some(konst) = op.as_mut().downcast_mut::<crate::ops::konst::Const>() {
+                    konst.0 = target.share_tensor(konst.0.clone());
+                    let fact = &mut outputs[0].fact as &mut dyn Fact;
+                    if let Some(fact) = fact.downcast_mut::<TypedFact>() {
+                        fact.konst = Some(konst.0.clone());
+                    }
+                }
+            }
             let n_outputs = outputs.len();
             let facts = outputs.into_iter().map(|of| of.fact).collect();
             let added_node_id = target.add_node(name, op, facts)?;
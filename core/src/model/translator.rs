@@ -57,6 +57,7 @@ where
         target.inputs = source.input_outlets()?.iter().map(|i| mapping[&i]).collect();
         target.outputs = source.output_outlets()?.iter().map(|o| mapping[&o]).collect();
         target.properties = source.properties.clone();
+        target.share_tensors = source.share_tensors;
         Ok((target, mapping))
     }
 }
@@ -156,4 +156,34 @@ mod test {
         fn is_sync<T: Sync>() {}
         is_sync::<TypedModel>();
     }
+
+    #[test]
+    fn patch_constants_are_shared() -> TractResult<()> {
+        fn model(share_tensors: bool) -> TractResult<TypedModel> {
+            let mut model = TypedModel { share_tensors, ..TypedModel::default() };
+            let s = model.add_source("s", TypedFact::dt_shape(f32::datum_type(), [3]))?;
+            model.set_output_outlets(&[s])?;
+            let mut patch = TypedModelPatch::default();
+            let s = patch.tap_model(&model, s)?;
+            let k = patch.add_const("k", tensor1(&[17f32, 42., 1789.]))?;
+            let sum = patch.wire_node("sum", crate::ops::math::add::bin_typed(), &[s, k])?[0];
+            patch.shunt_outside(&model, model.output_outlets()?[0], sum)?;
+            patch.apply(&mut model)?;
+            Ok(model)
+        }
+        fn konst(model: &TypedModel) -> Arc<Tensor> {
+            model
+                .nodes()
+                .iter()
+                .find_map(|n| n.op_as::<crate::ops::konst::Const>())
+                .unwrap()
+                .0
+                .clone()
+        }
+        let (m1, m2) = (model(true)?, model(true)?);
+        assert!(Arc::ptr_eq(&konst(&m1), &konst(&m2)));
+        let (m1, m2) = (model(false)?, model(false)?);
+        assert!(!Arc::ptr_eq(&konst(&m1), &konst(&m2)));
+        Ok(())
+    }
 }
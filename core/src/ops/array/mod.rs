@@ -6,11 +6,16 @@ mod gather_elements;
 mod gather_nd;
 mod one_hot;
 mod pad;
+mod range;
 mod reshape;
+mod reverse_sequence;
 mod scatter_elements;
 mod scatter_nd;
 mod slice;
 mod tile;
+mod topk;
+mod trilu;
+mod unique;
 
 pub use self::broadcast::MultiBroadcastTo;
 pub use self::concat::{ConcatSlice, TypedConcat};
@@ -19,8 +24,13 @@ pub use self::gather_elements::GatherElements;
 pub use self::gather_nd::GatherNd;
 pub use self::one_hot::OneHot;
 pub use self::pad::{Pad, PadMode};
+pub use self::range::Range;
 pub use self::reshape::FiniteReshape;
+pub use self::reverse_sequence::ReverseSequence;
 pub use self::scatter_elements::ScatterElements;
 pub use self::scatter_nd::ScatterNd;
 pub use self::slice::Slice;
 pub use self::tile::Tile;
+pub use self::topk::TopK;
+pub use self::trilu::Trilu;
+pub use self::unique::Unique;
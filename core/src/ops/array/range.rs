@@ -0,0 +1,197 @@
+use crate::internal::*;
+
+/// Generates the `[start, start + step, ...]` sequence, stopping before `end`.
+///
+/// start, end and step are three scalar inputs of the same type. When they are all known at
+/// compile time, the output length is computed (symbolically for `TDim` inputs). Otherwise it is
+/// `len`.
+#[derive(Debug, Clone, Hash)]
+pub struct Range {
+    pub len: TDim,
+}
+
+impl_dyn_hash!(Range);
+
+impl Default for Range {
+    fn default() -> Range {
+        Range { len: Symbol::new('R').into() }
+    }
+}
+
+impl Range {
+    fn len_for_numbers(start: f64, end: f64, step: f64) -> TractResult<usize> {
+        if step == 0.0 {
+            bail!("Range step can not be zero")
+        }
+        Ok(((end - start) / step).ceil().max(0.0) as usize)
+    }
+
+    fn len_for_dims(start: &TDim, end: &TDim, step: &TDim) -> TractResult<TDim> {
+        let step = step.to_i64().context("Range step must be known at compile time")?;
+        let len = match step {
+            0 => bail!("Range step can not be zero"),
+            s if s > 0 => (end.clone() - start).div_ceil(s as u64),
+            s => (start.clone() - end).div_ceil(-s as u64),
+        };
+        Ok(if let Ok(len) = len.to_i64() { len.max(0).to_dim() } else { len })
+    }
+}
+
+impl Op for Range {
+    fn name(&self) -> Cow<str> {
+        "Range".into()
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Range {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (start, end, step) = args_3!(inputs);
+        let dt = start.datum_type();
+        let output = if dt.is_float() {
+            let start = start.cast_to_scalar::<f64>()?;
+            let end = end.cast_to_scalar::<f64>()?;
+            let step = step.cast_to_scalar::<f64>()?;
+            let len = Self::len_for_numbers(start, end, step)?;
+            tensor1(&(0..len).map(|i| start + i as f64 * step).collect::<Vec<_>>())
+        } else {
+            let start = start.cast_to_scalar::<i64>()?;
+            let end = end.cast_to_scalar::<i64>()?;
+            let step = step.cast_to_scalar::<i64>()?;
+            let len = Self::len_for_numbers(start as f64, end as f64, step as f64)?;
+            tensor1(&(0..len as i64).map(|i| start + i * step).collect::<Vec<_>>())
+        };
+        Ok(tvec!(output.cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(RangeState)))
+    }
+}
+
+/// `TDim` bounds may refer to symbols that are only resolved in the session.
+#[derive(Clone, Debug)]
+struct RangeState;
+
+impl OpState for RangeState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op.downcast_ref::<Range>().context("Wrong op")?;
+        let inputs = inputs
+            .into_iter()
+            .map(|t| {
+                if t.datum_type() == TDim::datum_type() {
+                    let value = t.to_scalar::<TDim>()?.eval(&session.resolved_symbols);
+                    Ok(rctensor0(value))
+                } else {
+                    Ok(t)
+                }
+            })
+            .collect::<TractResult<_>>()?;
+        op.eval(inputs)
+    }
+}
+
+impl TypedOp for Range {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let dt = inputs[0].datum_type;
+        if inputs.iter().any(|i| i.datum_type != dt || i.rank() != 0) {
+            bail!("Range expects three scalars of the same type, got {:?}", inputs)
+        }
+        let len = if let (Some(start), Some(end), Some(step)) =
+            (&inputs[0].konst, &inputs[1].konst, &inputs[2].konst)
+        {
+            if dt.is_float() {
+                Self::len_for_numbers(
+                    start.cast_to_scalar::<f64>()?,
+                    end.cast_to_scalar::<f64>()?,
+                    step.cast_to_scalar::<f64>()?,
+                )?
+                .to_dim()
+            } else {
+                let start = start.cast_to::<TDim>()?;
+                let end = end.cast_to::<TDim>()?;
+                let step = step.cast_to::<TDim>()?;
+                Self::len_for_dims(
+                    start.to_scalar::<TDim>()?,
+                    end.to_scalar::<TDim>()?,
+                    step.to_scalar::<TDim>()?,
+                )?
+            }
+        } else {
+            self.len.clone()
+        };
+        Ok(tvec!(TypedFact::dt_shape(dt, [len])))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model.node_input_facts(node.id)?;
+        if let Some(values) = inputs.iter().map(|f| f.konst.clone()).collect::<Option<TVec<_>>>() {
+            if let Ok(mut output) = self.eval(values) {
+                let mut patch = TypedModelPatch::default();
+                let wire = patch.add_const(&node.name, output.remove(0))?;
+                patch.shunt_outside(model, node.id.into(), wire)?;
+                return Ok(Some(patch));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn float_positive_step() -> TractResult<()> {
+        let output =
+            Range::default().eval(tvec!(rctensor0(1f32), rctensor0(2.1f32), rctensor0(0.25f32)))?;
+        assert_eq!(*output[0], tensor1(&[1f32, 1.25, 1.5, 1.75, 2.]));
+        Ok(())
+    }
+
+    #[test]
+    fn int_negative_step() -> TractResult<()> {
+        let output = Range::default().eval(tvec!(rctensor0(10i32), rctensor0(4), rctensor0(-3)))?;
+        assert_eq!(*output[0], tensor1(&[10i32, 7]));
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> TractResult<()> {
+        let output = Range::default().eval(tvec!(rctensor0(3i64), rctensor0(0), rctensor0(1)))?;
+        assert_eq!(output[0].shape(), &[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_len() -> TractResult<()> {
+        let n: TDim = Symbol::new('n').into();
+        let start = TypedFact::from(rctensor0(TDim::from(1)));
+        let end = TypedFact::from(tensor0(n.clone()).into_arc_tensor());
+        let step = TypedFact::from(rctensor0(TDim::from(2)));
+        let facts = Range::default().output_facts(&[&start, &end, &step])?;
+        assert_eq!(facts[0].shape[0], (n - 1).div_ceil(2));
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::internal::*;
+use tract_ndarray::Axis;
+
+/// Reverses the first `seq_lens[b]` items along `time_axis` of each batch entry `b` along
+/// `batch_axis`, leaving the remaining items in place.
+#[derive(Debug, Clone, Hash)]
+pub struct ReverseSequence {
+    pub batch_axis: usize,
+    pub time_axis: usize,
+}
+
+impl_dyn_hash!(ReverseSequence);
+
+impl ReverseSequence {
+    fn eval_t<T: Datum>(&self, input: &mut Tensor, seq_lens: &[i64]) -> TractResult<()> {
+        let mut view = input.to_array_view_mut::<T>()?;
+        let time_len = view.shape()[self.time_axis];
+        // once the batch axis is removed, the time axis may have shifted
+        let time_axis = self.time_axis - (self.batch_axis < self.time_axis) as usize;
+        for (mut batch, &len) in view.axis_iter_mut(Axis(self.batch_axis)).zip(seq_lens) {
+            if len < 0 || len as usize > time_len {
+                bail!("Invalid sequence length {} for time axis of length {}", len, time_len)
+            }
+            for mut lane in batch.lanes_mut(Axis(time_axis)) {
+                let len = len as usize;
+                for i in 0..len / 2 {
+                    lane.swap(i, len - 1 - i);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Op for ReverseSequence {
+    fn name(&self) -> Cow<str> {
+        "ReverseSequence".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("batch_axis: {} time_axis: {}", self.batch_axis, self.time_axis)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ReverseSequence {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, seq_lens) = args_2!(inputs);
+        let seq_lens = seq_lens.cast_to::<i64>()?;
+        let seq_lens = seq_lens.as_slice::<i64>()?;
+        if seq_lens.len() != input.shape()[self.batch_axis] {
+            bail!(
+                "Expected {} sequence lengths, got {:?}",
+                input.shape()[self.batch_axis],
+                seq_lens
+            )
+        }
+        let mut input = input.into_tensor();
+        dispatch_datum!(Self::eval_t(input.datum_type())(self, &mut input, seq_lens))?;
+        Ok(tvec!(input.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for ReverseSequence {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.batch_axis == self.time_axis
+            || self.batch_axis >= inputs[0].rank()
+            || self.time_axis >= inputs[0].rank()
+        {
+            bail!(
+                "Invalid batch ({}) and time ({}) axes for {:?}",
+                self.batch_axis,
+                self.time_axis,
+                inputs[0]
+            )
+        }
+        if inputs[1].rank() != 1 {
+            bail!("ReverseSequence expects a rank 1 seq_lens input, got {:?}", inputs[1])
+        }
+        Ok(tvec!(inputs[0].without_value()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_major() -> TractResult<()> {
+        let op = ReverseSequence { batch_axis: 1, time_axis: 0 };
+        let input = rctensor2(&[[0f32, 4., 8.], [1., 5., 9.], [2., 6., 10.]]);
+        let output = op.eval(tvec!(input, rctensor1(&[3i64, 1, 2])))?;
+        assert_eq!(*output[0], tensor2(&[[2f32, 4., 9.], [1., 5., 8.], [0., 6., 10.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn batch_major() -> TractResult<()> {
+        let op = ReverseSequence { batch_axis: 0, time_axis: 1 };
+        let input = rctensor2(&[[0i32, 1, 2, 3], [4, 5, 6, 7]]);
+        let output = op.eval(tvec!(input, rctensor1(&[2i64, 4])))?;
+        assert_eq!(*output[0], tensor2(&[[1i32, 0, 2, 3], [7, 6, 5, 4]]));
+        Ok(())
+    }
+}
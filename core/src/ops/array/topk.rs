@@ -0,0 +1,179 @@
+use std::cmp::Ordering;
+
+use crate::internal::*;
+use tract_ndarray::Axis;
+
+/// Selects the k largest (or smallest) values along an axis, and their indices. NaNs are greater
+/// than any other value.
+///
+/// k is the second input. When it is not known at compile time, the output dimension on `axis`
+/// is `fallback_k`.
+#[derive(Debug, Clone, Hash)]
+pub struct TopK {
+    pub axis: usize,
+    pub largest: bool,
+    pub sorted: bool,
+    pub fallback_k: TDim,
+}
+
+impl_dyn_hash!(TopK);
+
+impl TopK {
+    pub fn new(axis: usize, largest: bool, sorted: bool) -> TopK {
+        TopK { axis, largest, sorted, fallback_k: Symbol::new('k').into() }
+    }
+
+    fn eval_t<T: Datum + PartialOrd>(
+        &self,
+        input: &Tensor,
+        values: &mut Tensor,
+        indices: &mut Tensor,
+    ) -> TractResult<()> {
+        let k = values.shape()[self.axis];
+        let input = input.to_array_view::<T>()?;
+        let mut values = values.to_array_view_mut::<T>()?;
+        let mut indices = indices.to_array_view_mut::<i64>()?;
+        let lanes = input
+            .lanes(Axis(self.axis))
+            .into_iter()
+            .zip(values.lanes_mut(Axis(self.axis)))
+            .zip(indices.lanes_mut(Axis(self.axis)));
+        for ((input, mut values), mut indices) in lanes {
+            let mut order = (0..input.len()).collect::<Vec<_>>();
+            // stable sort: equal values keep their original order
+            order.sort_by(|&a, &b| {
+                let ordering = nan_greatest_cmp(&input[a], &input[b]);
+                if self.largest {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+            order.truncate(k);
+            if !self.sorted {
+                order.sort();
+            }
+            for (ix, &pos) in order.iter().enumerate() {
+                values[ix] = input[pos].clone();
+                indices[ix] = pos as i64;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Total order on the input values, NaNs comparing equal to each other and greater than anything
+/// else.
+fn nan_greatest_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    // NaN is the only value not comparable to itself
+    a.partial_cmp(b).unwrap_or_else(|| a.partial_cmp(a).is_none().cmp(&b.partial_cmp(b).is_none()))
+}
+
+impl Op for TopK {
+    fn name(&self) -> Cow<str> {
+        "TopK".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} largest: {} sorted: {}", self.axis, self.largest, self.sorted)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for TopK {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, k) = args_2!(inputs);
+        let k = k.cast_to::<i64>()?.as_slice::<i64>()?[0];
+        if k < 0 || k as usize > input.shape()[self.axis] {
+            bail!("Invalid k ({}) for TopK on axis {} of {:?}", k, self.axis, input)
+        }
+        let mut shape: TVec<usize> = input.shape().into();
+        shape[self.axis] = k as usize;
+        let mut values = unsafe { Tensor::uninitialized_dt(input.datum_type(), &shape)? };
+        let mut indices = Tensor::zero::<i64>(&shape)?;
+        dispatch_numbers!(Self::eval_t(input.datum_type())(
+            self,
+            &input,
+            &mut values,
+            &mut indices
+        ))?;
+        Ok(tvec!(values.into_arc_tensor(), indices.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for TopK {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[1].shape.iter().product::<TDim>() != 1.to_dim() {
+            bail!("TopK expects a single value for k, got {:?}", inputs[1])
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[self.axis] = if let Some(k) = &inputs[1].konst {
+            k.cast_to::<i64>()?.as_slice::<i64>()?[0].to_dim()
+        } else {
+            self.fallback_k.clone()
+        };
+        Ok(tvec!(
+            TypedFact::dt_shape(inputs[0].datum_type, &*shape),
+            TypedFact::dt_shape(i64::datum_type(), &*shape)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(op: TopK, input: Tensor, k: i64) -> TractResult<(Tensor, Tensor)> {
+        let mut outputs = op.eval(tvec!(input.into_arc_tensor(), rctensor1(&[k])))?;
+        let (values, indices) = args_2!(outputs);
+        Ok((values.into_tensor(), indices.into_tensor()))
+    }
+
+    #[test]
+    fn largest() -> TractResult<()> {
+        let input = tensor2(&[[0f32, 1., 2., 3.], [7., 6., 5., 4.], [8., 10., 9., 11.]]);
+        let (values, indices) = run(TopK::new(1, true, true), input, 3)?;
+        assert_eq!(values, tensor2(&[[3f32, 2., 1.], [7., 6., 5.], [11., 10., 9.]]));
+        assert_eq!(indices, tensor2(&[[3i64, 2, 1], [0, 1, 2], [3, 1, 2]]));
+        Ok(())
+    }
+
+    #[test]
+    fn smallest_on_first_axis() -> TractResult<()> {
+        let input = tensor2(&[[3i32, 1], [2, 1], [1, 5]]);
+        let (values, indices) = run(TopK::new(0, false, true), input, 2)?;
+        assert_eq!(values, tensor2(&[[1i32, 1], [2, 1]]));
+        assert_eq!(indices, tensor2(&[[2i64, 0], [1, 1]]));
+        Ok(())
+    }
+
+    #[test]
+    fn unsorted_keeps_input_order() -> TractResult<()> {
+        let input = tensor1(&[1f32, 5., 3., 4.]);
+        let (values, indices) = run(TopK::new(0, true, false), input, 2)?;
+        assert_eq!(values, tensor1(&[5f32, 4.]));
+        assert_eq!(indices, tensor1(&[1i64, 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn nan_is_greatest() -> TractResult<()> {
+        let input = tensor1(&[1f32, f32::NAN, 3., f32::NAN, 2.]);
+        let (values, indices) = run(TopK::new(0, true, true), input.clone(), 3)?;
+        assert!(values.as_slice::<f32>()?[..2].iter().all(|v| v.is_nan()));
+        assert_eq!(values.as_slice::<f32>()?[2], 3.);
+        assert_eq!(indices, tensor1(&[1i64, 3, 2]));
+        let (values, indices) = run(TopK::new(0, false, true), input, 3)?;
+        assert_eq!(values, tensor1(&[1f32, 2., 3.]));
+        assert_eq!(indices, tensor1(&[0i64, 4, 2]));
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::internal::*;
+
+/// Keeps the upper (or lower) triangular part of the matrices on the two last axes, zeroing the
+/// rest.
+///
+/// The second input k is a scalar diagonal offset: element `(i, j)` is kept if `j - i >= k` for
+/// upper, `j - i <= k` for lower.
+#[derive(Debug, Clone, Hash)]
+pub struct Trilu {
+    pub upper: bool,
+}
+
+impl_dyn_hash!(Trilu);
+
+impl Trilu {
+    fn eval_t<T: Datum>(&self, input: &mut Tensor, k: i64) -> TractResult<()> {
+        let mut view = input.to_array_view_mut::<T>()?;
+        let rank = view.ndim();
+        for (coords, value) in view.indexed_iter_mut() {
+            let offset = coords[rank - 1] as i64 - coords[rank - 2] as i64;
+            if (self.upper && offset < k) || (!self.upper && offset > k) {
+                *value = T::default();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Op for Trilu {
+    fn name(&self) -> Cow<str> {
+        "Trilu".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("upper: {}", self.upper)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Trilu {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, k) = args_2!(inputs);
+        let k = k.cast_to_scalar::<i64>()?;
+        let mut input = input.into_tensor();
+        dispatch_datum!(Self::eval_t(input.datum_type())(self, &mut input, k))?;
+        Ok(tvec!(input.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Trilu {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() < 2 {
+            bail!("Trilu expects at least a rank 2 input, got {:?}", inputs[0])
+        }
+        if inputs[1].rank() != 0 {
+            bail!("Trilu expects a scalar k, got {:?}", inputs[1])
+        }
+        Ok(tvec!(inputs[0].without_value()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input() -> Arc<Tensor> {
+        rctensor2(&[[1i32, 2, 3], [4, 5, 6], [7, 8, 9]])
+    }
+
+    #[test]
+    fn upper() -> TractResult<()> {
+        let output = Trilu { upper: true }.eval(tvec!(input(), rctensor0(0i64)))?;
+        assert_eq!(*output[0], tensor2(&[[1i32, 2, 3], [0, 5, 6], [0, 0, 9]]));
+        Ok(())
+    }
+
+    #[test]
+    fn lower_with_negative_k() -> TractResult<()> {
+        let output = Trilu { upper: false }.eval(tvec!(input(), rctensor0(-1i64)))?;
+        assert_eq!(*output[0], tensor2(&[[0i32, 0, 0], [4, 0, 0], [7, 8, 0]]));
+        Ok(())
+    }
+}
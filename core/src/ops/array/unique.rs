@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+
+use crate::internal::*;
+use tract_ndarray::{ArrayViewD, Axis};
+
+/// Finds the unique slices of the input along `axis` (or its unique values when `axis` is `None`,
+/// on the flattened input).
+///
+/// Outputs are the unique items, the index of their first occurrence in the input, the index of
+/// each input item in the unique items, and the number of occurrences of each unique item.
+/// Unique items are sorted when `sorted` is set, and in first occurrence order otherwise. Their
+/// number is only known at runtime: it is `len` in the output facts.
+#[derive(Debug, Clone, Hash)]
+pub struct Unique {
+    pub axis: Option<usize>,
+    pub sorted: bool,
+    pub len: TDim,
+}
+
+impl_dyn_hash!(Unique);
+
+impl Unique {
+    pub fn new(axis: Option<usize>, sorted: bool) -> Unique {
+        Unique { axis, sorted, len: Symbol::new('U').into() }
+    }
+
+    fn eval_t<T: Datum + PartialOrd>(&self, input: &Tensor) -> TractResult<TVec<Arc<Tensor>>> {
+        let (view, axis) = if let Some(axis) = self.axis {
+            (input.to_array_view::<T>()?, axis)
+        } else {
+            (input.to_array_view::<T>()?.into_shape(input.len())?.into_dyn(), 0)
+        };
+        let items: Vec<ArrayViewD<T>> = view.axis_iter(Axis(axis)).collect();
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        // stable sort: the first item of each group is its first occurrence
+        order.sort_by(|&a, &b| {
+            items[a].iter().partial_cmp(items[b].iter()).unwrap_or(Ordering::Equal)
+        });
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for ix in order {
+            match groups.last_mut() {
+                Some(group) if items[group[0]] == items[ix] => group.push(ix),
+                _ => groups.push(vec![ix]),
+            }
+        }
+        if !self.sorted {
+            groups.sort_by_key(|g| g[0]);
+        }
+        let firsts: Vec<usize> = groups.iter().map(|g| g[0]).collect();
+        let mut inverse = vec![0i64; items.len()];
+        for (ix, group) in groups.iter().enumerate() {
+            for &item in group {
+                inverse[item] = ix as i64;
+            }
+        }
+        let unique = view.select(Axis(axis), &firsts).into_tensor();
+        let indices = firsts.iter().map(|&i| i as i64).collect::<Vec<_>>();
+        let counts = groups.iter().map(|g| g.len() as i64).collect::<Vec<_>>();
+        Ok(tvec!(
+            unique.into_arc_tensor(),
+            rctensor1(&indices),
+            rctensor1(&inverse),
+            rctensor1(&counts)
+        ))
+    }
+}
+
+impl Op for Unique {
+    fn name(&self) -> Cow<str> {
+        "Unique".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {:?} sorted: {}", self.axis, self.sorted)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Unique {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        dispatch_numbers!(Self::eval_t(input.datum_type())(self, &input))
+    }
+}
+
+impl TypedOp for Unique {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let (unique_shape, items) = if let Some(axis) = self.axis {
+            if axis >= inputs[0].rank() {
+                bail!("Invalid axis {} for Unique on {:?}", axis, inputs[0])
+            }
+            let mut shape = inputs[0].shape.to_tvec();
+            shape[axis] = self.len.clone();
+            (shape, inputs[0].shape[axis].clone())
+        } else {
+            (tvec!(self.len.clone()), inputs[0].shape.iter().product())
+        };
+        Ok(tvec!(
+            TypedFact::dt_shape(inputs[0].datum_type, unique_shape),
+            TypedFact::dt_shape(i64::datum_type(), [self.len.clone()]),
+            TypedFact::dt_shape(i64::datum_type(), [items]),
+            TypedFact::dt_shape(i64::datum_type(), [self.len.clone()])
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flattened_not_sorted() -> TractResult<()> {
+        let output =
+            Unique::new(None, false).eval(tvec!(rctensor1(&[2f32, 1., 1., 3., 4., 3.])))?;
+        assert_eq!(*output[0], tensor1(&[2f32, 1., 3., 4.]));
+        assert_eq!(*output[1], tensor1(&[0i64, 1, 3, 4]));
+        assert_eq!(*output[2], tensor1(&[0i64, 1, 1, 2, 3, 2]));
+        assert_eq!(*output[3], tensor1(&[1i64, 2, 2, 1]));
+        Ok(())
+    }
+
+    #[test]
+    fn sorted_with_axis() -> TractResult<()> {
+        let input = rctensor2(&[[1i32, 0, 0], [1, 0, 0], [2, 3, 4]]);
+        let output = Unique::new(Some(0), true).eval(tvec!(input))?;
+        assert_eq!(*output[0], tensor2(&[[1i32, 0, 0], [2, 3, 4]]));
+        assert_eq!(*output[1], tensor1(&[0i64, 2]));
+        assert_eq!(*output[2], tensor1(&[0i64, 0, 1]));
+        assert_eq!(*output[3], tensor1(&[2i64, 1]));
+        Ok(())
+    }
+}
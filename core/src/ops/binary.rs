@@ -73,7 +73,7 @@ pub trait BinMiniOp:
         } else {
             let c_shape = crate::broadcast::multi_broadcast(&[a.shape(), b.shape()])
                 .ok_or_else(|| format_err!("Can not compute resulting shape"))?;
-            let mut c = unsafe { Tensor::uninitialized_dt(c_dt, &*c_shape)? };
+            let mut c = unsafe { Tensor::uninitialized_output_dt(0, c_dt, &c_shape)? };
             self.eval_out_of_place(&mut c, a.as_ref(), b.as_ref())?;
             Ok(c)
         }
@@ -5,11 +5,11 @@ use ndarray::*;
 
 #[derive(Debug, Clone, new, Hash)]
 pub struct DepthWise {
-    patch: Patch,
-    input_shape: DataShape,
-    output_shape: DataShape,
-    kernel_chw: Arc<Tensor>,
-    bias: Option<Arc<Tensor>>,
+    pub patch: Patch,
+    pub input_shape: DataShape,
+    pub output_shape: DataShape,
+    pub kernel_chw: Arc<Tensor>,
+    pub bias: Option<Arc<Tensor>>,
 }
 
 impl_dyn_hash!(DepthWise);
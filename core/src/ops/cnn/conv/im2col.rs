@@ -108,7 +108,15 @@ impl Im2Col {
         input_full_shape: &ShapeFact,
         mmm: Box<dyn MatMatMul>,
     ) -> TractResult<Im2Col> {
-        let b_pack = mmm.b_pack(k);
+        Self::with_b_pack(pool_spec, group, input_full_shape, mmm.b_pack(k))
+    }
+
+    pub fn with_b_pack(
+        pool_spec: PoolSpec,
+        group: usize,
+        input_full_shape: &ShapeFact,
+        b_pack: Packer,
+    ) -> TractResult<Im2Col> {
         let pool_geometry = pool_spec.compute_geo(input_full_shape)?;
         let geometry: GeometryBound<_, _> =
             SymbolicGeometry { group, pool_spec: pool_spec.clone(), pool_geometry, b_pack }.into();
@@ -116,6 +124,10 @@ impl Im2Col {
         Ok(Im2Col { pool_spec, group, geometry })
     }
 
+    pub fn b_pack(&self) -> &Packer {
+        self.geometry.b_pack()
+    }
+
     fn packed_shape<D: DimLike>(
         input_shape: &BaseDataShape<D, TVec<D>>,
         conv_output_shape: &BaseDataShape<D, TVec<D>>,
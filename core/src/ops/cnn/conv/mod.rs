@@ -9,8 +9,9 @@ mod unary;
 
 use crate::internal::*;
 
+pub use self::depth_wise::DepthWise;
 pub use self::im2col::Im2Col;
-pub(crate) use self::q_sum_b::QSumB;
+pub use self::q_sum_b::QSumB;
 pub use self::unary::ConvUnary;
 
 #[derive(Debug, Copy, Clone, PartialEq, Hash)]
@@ -58,12 +58,19 @@ impl QConvProblem {
         let n = *self.shape_in.n().clone().unwrap_or(&1);
         let ci_per_g = self.shape_in.c() / self.group;
         let co_per_g = self.shape_out.c() / self.group;
-        let a0 = self.qp.a0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
+        // a0 and a_scale may be given per output channel
+        let a0 = self.qp.a0.as_static().unwrap().cast_to_dt(i32::datum_type()).unwrap();
+        let a0 = a0.as_slice::<i32>().unwrap();
+        let a0 = |co: usize| if a0.len() > 1 { a0[co] } else { a0[0] };
+        let a_scale = self.qp.a_scale.as_static().unwrap().as_slice::<f32>().unwrap();
+        let a_scale = |co: usize| if a_scale.len() > 1 { a_scale[co] } else { a_scale[0] };
         let b0 = self.qp.b0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
         let c0 = self.qp.c0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
-        let scale = self.qp.c_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
-            / self.qp.a_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
-            / self.qp.b_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap();
+        let scale = |co: usize| {
+            self.qp.c_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
+                / a_scale(co)
+                / self.qp.b_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
+        };
         let mut temp = ArrayD::<i32>::zeros(&*self.shape_out.shape);
         for n in 0..n {
             for g in 0..self.group {
@@ -97,7 +104,7 @@ impl QConvProblem {
                                     }
                                 }
                                 let k = self.kernel[&*kernel_coords] as i32;
-                                temp[&*output_coords] += (k - a0) * (i - b0);
+                                temp[&*output_coords] += (k - a0(co + g * co_per_g)) * (i - b0);
                             }
                         }
                     }
@@ -109,8 +116,9 @@ impl QConvProblem {
             shape[self.shape_out.c_axis()] = bias.len();
             temp += &bias.clone().into_shape(shape).unwrap();
         }
-        temp.mapv(|i| {
-            (round_ties_to_even(i as f32 / scale as f32) as i32 + c0)
+        ArrayD::from_shape_fn(temp.shape(), |coords| {
+            let scale = scale(coords[self.shape_out.c_axis()]);
+            (round_ties_to_even(temp[&coords] as f32 / scale) as i32 + c0)
                 .max(std::i8::MIN as i32)
                 .min(std::i8::MAX as i32) as i8
         })
@@ -516,3 +524,57 @@ fn bias_2() {
     .check()
     .unwrap();
 }
+
+#[test]
+fn per_channel_1() {
+    let mut qp = MatMulQParams::noop_static(i8::datum_type());
+    qp.a0 = AttrOrInput::Attr(rctensor1(&[1i32, -2]));
+    qp.a_scale = AttrOrInput::Attr(rctensor1(&[0.5f32, 2.0]));
+    for &optim in &[false, true] {
+        for &fmt in &[CHW, HWC] {
+            QConvProblem {
+                shape_in: fmt.from_n_c_hw(1, 1, &[2]).unwrap(),
+                shape_out: fmt.from_n_c_hw(1, 2, &[1]).unwrap(),
+                kernel_format: OIHW,
+                group: 1,
+                data: fmt
+                    .from_n_c_hw(1, 1, &[2])
+                    .map(|s| ArrayD::from_shape_vec(&*s.shape, vec![3i8, -5]).unwrap())
+                    .unwrap(),
+                kernel: arr3(&[[[4i8, -6]], [[7, 1]]]).into_dyn(),
+                bias: None,
+                qp: qp.clone(),
+                optim,
+            }
+            .check()
+            .unwrap();
+        }
+    }
+}
+
+#[test]
+fn per_channel_group() {
+    let mut qp = MatMulQParams::noop_static(i8::datum_type());
+    qp.a0 = AttrOrInput::Attr(rctensor1(&[1i32, -2, 0, 3]));
+    qp.a_scale = AttrOrInput::Attr(rctensor1(&[0.5f32, 2.0, 1.0, 0.25]));
+    for &optim in &[false, true] {
+        for &fmt in &[CHW, HWC] {
+            QConvProblem {
+                shape_in: fmt.from_n_c_hw(1, 2, &[2]).unwrap(),
+                shape_out: fmt.from_n_c_hw(1, 4, &[1]).unwrap(),
+                kernel_format: OIHW,
+                group: 2,
+                data: fmt
+                    .from_n_c_hw(1, 2, &[2])
+                    .map(|s| ArrayD::from_shape_vec(&*s.shape, vec![3i8, -5, 8, 2]).unwrap())
+                    .unwrap(),
+                kernel: arr3(&[[[4i8, -6]], [[7, 1]], [[-3, 5]], [[2, 9]]]).into_dyn(),
+                bias: Some(arr1(&[1i32, 2, 3, -4]).into_dyn()),
+                qp: qp.clone(),
+                optim,
+            }
+            .check()
+            .unwrap();
+        }
+    }
+}
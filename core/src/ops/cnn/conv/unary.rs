@@ -58,7 +58,12 @@ impl ConvUnary {
         )
     }
 
-    fn kernel_as_packed_as(&self, packer: &Packer, m: usize) -> TractResult<ArrayD<Arc<Tensor>>> {
+    fn kernel_as_packed_as(
+        &self,
+        model: &TypedModel,
+        packer: &Packer,
+        m: usize,
+    ) -> TractResult<ArrayD<Arc<Tensor>>> {
         let kernel = self.kernel_as_group_o_ihw()?;
         unsafe {
             let mut packed_as = Array1::from(
@@ -69,13 +74,15 @@ impl ConvUnary {
                             &[packer.len(m)],
                             packer.alignment(),
                         )?;
+                        // padding is hashed when sharing the panels
+                        packed.as_bytes_mut().fill(0);
                         packer.pack(
                             &mut TensorView::at_prefix(&mut packed, &[])?,
                             &kernel.view_at_prefix(&[g])?,
                             1,
                             0,
                         );
-                        Ok(packed.into_arc_tensor())
+                        Ok(model.share_tensor(packed.into_arc_tensor()))
                     })
                     .collect::<TractResult<Vec<_>>>()?,
             )
@@ -143,8 +150,17 @@ impl ConvUnary {
             })
             .collect::<TractResult<Vec<OutletId>>>()?;
 
-        let a0 = params[0];
-        let a_scale = params[1];
+        let (mmm_output_shape, c_axis, h_axis) = self.mmm_output_shape(&output_shape)?;
+        let rank = mmm_output_shape.len();
+        let a0 =
+            self.wire_per_channel_param(model, &format!("{}.a0", name), params[0], c_axis, rank)?;
+        let a_scale = self.wire_per_channel_param(
+            model,
+            &format!("{}.a_scale", name),
+            params[1],
+            c_axis,
+            rank,
+        )?;
         let b0 = params[2];
         let b_scale = params[3];
         let c0 = params[4];
@@ -187,7 +203,6 @@ impl ConvUnary {
         }
 
         let b_dt = model.outlet_fact(wires[0])?.datum_type;
-        let (mmm_output_shape, c_axis, h_axis) = self.mmm_output_shape(&output_shape)?;
         let mut geometry = MatMulGeometry::from(SymbolicMatMulGeometry {
             b_datum_type: b_dt,
             m: m.to_dim(),
@@ -247,6 +262,49 @@ impl ConvUnary {
         Ok(wire)
     }
 
+    /// Per-channel quantization parameters of the kernel are rank-1 tensors over the output
+    /// channels: split them by group and make them broadcast along the m axis of the matmul
+    /// output.
+    fn wire_per_channel_param(
+        &self,
+        model: &mut TypedModel,
+        name: &str,
+        param: OutletId,
+        m_axis: usize,
+        rank: usize,
+    ) -> TractResult<OutletId> {
+        let fact = model.outlet_fact(param)?.clone();
+        if fact.rank() != 1 || fact.shape[0] == 1.to_dim() {
+            return Ok(param);
+        }
+        if fact.shape[0] != self.output_channels().to_dim() {
+            bail!(
+                "Per-channel quantization expects {} values, got {:?}",
+                self.output_channels(),
+                fact
+            );
+        }
+        let mut wire = param;
+        if self.group > 1 {
+            wire = model.wire_node(
+                format!("{}.split_group", name),
+                AxisOp::Reshape(
+                    0,
+                    tvec!(fact.shape[0].clone()),
+                    tvec!(self.group.to_dim(), (self.output_channels() / self.group).to_dim()),
+                ),
+                &[wire],
+            )?[0];
+        }
+        for ix in 0..rank - 1 - m_axis {
+            let axis = model.outlet_fact(wire)?.rank();
+            wire =
+                model.wire_node(format!("{}.per_axis.{}", name, ix), AxisOp::Add(axis), &[wire])?
+                    [0];
+        }
+        Ok(wire)
+    }
+
     pub unsafe fn wire_as_im2col_pair(
         &self,
         model: &mut TypedModel,
@@ -443,7 +501,7 @@ impl ConvUnary {
         c_m_axis: usize,
         c_n_axis: usize,
     ) -> TractResult<OutletId> {
-        let kernels = self.kernel_as_packed_as(&mmm.a_pack(k), m)?;
+        let kernels = self.kernel_as_packed_as(model, &mmm.a_pack(k), m)?;
         let shape = kernels.shape();
         let fused_ops = dispatch_copy!(Self::bias_as_non_linear(mmm.internal_type())(self))?;
         let mut iter = kernels.iter().cloned().zip(fused_ops.iter().cloned());
@@ -662,6 +720,9 @@ impl TypedOp for ConvUnary {
         if inputs.len() != 1 + q_inputs {
             bail!("Wrong number of inputs: expected {} got {}", 1 + q_inputs, inputs.len());
         }
+        if let Some(qp) = &inputs[0].axis_qparams {
+            bail!("Per-axis quantization is only supported on the kernel, got {:?} on input", qp);
+        }
         if self.pool_spec.data_format.shape(&*inputs[0].shape)?.c()
             != &self.input_channels().to_dim()
         {
@@ -883,6 +944,7 @@ impl TypedOp for ConvUnary {
             let dt = input_fact.datum_type;
             if self.q_params.is_some() {
                 let mut patch = TypedModelPatch::default();
+                patch.model.share_tensors = model.share_tensors;
                 let inputs = node
                     .inputs
                     .iter()
@@ -970,6 +1032,7 @@ impl TypedOp for ConvUnary {
                 .unwrap_or(false)
             {
                 let mut patch = TypedModelPatch::default();
+                patch.model.share_tensors = model.share_tensors;
                 let wire = patch.tap_model(model, node.inputs[0])?;
                 let wire = self
                     .wire_as_direct(
@@ -991,6 +1054,7 @@ impl TypedOp for ConvUnary {
                 return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
             } else {
                 let mut patch = TypedModelPatch::default();
+                patch.model.share_tensors = model.share_tensors;
                 let wire = patch.tap_model(model, node.inputs[0])?;
                 let wire = self
                     .wire_as_im2col_pair(&mut patch, &*node.name, wire)
@@ -1056,6 +1120,36 @@ mod test {
         assert_eq!(&*output[0], &tensor4(&[[[[8i32, 12], [20, 24]]]]));
     }
 
+    #[test]
+    fn f64_im2col_codegen() -> TractResult<()> {
+        let op = ConvUnary {
+            pool_spec: PoolSpec {
+                data_format: NCHW,
+                kernel_shape: tvec!(2, 2),
+                padding: PaddingSpec::Valid,
+                dilations: None,
+                strides: None,
+                output_channel_override: Some(1),
+            },
+            kernel_fmt: KernelFormat::OIHW,
+            kernel: rctensor4(&[[[[1f64, 1.], [1., 1.]]]]),
+            group: 1,
+            bias: Some(rctensor1(&[0.5f64])),
+            q_params: None,
+        };
+        let mut model = TypedModel::default();
+        let source =
+            model.add_source("input", TypedFact::dt_shape(f64::datum_type(), &[1, 1, 3, 3]))?;
+        let conv = model.wire_node("conv", op, &[source])?;
+        model.set_output_outlets(&conv)?;
+        let model = model.into_optimized()?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<ConvUnary>()));
+        let input = tensor4(&[[[[1f64, 2., 3.], [4., 5., 6.], [7., 8., 9.]]]]);
+        let output = model.into_runnable()?.run(tvec!(input))?;
+        assert_eq!(&*output[0], &tensor4(&[[[[12.5f64, 16.5], [24.5, 28.5]]]]));
+        Ok(())
+    }
+
     #[test]
     fn conv_vs_direct_arm_ml_kws_cnn_m_0() {
         let input = NHWC.from_n_c_hw(1, 1, &[49, 10]).unwrap();
@@ -0,0 +1,139 @@
+use crate::internal::*;
+
+/// Partial inverse of MaxPool: scatters the values of a NCHW-like input to the positions given by
+/// the second input (indices in the flattened output, as produced by MaxPool index outputs), and
+/// zeroes everything else.
+///
+/// The spatial output dims are computed from the pooling geometry. An optional third input gives
+/// the full output shape explicitly.
+#[derive(Debug, Clone, Hash)]
+pub struct MaxUnpool {
+    pub kernel_shape: TVec<usize>,
+    pub strides: TVec<usize>,
+    pub pads: TVec<(usize, usize)>,
+}
+
+impl_dyn_hash!(MaxUnpool);
+
+impl MaxUnpool {
+    fn output_shape<D: DimLike>(&self, input_shape: &[D]) -> TVec<D> {
+        let mut shape: TVec<D> = input_shape[..2].into();
+        for (ix, dim) in input_shape[2..].iter().enumerate() {
+            let (before, after) = self.pads[ix];
+            shape.push(
+                (dim.clone() - 1) * self.strides[ix] + self.kernel_shape[ix] - before - after,
+            );
+        }
+        shape
+    }
+
+    fn eval_t<T: Datum + Copy>(
+        &self,
+        input: &Tensor,
+        indices: &[i64],
+        output: &mut Tensor,
+    ) -> TractResult<()> {
+        let output = output.as_slice_mut::<T>()?;
+        for (&value, &ix) in input.as_slice::<T>()?.iter().zip(indices) {
+            if ix < 0 || ix as usize >= output.len() {
+                bail!("Invalid index {} for MaxUnpool output of size {}", ix, output.len())
+            }
+            output[ix as usize] = value;
+        }
+        Ok(())
+    }
+}
+
+impl Op for MaxUnpool {
+    fn name(&self) -> Cow<str> {
+        "MaxUnpool".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "kernel_shape: {:?} strides: {:?} pads: {:?}",
+            self.kernel_shape, self.strides, self.pads
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for MaxUnpool {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let shape: TVec<usize> = if let Some(shape) = inputs.get(2) {
+            shape.cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&d| d as usize).collect()
+        } else {
+            self.output_shape(inputs[0].shape())
+        };
+        let indices = inputs[1].cast_to::<i64>()?;
+        let mut output = Tensor::zero_dt(inputs[0].datum_type(), &shape)?;
+        dispatch_numbers!(Self::eval_t(inputs[0].datum_type())(
+            self,
+            &inputs[0],
+            indices.as_slice::<i64>()?,
+            &mut output
+        ))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for MaxUnpool {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != self.kernel_shape.len() + 2 || inputs[0].shape != inputs[1].shape {
+            bail!("MaxUnpool expects matching input and indices, got {:?}", inputs)
+        }
+        let shape = if let Some(shape) = inputs.get(2) {
+            if let Some(shape) = &shape.konst {
+                shape.cast_to::<TDim>()?.as_slice::<TDim>()?.into()
+            } else {
+                bail!("MaxUnpool output shape must be known at compile time")
+            }
+        } else {
+            self.output_shape(&inputs[0].shape.to_tvec())
+        };
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn op() -> MaxUnpool {
+        MaxUnpool { kernel_shape: tvec!(2, 2), strides: tvec!(2, 2), pads: tvec!((0, 0), (0, 0)) }
+    }
+
+    #[test]
+    fn without_output_shape() -> TractResult<()> {
+        let input = tensor4(&[[[[1f32, 2.], [3., 4.]]]]).into_arc_tensor();
+        let indices = tensor4(&[[[[5i64, 7], [13, 15]]]]).into_arc_tensor();
+        let output = op().eval(tvec!(input, indices))?;
+        let expected = tensor4(&[[[
+            [0f32, 0., 0., 0.],
+            [0., 1., 0., 2.],
+            [0., 0., 0., 0.],
+            [0., 3., 0., 4.],
+        ]]]);
+        assert_eq!(*output[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn with_output_shape() -> TractResult<()> {
+        let input = tensor4(&[[[[5f32, 6.], [7., 8.]]]]).into_arc_tensor();
+        let indices = tensor4(&[[[[5i64, 7], [13, 15]]]]).into_arc_tensor();
+        let output = op().eval(tvec!(input, indices, rctensor1(&[1i64, 1, 5, 5])))?;
+        assert_eq!(output[0].shape(), &[1, 1, 5, 5]);
+        assert_eq!(output[0].as_slice::<f32>()?[5], 5.);
+        assert_eq!(output[0].as_slice::<f32>()?[15], 8.);
+        Ok(())
+    }
+}
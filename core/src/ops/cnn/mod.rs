@@ -3,6 +3,7 @@ use crate::internal::*;
 pub mod conv;
 pub mod deconv;
 mod maxpool;
+mod maxunpool;
 mod padding;
 mod patch_axis;
 mod patches;
@@ -12,6 +13,7 @@ mod sumpool;
 pub use self::conv::{ConvUnary, KernelFormat};
 pub use self::deconv::DeconvUnary;
 pub use self::maxpool::MaxPool;
+pub use self::maxunpool::MaxUnpool;
 pub use self::padding::PaddingSpec;
 pub use self::patch_axis::PatchAxis;
 pub use self::patches::{Patch, PatchSpec};
@@ -96,8 +96,9 @@ impl EvalOp for LirSumPool {
         let input = args_1!(inputs);
         let geo = self.geometry.to_concrete(input.shape())?;
         let values = if input.datum_type().is_float() {
-            let mut values =
-                unsafe { Tensor::uninitialized_dt(input.datum_type(), &*geo.output_shape.shape)? };
+            let mut values = unsafe {
+                Tensor::uninitialized_output_dt(0, input.datum_type(), &geo.output_shape.shape)?
+            };
             dispatch_floatlike!(Self::eval_t(input.datum_type())(
                 self,
                 &*input,
@@ -0,0 +1,324 @@
+use crate::internal::*;
+
+/// Conditional execution of one of two nested models.
+///
+/// The first input is the boolean condition, the other inputs are made available to the
+/// branches through `then_input_mapping` and `else_input_mapping`: the body input `ix` of a
+/// branch is fed with the outer input `mapping[ix]`.
+///
+/// Both branches outputs must have the same datum types and ranks, the dimensions they do not
+/// agree on are only known at runtime.
+#[derive(Debug, Clone, Hash)]
+pub struct If {
+    pub then_plan: Arc<TypedSimplePlan<TypedModel>>,
+    pub then_input_mapping: Vec<usize>,
+    pub else_plan: Arc<TypedSimplePlan<TypedModel>>,
+    pub else_input_mapping: Vec<usize>,
+    merged_facts: TVec<TypedFact>,
+    decluttered: bool,
+    optimized: bool,
+}
+
+impl_dyn_hash!(If);
+
+impl If {
+    pub fn new(
+        then_body: TypedModel,
+        then_input_mapping: Vec<usize>,
+        else_body: TypedModel,
+        else_input_mapping: Vec<usize>,
+    ) -> TractResult<If> {
+        if then_body.input_outlets()?.len() != then_input_mapping.len() {
+            bail!("Then branch input count does not match its mapping")
+        }
+        if else_body.input_outlets()?.len() != else_input_mapping.len() {
+            bail!("Else branch input count does not match its mapping")
+        }
+        if then_body.output_outlets()?.len() != else_body.output_outlets()?.len() {
+            bail!("Then and else branches must have the same number of outputs")
+        }
+        let merged_facts = Self::merge_facts(&then_body, &else_body, &[])?;
+        Ok(If {
+            then_plan: Arc::new(SimplePlan::new(then_body)?),
+            then_input_mapping,
+            else_plan: Arc::new(SimplePlan::new(else_body)?),
+            else_input_mapping,
+            merged_facts,
+            decluttered: false,
+            optimized: false,
+        })
+    }
+
+    pub fn then_body(&self) -> &TypedModel {
+        self.then_plan.model()
+    }
+
+    pub fn else_body(&self) -> &TypedModel {
+        self.else_plan.model()
+    }
+
+    fn branch(&self, cond: bool) -> (&TypedSimplePlan<TypedModel>, &[usize]) {
+        if cond {
+            (&self.then_plan, &self.then_input_mapping)
+        } else {
+            (&self.else_plan, &self.else_input_mapping)
+        }
+    }
+
+    /// Output facts covering both branches outputs. Dimensions the branches disagree on are
+    /// taken from `previous` if it had a symbol for them, or get a new one.
+    fn merge_facts(
+        then_body: &TypedModel,
+        else_body: &TypedModel,
+        previous: &[TypedFact],
+    ) -> TractResult<TVec<TypedFact>> {
+        let mut facts = tvec!();
+        for ix in 0..then_body.output_outlets()?.len() {
+            let then_fact = then_body.output_fact(ix)?;
+            let else_fact = else_body.output_fact(ix)?;
+            if then_fact.datum_type != else_fact.datum_type || then_fact.rank() != else_fact.rank()
+            {
+                bail!(
+                    "If branches output #{} are incompatible: {:?} and {:?}",
+                    ix,
+                    then_fact,
+                    else_fact
+                )
+            }
+            let shape = then_fact
+                .shape
+                .iter()
+                .zip(else_fact.shape.iter())
+                .enumerate()
+                .map(|(axis, (t, e))| {
+                    if t == e {
+                        return t;
+                    }
+                    match previous.get(ix).map(|fact| fact.shape[axis].clone()) {
+                        Some(prev) if prev != t && prev != e => prev,
+                        _ => Symbol::new('I').into(),
+                    }
+                })
+                .collect::<TVec<_>>();
+            facts.push(TypedFact::dt_shape(then_fact.datum_type, shape));
+        }
+        Ok(facts)
+    }
+
+    fn with_bodies(&self, then_body: TypedModel, else_body: TypedModel) -> TractResult<If> {
+        let merged_facts = Self::merge_facts(&then_body, &else_body, &self.merged_facts)?;
+        Ok(If {
+            then_plan: Arc::new(SimplePlan::new(then_body)?),
+            else_plan: Arc::new(SimplePlan::new(else_body)?),
+            merged_facts,
+            ..self.clone()
+        })
+    }
+
+    fn declutter_const_condition(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let cond = if let Some(cond) = &model.outlet_fact(node.inputs[0])?.konst {
+            cond.cast_to_scalar::<bool>()?
+        } else {
+            return Ok(None);
+        };
+        let (plan, mapping) = self.branch(cond);
+        let mut patch = TypedModelPatch::default();
+        let inputs = mapping
+            .iter()
+            .map(|&slot| patch.tap_model(model, node.inputs[slot]))
+            .collect::<TractResult<TVec<_>>>()?;
+        let outputs = super::inline_model(&mut patch, &node.name, plan.model(), &inputs)?;
+        for (ix, o) in outputs.into_iter().enumerate() {
+            patch.shunt_outside(model, OutletId::new(node.id, ix), o)?;
+        }
+        Ok(Some(patch))
+    }
+
+    fn declutter_bodies(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.decluttered {
+            return Ok(None);
+        }
+        let mut new =
+            self.with_bodies(self.then_body().declutter()?, self.else_body().declutter()?)?;
+        new.decluttered = true;
+        Ok(Some(TypedModelPatch::replace_single_op(model, node, &node.inputs, new)?))
+    }
+}
+
+impl Op for If {
+    fn name(&self) -> Cow<str> {
+        "If".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!("Then branch inputs: {:?}", self.then_input_mapping),
+            format!("Else branch inputs: {:?}", self.else_input_mapping),
+        ])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for If {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let cond = inputs[0].cast_to_scalar::<bool>()?;
+        let (plan, mapping) = self.branch(cond);
+        let body_inputs = mapping.iter().map(|&slot| inputs[slot].clone().into_tensor()).collect();
+        plan.run(body_inputs)
+    }
+}
+
+impl TypedOp for If {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != bool::datum_type() || inputs[0].rank() != 0 {
+            bail!("If condition must be a boolean scalar, got {:?}", inputs[0])
+        }
+        Ok(self.merged_facts.clone())
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(patch) = self.declutter_const_condition(model, node)? {
+            return Ok(Some(patch));
+        }
+        self.declutter_bodies(model, node)
+    }
+
+    fn concretize_dims(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        values: &SymbolValues,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+        let op = self.with_bodies(
+            self.then_body().concretize_dims(values)?,
+            self.else_body().concretize_dims(values)?,
+        )?;
+        target.wire_node(&node.name, op, &inputs)
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.optimized {
+            return Ok(None);
+        }
+        let mut new = self.with_bodies(
+            self.then_body().clone().optimize()?,
+            self.else_body().clone().optimize()?,
+        )?;
+        new.optimized = true;
+        Ok(Some(TypedModelPatch::replace_single_op(model, node, &node.inputs, new)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+
+    fn branches() -> TractResult<(TypedModel, TypedModel)> {
+        let mut then_body = TypedModel::default();
+        let a = then_body.add_source("a", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        let b = then_body.add_source("b", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        let sum = then_body.wire_node("sum", math::add::bin_typed(), &[a, b])?;
+        then_body.set_output_outlets(&sum)?;
+        let mut else_body = TypedModel::default();
+        let b = else_body.add_source("b", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        let c = else_body.add_const("c", tensor1(&[10f32, 10.]))?;
+        let prod = else_body.wire_node("prod", math::mul::bin_typed(), &[b, c])?;
+        else_body.set_output_outlets(&prod)?;
+        Ok((then_body, else_body))
+    }
+
+    fn model(cond: Option<bool>) -> TractResult<TypedModel> {
+        let (then_body, else_body) = branches()?;
+        let mut model = TypedModel::default();
+        let c = if let Some(cond) = cond {
+            model.add_const("cond", tensor0(cond))?
+        } else {
+            model.add_source("cond", TypedFact::dt_scalar(bool::datum_type()))?
+        };
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        let op = If::new(then_body, vec![1, 2], else_body, vec![2])?;
+        let out = model.wire_node("if", op, &[c, a, b])?;
+        model.set_output_outlets(&out)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn eval_both_branches() -> TractResult<()> {
+        let plan = SimplePlan::new(model(None)?)?;
+        let a = tensor1(&[1f32, 2.]);
+        let b = tensor1(&[3f32, 4.]);
+        let result = plan.run(tvec!(tensor0(true), a.clone(), b.clone()))?;
+        assert_eq!(*result[0], tensor1(&[4f32, 6.]));
+        let result = plan.run(tvec!(tensor0(false), a, b))?;
+        assert_eq!(*result[0], tensor1(&[30f32, 40.]));
+        Ok(())
+    }
+
+    #[test]
+    fn fold_const_condition() -> TractResult<()> {
+        for &cond in &[true, false] {
+            let model = model(Some(cond))?.declutter()?;
+            assert!(model.nodes().iter().all(|n| !n.op_is::<If>()));
+            let a = tensor1(&[1f32, 2.]);
+            let b = tensor1(&[3f32, 4.]);
+            let result = SimplePlan::new(model)?.run(tvec!(a, b))?;
+            let expected = if cond { tensor1(&[4f32, 6.]) } else { tensor1(&[30f32, 40.]) };
+            assert_eq!(*result[0], expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn branches_with_different_shapes() -> TractResult<()> {
+        let mut then_body = TypedModel::default();
+        let a = then_body.add_source("a", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        then_body.set_output_outlets(&[a])?;
+        let mut else_body = TypedModel::default();
+        let c = else_body.add_const("c", tensor1(&[1f32, 2., 3.]))?;
+        else_body.set_output_outlets(&[c])?;
+        let mut model = TypedModel::default();
+        let cond = model.add_source("cond", TypedFact::dt_scalar(bool::datum_type()))?;
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), [2]))?;
+        let op = If::new(then_body, vec![1], else_body, vec![])?;
+        let out = model.wire_node("if", op, &[cond, a])?;
+        model.set_output_outlets(&out)?;
+        let shape = &model.outlet_fact(out[0])?.shape;
+        assert_eq!(shape.rank(), 1);
+        assert!(shape[0].to_usize().is_err());
+        let plan = SimplePlan::new(model.declutter()?)?;
+        let result = plan.run(tvec!(tensor0(true), tensor1(&[5f32, 6.])))?;
+        assert_eq!(*result[0], tensor1(&[5f32, 6.]));
+        let result = plan.run(tvec!(tensor0(false), tensor1(&[5f32, 6.])))?;
+        assert_eq!(*result[0], tensor1(&[1f32, 2., 3.]));
+        Ok(())
+    }
+}
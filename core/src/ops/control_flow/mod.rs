@@ -0,0 +1,38 @@
+use crate::internal::*;
+
+mod if_then_else;
+mod while_loop;
+
+pub use if_then_else::If;
+pub use while_loop::Loop;
+
+/// Copy the nodes of `body` into `target`, feeding its inputs from `inputs`.
+///
+/// Node names are prefixed by `prefix`. Returns the outlets in `target` matching the outputs of
+/// `body`.
+pub fn inline_model(
+    target: &mut TypedModel,
+    prefix: &str,
+    body: &TypedModel,
+    inputs: &[OutletId],
+) -> TractResult<TVec<OutletId>> {
+    let body_inputs = body.input_outlets()?;
+    if body_inputs.len() != inputs.len() {
+        bail!("Inlining a model with {} inputs, got {} wires", body_inputs.len(), inputs.len())
+    }
+    let mut mapping: HashMap<OutletId, OutletId> =
+        body_inputs.iter().cloned().zip(inputs.iter().cloned()).collect();
+    for node_id in body.eval_order()? {
+        let node = body.node(node_id);
+        if body_inputs.contains(&OutletId::new(node_id, 0)) {
+            continue;
+        }
+        let node_inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        let wires =
+            target.wire_node(format!("{}.{}", prefix, node.name), node.op.clone(), &node_inputs)?;
+        for (ix, w) in wires.into_iter().enumerate() {
+            mapping.insert(OutletId::new(node_id, ix), w);
+        }
+    }
+    Ok(body.output_outlets()?.iter().map(|o| mapping[o]).collect())
+}
@@ -0,0 +1,398 @@
+use crate::internal::*;
+use crate::ops::scan::{InputMapping, OutputMapping, StateInitializer};
+
+/// Loop over a nested model while a condition holds, up to a maximum trip count.
+///
+/// Outer inputs are `[max_trip_count, cond, ...]`. The body receives `[iteration, cond, ...]` and
+/// produces `[cond, ...]`. The remaining body inputs and outputs are described by scan mappings:
+/// states are carried from one iteration to the next, full inputs are fed unchanged, and full
+/// outputs are concatenated along their axis.
+///
+/// States may change shape from one iteration to the next: the changing axes are symbolic in the
+/// body.
+#[derive(Debug, Clone, Hash)]
+pub struct Loop {
+    pub plan: Arc<TypedSimplePlan<TypedModel>>,
+    pub input_mapping: Vec<InputMapping>,
+    pub output_mapping: Vec<OutputMapping<TDim>>,
+    pub iters: Symbol,
+    decluttered: bool,
+    optimized: bool,
+}
+
+impl_dyn_hash!(Loop);
+
+impl Loop {
+    /// Builds a loop from a body with inputs `[iteration, cond, states..., closures...]` and
+    /// outputs `[cond, states..., scans...]`.
+    ///
+    /// The op outputs are the final states, followed by the scan outputs stacked along a new
+    /// leading axis.
+    pub fn new(body: TypedModel, states: usize) -> TractResult<Loop> {
+        let inputs = body.input_outlets()?.len();
+        let outputs = body.output_outlets()?.len();
+        if inputs < 2 + states {
+            bail!("Loop body must have at least {} inputs", 2 + states)
+        }
+        if outputs < 1 + states {
+            bail!("Loop body must have at least {} outputs", 1 + states)
+        }
+        let mut body = generalize_states(body, states)?;
+        let mut outlets = body.output_outlets()?.to_vec();
+        // scan outputs are stacked: add the axis they will be concatenated along
+        for outlet in outlets.iter_mut().skip(1 + states) {
+            let name = format!("{}.{}.stack", body.node(outlet.node).name, outlet.slot);
+            *outlet = body.wire_node(name, AxisOp::Add(0), &[*outlet])?[0];
+        }
+        body.set_output_outlets(&outlets)?;
+        let input_mapping = (2..inputs)
+            .map(|slot| {
+                if slot < 2 + states {
+                    InputMapping::State { initializer: StateInitializer::FromInput(slot) }
+                } else {
+                    InputMapping::Full { slot }
+                }
+            })
+            .collect();
+        let output_mapping = (0..outputs - 1)
+            .map(|ix| OutputMapping {
+                full_slot: if ix < states { None } else { Some(ix) },
+                axis: 0,
+                chunk: 1,
+                full_dim_hint: None,
+                last_value_slot: if ix < states { Some(ix) } else { None },
+                state: ix < states,
+            })
+            .collect();
+        Ok(Loop {
+            plan: Arc::new(SimplePlan::new(body)?),
+            input_mapping,
+            output_mapping,
+            iters: Symbol::new('L'),
+            decluttered: false,
+            optimized: false,
+        })
+    }
+
+    pub fn body(&self) -> &TypedModel {
+        self.plan.model()
+    }
+
+    fn with_body(&self, body: TypedModel) -> TractResult<Loop> {
+        Ok(Loop { plan: Arc::new(SimplePlan::new(body)?), ..self.clone() })
+    }
+
+    fn iteration_count(&self, inputs: &[&TypedFact]) -> TractResult<TDim> {
+        let always_true = |fact: &TypedFact| -> TractResult<bool> {
+            Ok(if let Some(k) = &fact.konst { k.cast_to_scalar::<bool>()? } else { false })
+        };
+        if always_true(inputs[1])? && always_true(self.body().output_fact(0)?)? {
+            if let Some(max) = &inputs[0].konst {
+                return Ok(max.cast_to_scalar::<i64>()?.to_dim());
+            }
+        }
+        Ok(self.iters.into())
+    }
+}
+
+/// Rebuilds `body` with new facts for its inputs.
+fn with_input_facts(body: &TypedModel, facts: &[TypedFact]) -> TractResult<TypedModel> {
+    let mut model = TypedModel::default();
+    let body_inputs = body.input_outlets()?;
+    let mut mapping = HashMap::new();
+    for (outlet, fact) in body_inputs.iter().zip(facts) {
+        let source = model.add_source(&*body.node(outlet.node).name, fact.clone())?;
+        mapping.insert(*outlet, source);
+    }
+    for node_id in body.eval_order()? {
+        if body_inputs.contains(&OutletId::new(node_id, 0)) {
+            continue;
+        }
+        let node = body.node(node_id);
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        let wires = model.wire_node(&*node.name, node.op.clone(), &inputs)?;
+        for (ix, w) in wires.into_iter().enumerate() {
+            mapping.insert(OutletId::new(node_id, ix), w);
+        }
+    }
+    let outputs = body.output_outlets()?.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+    model.set_output_outlets(&outputs)?;
+    Ok(model)
+}
+
+/// Replaces the axes of states that change from one iteration to the next by new symbols, until
+/// the body is consistent with itself.
+fn generalize_states(mut body: TypedModel, states: usize) -> TractResult<TypedModel> {
+    let mut symbols: Vec<Symbol> = vec![];
+    loop {
+        let mut facts = body
+            .input_outlets()?
+            .iter()
+            .map(|o| body.outlet_fact(*o).cloned())
+            .collect::<TractResult<Vec<_>>>()?;
+        let mut changed = false;
+        for ix in 0..states {
+            let input = &mut facts[2 + ix];
+            let output = body.output_fact(1 + ix)?;
+            if input.datum_type != output.datum_type || input.rank() != output.rank() {
+                bail!(
+                    "Loop state #{} must keep the same type and rank, got {:?} and {:?}",
+                    ix,
+                    input,
+                    output
+                )
+            }
+            for axis in 0..input.rank() {
+                let generalized = matches!(input.shape[axis], TDim::Sym(s) if symbols.contains(&s));
+                if !generalized && input.shape[axis] != output.shape[axis] {
+                    let symbol = Symbol::new('S');
+                    symbols.push(symbol);
+                    input.shape.set(axis, symbol.into());
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return Ok(body);
+        }
+        body = with_input_facts(&body, &facts)?;
+    }
+}
+
+impl Op for Loop {
+    fn name(&self) -> Cow<str> {
+        "Loop".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let mut lines = vec![];
+        for (ix, im) in self.input_mapping.iter().enumerate() {
+            lines.push(format!("Model input  #{}: {:?}", ix + 2, im));
+        }
+        for (ix, om) in self.output_mapping.iter().enumerate() {
+            lines.push(format!("Model output #{}: {:?}", ix + 1, om));
+        }
+        Ok(lines)
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Loop {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let max_trip_count = inputs[0].cast_to_scalar::<i64>()?;
+        let mut cond = inputs[1].cast_to_scalar::<bool>()?;
+        let mut hidden: TVec<Tensor> = self
+            .input_mapping
+            .iter()
+            .filter_map(|m| m.as_state())
+            .map(|init| match init {
+                StateInitializer::FromInput(slot) => inputs[*slot].clone().into_tensor(),
+                StateInitializer::Value(v) => (**v).clone(),
+            })
+            .collect();
+        let mut state = TypedSimpleState::new(Arc::clone(&self.plan))?;
+        let mut full: Vec<Vec<Tensor>> = vec![vec![]; self.output_mapping.len()];
+        let mut iteration = 0i64;
+        while iteration < max_trip_count && cond {
+            let mut previous = std::mem::take(&mut hidden).into_iter();
+            let mut body_inputs = tvec!(tensor0(iteration), tensor0(cond));
+            for mapping in &self.input_mapping {
+                body_inputs.push(match mapping {
+                    InputMapping::State { .. } => previous.next().unwrap(),
+                    InputMapping::Full { slot } => inputs[*slot].clone().into_tensor(),
+                    InputMapping::Scan { .. } => bail!("Loop does not support scan inputs"),
+                });
+            }
+            let outputs = state.run(body_inputs).context("Evaluating loop body")?;
+            cond = outputs[0].cast_to_scalar::<bool>()?;
+            for ((output, mapping), full) in
+                outputs[1..].iter().zip(&self.output_mapping).zip(full.iter_mut())
+            {
+                if mapping.state {
+                    hidden.push(output.clone().into_tensor());
+                }
+                if mapping.full_slot.is_some() {
+                    full.push(output.clone().into_tensor());
+                }
+            }
+            iteration += 1;
+        }
+        let mut hidden = hidden.into_iter();
+        let mut outputs = tvec!();
+        for (ix, (mapping, full)) in self.output_mapping.iter().zip(full).enumerate() {
+            let last = if mapping.state { hidden.next() } else { None };
+            if let Some(slot) = mapping.last_value_slot {
+                outputs.push((slot, last.context("Loop last value outputs must be states")?));
+            }
+            if let Some(slot) = mapping.full_slot {
+                let output = if full.is_empty() {
+                    let fact = self.body().output_fact(1 + ix)?;
+                    let mut shape = if let Some(shape) = fact.shape.as_concrete() {
+                        shape.to_vec()
+                    } else {
+                        bail!("Can not build empty scan output with non concrete shape {:?}", fact)
+                    };
+                    shape[mapping.axis] = 0;
+                    unsafe { Tensor::uninitialized_dt(fact.datum_type, &shape)? }
+                } else {
+                    Tensor::stack_tensors(mapping.axis, &full)?
+                };
+                outputs.push((slot, output));
+            }
+        }
+        outputs.sort_by_key(|a| a.0);
+        Ok(outputs.into_iter().map(|(_slot, t)| t.into_arc_tensor()).collect())
+    }
+}
+
+impl TypedOp for Loop {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let iters = self.iteration_count(inputs)?;
+        let mut facts = tvec!();
+        for (ix, mapping) in self.output_mapping.iter().enumerate() {
+            let fact = self.body().output_fact(1 + ix)?;
+            if let Some(slot) = mapping.last_value_slot {
+                facts.push((slot, TypedFact::dt_shape(fact.datum_type, fact.shape.clone())));
+            }
+            if let Some(slot) = mapping.full_slot {
+                let mut shape = fact.shape.clone();
+                shape.set(mapping.axis, shape[mapping.axis].clone() * &iters);
+                facts.push((slot, TypedFact::dt_shape(fact.datum_type, shape)));
+            }
+        }
+        facts.sort_by_key(|a| a.0);
+        Ok(facts.into_iter().map(|(_slot, f)| f).collect())
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.decluttered {
+            return Ok(None);
+        }
+        let mut new = self.with_body(self.body().declutter()?)?;
+        new.decluttered = true;
+        Ok(Some(TypedModelPatch::replace_single_op(model, node, &node.inputs, new)?))
+    }
+
+    fn concretize_dims(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        values: &SymbolValues,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+        let op = self.with_body(self.body().concretize_dims(values)?)?;
+        target.wire_node(&node.name, op, &inputs)
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.optimized {
+            return Ok(None);
+        }
+        let mut new = self.with_body(self.body().clone().optimize()?)?;
+        new.optimized = true;
+        Ok(Some(TypedModelPatch::replace_single_op(model, node, &node.inputs, new)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::array::TypedConcat;
+    use crate::ops::{cast, logic, math};
+
+    // acc = acc + x, y = acc * iteration, while acc < 10
+    fn body() -> TractResult<TypedModel> {
+        let mut body = TypedModel::default();
+        let iter = body.add_source("iter", TypedFact::dt_scalar(i64::datum_type()))?;
+        let _cond = body.add_source("cond", TypedFact::dt_scalar(bool::datum_type()))?;
+        let acc = body.add_source("acc", TypedFact::dt_shape(f32::datum_type(), [1]))?;
+        let x = body.add_source("x", TypedFact::dt_shape(f32::datum_type(), [1]))?;
+        let acc = body.wire_node("add", math::add::bin_typed(), &[acc, x])?[0];
+        let ten = body.add_const("ten", tensor1(&[10f32]))?;
+        let cond = body.wire_node("lt", logic::lesser::bin_typed(), &[acc, ten])?[0];
+        let cond = body.wire_node("cond_rm", AxisOp::Rm(0), &[cond])?[0];
+        let iter = body.wire_node("iter_f32", cast::cast(f32::datum_type()), &[iter])?[0];
+        let iter = body.wire_node("iter_add", AxisOp::Add(0), &[iter])?[0];
+        let y = body.wire_node("mul", math::mul::bin_typed(), &[acc, iter])?[0];
+        body.set_output_outlets(&[cond, acc, y])?;
+        Ok(body)
+    }
+
+    fn run(max: i64, x: f32) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut model = TypedModel::default();
+        let m = model.add_const("m", tensor0(max))?;
+        let c = model.add_const("c", tensor0(true))?;
+        let acc = model.add_const("acc", tensor1(&[0f32]))?;
+        let x = model.add_const("x", tensor1(&[x]))?;
+        let out = model.wire_node("loop", Loop::new(body()?, 1)?, &[m, c, acc, x])?;
+        model.set_output_outlets(&out)?;
+        SimplePlan::new(model)?.run(tvec!())
+    }
+
+    #[test]
+    fn loop_until_condition() -> TractResult<()> {
+        let result = run(100, 3.)?;
+        assert_eq!(*result[0], tensor1(&[12f32]));
+        assert_eq!(*result[1], tensor2(&[[0f32], [6.], [18.], [36.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn loop_until_max_trip_count() -> TractResult<()> {
+        let result = run(2, 1.)?;
+        assert_eq!(*result[0], tensor1(&[2f32]));
+        assert_eq!(*result[1], tensor2(&[[0f32], [2.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn loop_no_iteration() -> TractResult<()> {
+        let result = run(0, 1.)?;
+        assert_eq!(*result[0], tensor1(&[0f32]));
+        assert_eq!(result[1].shape(), &[0, 1]);
+        Ok(())
+    }
+
+    // acc = concat(acc, x), M times
+    #[test]
+    fn loop_with_growing_state() -> TractResult<()> {
+        let mut body = TypedModel::default();
+        let _iter = body.add_source("iter", TypedFact::dt_scalar(i64::datum_type()))?;
+        let cond = body.add_source("cond", TypedFact::dt_scalar(bool::datum_type()))?;
+        let acc = body.add_source("acc", TypedFact::dt_shape(f32::datum_type(), [1]))?;
+        let x = body.add_source("x", TypedFact::dt_shape(f32::datum_type(), [1]))?;
+        let acc = body.wire_node("concat", TypedConcat::concat_vars(0, 2), &[acc, x])?[0];
+        body.set_output_outlets(&[cond, acc])?;
+
+        let mut model = TypedModel::default();
+        let m = model.add_source("m", TypedFact::dt_scalar(i64::datum_type()))?;
+        let c = model.add_const("c", tensor0(true))?;
+        let acc = model.add_const("acc", tensor1(&[0f32]))?;
+        let x = model.add_const("x", tensor1(&[1f32]))?;
+        let out = model.wire_node("loop", Loop::new(body, 1)?, &[m, c, acc, x])?;
+        model.set_output_outlets(&out)?;
+        assert!(model.outlet_fact(out[0])?.shape[0].to_usize().is_err());
+        let plan = SimplePlan::new(model)?;
+        let result = plan.run(tvec!(tensor0(3i64)))?;
+        assert_eq!(*result[0], tensor1(&[0f32, 1., 1., 1.]));
+        Ok(())
+    }
+}
@@ -0,0 +1,559 @@
+//! Einstein summation.
+//!
+//! `EinSum` computes the sums of products described by an expression like `bij,bjk->bik`. Its
+//! declutter rewrites the usual cases (transpositions, reductions and batched products of two
+//! operands) to `AxisOp`, `Reduce` and `MatMul`, leaving the generic evaluation as a fallback.
+use std::fmt;
+use std::ops::{Add, Mul};
+
+use num_traits::Zero;
+use tract_ndarray::Dimension;
+
+use crate::internal::*;
+use crate::ops::matmul::MatMul;
+use crate::ops::nn::{Reduce, Reducer};
+
+/// An einsum expression, with one letter for each axis of each input and of the output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Expr {
+    pub inputs: TVec<TVec<char>>,
+    pub output: TVec<char>,
+}
+
+impl Expr {
+    /// Parse an equation, given the ranks of its operands.
+    ///
+    /// Ellipsis (`...`) are expanded to letters absent from the equation, and aligned to the
+    /// right like numpy broadcasting does. Without an explicit output (`->`), the output is made
+    /// of the ellipsis axes followed by the letters appearing only once, in alphabetical order.
+    pub fn parse(equation: &str, ranks: &[usize]) -> TractResult<Expr> {
+        let equation: String = equation.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(c) = equation.chars().find(|c| !c.is_ascii_alphabetic() && !",.->".contains(*c))
+        {
+            bail!("Invalid character {:?} in einsum equation {}", c, equation);
+        }
+        let (inputs, output) = if let Some(pos) = equation.find("->") {
+            (&equation[..pos], Some(&equation[pos + 2..]))
+        } else {
+            (&*equation, None)
+        };
+        let inputs: TVec<&str> = inputs.split(',').collect();
+        if inputs.len() != ranks.len() {
+            bail!(
+                "Einsum equation {} expects {} operands, got {}",
+                equation,
+                inputs.len(),
+                ranks.len()
+            );
+        }
+        let explicit_rank = |s: &str| s.chars().filter(char::is_ascii_alphabetic).count();
+        let mut ellipsis_rank = 0;
+        for (s, &rank) in inputs.iter().zip(ranks) {
+            if s.contains("...") {
+                let len = rank.checked_sub(explicit_rank(s)).ok_or_else(|| {
+                    format_err!("Einsum operand {} does not match rank {}", s, rank)
+                })?;
+                ellipsis_rank = ellipsis_rank.max(len);
+            }
+        }
+        let used: TVec<char> = inputs.iter().flat_map(|s| s.chars()).collect();
+        let fresh: TVec<char> = ('a'..='z')
+            .chain('A'..='Z')
+            .filter(|c| !equation.contains(*c))
+            .take(ellipsis_rank)
+            .collect();
+        if fresh.len() < ellipsis_rank {
+            bail!("Not enough letters left to expand the ellipsis in {}", equation);
+        }
+        let expand = |s: &str, rank: usize| -> TractResult<TVec<char>> {
+            let letters: TVec<char> = if let Some(pos) = s.find("...") {
+                let len = rank.checked_sub(explicit_rank(s)).ok_or_else(|| {
+                    format_err!("Einsum operand {} does not match rank {}", s, rank)
+                })?;
+                s[..pos]
+                    .chars()
+                    .chain(fresh[ellipsis_rank - len..].iter().cloned())
+                    .chain(s[pos + 3..].chars())
+                    .collect()
+            } else {
+                s.chars().collect()
+            };
+            if letters.len() != rank || letters.contains(&'.') {
+                bail!("Einsum operand {} does not match rank {}", s, rank);
+            }
+            Ok(letters)
+        };
+        let inputs = inputs
+            .iter()
+            .zip(ranks)
+            .map(|(s, &rank)| expand(s, rank))
+            .collect::<TractResult<TVec<_>>>()?;
+        let output = if let Some(output) = output {
+            let rank = explicit_rank(output) + ellipsis_rank * output.contains("...") as usize;
+            expand(output, rank)?
+        } else {
+            let mut once: TVec<char> = used
+                .iter()
+                .filter(|c| c.is_ascii_alphabetic() && used.iter().filter(|d| d == c).count() == 1)
+                .cloned()
+                .collect();
+            once.sort();
+            fresh.iter().cloned().chain(once).collect()
+        };
+        for (ix, c) in output.iter().enumerate() {
+            if output[..ix].contains(c) {
+                bail!("Letter {} appears twice in einsum output {}", c, equation);
+            }
+            if !inputs.iter().any(|i| i.contains(c)) {
+                bail!("Letter {} of einsum output does not appear in inputs {}", c, equation);
+            }
+        }
+        Ok(Expr { inputs, output })
+    }
+
+    /// Size of each letter given the operands shapes, axes of size one being broadcast.
+    pub fn letter_dims<D: DimLike>(&self, shapes: &[&[D]]) -> TractResult<HashMap<char, D>> {
+        if shapes.len() != self.inputs.len() {
+            bail!("Einsum {} expects {} operands, got {}", self, self.inputs.len(), shapes.len());
+        }
+        let mut dims: HashMap<char, D> = HashMap::default();
+        for (letters, shape) in self.inputs.iter().zip(shapes) {
+            if letters.len() != shape.len() {
+                bail!("Einsum {} operand rank mismatch with {:?}", self, shape);
+            }
+            for (c, d) in letters.iter().zip(shape.iter()) {
+                let dim = dims.entry(*c).or_insert_with(|| d.clone());
+                if *dim == D::from(1) {
+                    *dim = d.clone();
+                } else if *d != D::from(1) && dim != d {
+                    bail!("Einsum {}: inconsistent dimensions for {} in {:?}", self, c, shapes);
+                }
+            }
+        }
+        Ok(dims)
+    }
+
+    pub fn output_shape<D: DimLike>(&self, shapes: &[&[D]]) -> TractResult<TVec<D>> {
+        let dims = self.letter_dims(shapes)?;
+        Ok(self.output.iter().map(|c| dims[c].clone()).collect())
+    }
+
+    fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.inputs.iter().flatten().chain(self.output.iter()).cloned()
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (ix, input) in self.inputs.iter().enumerate() {
+            if ix > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", input.iter().collect::<String>())?;
+        }
+        write!(f, "->{}", self.output.iter().collect::<String>())
+    }
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct EinSum {
+    pub expr: Expr,
+}
+
+impl_dyn_hash!(EinSum);
+
+impl EinSum {
+    fn eval_t<T>(&self, inputs: &[Arc<Tensor>]) -> TractResult<Tensor>
+    where
+        T: Datum + Zero + Copy + Add<Output = T> + Mul<Output = T>,
+    {
+        let shapes: TVec<&[usize]> = inputs.iter().map(|i| i.shape()).collect();
+        let dims = self.expr.letter_dims(&shapes)?;
+        let mut summed: TVec<char> =
+            dims.keys().filter(|c| !self.expr.output.contains(c)).cloned().collect();
+        summed.sort();
+        let letters: TVec<char> = self.expr.output.iter().chain(summed.iter()).cloned().collect();
+        // for each axis of each input, its position in letters, or None if it is broadcast
+        let positions: TVec<TVec<Option<usize>>> = self
+            .expr
+            .inputs
+            .iter()
+            .zip(shapes.iter())
+            .map(|(input, shape)| {
+                input
+                    .iter()
+                    .zip(shape.iter())
+                    .map(|(c, &d)| {
+                        if d == 1 && dims[c] != 1 {
+                            None
+                        } else {
+                            letters.iter().position(|l| l == c)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let views =
+            inputs.iter().map(|i| i.to_array_view::<T>()).collect::<TractResult<TVec<_>>>()?;
+        let output_shape: TVec<usize> = self.expr.output.iter().map(|c| dims[c]).collect();
+        let summed_shape: TVec<usize> = summed.iter().map(|c| dims[c]).collect();
+        let mut coords = vec![0; letters.len()];
+        let mut input_coords: TVec<Vec<usize>> = shapes.iter().map(|s| vec![0; s.len()]).collect();
+        let mut output = tract_ndarray::ArrayD::<T>::zeros(&*output_shape);
+        for (ocoords, o) in output.indexed_iter_mut() {
+            coords[..output_shape.len()].copy_from_slice(ocoords.slice());
+            let mut sum = T::zero();
+            for scoords in tract_ndarray::indices(&*summed_shape) {
+                coords[output_shape.len()..].copy_from_slice(scoords.slice());
+                let mut product: Option<T> = None;
+                for ((view, positions), icoords) in
+                    views.iter().zip(positions.iter()).zip(input_coords.iter_mut())
+                {
+                    for (ic, pos) in icoords.iter_mut().zip(positions.iter()) {
+                        *ic = pos.map(|p| coords[p]).unwrap_or(0);
+                    }
+                    let value = view[&**icoords];
+                    product = Some(product.map(|p| p * value).unwrap_or(value));
+                }
+                sum = sum + product.unwrap();
+            }
+            *o = sum;
+        }
+        Ok(output.into_tensor())
+    }
+
+    fn declutter_unary(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut patch = TypedModelPatch::default();
+        let mut letters = self.expr.inputs[0].clone();
+        let wire = patch.tap_model(model, node.inputs[0])?;
+        let output = &self.expr.output;
+        let wire = wire_sum(&mut patch, &node.name, wire, &mut letters, |c| output.contains(&c))?;
+        let wire = wire_permute(&mut patch, &node.name, wire, &mut letters, output)?;
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+
+    fn declutter_binary(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let facts = model.node_input_facts(node.id)?;
+        let output = &self.expr.output;
+        let (mut a_letters, mut b_letters) =
+            (self.expr.inputs[0].clone(), self.expr.inputs[1].clone());
+        let in_a = |c: &char| self.expr.inputs[0].contains(c);
+        let in_b = |c: &char| self.expr.inputs[1].contains(c);
+        let k: TVec<char> =
+            a_letters.iter().filter(|c| in_b(c) && !output.contains(c)).cloned().collect();
+        // contracted axes can not be broadcast by MatMul
+        for c in &k {
+            let a_dim = &facts[0].shape[a_letters.iter().position(|l| l == c).unwrap()];
+            let b_dim = &facts[1].shape[b_letters.iter().position(|l| l == c).unwrap()];
+            if a_dim != b_dim {
+                return Ok(None);
+            }
+        }
+        let mut fresh =
+            ('a'..='z').chain('A'..='Z').filter(|c| !self.expr.letters().any(|l| l == *c));
+        let mut fresh = || fresh.next().ok_or_else(|| format_err!("No letter left"));
+        let name = &*node.name;
+        let mut patch = TypedModelPatch::default();
+        let mut a = patch.tap_model(model, node.inputs[0])?;
+        let mut b = patch.tap_model(model, node.inputs[1])?;
+        a = wire_sum(&mut patch, &format!("{}.a", name), a, &mut a_letters, |c| {
+            output.contains(&c) || in_b(&c)
+        })?;
+        b = wire_sum(&mut patch, &format!("{}.b", name), b, &mut b_letters, |c| {
+            output.contains(&c) || in_a(&c)
+        })?;
+
+        // all but one of the axes specific to a (resp. b) become batch axes, b (resp. a) being
+        // broadcast along them
+        let m: TVec<char> = output.iter().filter(|c| in_a(c) && !in_b(c)).cloned().collect();
+        let n: TVec<char> = output.iter().filter(|c| in_b(c) && !in_a(c)).cloned().collect();
+        let mut removed = tvec!();
+        let m = if let Some((m, others)) = m.split_last() {
+            for c in others {
+                b = wire_add_axis(&mut patch, &format!("{}.b", name), b, &mut b_letters, *c)?;
+            }
+            *m
+        } else {
+            let m = fresh()?;
+            a = wire_add_axis(&mut patch, &format!("{}.a", name), a, &mut a_letters, m)?;
+            removed.push(m);
+            m
+        };
+        let n = if let Some((n, others)) = n.split_last() {
+            for c in others {
+                a = wire_add_axis(&mut patch, &format!("{}.a", name), a, &mut a_letters, *c)?;
+            }
+            *n
+        } else {
+            let n = fresh()?;
+            b = wire_add_axis(&mut patch, &format!("{}.b", name), b, &mut b_letters, n)?;
+            removed.push(n);
+            n
+        };
+        let k = if k.is_empty() {
+            let k = fresh()?;
+            a = wire_add_axis(&mut patch, &format!("{}.a", name), a, &mut a_letters, k)?;
+            b = wire_add_axis(&mut patch, &format!("{}.b", name), b, &mut b_letters, k)?;
+            tvec!(k)
+        } else {
+            k
+        };
+        let batch: TVec<char> =
+            output.iter().filter(|c| !k.contains(c) && **c != m && **c != n).cloned().collect();
+        let a_order: TVec<char> = batch.iter().chain(&[m]).chain(k.iter()).cloned().collect();
+        let b_order: TVec<char> = batch.iter().chain(k.iter()).chain(&[n]).cloned().collect();
+        a = wire_permute(&mut patch, &format!("{}.a", name), a, &mut a_letters, &a_order)?;
+        b = wire_permute(&mut patch, &format!("{}.b", name), b, &mut b_letters, &b_order)?;
+        if k.len() > 1 {
+            let k_dims: TVec<TDim> =
+                patch.outlet_fact(a)?.shape[batch.len() + 1..].iter().cloned().collect();
+            let merged = tvec!(k_dims.iter().product::<TDim>());
+            a = patch.wire_node(
+                format!("{}.a.merge-k", name),
+                AxisOp::Reshape(batch.len() + 1, k_dims.clone(), merged.clone()),
+                &[a],
+            )?[0];
+            b = patch.wire_node(
+                format!("{}.b.merge-k", name),
+                AxisOp::Reshape(batch.len(), k_dims, merged),
+                &[b],
+            )?[0];
+        }
+        let mut wire = patch.wire_node(format!("{}.matmul", name), MatMul::default(), &[a, b])?[0];
+        let mut letters: TVec<char> = batch.iter().chain(&[m, n]).cloned().collect();
+        for c in removed {
+            let axis = letters.iter().position(|l| *l == c).unwrap();
+            wire = patch.wire_node(format!("{}.rm-{}", name, c), AxisOp::Rm(axis), &[wire])?[0];
+            letters.remove(axis);
+        }
+        let wire = wire_permute(&mut patch, name, wire, &mut letters, output)?;
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+}
+
+/// Sum and remove the axes of `wire` whose letter is not kept.
+fn wire_sum(
+    patch: &mut TypedModelPatch,
+    name: &str,
+    mut wire: OutletId,
+    letters: &mut TVec<char>,
+    keep: impl Fn(char) -> bool,
+) -> TractResult<OutletId> {
+    let axes: TVec<usize> = (0..letters.len()).filter(|ix| !keep(letters[*ix])).collect();
+    if axes.is_empty() {
+        return Ok(wire);
+    }
+    wire = patch.wire_node(
+        format!("{}.sum", name),
+        Reduce::new(axes.clone(), Reducer::Sum),
+        &[wire],
+    )?[0];
+    for axis in axes.into_iter().rev() {
+        let c = letters.remove(axis);
+        wire = patch.wire_node(format!("{}.rm-{}", name, c), AxisOp::Rm(axis), &[wire])?[0];
+    }
+    Ok(wire)
+}
+
+/// Append an axis of size one to `wire`.
+fn wire_add_axis(
+    patch: &mut TypedModelPatch,
+    name: &str,
+    wire: OutletId,
+    letters: &mut TVec<char>,
+    letter: char,
+) -> TractResult<OutletId> {
+    let wire =
+        patch.wire_node(format!("{}.add-{}", name, letter), AxisOp::Add(letters.len()), &[wire])?
+            [0];
+    letters.push(letter);
+    Ok(wire)
+}
+
+/// Move the axes of `wire` so that its letters are in the `target` order.
+fn wire_permute(
+    patch: &mut TypedModelPatch,
+    name: &str,
+    mut wire: OutletId,
+    letters: &mut TVec<char>,
+    target: &[char],
+) -> TractResult<OutletId> {
+    for (ix, c) in target.iter().enumerate() {
+        let from = letters.iter().position(|l| l == c).unwrap();
+        if from != ix {
+            wire =
+                patch.wire_node(format!("{}.move-{}", name, c), AxisOp::Move(from, ix), &[wire])?
+                    [0];
+            letters.remove(from);
+            letters.insert(ix, *c);
+        }
+    }
+    Ok(wire)
+}
+
+impl Op for EinSum {
+    fn name(&self) -> Cow<str> {
+        "EinSum".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![self.expr.to_string()])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for EinSum {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let output = dispatch_numbers!(Self::eval_t(inputs[0].datum_type())(self, &inputs))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for EinSum {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs.iter().any(|i| i.datum_type != inputs[0].datum_type) {
+            bail!("Einsum operands must share their datum type, got {:?}", inputs);
+        }
+        let shapes: TVec<TVec<TDim>> = inputs.iter().map(|i| i.shape.to_tvec()).collect();
+        let shapes: TVec<&[TDim]> = shapes.iter().map(|s| &**s).collect();
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, self.expr.output_shape(&shapes)?)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        // MatMul and Reduce accumulate integers differently, and repeated letters (diagonals)
+        // have no equivalent
+        if !model.outlet_fact(node.inputs[0])?.datum_type.is_float()
+            || self
+                .expr
+                .inputs
+                .iter()
+                .any(|i| i.iter().any(|c| i.iter().filter(|d| d == &c).count() > 1))
+        {
+            return Ok(None);
+        }
+        match node.inputs.len() {
+            1 => self.declutter_unary(model, node),
+            2 => self.declutter_binary(model, node),
+            _ => Ok(None),
+        }
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expr(eq: &str, ranks: &[usize]) -> String {
+        Expr::parse(eq, ranks).unwrap().to_string()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(expr("ij,jk->ik", &[2, 2]), "ij,jk->ik");
+        assert_eq!(expr("ij,jk", &[2, 2]), "ij,jk->ik");
+        assert_eq!(expr("ba", &[2]), "ba->ab");
+        assert_eq!(expr("ii", &[2]), "ii->");
+        assert_eq!(expr("...ij,...jk->...ik", &[4, 2]), "abij,jk->abik");
+        assert_eq!(expr("...j,j", &[3, 1]), "abj,j->ab");
+        assert!(Expr::parse("ij,jk->il", &[2, 2]).is_err());
+        assert!(Expr::parse("ij,jk->ik", &[2, 3]).is_err());
+        assert!(Expr::parse("ij->iji", &[2]).is_err());
+    }
+
+    fn check(eq: &str, shapes: &[&[usize]]) -> TractResult<()> {
+        let ranks = shapes.iter().map(|s| s.len()).collect::<TVec<_>>();
+        let op = EinSum::new(Expr::parse(eq, &ranks)?);
+        let inputs: TVec<Tensor> = shapes
+            .iter()
+            .enumerate()
+            .map(|(ix, shape)| {
+                let len = shape.iter().product::<usize>();
+                let data =
+                    (0..len).map(|i| ((i * 7 + ix * 3) % 11) as f32 - 5.0).collect::<Vec<_>>();
+                tract_ndarray::ArrayD::from_shape_vec(*shape, data).unwrap().into_tensor()
+            })
+            .collect();
+        let mut model = TypedModel::default();
+        let sources = inputs
+            .iter()
+            .enumerate()
+            .map(|(ix, t)| {
+                model.add_source(
+                    format!("input-{}", ix),
+                    TypedFact::dt_shape(f32::datum_type(), t.shape()),
+                )
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        let output = model.wire_node("einsum", op, &sources)?;
+        model.set_output_outlets(&output)?;
+        let expected = model.clone().into_runnable()?.run(inputs.clone())?.remove(0);
+        let decluttered = model.declutter()?;
+        assert!(decluttered.nodes().iter().all(|n| !n.op_is::<EinSum>()), "{}", eq);
+        let found = decluttered.into_runnable()?.run(inputs)?.remove(0);
+        found.close_enough(&expected, true)
+    }
+
+    #[test]
+    fn eval_matmul() -> TractResult<()> {
+        let op = EinSum::new(Expr::parse("ij,jk->ik", &[2, 2])?);
+        let a = tensor2(&[[1f32, 2.], [3., 4.]]);
+        let b = tensor2(&[[5f32, 6.], [7., 8.]]);
+        let output = op.eval(tvec!(a.into_arc_tensor(), b.into_arc_tensor()))?;
+        assert_eq!(output[0], rctensor2(&[[19f32, 22.], [43., 50.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn eval_diagonal() -> TractResult<()> {
+        let op = EinSum::new(Expr::parse("ii->i", &[2])?);
+        let output = op.eval(tvec!(rctensor2(&[[1f32, 2.], [3., 4.]])))?;
+        assert_eq!(output[0], rctensor1(&[1f32, 4.]));
+        Ok(())
+    }
+
+    #[test]
+    fn declutter_unary() -> TractResult<()> {
+        check("ij->ji", &[&[2, 3]])?;
+        check("ijk->k", &[&[2, 3, 4]])?;
+        check("ij->", &[&[2, 3]])
+    }
+
+    #[test]
+    fn declutter_binary() -> TractResult<()> {
+        check("ij,jk->ik", &[&[2, 3], &[3, 4]])?;
+        check("ij,kj->ki", &[&[2, 3], &[4, 3]])?;
+        check("bhqd,bhkd->bhqk", &[&[2, 3, 4, 5], &[2, 3, 6, 5]])?;
+        check("bij,jk->bik", &[&[2, 3, 4], &[4, 5]])?;
+        check("i,j->ij", &[&[2], &[3]])?;
+        check("i,i->", &[&[3], &[3]])?;
+        check("ijk,jkl->li", &[&[2, 3, 4], &[3, 4, 5]])?;
+        check("ij,jk->i", &[&[2, 3], &[3, 4]])?;
+        check("...ij,...jk->...ik", &[&[2, 1, 3, 4], &[3, 4, 5]])
+    }
+}
@@ -0,0 +1,116 @@
+use crate::internal::*;
+use num_complex::Complex64;
+use tract_ndarray::{ArrayD, Axis};
+
+use super::{check_signal_fact, fft, from_complex, to_complex};
+
+/// Discrete Fourier transform of a `[..., 1|2]` real or complex signal along `axis`.
+///
+/// Lanes are zero-padded or truncated to `length` when it is set. The inverse transform is
+/// normalized by `1/n`. With `onesided`, only the `n/2+1` non redundant bins are kept.
+#[derive(Debug, Clone, Hash)]
+pub struct Dft {
+    pub axis: usize,
+    pub inverse: bool,
+    pub onesided: bool,
+    pub length: Option<usize>,
+}
+
+impl_dyn_hash!(Dft);
+
+impl Dft {
+    fn transform(&self, input: &ArrayD<Complex64>) -> ArrayD<Complex64> {
+        let n = self.length.unwrap_or(input.shape()[self.axis]);
+        let bins = if self.onesided { n / 2 + 1 } else { n };
+        let mut shape = input.shape().to_vec();
+        shape[self.axis] = bins;
+        let mut output = ArrayD::<Complex64>::zeros(shape);
+        let mut buffer = vec![Complex64::default(); n];
+        for (lane, mut out) in
+            input.lanes(Axis(self.axis)).into_iter().zip(output.lanes_mut(Axis(self.axis)))
+        {
+            buffer.iter_mut().for_each(|x| *x = Complex64::default());
+            buffer.iter_mut().zip(lane.iter()).for_each(|(b, x)| *b = *x);
+            fft(&mut buffer, self.inverse);
+            for (o, b) in out.iter_mut().zip(&buffer) {
+                *o = if self.inverse { b / n as f64 } else { *b };
+            }
+        }
+        output
+    }
+}
+
+impl Op for Dft {
+    fn name(&self) -> Cow<str> {
+        "Dft".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {} inverse: {} onesided: {} length: {:?}",
+            self.axis, self.inverse, self.onesided, self.length
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Dft {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = self.transform(&to_complex(&input)?);
+        Ok(tvec!(from_complex(output, input.datum_type())?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Dft {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        check_signal_fact(inputs[0])?;
+        if self.axis + 1 >= inputs[0].rank() {
+            bail!("Invalid axis {} for Dft on {:?}", self.axis, inputs[0])
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        let n = self.length.map(|l| l.to_dim()).unwrap_or_else(|| shape[self.axis].clone());
+        shape[self.axis] = if self.onesided { n / 2 + 1 } else { n };
+        *shape.last_mut().unwrap() = 2.to_dim();
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(a: &Tensor, b: &Tensor) {
+        assert_eq!(a.shape(), b.shape());
+        for (x, y) in a.as_slice::<f32>().unwrap().iter().zip(b.as_slice::<f32>().unwrap()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn real_onesided() -> TractResult<()> {
+        let op = Dft { axis: 1, inverse: false, onesided: true, length: None };
+        let input = tensor3(&[[[1f32], [2.], [3.], [4.]]]).into_arc_tensor();
+        let output = op.eval(tvec!(input))?;
+        assert_close(&output[0], &tensor3(&[[[10f32, 0.], [-2., 2.], [-2., 0.]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn inverse_round_trip() -> TractResult<()> {
+        let input = tensor3(&[[[1f32, 0.5], [2., -1.], [3., 0.], [4., 2.], [0., 1.]]]);
+        let forward = Dft { axis: 1, inverse: false, onesided: false, length: None };
+        let inverse = Dft { axis: 1, inverse: true, onesided: false, length: None };
+        let output = inverse.eval(forward.eval(tvec!(input.clone().into_arc_tensor()))?)?;
+        assert_close(&output[0], &input);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::internal::*;
+
+/// Builds the `[dft_length/2+1, num_mel_bins]` matrix mapping onesided spectrum bins to mel
+/// bands, made of triangular filters evenly spaced on the mel scale between the lower and upper
+/// edge frequencies.
+///
+/// Inputs are five scalars: num_mel_bins, dft_length, sample_rate, lower_edge_hertz and
+/// upper_edge_hertz.
+#[derive(Debug, Clone, Hash)]
+pub struct MelWeightMatrix {
+    pub datum_type: DatumType,
+}
+
+impl_dyn_hash!(MelWeightMatrix);
+
+impl MelWeightMatrix {
+    fn hz_to_mel(hz: f64) -> f64 {
+        2595.0 * (1.0 + hz / 700.0).log10()
+    }
+
+    fn mel_to_hz(mel: f64) -> f64 {
+        700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+    }
+
+    pub fn matrix(
+        num_mel_bins: usize,
+        dft_length: usize,
+        sample_rate: f64,
+        lower_edge_hertz: f64,
+        upper_edge_hertz: f64,
+    ) -> TractResult<Tensor> {
+        let spectrum_bins = dft_length / 2 + 1;
+        let low_mel = Self::hz_to_mel(lower_edge_hertz);
+        let high_mel = Self::hz_to_mel(upper_edge_hertz);
+        let step = (high_mel - low_mel) / (num_mel_bins + 1) as f64;
+        let frequency_bins: Vec<usize> = (0..num_mel_bins + 2)
+            .map(|i| {
+                let hz = Self::mel_to_hz(low_mel + i as f64 * step);
+                ((dft_length + 1) as f64 * hz / sample_rate).floor().max(0.0) as usize
+            })
+            .collect();
+        let mut output = tract_ndarray::Array2::<f64>::zeros((spectrum_bins, num_mel_bins));
+        for i in 0..num_mel_bins {
+            let (low, center, high) =
+                (frequency_bins[i], frequency_bins[i + 1], frequency_bins[i + 2]);
+            if low == center {
+                if center < spectrum_bins {
+                    output[(center, i)] = 1.0;
+                }
+            } else {
+                for j in low..=center.min(spectrum_bins - 1) {
+                    output[(j, i)] = (j - low) as f64 / (center - low) as f64;
+                }
+            }
+            for j in center..high.min(spectrum_bins) {
+                output[(j, i)] = (high - j) as f64 / (high - center) as f64;
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for MelWeightMatrix {
+    fn name(&self) -> Cow<str> {
+        "MelWeightMatrix".into()
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for MelWeightMatrix {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let num_mel_bins = inputs[0].cast_to_scalar::<i64>()?;
+        let dft_length = inputs[1].cast_to_scalar::<i64>()?;
+        if num_mel_bins < 0 || dft_length < 0 {
+            bail!("Invalid num_mel_bins {} or dft_length {}", num_mel_bins, dft_length)
+        }
+        let output = Self::matrix(
+            num_mel_bins as usize,
+            dft_length as usize,
+            inputs[2].cast_to_scalar::<f64>()?,
+            inputs[3].cast_to_scalar::<f64>()?,
+            inputs[4].cast_to_scalar::<f64>()?,
+        )?;
+        Ok(tvec!(output.cast_to_dt(self.datum_type)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for MelWeightMatrix {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let (Some(num_mel_bins), Some(dft_length)) = (&inputs[0].konst, &inputs[1].konst) {
+            let num_mel_bins = num_mel_bins.cast_to_scalar::<i64>()?;
+            let dft_length = dft_length.cast_to_scalar::<i64>()?;
+            Ok(tvec!(TypedFact::dt_shape(
+                self.datum_type,
+                [(dft_length / 2 + 1).to_dim(), num_mel_bins.to_dim()]
+            )))
+        } else {
+            bail!("MelWeightMatrix num_mel_bins and dft_length must be known at compile time")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn triangles() -> TractResult<()> {
+        let op = MelWeightMatrix { datum_type: f32::datum_type() };
+        let output = op.eval(tvec!(
+            rctensor0(2i64),
+            rctensor0(16i64),
+            rctensor0(8000i64),
+            rctensor0(0f32),
+            rctensor0(4000f32)
+        ))?;
+        assert_eq!(output[0].shape(), &[9, 2]);
+        let output =
+            output[0].to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix2>()?;
+        // each band peaks at its center bin
+        for band in output.columns() {
+            assert_eq!(band.iter().cloned().fold(0f32, f32::max), 1.);
+        }
+        assert_eq!(output[(0, 0)], 0.);
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! # Signal processing operators
+//!
+//! Complex tensors are represented as real tensors with an extra trailing axis of size 2 (real
+//! and imaginary parts). Real inputs may use a trailing axis of size 1 instead.
+
+use std::f64::consts::PI;
+
+use crate::internal::*;
+use num_complex::Complex64;
+use num_traits::Zero;
+
+mod dft;
+mod mel_weight_matrix;
+mod stft;
+mod window;
+
+pub use self::dft::Dft;
+pub use self::mel_weight_matrix::MelWeightMatrix;
+pub use self::stft::Stft;
+pub use self::window::{Window, WindowKind};
+
+/// In-place unnormalized discrete Fourier transform (or its inverse).
+///
+/// Power of two lengths use an iterative radix-2 FFT, other lengths go through Bluestein's
+/// algorithm, so any length runs in O(n log n).
+pub fn fft(data: &mut [Complex64], inverse: bool) {
+    if data.len() <= 1 {
+        return;
+    }
+    if data.len().is_power_of_two() {
+        radix2(data, inverse)
+    } else {
+        bluestein(data, inverse)
+    }
+}
+
+fn radix2(data: &mut [Complex64], inverse: bool) {
+    let n = data.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let twiddles: Vec<Complex64> = (0..len / 2)
+            .map(|k| Complex64::from_polar(1.0, sign * 2.0 * PI * k as f64 / len as f64))
+            .collect();
+        for chunk in data.chunks_mut(len) {
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for ((a, b), w) in low.iter_mut().zip(high.iter_mut()).zip(&twiddles) {
+                let t = *b * w;
+                *b = *a - t;
+                *a += t;
+            }
+        }
+        len *= 2;
+    }
+}
+
+fn bluestein(data: &mut [Complex64], inverse: bool) {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { 1.0 } else { -1.0 };
+    // k² is taken modulo 2n to keep the chirp angles small and accurate
+    let chirp: Vec<Complex64> = (0..n as u64)
+        .map(|k| {
+            let k2 = (k * k) % (2 * n as u64);
+            Complex64::from_polar(1.0, sign * PI * k2 as f64 / n as f64)
+        })
+        .collect();
+    let mut a = vec![Complex64::zero(); m];
+    let mut b = vec![Complex64::zero(); m];
+    for k in 0..n {
+        a[k] = data[k] * chirp[k];
+    }
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (a, b) in a.iter_mut().zip(&b) {
+        *a *= b;
+    }
+    radix2(&mut a, true);
+    for k in 0..n {
+        data[k] = a[k] * chirp[k] / m as f64;
+    }
+}
+
+/// Reads a `[..., 1|2]` real or complex tensor as an array of complex numbers, dropping the
+/// trailing axis.
+fn to_complex(input: &Tensor) -> TractResult<tract_ndarray::ArrayD<Complex64>> {
+    let input = input.cast_to::<f64>()?;
+    let input = input.to_array_view::<f64>()?;
+    let rank = input.ndim();
+    let complex = match input.shape()[rank - 1] {
+        1 => false,
+        2 => true,
+        n => bail!("Expected a trailing axis of size 1 (real) or 2 (complex), got {}", n),
+    };
+    let values = input
+        .lanes(tract_ndarray::Axis(rank - 1))
+        .into_iter()
+        .map(|l| Complex64::new(l[0], if complex { l[1] } else { 0.0 }))
+        .collect();
+    Ok(tract_ndarray::ArrayD::from_shape_vec(&input.shape()[..rank - 1], values)?)
+}
+
+/// Writes an array of complex numbers as a `[..., 2]` tensor of type `dt`.
+fn from_complex(array: tract_ndarray::ArrayD<Complex64>, dt: DatumType) -> TractResult<Tensor> {
+    let mut shape: TVec<usize> = array.shape().into();
+    shape.push(2);
+    let values: Vec<f64> = array.iter().flat_map(|c| [c.re, c.im]).collect();
+    Ok(tensor1(&values).into_shape(&shape)?.cast_to_dt(dt)?.into_owned())
+}
+
+fn check_signal_fact(fact: &TypedFact) -> TractResult<()> {
+    if !fact.datum_type.is_float() {
+        bail!("Expected a float signal, got {:?}", fact)
+    }
+    let last = fact.shape.last().cloned().unwrap_or_else(|| 0.to_dim());
+    if last != 1.to_dim() && last != 2.to_dim() {
+        bail!("Expected a trailing axis of size 1 (real) or 2 (complex), got {:?}", fact)
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn naive_dft(data: &[Complex64]) -> Vec<Complex64> {
+        let n = data.len();
+        (0..n)
+            .map(|k| {
+                data.iter()
+                    .enumerate()
+                    .map(|(j, x)| {
+                        x * Complex64::from_polar(1.0, -2.0 * PI * (j * k) as f64 / n as f64)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn check(n: usize) {
+        let data: Vec<Complex64> =
+            (0..n).map(|i| Complex64::new((i as f64).sin(), (i as f64 * 0.3).cos())).collect();
+        let mut fast = data.clone();
+        fft(&mut fast, false);
+        for (a, b) in fast.iter().zip(naive_dft(&data)) {
+            assert!((a - b).norm() < 1e-9, "n={} {} != {}", n, a, b);
+        }
+        fft(&mut fast, true);
+        for (a, b) in fast.iter().zip(&data) {
+            assert!((a / n as f64 - b).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn radix2_lengths() {
+        for n in [1, 2, 4, 8, 64] {
+            check(n)
+        }
+    }
+
+    #[test]
+    fn other_lengths() {
+        for n in [3, 5, 6, 7, 12, 100] {
+            check(n)
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::internal::*;
+use num_complex::Complex64;
+use tract_ndarray::ArrayD;
+
+use super::{check_signal_fact, fft, from_complex, to_complex};
+
+/// Short time Fourier transform of a `[batch, len, 1|2]` real or complex signal.
+///
+/// Frames of `frame_length` samples are taken every `frame_step` samples, multiplied by the
+/// optional `window` (of `frame_length` values), and transformed. Output is
+/// `[batch, frames, bins, 2]`, where `bins` is `frame_length/2+1` with `onesided`, and
+/// `frame_length` otherwise.
+#[derive(Debug, Clone, Hash)]
+pub struct Stft {
+    pub frame_step: usize,
+    pub frame_length: usize,
+    pub onesided: bool,
+    pub window: Option<Arc<Tensor>>,
+}
+
+impl_dyn_hash!(Stft);
+
+impl Stft {
+    pub fn bins(&self) -> usize {
+        if self.onesided {
+            self.frame_length / 2 + 1
+        } else {
+            self.frame_length
+        }
+    }
+
+    pub fn frames<D: DimLike>(&self, len: &D) -> D {
+        (len.clone() + 1 - self.frame_length).div_ceil(self.frame_step)
+    }
+}
+
+impl Op for Stft {
+    fn name(&self) -> Cow<str> {
+        "Stft".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "frame_step: {} frame_length: {} onesided: {} window: {}",
+            self.frame_step,
+            self.frame_length,
+            self.onesided,
+            self.window.is_some()
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Stft {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let signal = to_complex(&input)?;
+        let (batch, len) = (signal.shape()[0], signal.shape()[1]);
+        if len < self.frame_length {
+            bail!("Signal of length {} is shorter than frame length {}", len, self.frame_length)
+        }
+        let window = if let Some(w) = &self.window {
+            w.cast_to::<f64>()?.as_slice::<f64>()?.to_vec()
+        } else {
+            vec![1.0; self.frame_length]
+        };
+        let frames = self.frames(&len);
+        let bins = self.bins();
+        let mut output = ArrayD::<Complex64>::zeros(&[batch, frames, bins][..]);
+        let mut buffer = vec![Complex64::default(); self.frame_length];
+        for b in 0..batch {
+            for f in 0..frames {
+                for (j, x) in buffer.iter_mut().enumerate() {
+                    *x = signal[[b, f * self.frame_step + j]] * window[j];
+                }
+                fft(&mut buffer, false);
+                for (k, x) in buffer.iter().take(bins).enumerate() {
+                    output[[b, f, k]] = *x;
+                }
+            }
+        }
+        Ok(tvec!(from_complex(output, input.datum_type())?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Stft {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        check_signal_fact(inputs[0])?;
+        if inputs[0].rank() != 3 {
+            bail!("Stft expects a [batch, len, 1|2] signal, got {:?}", inputs[0])
+        }
+        if self.frame_step == 0 || self.frame_length == 0 {
+            bail!("Stft frame step and length must be positive")
+        }
+        if self.window.as_ref().map(|w| w.len() != self.frame_length).unwrap_or(false) {
+            bail!("Stft window length must match frame length {}", self.frame_length)
+        }
+        let shape = [
+            inputs[0].shape[0].clone(),
+            self.frames(&inputs[0].shape[1]),
+            self.bins().into(),
+            2.into(),
+        ];
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frames_and_window() -> TractResult<()> {
+        let op = Stft {
+            frame_step: 2,
+            frame_length: 4,
+            onesided: true,
+            window: Some(rctensor1(&[0f32, 1., 1., 0.])),
+        };
+        let signal = tensor3(&[[[1f32], [2.], [3.], [4.], [5.], [6.], [7.]]]).into_arc_tensor();
+        let output = op.eval(tvec!(signal))?;
+        // windowed frames are [0, 2, 3, 0] and [0, 4, 5, 0]
+        let expected =
+            tensor4(&[[[[5f32, 0.], [-3., -2.], [1., 0.]], [[9., 0.], [-5., -4.], [1., 0.]]]]);
+        assert_eq!(output[0].shape(), expected.shape());
+        for (a, b) in output[0].as_slice::<f32>()?.iter().zip(expected.as_slice::<f32>()?) {
+            assert!((a - b).abs() < 1e-5, "{:?}", output[0]);
+        }
+        Ok(())
+    }
+}
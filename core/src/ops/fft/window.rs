@@ -0,0 +1,100 @@
+use std::f64::consts::PI;
+
+use crate::internal::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowKind {
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+/// Generates a window function of the size given by its scalar input.
+///
+/// Periodic windows (suitable for spectral analysis) are computed as if they were one sample
+/// longer, symmetric ones are not.
+#[derive(Debug, Clone, Hash)]
+pub struct Window {
+    pub kind: WindowKind,
+    pub periodic: bool,
+    pub datum_type: DatumType,
+}
+
+impl_dyn_hash!(Window);
+
+impl Window {
+    pub fn values(&self, size: usize) -> Vec<f64> {
+        let n = if self.periodic { size } else { size.saturating_sub(1) }.max(1) as f64;
+        (0..size)
+            .map(|i| {
+                let x = 2.0 * PI * i as f64 / n;
+                match self.kind {
+                    WindowKind::Hann => 0.5 - 0.5 * x.cos(),
+                    WindowKind::Hamming => {
+                        let alpha = 25.0 / 46.0;
+                        alpha - (1.0 - alpha) * x.cos()
+                    }
+                    WindowKind::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Op for Window {
+    fn name(&self) -> Cow<str> {
+        format!("{:?}Window", self.kind).into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("periodic: {} datum_type: {:?}", self.periodic, self.datum_type)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Window {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let size = args_1!(inputs);
+        let size = size.cast_to_scalar::<i64>()?;
+        if size < 0 {
+            bail!("Invalid window size {}", size)
+        }
+        let values = tensor1(&self.values(size as usize));
+        Ok(tvec!(values.cast_to_dt(self.datum_type)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Window {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let Some(size) = &inputs[0].konst {
+            let size = size.cast_to_scalar::<i64>()?;
+            Ok(tvec!(TypedFact::dt_shape(self.datum_type, [size.to_dim()])))
+        } else {
+            bail!("{} size must be known at compile time", self.name())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hann() -> TractResult<()> {
+        let op = Window { kind: WindowKind::Hann, periodic: true, datum_type: f32::datum_type() };
+        let output = op.eval(tvec!(rctensor0(4i64)))?;
+        assert_eq!(*output[0], tensor1(&[0f32, 0.5, 1., 0.5]));
+        let op = Window { periodic: false, ..op };
+        let output = op.eval(tvec!(rctensor0(3i64)))?;
+        assert_eq!(*output[0], tensor1(&[0f32, 1., 0.]));
+        Ok(())
+    }
+}
@@ -25,6 +25,17 @@ impl EvalOp for Identity {
     }
 }
 
+impl Identity {
+    fn shunt(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Option<TypedModelPatch>> {
+        // a model can not expose the same outlet twice (think nested loop bodies)
+        let outputs = model.output_outlets()?;
+        if outputs.contains(&node.inputs[0]) && outputs.contains(&OutletId::new(node.id, 0)) {
+            return Ok(None);
+        }
+        Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
+    }
+}
+
 impl TypedOp for Identity {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         Ok(tvec!(inputs[0].clone()))
@@ -35,11 +46,11 @@ impl TypedOp for Identity {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
-        Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
+        self.shunt(model, node)
     }
 
     fn fuse(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Option<TypedModelPatch>> {
-        Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
+        self.shunt(model, node)
     }
 
     as_op!();
@@ -0,0 +1,101 @@
+use crate::internal::*;
+use num_traits::Zero;
+use tract_ndarray::Axis;
+
+/// Cumulative sum along an axis.
+///
+/// With `exclusive`, each output item excludes the matching input item. With `reverse`, the sum
+/// runs from the end of the axis.
+#[derive(Debug, Clone, Hash)]
+pub struct CumSum {
+    pub axis: usize,
+    pub exclusive: bool,
+    pub reverse: bool,
+}
+
+impl_dyn_hash!(CumSum);
+
+impl CumSum {
+    fn eval_t<T: Datum + Zero + Copy>(&self, input: &mut Tensor) -> TractResult<()> {
+        let mut view = input.to_array_view_mut::<T>()?;
+        for mut lane in view.lanes_mut(Axis(self.axis)) {
+            let len = lane.len();
+            let mut acc = T::zero();
+            for i in 0..len {
+                let ix = if self.reverse { len - 1 - i } else { i };
+                let value = lane[ix];
+                if self.exclusive {
+                    lane[ix] = acc;
+                    acc = acc + value;
+                } else {
+                    acc = acc + value;
+                    lane[ix] = acc;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Op for CumSum {
+    fn name(&self) -> Cow<str> {
+        "CumSum".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {} exclusive: {} reverse: {}",
+            self.axis, self.exclusive, self.reverse
+        )])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for CumSum {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut input = args_1!(inputs).into_tensor();
+        dispatch_numbers!(Self::eval_t(input.datum_type())(self, &mut input))?;
+        Ok(tvec!(input.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for CumSum {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.axis >= inputs[0].rank() {
+            bail!("Invalid axis {} for CumSum on {:?}", self.axis, inputs[0])
+        }
+        Ok(tvec!(inputs[0].without_value()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(axis: usize, exclusive: bool, reverse: bool) -> TractResult<Tensor> {
+        let op = CumSum { axis, exclusive, reverse };
+        let input = rctensor2(&[[1f32, 2., 3.], [4., 5., 6.]]);
+        Ok(op.eval(tvec!(input))?.remove(0).into_tensor())
+    }
+
+    #[test]
+    fn inclusive() -> TractResult<()> {
+        assert_eq!(run(1, false, false)?, tensor2(&[[1f32, 3., 6.], [4., 9., 15.]]));
+        assert_eq!(run(0, false, false)?, tensor2(&[[1f32, 2., 3.], [5., 7., 9.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn exclusive_reverse() -> TractResult<()> {
+        assert_eq!(run(1, true, true)?, tensor2(&[[5f32, 3., 0.], [11., 6., 0.]]));
+        Ok(())
+    }
+}
@@ -10,6 +10,9 @@ use num_traits::{Float, Zero};
 use tract_num_traits::AsPrimitive;
 pub use tract_data::prelude::round_ties_to_even;
 
+mod cumsum;
+pub use self::cumsum::CumSum;
+
 bin_to_super_type!(add, Add,
     declutter_unary: declutter_unary_add,
     flip:commute,
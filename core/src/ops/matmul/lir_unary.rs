@@ -207,7 +207,7 @@ fn eval(
 ) -> TractResult<TVec<Arc<Tensor>>> {
     unsafe {
         let a_dt = op.micro_ops.iter().next().unwrap().0.datum_type();
-        let mut c = Tensor::uninitialized_dt(op.c_fact.datum_type, &c_shape)?;
+        let mut c = Tensor::uninitialized_output_dt(0, op.c_fact.datum_type, c_shape)?;
         let c_storage = op.mmm.c_view_with_axis(c_m_axis, c_n_axis);
         if op
             .c_fact
@@ -150,6 +150,19 @@ mod test {
         c.close_enough(&c_found, true).unwrap();
     }
 
+    #[test]
+    fn bin_f16() {
+        let a =
+            tensor2(&[[0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]).cast_to::<f16>().unwrap().into_owned();
+        let b = tensor2(&[[0f32], [1.0], [2.0]]).cast_to::<f16>().unwrap().into_owned();
+        let c = tensor2(&[[5f32], [14.0]]).cast_to::<f16>().unwrap().into_owned();
+        let op = MatMul::default();
+        let c_found =
+            op.eval(tvec!(a.into_arc_tensor(), b.into_arc_tensor())).unwrap().pop().unwrap();
+        assert_eq!(c_found.datum_type(), f16::datum_type());
+        c.close_enough(&c_found, true).unwrap();
+    }
+
     #[test]
     fn bin_transpose() {
         let a = rctensor2(&[[0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]);
@@ -188,4 +201,29 @@ mod test {
         model.declutter()?.optimize()?.into_runnable()?.run(tvec!(input))?;
         Ok(())
     }
+
+    #[test]
+    fn models_share_weights() -> TractResult<()> {
+        fn model(share_tensors: bool) -> TractResult<TypedModel> {
+            let mut model = TypedModel { share_tensors, ..TypedModel::default() };
+            let s = model.add_source("s", TypedFact::dt_shape(f32::datum_type(), &[3, 2]))?;
+            let a = model.add_const("a", tensor2(&[[1f32, 2.0, 3.0], [4.0, 5.0, 6.0]]))?;
+            let c = model.wire_node("m", MatMul::default(), &[a, s])?;
+            model.set_output_outlets(&c)?;
+            model.into_optimized()
+        }
+        fn packed_a(model: &TypedModel) -> Arc<Tensor> {
+            let lir = model
+                .nodes()
+                .iter()
+                .find_map(|n| n.op_as::<super::super::lir_unary::LirMatMulUnary>())
+                .unwrap();
+            lir.micro_ops.iter().next().unwrap().0.clone()
+        }
+        let (m1, m2) = (model(true)?, model(true)?);
+        assert!(Arc::ptr_eq(&packed_a(&m1), &packed_a(&m2)));
+        let (m1, m2) = (model(false)?, model(false)?);
+        assert!(!Arc::ptr_eq(&packed_a(&m1), &packed_a(&m2)));
+        Ok(())
+    }
 }
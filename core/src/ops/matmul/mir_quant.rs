@@ -213,6 +213,22 @@ impl TypedOp for QMatMul {
             anyhow::ensure!(inputs[2].shape.iter().product::<TDim>() == 1.to_dim());
         };
 
+        let rank = inputs[0].rank();
+        check_axis_qparams(
+            inputs[0],
+            rank - 2 + self.a_trans as usize,
+            &self.params.a0,
+            &self.params.a_scale,
+            inputs,
+        )?;
+        check_axis_qparams(
+            inputs[1],
+            rank - 1 - self.b_trans as usize,
+            &self.params.b0,
+            &self.params.b_scale,
+            inputs,
+        )?;
+
         Ok(tvec!(TypedFact::dt_shape(self.output_type, c_shape)))
     }
 
@@ -376,6 +392,21 @@ pub(crate) fn wire_matmul_quant(
     let m_axis = rank - 2 + c_trans as usize;
     let n_axis = rank - 1 - c_trans as usize;
 
+    let mut params = params.to_vec();
+    for (ix, (param_name, axis)) in
+        [("a0", m_axis), ("a_scale", m_axis), ("b0", n_axis), ("b_scale", n_axis)]
+            .iter()
+            .enumerate()
+    {
+        params[ix] = wire_per_axis_param(
+            model,
+            &format!("{}.{}", name, param_name),
+            params[ix],
+            *axis,
+            rank,
+        )?;
+    }
+
     if let Some(bias) = bias {
         result = wire_with_rank_broadcast(
             &format!("{}.add_bias", &name),
@@ -415,6 +446,62 @@ pub(crate) fn wire_matmul_quant(
     requant(model, name, result, output_type, abc_scale, params[4])
 }
 
+/// Per-axis quantization parameters are rank-1 tensors running along the m axis of the product
+/// for a0 and a_scale, and along n for b0 and b_scale. Reshape them so that they broadcast
+/// against the rank `rank` product.
+pub(crate) fn wire_per_axis_param(
+    model: &mut TypedModel,
+    name: &str,
+    param: OutletId,
+    axis: usize,
+    rank: usize,
+) -> TractResult<OutletId> {
+    let fact = model.outlet_fact(param)?;
+    if fact.rank() != 1 || fact.shape[0] == 1.to_dim() || axis + 1 == rank {
+        return Ok(param);
+    }
+    let mut wire = param;
+    for ix in 0..rank - 1 - axis {
+        wire =
+            model.wire_node(format!("{}.per_axis.{}", name, ix), AxisOp::Add(1 + ix), &[wire])?[0];
+    }
+    Ok(wire)
+}
+
+/// The quantized matmul ops only read their params: per-axis quantization parameters found on
+/// an operand fact must be the ones passed as its zero point and scale, along `axis`.
+pub(crate) fn check_axis_qparams(
+    fact: &TypedFact,
+    axis: usize,
+    zero_point: &AttrOrInput,
+    scale: &AttrOrInput,
+    inputs: &[&TypedFact],
+) -> TractResult<()> {
+    if let Some(qp) = &fact.axis_qparams {
+        let same = |param: &AttrOrInput, expected: Tensor| -> TractResult<bool> {
+            let value = match param {
+                AttrOrInput::Attr(t) => Some(t.clone()),
+                AttrOrInput::Input(i) => inputs[*i].konst.clone(),
+            };
+            Ok(match value {
+                Some(t) => *t.cast_to_dt(expected.datum_type())? == expected,
+                None => false,
+            })
+        };
+        if qp.axis != axis
+            || !same(zero_point, tensor1(&qp.zero_points))?
+            || !same(scale, tensor1(&qp.scales))?
+        {
+            bail!(
+                "Per-axis quantization {:?} must be passed as the operand zero point and scale, along axis {}",
+                qp,
+                axis
+            );
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn combine_scales(
     model: &mut TypedModel,
     name: &str,
@@ -734,6 +821,90 @@ mod test {
         .check()
     }
 
+    #[test]
+    fn per_axis() -> TractResult<()> {
+        let a = arr2(&[[1i8, -2, 3], [4, 5, -6]]);
+        let b = arr2(&[[1i8, 2, -3, 4], [3, -4, 5, 6], [5, 6, -7, 8]]);
+        let (a0, a_scale) = ([1i32, -2], [0.5f32, 0.25]);
+        let (b0, b_scale) = ([0i32, 1, -1, 2], [1f32, 0.5, 2.0, 0.25]);
+        let a_f32 =
+            Array2::from_shape_fn(a.dim(), |(m, k)| (a[(m, k)] as i32 - a0[m]) as f32 * a_scale[m]);
+        let b_f32 =
+            Array2::from_shape_fn(b.dim(), |(k, n)| (b[(k, n)] as i32 - b0[n]) as f32 * b_scale[n]);
+        let reference = a_f32.dot(&b_f32).map(|&x| round_ties_to_right(x));
+        for &(a_const, c_trans, optimize) in &[
+            (true, false, false),
+            (true, true, false),
+            (true, false, true),
+            (true, true, true),
+            (false, false, true),
+            (false, true, true),
+        ] {
+            let mut model = TypedModel::default();
+            let (a_wire, b_wire, input) = if a_const {
+                let b_wire =
+                    model.add_source("b", TypedFact::dt_shape(i8::datum_type(), b.shape()))?;
+                (model.add_const("a", a.clone())?, b_wire, b.clone().into_tensor())
+            } else {
+                let a_wire =
+                    model.add_source("a", TypedFact::dt_shape(i8::datum_type(), a.shape()))?;
+                (a_wire, model.add_const("b", b.clone())?, a.clone().into_tensor())
+            };
+            let bias = model.add_const("bias", rctensor0(0i32))?;
+            let params = MatMulQParams {
+                a0: rctensor1(&a0).into(),
+                a_scale: rctensor1(&a_scale).into(),
+                b0: rctensor1(&b0).into(),
+                b_scale: rctensor1(&b_scale).into(),
+                c0: rctensor0(0i32).into(),
+                c_scale: rctensor0(1f32).into(),
+            };
+            let op = QMatMul::new(false, false, c_trans, i32::datum_type(), params);
+            let wire = model.wire_node("qmm", op, &[a_wire, b_wire, bias])?;
+            model.set_output_outlets(&wire)?;
+            let model = if optimize { model.into_optimized()? } else { model };
+            let found = model.into_runnable()?.run(tvec!(input))?.remove(0);
+            let found = found.to_array_view::<i32>()?.into_dimensionality::<Ix2>()?.to_owned();
+            let found = if c_trans { found.reversed_axes() } else { found };
+            if reference.iter().zip(found.iter()).any(|(r, f)| (r - f).abs() > 1) {
+                bail!(
+                    "a_const:{} c_trans:{} optimize:{} expected {:?} got {:?}",
+                    a_const,
+                    c_trans,
+                    optimize,
+                    reference,
+                    found
+                )
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn per_axis_fact_needs_matching_params() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let qp = AxisQParams { axis: 0, zero_points: vec![1, -2], scales: vec![0.5, 0.25] };
+        let a_fact = TypedFact::dt_shape(i8::datum_type(), [2, 3]).with_axis_qparams(qp);
+        let a = model.add_source("a", a_fact)?;
+        let b = model.add_source("b", TypedFact::dt_shape(i8::datum_type(), [3, 4]))?;
+        let bias = model.add_const("bias", rctensor0(0i32))?;
+        let op = QMatMul::new(
+            false,
+            false,
+            false,
+            i32::datum_type(),
+            MatMulQParams::noop_static(i8::datum_type()),
+        );
+        assert!(model.wire_node("qmm", op.clone(), &[a, b, bias]).is_err());
+        let params = MatMulQParams {
+            a0: rctensor1(&[1i32, -2]).into(),
+            a_scale: rctensor1(&[0.5f32, 0.25]).into(),
+            ..op.params.clone()
+        };
+        model.wire_node("qmm", QMatMul { params, ..op }, &[a, b, bias])?;
+        Ok(())
+    }
+
     #[derive(Debug)]
     struct QMatMulProblem {
         a: Array2<i8>,
@@ -2,8 +2,10 @@ use ops::matmul::mir_quant::wire_matmul_quant;
 
 use crate::internal::*;
 use crate::ops;
+use crate::ops::matmul::mir_quant::check_axis_qparams;
 use crate::ops::matmul::mir_quant::combine_scales;
 use crate::ops::matmul::mir_quant::requant;
+use crate::ops::matmul::mir_quant::wire_per_axis_param;
 use crate::ops::matmul::*;
 use mir_quant::MatMulQParams;
 
@@ -127,6 +129,13 @@ impl TypedOp for QMatMulUnary {
                 anyhow::ensure!(bias.len() == 1);
             };
         }
+        check_axis_qparams(
+            inputs[0],
+            inputs[0].rank() - 1 - self.b_trans as usize,
+            &self.params.b0,
+            &self.params.b_scale,
+            inputs,
+        )?;
 
         Ok(tvec!(TypedFact::dt_shape(self.output_type, c_shape)))
     }
@@ -215,11 +224,23 @@ impl TypedOp for QMatMulUnary {
             } else {
                 self.bias.clone()
             };
+            let mut params = self.params.clone();
+            for p in &mut [&mut params.a0, &mut params.a_scale] {
+                match p {
+                    AttrOrInput::Attr(t) if t.rank() == 1 && t.len() > 1 => {
+                        **p = AttrOrInput::Attr(t.slice(0, start, end)?.into_arc_tensor())
+                    }
+                    AttrOrInput::Input(i) if model.outlet_fact(node.inputs[*i])?.rank() == 1 => {
+                        return Ok(None)
+                    }
+                    _ => (),
+                }
+            }
             let wire = patch.tap_model(model, node.inputs[0])?;
             return Ok(Some(
                 patch.wire_node(
                     format!("{}.sliced-m-{}-{}", node.name, start, end),
-                    Self { a, bias, ..self.clone() },
+                    Self { a, bias, params, ..self.clone() },
                     &[wire],
                 )?[0],
             ));
@@ -268,13 +289,25 @@ impl TypedOp for QMatMulUnary {
                     };
                     params_outlets.push(outlet);
                 }
-                let scale = combine_scales(
+                let rank = input_fact.rank();
+                let m_axis = rank - 2 + self.c_trans as usize;
+                let n_axis = rank - 1 - self.c_trans as usize;
+                let a_scale = wire_per_axis_param(
                     &mut patch,
-                    &node.name,
+                    &format!("{}.a_scale", node.name),
                     params_outlets[0],
+                    m_axis,
+                    rank,
+                )?;
+                let b_scale = wire_per_axis_param(
+                    &mut patch,
+                    &format!("{}.b_scale", node.name),
                     params_outlets[1],
-                    params_outlets[2],
+                    n_axis,
+                    rank,
                 )?;
+                let scale =
+                    combine_scales(&mut patch, &node.name, a_scale, b_scale, params_outlets[2])?;
                 let c0 = params_outlets[3];
 
                 for (ix, slice) in concat.slices.iter().enumerate() {
@@ -194,13 +194,15 @@ impl MatMulUnary {
                     mmm.a_pack(k).alignment(),
                 )
                 .unwrap();
+                // padding is hashed when sharing the panels
+                pa.as_bytes_mut().fill(0);
                 mmm.a_pack(k).pack(
                     &mut pa.view_mut(),
                     &self.a.view_at_prefix(a_prefix.slice()).unwrap(),
                     !self.a_trans as usize,
                     self.a_trans as usize,
                 );
-                (pa.into_arc_tensor(), vec![])
+                (model.share_tensor(pa.into_arc_tensor()), vec![])
             });
         unsafe {
             let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
@@ -6,9 +6,9 @@ use tract_linalg::frame::Packer;
 #[derive(Debug, Clone, PartialEq, Educe)]
 #[educe(Hash)]
 pub struct MatMatMulPack {
-    pub(crate) packer: Packer,
-    pub(crate) trans: bool,
-    pub(crate) output_shape: TVec<usize>,
+    pub packer: Packer,
+    pub trans: bool,
+    pub output_shape: TVec<usize>,
 }
 
 impl DynHash for MatMatMulPack {
@@ -18,8 +18,11 @@ pub mod array;
 pub mod cast;
 pub mod change_axes;
 pub mod cnn;
+pub mod control_flow;
 pub mod downsample;
 pub mod dummy;
+pub mod einsum;
+pub mod fft;
 pub mod identity;
 pub mod konst;
 pub mod logic;
@@ -27,6 +30,7 @@ pub mod math;
 pub mod matmul;
 pub mod nn;
 pub mod quant;
+pub mod random;
 pub mod scan;
 pub mod source;
 pub mod unimpl;
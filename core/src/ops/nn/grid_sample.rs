@@ -0,0 +1,211 @@
+use crate::internal::*;
+use crate::ops::math::round_ties_to_even;
+use tract_ndarray::{ArrayView2, Ix4};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    Bilinear,
+    Nearest,
+}
+
+/// How samples outside of the input image are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridPadding {
+    /// out of bound pixels are zeros
+    Zeros,
+    /// out of bound coordinates are clamped to the image border
+    Border,
+    /// out of bound coordinates are reflected by the image border
+    Reflection,
+}
+
+/// Samples a `[N, C, H, W]` input at the locations given by a `[N, H_out, W_out, 2]` grid of
+/// `(x, y)` coordinates normalized to `[-1, 1]`, producing a `[N, C, H_out, W_out]` output.
+///
+/// With `align_corners`, -1 and 1 are the centers of the corner pixels, otherwise they are the
+/// outer edges of the corner pixels.
+#[derive(Debug, Clone, Hash)]
+pub struct GridSample {
+    pub interpolation: Interpolation,
+    pub padding: GridPadding,
+    pub align_corners: bool,
+}
+
+impl_dyn_hash!(GridSample);
+
+impl GridSample {
+    fn denormalize(&self, x: f32, len: usize) -> f32 {
+        if self.align_corners {
+            (x + 1.0) / 2.0 * (len as f32 - 1.0)
+        } else {
+            ((x + 1.0) * len as f32 - 1.0) / 2.0
+        }
+    }
+
+    fn reflect(x: f32, min: f32, max: f32) -> f32 {
+        let range = max - min;
+        if range <= 0.0 {
+            return min;
+        }
+        let (dx, from_min) = if x < min {
+            (min - x, true)
+        } else if x > max {
+            (x - max, false)
+        } else {
+            return x;
+        };
+        let n = (dx / range).floor();
+        let r = dx - n * range;
+        if (n as usize % 2 == 0) == from_min {
+            min + r
+        } else {
+            max - r
+        }
+    }
+
+    /// Maps a normalized coordinate to a pixel coordinate, according to the padding mode.
+    fn coordinate(&self, x: f32, len: usize) -> f32 {
+        let x = self.denormalize(x, len);
+        match self.padding {
+            GridPadding::Zeros => x,
+            GridPadding::Border => x.max(0.0).min(len as f32 - 1.0),
+            GridPadding::Reflection if self.align_corners => {
+                Self::reflect(x, 0.0, len as f32 - 1.0)
+            }
+            GridPadding::Reflection => Self::reflect(x, -0.5, len as f32 - 0.5),
+        }
+    }
+
+    fn pixel(&self, image: &ArrayView2<f32>, y: isize, x: isize) -> f32 {
+        let (h, w) = image.dim();
+        let outside = y < 0 || x < 0 || y >= h as isize || x >= w as isize;
+        if outside && self.padding == GridPadding::Zeros {
+            return 0.0;
+        }
+        image[(y.max(0).min(h as isize - 1) as usize, x.max(0).min(w as isize - 1) as usize)]
+    }
+
+    fn sample(&self, image: &ArrayView2<f32>, y: f32, x: f32) -> f32 {
+        match self.interpolation {
+            Interpolation::Nearest => {
+                self.pixel(image, round_ties_to_even(y) as isize, round_ties_to_even(x) as isize)
+            }
+            Interpolation::Bilinear => {
+                let (y0, x0) = (y.floor(), x.floor());
+                let (dy, dx) = (y - y0, x - x0);
+                let (y0, x0) = (y0 as isize, x0 as isize);
+                self.pixel(image, y0, x0) * (1.0 - dy) * (1.0 - dx)
+                    + self.pixel(image, y0, x0 + 1) * (1.0 - dy) * dx
+                    + self.pixel(image, y0 + 1, x0) * dy * (1.0 - dx)
+                    + self.pixel(image, y0 + 1, x0 + 1) * dy * dx
+            }
+        }
+    }
+}
+
+impl Op for GridSample {
+    fn name(&self) -> Cow<str> {
+        "GridSample".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "interpolation: {:?} padding: {:?} align_corners: {}",
+            self.interpolation, self.padding, self.align_corners
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for GridSample {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, grid) = args_2!(inputs);
+        let dt = input.datum_type();
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix4>()?;
+        let grid = grid.cast_to::<f32>()?;
+        let grid = grid.to_array_view::<f32>()?.into_dimensionality::<Ix4>()?;
+        let (n, c, h, w) = input.dim();
+        let (_, out_h, out_w, _) = grid.dim();
+        let mut output = tract_ndarray::Array4::<f32>::zeros((n, c, out_h, out_w));
+        for b in 0..n {
+            for oy in 0..out_h {
+                for ox in 0..out_w {
+                    let x = self.coordinate(grid[(b, oy, ox, 0)], w);
+                    let y = self.coordinate(grid[(b, oy, ox, 1)], h);
+                    for ci in 0..c {
+                        let image = input.slice(s![b, ci, .., ..]);
+                        output[(b, ci, oy, ox)] = self.sample(&image, y, x);
+                    }
+                }
+            }
+        }
+        Ok(tvec!(output.into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for GridSample {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let (input, grid) = (inputs[0], inputs[1]);
+        if input.rank() != 4 || grid.rank() != 4 || grid.shape[3] != 2.to_dim() {
+            bail!(
+                "GridSample expects a [N, C, H, W] input and a [N, H, W, 2] grid, got {:?}",
+                inputs
+            )
+        }
+        let shape = [
+            input.shape[0].clone(),
+            input.shape[1].clone(),
+            grid.shape[1].clone(),
+            grid.shape[2].clone(),
+        ];
+        Ok(tvec!(TypedFact::dt_shape(input.datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input() -> Arc<Tensor> {
+        tensor4(&[[[[0f32, 1.], [2., 3.]]]]).into_arc_tensor()
+    }
+
+    #[test]
+    fn bilinear_align_corners() -> TractResult<()> {
+        let op = GridSample {
+            interpolation: Interpolation::Bilinear,
+            padding: GridPadding::Zeros,
+            align_corners: true,
+        };
+        let grid = tensor4(&[[[[-1f32, -1.], [0., 0.], [1., -1.]]]]).into_arc_tensor();
+        let output = op.eval(tvec!(input(), grid))?;
+        assert_eq!(*output[0], tensor4(&[[[[0f32, 1.5, 1.]]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn padding_modes() -> TractResult<()> {
+        // x = 2 maps to 1.5, half a pixel beyond the last pixel center
+        let grid = tensor4(&[[[[2f32, -1.]]]]).into_arc_tensor();
+        let mut op = GridSample {
+            interpolation: Interpolation::Nearest,
+            padding: GridPadding::Zeros,
+            align_corners: true,
+        };
+        assert_eq!(*op.eval(tvec!(input(), grid.clone()))?[0], tensor4(&[[[[0f32]]]]));
+        op.padding = GridPadding::Border;
+        assert_eq!(*op.eval(tvec!(input(), grid.clone()))?[0], tensor4(&[[[[1f32]]]]));
+        op.padding = GridPadding::Reflection;
+        assert_eq!(*op.eval(tvec!(input(), grid))?[0], tensor4(&[[[[0f32]]]]));
+        Ok(())
+    }
+}
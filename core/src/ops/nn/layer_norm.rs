@@ -0,0 +1,149 @@
+use crate::internal::*;
+use tract_ndarray::ArrayD;
+
+/// Normalizes the input to zero mean and unit variance over `axes`:
+/// `(x - mean) / sqrt(var + epsilon)`.
+///
+/// Scaling and bias, if any, are left to separate operators.
+#[derive(Debug, Clone, new, Educe)]
+#[educe(Hash)]
+pub struct LayerNorm {
+    pub axes: TVec<usize>,
+    #[educe(Hash(method = "hash_f32"))]
+    pub epsilon: f32,
+}
+
+impl_dyn_hash!(LayerNorm);
+
+impl LayerNorm {
+    fn eval_f32(&self, input: &Tensor) -> TractResult<Tensor> {
+        let rank = input.rank();
+        let mut axes = self.axes.clone();
+        axes.sort();
+        let mut perm: TVec<usize> = (0..rank).filter(|ax| !axes.contains(ax)).collect();
+        perm.extend(axes.iter().cloned());
+        let inner: usize = self.axes.iter().map(|&ax| input.shape()[ax]).product();
+        let norm = (tract_linalg::ops().layer_norm_f32)();
+        let view = input.to_array_view::<f32>()?.permuted_axes(&*perm);
+        let mut data: ArrayD<f32> = view.as_standard_layout().into_owned();
+        if inner > 0 {
+            for chunk in data.as_slice_mut().unwrap().chunks_exact_mut(inner) {
+                norm.run(chunk, self.epsilon)?;
+            }
+        }
+        let mut inverse = tvec!(0; rank);
+        for (ix, &axis) in perm.iter().enumerate() {
+            inverse[axis] = ix;
+        }
+        let data = data.permuted_axes(&*inverse);
+        Ok(data.as_standard_layout().into_owned().into_tensor())
+    }
+}
+
+impl Op for LayerNorm {
+    fn name(&self) -> Cow<str> {
+        "LayerNorm".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axes: {:?} epsilon: {}", self.axes, self.epsilon)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LayerNorm {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = &inputs[0];
+        let output = if input.datum_type() == f32::datum_type() {
+            self.eval_f32(input)?
+        } else {
+            self.eval_f32(&*input.cast_to::<f32>()?)?.cast_to_dt(input.datum_type())?.into_owned()
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LayerNorm {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if !inputs[0].datum_type.is_float() {
+            bail!("LayerNorm expects a float input, got {:?}", inputs[0])
+        }
+        if self.axes.iter().any(|&ax| ax >= inputs[0].rank()) {
+            bail!("Invalid axes {:?} for LayerNorm on {:?}", self.axes, inputs[0])
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, inputs[0].shape.clone())))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let axes = (0..inputs[0].rank())
+            .filter(|axis| !self.axes.contains(axis))
+            .map(AxisInfo::simple)
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        let mut axes = tvec!();
+        for normalized in &self.axes {
+            if let Some(axis) = change.transform_axis(*normalized) {
+                axes.push(axis);
+            } else {
+                return Ok(None);
+            }
+        }
+        let op = Some(Box::new(LayerNorm { axes, ..self.clone() }) as _);
+        Ok(Some(AxisChangeConsequence::new(model, node, op, change)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_ndarray::Axis;
+
+    fn naive(input: &ArrayD<f32>, axis: usize, epsilon: f32) -> ArrayD<f32> {
+        let mean = input.mean_axis(Axis(axis)).unwrap().insert_axis(Axis(axis));
+        let centered = input - &mean;
+        let var = centered.mapv(|x| x * x).mean_axis(Axis(axis)).unwrap().insert_axis(Axis(axis));
+        centered / var.mapv(|v| (v + epsilon).sqrt())
+    }
+
+    #[test]
+    fn last_axis() -> TractResult<()> {
+        let input = tensor2(&[[1f32, 2., 3., 4.], [10., 10., 10., 10.]]);
+        let op = LayerNorm::new(tvec!(1), 1e-5);
+        let found = op.eval(tvec!(input.clone().into_arc_tensor()))?.remove(0);
+        let expected = naive(&input.into_array::<f32>()?, 1, 1e-5).into_tensor();
+        found.close_enough(&expected, true)
+    }
+
+    #[test]
+    fn inner_axis() -> TractResult<()> {
+        let input = tract_ndarray::Array1::range(0f32, 24., 1.)
+            .mapv(|x| (x * 7.) % 5.)
+            .into_shape((2, 3, 4))?
+            .into_dyn();
+        let op = LayerNorm::new(tvec!(1), 1e-5);
+        let found = op.eval(tvec!(input.clone().into_arc_tensor()))?.remove(0);
+        let expected = naive(&input, 1, 1e-5).into_tensor();
+        found.close_enough(&expected, true)
+    }
+}
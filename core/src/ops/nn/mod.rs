@@ -1,8 +1,16 @@
 mod data_formats;
+mod grid_sample;
+mod layer_norm;
+mod non_max_suppression;
 mod reduce;
+mod roi_align;
 
 pub use self::data_formats::{BaseDataShape, DataFormat, DataShape, SymDataShape};
+pub use self::grid_sample::{GridPadding, GridSample, Interpolation};
+pub use self::layer_norm::LayerNorm;
+pub use self::non_max_suppression::{BoxRepr, NonMaxSuppression};
 pub use self::reduce::{Reduce, Reducer};
+pub use self::roi_align::{RoiAlign, RoiPooling};
 
 pub use crate::internal::*;
 
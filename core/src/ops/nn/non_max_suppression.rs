@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+/// Coordinates layout of the last axis of the boxes tensor.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum BoxRepr {
+    /// `[y1, x1, y2, x2]`, any pair of diagonal corners
+    TwoPoints,
+    /// `[x_center, y_center, width, height]`
+    CenterWidthHeight,
+}
+
+impl BoxRepr {
+    /// Returns `(y_min, x_min, y_max, x_max)`.
+    fn corners(&self, b: ArrayView1<f32>) -> (f32, f32, f32, f32) {
+        match self {
+            BoxRepr::TwoPoints => (b[0].min(b[2]), b[1].min(b[3]), b[0].max(b[2]), b[1].max(b[3])),
+            BoxRepr::CenterWidthHeight => {
+                (b[1] - b[3] / 2.0, b[0] - b[2] / 2.0, b[1] + b[3] / 2.0, b[0] + b[2] / 2.0)
+            }
+        }
+    }
+
+    fn iou(&self, a: ArrayView1<f32>, b: ArrayView1<f32>) -> f32 {
+        let (ay1, ax1, ay2, ax2) = self.corners(a);
+        let (by1, bx1, by2, bx2) = self.corners(b);
+        let area_a = (ay2 - ay1) * (ax2 - ax1);
+        let area_b = (by2 - by1) * (bx2 - bx1);
+        if area_a <= 0.0 || area_b <= 0.0 {
+            return 0.0;
+        }
+        let inter_h = (ay2.min(by2) - ay1.max(by1)).max(0.0);
+        let inter_w = (ax2.min(bx2) - ax1.max(bx1)).max(0.0);
+        let inter = inter_h * inter_w;
+        inter / (area_a + area_b - inter)
+    }
+}
+
+/// Greedy non-maximum suppression, per batch and per class.
+///
+/// Inputs are boxes `[batch, boxes, 4]`, scores `[batch, classes, boxes]`, and three scalars:
+/// the maximum number of boxes to select per class, the IoU threshold above which a box is
+/// suppressed, and the score threshold a box must exceed to be considered. The output is the
+/// `[selected, 3]` tensor of `(batch, class, box)` indices.
+#[derive(Debug, Clone, Hash)]
+pub struct NonMaxSuppression {
+    pub box_repr: BoxRepr,
+    pub selected: TDim,
+}
+
+impl_dyn_hash!(NonMaxSuppression);
+
+impl NonMaxSuppression {
+    pub fn new(box_repr: BoxRepr) -> NonMaxSuppression {
+        NonMaxSuppression { box_repr, selected: Symbol::new('n').into() }
+    }
+}
+
+impl Op for NonMaxSuppression {
+    fn name(&self) -> Cow<str> {
+        "NonMaxSuppression".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{:?}", self.box_repr)])
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for NonMaxSuppression {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let boxes = inputs[0].cast_to::<f32>()?;
+        let boxes = boxes.to_array_view::<f32>()?.into_dimensionality::<Ix3>()?;
+        let scores = inputs[1].cast_to::<f32>()?;
+        let scores = scores.to_array_view::<f32>()?.into_dimensionality::<Ix3>()?;
+        let max_per_class = inputs[2].cast_to::<i64>()?.as_slice::<i64>()?[0].max(0) as usize;
+        let iou_threshold = inputs[3].cast_to::<f32>()?.as_slice::<f32>()?[0];
+        let score_threshold = inputs[4].cast_to::<f32>()?.as_slice::<f32>()?[0];
+        let mut selected: Vec<i64> = vec![];
+        for batch in 0..scores.shape()[0] {
+            let boxes = boxes.index_axis(Axis(0), batch);
+            for class in 0..scores.shape()[1] {
+                let scores = scores.slice(s![batch, class, ..]);
+                let mut candidates: Vec<usize> =
+                    (0..scores.len()).filter(|&ix| scores[ix] > score_threshold).collect();
+                candidates
+                    .sort_by(|&a, &b| scores[b].partial_cmp(&scores[a]).unwrap_or(Ordering::Equal));
+                let mut kept: Vec<usize> = vec![];
+                for candidate in candidates {
+                    if kept.len() >= max_per_class {
+                        break;
+                    }
+                    if kept.iter().all(|&k| {
+                        self.box_repr.iou(boxes.row(k), boxes.row(candidate)) <= iou_threshold
+                    }) {
+                        kept.push(candidate);
+                    }
+                }
+                for k in kept {
+                    selected.extend(&[batch as i64, class as i64, k as i64]);
+                }
+            }
+        }
+        let selected = Array2::from_shape_vec((selected.len() / 3, 3), selected)?;
+        Ok(tvec!(selected.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for NonMaxSuppression {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 3 || inputs[0].shape[2] != 4.to_dim() {
+            bail!("NonMaxSuppression expects boxes as [batch, boxes, 4], got {:?}", inputs[0])
+        }
+        if inputs[1].rank() != 3 {
+            bail!(
+                "NonMaxSuppression expects scores as [batch, classes, boxes], got {:?}",
+                inputs[1]
+            )
+        }
+        Ok(tvec!(TypedFact::dt_shape(i64::datum_type(), [self.selected.clone(), 3.to_dim()])))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // from https://github.com/onnx/onnx/blob/master/docs/Operators.md#NonMaxSuppression
+    fn boxes() -> Tensor {
+        tensor3(&[[
+            [0.5f32, 0.5, 1.0, 1.0],
+            [0.5, 0.6, 1.0, 1.0],
+            [0.5, 0.4, 1.0, 1.0],
+            [0.5, 10.5, 1.0, 1.0],
+            [0.5, 10.6, 1.0, 1.0],
+            [0.5, 100.5, 1.0, 1.0],
+        ]])
+    }
+
+    fn run(
+        op: NonMaxSuppression,
+        boxes: Tensor,
+        max: i64,
+        iou: f32,
+        score: f32,
+    ) -> TractResult<Tensor> {
+        let scores = tensor3(&[[[0.9f32, 0.75, 0.6, 0.95, 0.5, 0.3]]]);
+        let inputs = tvec!(
+            boxes.into_arc_tensor(),
+            scores.into_arc_tensor(),
+            rctensor0(max),
+            rctensor0(iou),
+            rctensor0(score),
+        );
+        Ok(op.eval(inputs)?.remove(0).into_tensor())
+    }
+
+    #[test]
+    fn center_point_box() -> TractResult<()> {
+        let op = NonMaxSuppression::new(BoxRepr::CenterWidthHeight);
+        let selected = run(op, boxes(), 3, 0.5, 0.0)?;
+        assert_eq!(selected, tensor2(&[[0i64, 0, 3], [0, 0, 0], [0, 0, 5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn two_points_with_score_threshold() -> TractResult<()> {
+        let boxes = tensor3(&[[
+            [0.0f32, 0.0, 1.0, 1.0],
+            [0.0, 0.1, 1.0, 1.1],
+            [0.0, -0.1, 1.0, 0.9],
+            [0.0, 10.0, 1.0, 11.0],
+            [0.0, 10.1, 1.0, 11.1],
+            [0.0, 100.0, 1.0, 101.0],
+        ]]);
+        let op = NonMaxSuppression::new(BoxRepr::TwoPoints);
+        let selected = run(op, boxes, 3, 0.5, 0.4)?;
+        assert_eq!(selected, tensor2(&[[0i64, 0, 3], [0, 0, 0]]));
+        Ok(())
+    }
+}
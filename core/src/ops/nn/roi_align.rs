@@ -0,0 +1,241 @@
+use crate::internal::*;
+use tract_ndarray::{ArrayView2, Ix2, Ix4};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoiPooling {
+    Avg,
+    Max,
+}
+
+/// Pools each region of interest of a `[N, C, H, W]` input to a fixed size
+/// `[C, output_height, output_width]` grid of bilinearly interpolated samples.
+///
+/// Inputs are the image, the `[num_rois, 4]` regions as `(x1, y1, x2, y2)` in input
+/// coordinates scaled by `spatial_scale`, and the `[num_rois]` batch index of each region.
+/// Each output bin averages (or takes the max of) `sampling_ratio` × `sampling_ratio` samples,
+/// or an adaptive number of samples when `sampling_ratio` is zero. With `half_pixel`, region
+/// coordinates are shifted by half a pixel, otherwise regions are at least one pixel wide.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct RoiAlign {
+    pub pooling: RoiPooling,
+    pub output_height: usize,
+    pub output_width: usize,
+    pub sampling_ratio: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub spatial_scale: f32,
+    pub half_pixel: bool,
+}
+
+impl_dyn_hash!(RoiAlign);
+
+impl RoiAlign {
+    /// Returns the four `(weight, y, x)` bilinear interpolation terms of a sample, or none when
+    /// it is more than one pixel outside of the image.
+    fn bilinear(y: f32, x: f32, h: usize, w: usize) -> Option<[(f32, usize, usize); 4]> {
+        if y < -1.0 || y > h as f32 || x < -1.0 || x > w as f32 {
+            return None;
+        }
+        let axis = |v: f32, len: usize| {
+            let v = v.max(0.0);
+            let low = v as usize;
+            if low >= len - 1 {
+                (len - 1, len - 1, 0.0)
+            } else {
+                (low, low + 1, v - low as f32)
+            }
+        };
+        let (y0, y1, ly) = axis(y, h);
+        let (x0, x1, lx) = axis(x, w);
+        let (hy, hx) = (1.0 - ly, 1.0 - lx);
+        Some([(hy * hx, y0, x0), (hy * lx, y0, x1), (ly * hx, y1, x0), (ly * lx, y1, x1)])
+    }
+
+    fn pool(&self, image: &ArrayView2<f32>, roi: &[f32], output: &mut [f32]) {
+        let (h, w) = image.dim();
+        let offset = if self.half_pixel { 0.5 } else { 0.0 };
+        let y_start = roi[1] * self.spatial_scale - offset;
+        let x_start = roi[0] * self.spatial_scale - offset;
+        let mut roi_h = roi[3] * self.spatial_scale - offset - y_start;
+        let mut roi_w = roi[2] * self.spatial_scale - offset - x_start;
+        if !self.half_pixel {
+            roi_h = roi_h.max(1.0);
+            roi_w = roi_w.max(1.0);
+        }
+        let bin_h = roi_h / self.output_height as f32;
+        let bin_w = roi_w / self.output_width as f32;
+        let (grid_h, grid_w) = if self.sampling_ratio > 0 {
+            (self.sampling_ratio, self.sampling_ratio)
+        } else {
+            (bin_h.ceil() as usize, bin_w.ceil() as usize)
+        };
+        let count = (grid_h * grid_w).max(1) as f32;
+        for ph in 0..self.output_height {
+            for pw in 0..self.output_width {
+                let mut acc = match self.pooling {
+                    RoiPooling::Avg => 0.0,
+                    RoiPooling::Max => f32::MIN,
+                };
+                let mut empty = true;
+                for iy in 0..grid_h {
+                    let y = y_start + ph as f32 * bin_h + (iy as f32 + 0.5) * bin_h / grid_h as f32;
+                    for ix in 0..grid_w {
+                        let x =
+                            x_start + pw as f32 * bin_w + (ix as f32 + 0.5) * bin_w / grid_w as f32;
+                        let terms = Self::bilinear(y, x, h, w).unwrap_or([(0.0, 0, 0); 4]);
+                        empty = false;
+                        match self.pooling {
+                            RoiPooling::Avg => {
+                                acc += terms.iter().map(|&(k, y, x)| k * image[(y, x)]).sum::<f32>()
+                            }
+                            // same as the reference implementation: the max of the weighted
+                            // interpolation terms
+                            RoiPooling::Max => {
+                                for &(k, y, x) in &terms {
+                                    acc = acc.max(k * image[(y, x)]);
+                                }
+                            }
+                        }
+                    }
+                }
+                output[ph * self.output_width + pw] = match self.pooling {
+                    RoiPooling::Avg => acc / count,
+                    RoiPooling::Max if empty => 0.0,
+                    RoiPooling::Max => acc,
+                };
+            }
+        }
+    }
+}
+
+impl Op for RoiAlign {
+    fn name(&self) -> Cow<str> {
+        "RoiAlign".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "pooling: {:?} output: {}x{} sampling_ratio: {} spatial_scale: {} half_pixel: {}",
+            self.pooling,
+            self.output_height,
+            self.output_width,
+            self.sampling_ratio,
+            self.spatial_scale,
+            self.half_pixel
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for RoiAlign {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, rois, batch_indices) = args_3!(inputs);
+        let dt = input.datum_type();
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix4>()?;
+        let rois = rois.cast_to::<f32>()?;
+        let rois = rois.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let batch_indices = batch_indices.cast_to::<i64>()?;
+        let batch_indices = batch_indices.as_slice::<i64>()?;
+        let (n, c, h, w) = input.dim();
+        let num_rois = rois.nrows();
+        let mut output = tract_ndarray::Array4::<f32>::zeros((
+            num_rois,
+            c,
+            self.output_height,
+            self.output_width,
+        ));
+        if h == 0 || w == 0 {
+            return Ok(tvec!(output.into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()));
+        }
+        for (ix, roi) in rois.outer_iter().enumerate() {
+            let batch = batch_indices[ix];
+            if batch < 0 || batch as usize >= n {
+                bail!("Invalid batch index {} for RoiAlign input with batch size {}", batch, n)
+            }
+            let roi = roi.iter().copied().collect::<Vec<f32>>();
+            for ci in 0..c {
+                let image = input.slice(s![batch as usize, ci, .., ..]);
+                let mut bins = output.slice_mut(s![ix, ci, .., ..]);
+                self.pool(&image, &roi, bins.as_slice_mut().unwrap());
+            }
+        }
+        Ok(tvec!(output.into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for RoiAlign {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let (input, rois, batch_indices) = (inputs[0], inputs[1], inputs[2]);
+        if input.rank() != 4
+            || rois.rank() != 2
+            || rois.shape[1] != 4.to_dim()
+            || batch_indices.rank() != 1
+        {
+            bail!(
+                "RoiAlign expects a [N, C, H, W] input, [R, 4] rois and [R] indices, got {:?}",
+                inputs
+            )
+        }
+        let shape = [
+            rois.shape[0].clone(),
+            input.shape[1].clone(),
+            self.output_height.to_dim(),
+            self.output_width.to_dim(),
+        ];
+        Ok(tvec!(TypedFact::dt_shape(input.datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn op(pooling: RoiPooling) -> RoiAlign {
+        RoiAlign {
+            pooling,
+            output_height: 1,
+            output_width: 2,
+            sampling_ratio: 1,
+            spatial_scale: 1.0,
+            half_pixel: true,
+        }
+    }
+
+    fn input() -> Arc<Tensor> {
+        tensor4(&[[[
+            [0f32, 1., 2., 3.],
+            [4., 5., 6., 7.],
+            [8., 9., 10., 11.],
+            [12., 13., 14., 15.],
+        ]]])
+        .into_arc_tensor()
+    }
+
+    #[test]
+    fn avg() -> TractResult<()> {
+        // the region covers pixels [0, 4) in both directions, bin samples are at the centers of
+        // its left and right halves: (y, x) = (1.5, 0.5) and (1.5, 2.5)
+        let rois = rctensor2(&[[0f32, 0., 4., 4.]]);
+        let output = op(RoiPooling::Avg).eval(tvec!(input(), rois, rctensor1(&[0i64])))?;
+        assert_eq!(*output[0], tensor4(&[[[[6.5f32, 8.5]]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn max_is_max_of_weighted_terms() -> TractResult<()> {
+        let rois = rctensor2(&[[0f32, 0., 4., 4.]]);
+        let output = op(RoiPooling::Max).eval(tvec!(input(), rois, rctensor1(&[0i64])))?;
+        // at (1.5, 0.5), terms are 0.25 * [4, 5, 8, 9]
+        assert_eq!(*output[0], tensor4(&[[[[2.25f32, 2.75]]]]));
+        Ok(())
+    }
+}
@@ -246,6 +246,59 @@ impl TypedOp for DequantizeLinearF32 {
     as_op!();
 }
 
+/// Quantize a f32 tensor to i8 using its own range, extended to include zero.
+///
+/// Outputs the quantized tensor, the scale (f32 scalar) and the zero point (i32 scalar). This is
+/// the signed counterpart of ONNX DynamicQuantizeLinear.
+#[derive(Clone, Debug, Default, Hash)]
+pub struct DynamicQuantizeLinearI8;
+
+impl Op for DynamicQuantizeLinearI8 {
+    fn name(&self) -> Cow<str> {
+        "DynamicQuantizeLinearI8".into()
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Accurate
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl_dyn_hash!(DynamicQuantizeLinearI8);
+
+impl EvalOp for DynamicQuantizeLinearI8 {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = inputs[0].as_slice::<f32>()?;
+        let (min, max) = input.iter().fold((0f32, 0f32), |(min, max), &x| (min.min(x), max.max(x)));
+        let scale = if max > min { (max - min) / 255.0 } else { 1.0 };
+        let zero_point = (-128.0 - min / scale).round().clamp(-128.0, 127.0) as i32;
+        let mut output = unsafe { Tensor::uninitialized::<i8>(inputs[0].shape())? };
+        // x / scale, not x * scale.recip(), to round like DynamicQuantizeLinear
+        input.iter().zip(output.as_slice_mut::<i8>()?.iter_mut()).for_each(|(x, y)| {
+            *y = ((x / scale).round() as i32 + zero_point).clamp(-128, 127) as i8
+        });
+        Ok(tvec!(output.into_arc_tensor(), rctensor0(scale), rctensor0(zero_point)))
+    }
+}
+
+impl TypedOp for DynamicQuantizeLinearI8 {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(
+            TypedFact::dt_shape(i8::datum_type(), inputs[0].shape.clone()),
+            TypedFact::dt_shape(f32::datum_type(), [0usize; 0]),
+            TypedFact::dt_shape(i32::datum_type(), [0usize; 0])
+        ))
+    }
+
+    as_op!();
+}
+
 element_wise_oop!(lookup_table,
  LookupTable {
      #[educe(Hash(method="hash_lookup_table"))]
@@ -0,0 +1,98 @@
+use crate::internal::*;
+
+use super::{Generator, Sampler};
+
+/// Draws 1 with the probability given by each input value, and 0 otherwise.
+#[derive(Debug, Clone, Hash)]
+pub struct Bernoulli {
+    pub datum_type: DatumType,
+    pub seed: Option<(u64, u64)>,
+    pub stream: u64,
+}
+
+impl_dyn_hash!(Bernoulli);
+
+impl Sampler for Bernoulli {
+    fn seed(&self) -> Option<(u64, u64)> {
+        self.seed
+    }
+
+    fn stream(&self) -> u64 {
+        self.stream
+    }
+
+    fn sample(
+        &self,
+        rng: &mut Generator,
+        _symbols: &SymbolValues,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let probs = input.cast_to::<f32>()?;
+        let mut output = probs.into_owned();
+        output
+            .as_slice_mut::<f32>()?
+            .iter_mut()
+            .for_each(|p| *p = (rng.uniform_f32() < *p) as usize as f32);
+        Ok(tvec!(output.cast_to_dt(self.datum_type)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl Op for Bernoulli {
+    fn name(&self) -> Cow<str> {
+        "Bernoulli".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("seed: {:?}", self.seed)])
+    }
+
+    fn validation(&self) -> Validation {
+        self.random_validation()
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Bernoulli {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.sample_once(inputs)
+    }
+
+    fn state(
+        &self,
+        session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        self.random_state(session)
+    }
+}
+
+impl TypedOp for Bernoulli {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if !inputs[0].datum_type.is_float() {
+            bail!("Bernoulli expects float probabilities, got {:?}", inputs[0])
+        }
+        Ok(tvec!(TypedFact::dt_shape(self.datum_type, inputs[0].shape.clone())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn certain_outcomes() -> TractResult<()> {
+        let op = Bernoulli { datum_type: bool::datum_type(), seed: Some((1, 0)), stream: 0 };
+        let output = op.eval(tvec!(rctensor1(&[0f32, 1., 0., 1.])))?;
+        assert_eq!(*output[0], tensor1(&[false, true, false, true]));
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::internal::*;
+
+use super::{Generator, Sampler};
+
+/// Parameters are scalar tensors.
+#[derive(Debug, Clone, Hash)]
+pub enum Distribution {
+    /// In `[low, high)`. Integer samples are `low + u32 % (high - low)`, as in TensorFlow.
+    Uniform {
+        low: Arc<Tensor>,
+        high: Arc<Tensor>,
+    },
+    Normal {
+        mean: Arc<Tensor>,
+        dev: Arc<Tensor>,
+    },
+}
+
+/// Generates a tensor of random values.
+///
+/// `shape` may be symbolic, it is then evaluated with the session symbols. With an input (as for
+/// the ONNX `*Like` operators), the output takes the shape of the input tensor instead.
+#[derive(Debug, Clone, Hash)]
+pub struct Random {
+    pub datum_type: DatumType,
+    pub shape: TVec<TDim>,
+    pub dist: Distribution,
+    pub seed: Option<(u64, u64)>,
+    pub stream: u64,
+}
+
+impl_dyn_hash!(Random);
+
+impl Random {
+    fn sample_floats(&self, rng: &mut Generator, len: usize) -> TractResult<Tensor> {
+        let values: Vec<f32> = match &self.dist {
+            Distribution::Uniform { low, high } => {
+                let low = low.cast_to_scalar::<f32>()?;
+                let high = high.cast_to_scalar::<f32>()?;
+                (0..len).map(|_| low + (high - low) * rng.uniform_f32()).collect()
+            }
+            Distribution::Normal { mean, dev } => {
+                let mean = mean.cast_to_scalar::<f32>()?;
+                let dev = dev.cast_to_scalar::<f32>()?;
+                (0..len).map(|_| mean + dev * rng.normal_f32()).collect()
+            }
+        };
+        Ok(tensor1(&values))
+    }
+
+    fn sample_ints(&self, rng: &mut Generator, len: usize) -> TractResult<Tensor> {
+        if let Distribution::Uniform { low, high } = &self.dist {
+            let low = low.cast_to_scalar::<i64>()?;
+            let high = high.cast_to_scalar::<i64>()?;
+            if high <= low {
+                bail!("Invalid uniform range [{}, {})", low, high)
+            }
+            let values: Vec<i64> =
+                (0..len).map(|_| low + (rng.next_u32() as i64) % (high - low)).collect();
+            Ok(tensor1(&values))
+        } else {
+            bail!("{:?} is only implemented for floats", self.dist)
+        }
+    }
+}
+
+impl Sampler for Random {
+    fn seed(&self) -> Option<(u64, u64)> {
+        self.seed
+    }
+
+    fn stream(&self) -> u64 {
+        self.stream
+    }
+
+    fn sample(
+        &self,
+        rng: &mut Generator,
+        symbols: &SymbolValues,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let shape = if let Some(input) = inputs.first() {
+            input.shape().into()
+        } else {
+            self.shape
+                .iter()
+                .map(|d| d.eval(symbols).to_usize())
+                .collect::<TractResult<TVec<_>>>()?
+        };
+        let len = shape.iter().product();
+        let values = if self.datum_type.is_float() {
+            self.sample_floats(rng, len)?
+        } else {
+            self.sample_ints(rng, len)?
+        };
+        let values = values.cast_to_dt(self.datum_type)?.into_owned().into_shape(&shape)?;
+        Ok(tvec!(values.into_arc_tensor()))
+    }
+}
+
+impl Op for Random {
+    fn name(&self) -> Cow<str> {
+        "Random".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{:?} seed: {:?}", self.dist, self.seed)])
+    }
+
+    fn validation(&self) -> Validation {
+        self.random_validation()
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Random {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.sample_once(inputs)
+    }
+
+    fn state(
+        &self,
+        session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        self.random_state(session)
+    }
+}
+
+impl TypedOp for Random {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let Some(input) = inputs.first() {
+            Ok(tvec!(TypedFact::dt_shape(self.datum_type, input.shape.clone())))
+        } else {
+            Ok(tvec!(TypedFact::dt_shape(self.datum_type, &*self.shape)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uniform_int_range() -> TractResult<()> {
+        let op = Random {
+            datum_type: i32::datum_type(),
+            shape: tvec!(100.to_dim()),
+            dist: Distribution::Uniform { low: rctensor0(-2i32), high: rctensor0(3i32) },
+            seed: Some((1, 2)),
+            stream: 0,
+        };
+        let output = op.eval(tvec!())?;
+        assert!(output[0].as_slice::<i32>()?.iter().all(|&x| (-2..3).contains(&x)));
+        Ok(())
+    }
+
+    #[test]
+    fn normal_moments() -> TractResult<()> {
+        let op = Random {
+            datum_type: f32::datum_type(),
+            shape: tvec!(10000.to_dim()),
+            dist: Distribution::Normal { mean: rctensor0(2f32), dev: rctensor0(0.5f32) },
+            seed: Some((3, 0)),
+            stream: 0,
+        };
+        let output = op.eval(tvec!())?;
+        let values = output[0].as_slice::<f32>()?;
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let var = values.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / values.len() as f32;
+        assert!((mean - 2.0).abs() < 0.05, "{}", mean);
+        assert!((var.sqrt() - 0.5).abs() < 0.05, "{}", var);
+        Ok(())
+    }
+}
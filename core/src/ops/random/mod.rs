@@ -0,0 +1,217 @@
+use std::collections::hash_map::RandomState;
+use std::f32::consts::PI;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::internal::*;
+
+/// # Random number operators
+///
+/// Random operators draw from a Philox generator. An operator with a fixed `seed` restarts its
+/// stream for each new state. Other operators are seeded from the session `random_seed` (or from
+/// entropy when it is not set) and their `stream`, derived from the node name by
+/// `stream_for_name`, so that a given session seed reproduces the same values across runs and
+/// model transformations, while distinct operators still get independent streams. Stateless
+/// evaluation of an operator without a seed draws from entropy.
+mod bernoulli;
+mod distribution;
+mod multinomial;
+mod philox;
+
+pub use self::bernoulli::Bernoulli;
+pub use self::distribution::{Distribution, Random};
+pub use self::multinomial::Multinomial;
+pub use self::philox::Philox4x32x10;
+
+fn entropy() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// FNV-1a hash of the node name, stable across builds.
+pub fn stream_for_name(name: &str) -> u64 {
+    name.bytes().fold(0xcbf29ce484222325, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// A stream of 32 bit values from a Philox generator.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    philox: Philox4x32x10,
+    buffer: u128,
+    available: usize,
+}
+
+impl Generator {
+    /// `(seed, seed2)` are combined as in TensorFlow.
+    pub fn for_seeds(seed: u64, seed2: u64) -> Generator {
+        Generator {
+            philox: Philox4x32x10::weird_tf_constructor(seed, seed2),
+            buffer: 0,
+            available: 0,
+        }
+    }
+
+    /// Seeded from entropy.
+    pub fn unseeded() -> Generator {
+        Generator::for_seeds(entropy(), 0)
+    }
+
+    fn for_op(seed: Option<(u64, u64)>, session: &SessionState, stream: u64) -> Generator {
+        if let Some((seed, seed2)) = seed {
+            Generator::for_seeds(seed, seed2)
+        } else {
+            Generator::for_seeds(session.random_seed.unwrap_or_else(entropy), stream)
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.available == 0 {
+            self.buffer = self.philox.next();
+            self.available = 4;
+        }
+        let value = self.buffer as u32;
+        self.buffer >>= 32;
+        self.available -= 1;
+        value
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn uniform_f32(&mut self) -> f32 {
+        let mantissa = self.next_u32() & 0x7fffff;
+        f32::from_bits(127 << 23 | mantissa) - 1.0
+    }
+
+    /// Standard normal, using the Box-Muller transform.
+    pub fn normal_f32(&mut self) -> f32 {
+        let u1 = 1.0 - self.uniform_f32();
+        let u2 = self.uniform_f32();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+}
+
+trait Sampler: Op {
+    fn seed(&self) -> Option<(u64, u64)>;
+    fn stream(&self) -> u64;
+
+    fn sample(
+        &self,
+        rng: &mut Generator,
+        symbols: &SymbolValues,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>>;
+
+    /// Stateless evaluation, from a fresh generator.
+    fn sample_once(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut rng = if let Some((seed, seed2)) = self.seed() {
+            Generator::for_seeds(seed, seed2)
+        } else {
+            Generator::unseeded()
+        };
+        self.sample(&mut rng, &SymbolValues::default(), inputs)
+    }
+
+    fn random_state(&self, session: &mut SessionState) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(GeneratorState(Generator::for_op(self.seed(), session, self.stream())))))
+    }
+
+    fn random_validation(&self) -> Validation {
+        if self.seed().is_some() {
+            Validation::Accurate
+        } else {
+            Validation::Random
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct GeneratorState(Generator);
+
+impl OpState for GeneratorState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op: &dyn Sampler = if let Some(op) = op.downcast_ref::<Random>() {
+            op
+        } else if let Some(op) = op.downcast_ref::<Bernoulli>() {
+            op
+        } else if let Some(op) = op.downcast_ref::<Multinomial>() {
+            op
+        } else {
+            bail!("Wrong op")
+        };
+        op.sample(&mut self.0, &session.resolved_symbols, inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn op(name: &str) -> Random {
+        Random {
+            datum_type: f32::datum_type(),
+            shape: tvec!(3.to_dim()),
+            dist: Distribution::Uniform { low: rctensor0(0f32), high: rctensor0(1f32) },
+            seed: None,
+            stream: stream_for_name(name),
+        }
+    }
+
+    fn model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let a = model.wire_node("a", op("a"), &[])?[0];
+        let b = model.wire_node("b", op("b"), &[])?[0];
+        model.set_output_outlets(&[a, b])?;
+        Ok(model)
+    }
+
+    #[test]
+    fn session_seed() -> TractResult<()> {
+        let plan = SimplePlan::new(model()?)?;
+        let mut state = SimpleState::new(&plan)?;
+        state.set_random_seed(42)?;
+        let first = state.run(tvec!())?;
+        let second = state.run(tvec!())?;
+        // independent streams for each node and each run
+        assert_ne!(first[0], first[1]);
+        assert_ne!(first[0], second[0]);
+        state.set_random_seed(42)?;
+        assert_eq!(state.run(tvec!())?, first);
+        let mut other = SimpleState::new(&plan)?;
+        other.set_random_seed(42)?;
+        assert_eq!(other.run(tvec!())?, first);
+        Ok(())
+    }
+
+    #[test]
+    fn stream_does_not_depend_on_node_ids() -> TractResult<()> {
+        let mut other = TypedModel::default();
+        other.add_const("unused", tensor0(0f32))?;
+        let b = other.wire_node("b", op("b"), &[])?[0];
+        let a = other.wire_node("a", op("a"), &[])?[0];
+        other.set_output_outlets(&[a, b])?;
+        let mut reordered = SimpleState::new(SimplePlan::new(other)?)?;
+        reordered.set_random_seed(42)?;
+        let mut state = SimpleState::new(SimplePlan::new(model()?)?)?;
+        state.set_random_seed(42)?;
+        assert_eq!(reordered.run(tvec!())?, state.run(tvec!())?);
+        Ok(())
+    }
+
+    #[test]
+    fn unseeded_eval() -> TractResult<()> {
+        let op = op("a");
+        assert_ne!(op.eval(tvec!())?, op.eval(tvec!())?);
+        Ok(())
+    }
+
+    #[test]
+    fn generator_matches_philox() {
+        let mut rng = Generator::for_seeds(1, 2);
+        let mut philox = Philox4x32x10::weird_tf_constructor(1, 2).u32_iter();
+        for _ in 0..10 {
+            assert_eq!(rng.next_u32(), philox.next().unwrap());
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::internal::*;
+use tract_ndarray::Ix2;
+
+use super::{Generator, Sampler};
+
+/// Draws `sample_size` class indices for each row of a `[batch, classes]` input of unnormalized
+/// log-probabilities, producing a `[batch, sample_size]` output.
+#[derive(Debug, Clone, Hash)]
+pub struct Multinomial {
+    pub datum_type: DatumType,
+    pub sample_size: usize,
+    pub seed: Option<(u64, u64)>,
+    pub stream: u64,
+}
+
+impl_dyn_hash!(Multinomial);
+
+impl Sampler for Multinomial {
+    fn seed(&self) -> Option<(u64, u64)> {
+        self.seed
+    }
+
+    fn stream(&self) -> u64 {
+        self.stream
+    }
+
+    fn sample(
+        &self,
+        rng: &mut Generator,
+        _symbols: &SymbolValues,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let logits = input.cast_to::<f32>()?;
+        let logits = logits.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let mut output = tract_ndarray::Array2::<i64>::zeros((logits.nrows(), self.sample_size));
+        let mut cdf = vec![0f32; logits.ncols()];
+        for (row, mut samples) in logits.outer_iter().zip(output.outer_iter_mut()) {
+            let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let mut total = 0.0;
+            for (c, &logit) in cdf.iter_mut().zip(row.iter()) {
+                total += (logit - max).exp();
+                *c = total;
+            }
+            for sample in samples.iter_mut() {
+                let threshold = rng.uniform_f32() * total;
+                *sample = cdf.iter().position(|&c| c > threshold).unwrap_or(cdf.len() - 1) as i64;
+            }
+        }
+        Ok(tvec!(output.into_tensor().cast_to_dt(self.datum_type)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl Op for Multinomial {
+    fn name(&self) -> Cow<str> {
+        "Multinomial".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("sample_size: {} seed: {:?}", self.sample_size, self.seed)])
+    }
+
+    fn validation(&self) -> Validation {
+        self.random_validation()
+    }
+
+    op_core!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Multinomial {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.sample_once(inputs)
+    }
+
+    fn state(
+        &self,
+        session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        self.random_state(session)
+    }
+}
+
+impl TypedOp for Multinomial {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 2 || !inputs[0].datum_type.is_float() {
+            bail!("Multinomial expects [batch, classes] float logits, got {:?}", inputs[0])
+        }
+        let shape = [inputs[0].shape[0].clone(), self.sample_size.to_dim()];
+        Ok(tvec!(TypedFact::dt_shape(self.datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn follows_probabilities() -> TractResult<()> {
+        let op = Multinomial {
+            datum_type: i32::datum_type(),
+            sample_size: 1000,
+            seed: Some((3, 0)),
+            stream: 0,
+        };
+        // class 2 is impossible, class 1 is three times as likely as class 0
+        let logits = rctensor2(&[[0f32, 3f32.ln(), f32::NEG_INFINITY]]);
+        let output = op.eval(tvec!(logits))?;
+        let samples = output[0].as_slice::<i32>()?;
+        let ones = samples.iter().filter(|&&s| s == 1).count();
+        assert!(samples.iter().all(|&s| s < 2));
+        assert!((700..800).contains(&ones), "{}", ones);
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 // from https://github.com/tensorflow/tensorflow/blob/master/tensorflow/core/lib/random/philox_random.h
 
-use tract_hir::internal::*;
+use crate::internal::*;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct Philox4x32x10 {
     key: u64,
     counter: u128,
@@ -0,0 +1,192 @@
+use crate::internal::*;
+use crate::ops::binary::{BinMiniOp, TypedBinOp, UnaryOp};
+use crate::ops::element_wise::{ElementWiseMiniOp, ElementWiseOp};
+use crate::ops::math::{Add, Mul, Rsqrt, Square, Sub};
+use crate::ops::nn::{LayerNorm, Reduce, Reducer};
+
+/// Collapses the expanded form of a layer normalization into a single `LayerNorm` op.
+///
+/// It matches the decluttered form of `(x - mean(x)) / sqrt(mean((x - mean(x))²) + eps)`: means
+/// are a sum followed by a product with the inverse of the reduced size, the square can be a
+/// product, and the division has become a product with a `Rsqrt`.
+#[derive(Clone, Debug)]
+pub struct FuseLayerNorm;
+
+impl super::TypedPass for FuseLayerNorm {
+    fn reset(&mut self) -> TractResult<()> {
+        Ok(())
+    }
+
+    fn next(&mut self, model: &TypedModel) -> TractResult<Option<TypedModelPatch>> {
+        for node in model.eval_order()? {
+            if let Some(patch) = fuse(model, model.node(node))? {
+                return Ok(Some(patch));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Intermediate nodes must only feed the pattern: one successor, and not a model output.
+fn single_use(model: &TypedModel, node: &TypedNode) -> TractResult<bool> {
+    Ok(node.outputs.len() == 1
+        && node.outputs[0].successors.len() == 1
+        && !model.output_outlets()?.contains(&node.id.into()))
+}
+
+fn is_bin<M: BinMiniOp>(node: &TypedNode) -> bool {
+    node.op_as::<TypedBinOp>().map(|op| op.0.is::<M>()).unwrap_or(false)
+}
+
+fn is_element_wise<M: ElementWiseMiniOp>(node: &TypedNode) -> bool {
+    node.op_as::<ElementWiseOp>().map(|op| op.0.is::<M>()).unwrap_or(false)
+}
+
+/// Value of the uniform constant operand of an `UnaryOp` running `M`.
+fn unary_const<M: BinMiniOp>(node: &TypedNode) -> Option<f32> {
+    let op = node.op_as::<UnaryOp>().filter(|op| op.mini_op.is::<M>())?;
+    op.a.as_uniform()?.cast_to_scalar::<f32>().ok()
+}
+
+/// Matches a mean: a `Reduce<Sum>` only feeding a product with the inverse of the reduced size.
+fn mean(model: &TypedModel, node: &TypedNode) -> TractResult<Option<(OutletId, TVec<usize>)>> {
+    let factor =
+        if let Some(factor) = unary_const::<Mul>(node) { factor } else { return Ok(None) };
+    let sum = model.node(node.inputs[0].node);
+    let reduce = if let Some(reduce) = sum.op_as::<Reduce>() { reduce } else { return Ok(None) };
+    if reduce.reducer != Reducer::Sum || !single_use(model, sum)? {
+        return Ok(None);
+    }
+    let fact = model.outlet_fact(sum.inputs[0])?;
+    let size = reduce.axes.iter().map(|&ax| fact.shape[ax].clone()).product::<TDim>();
+    if let Ok(size) = size.to_i64() {
+        if (factor * size as f32 - 1.0).abs() < 1e-5 {
+            return Ok(Some((sum.inputs[0], reduce.axes.clone())));
+        }
+    }
+    Ok(None)
+}
+
+fn fuse(model: &TypedModel, node: &TypedNode) -> TractResult<Option<TypedModelPatch>> {
+    if !is_bin::<Mul>(node) {
+        return Ok(None);
+    }
+    for &(centered_slot, rsqrt_slot) in &[(0, 1), (1, 0)] {
+        let rsqrt = model.node(node.inputs[rsqrt_slot].node);
+        if !is_element_wise::<Rsqrt>(rsqrt) || !single_use(model, rsqrt)? {
+            continue;
+        }
+        let mut var = model.node(rsqrt.inputs[0].node);
+        let mut epsilon = 0.0;
+        if let Some(eps) = unary_const::<Add>(var) {
+            if !single_use(model, var)? {
+                continue;
+            }
+            epsilon = eps;
+            var = model.node(var.inputs[0].node);
+        }
+        if !single_use(model, var)? {
+            continue;
+        }
+        let (square, axes) = if let Some(mean) = mean(model, var)? { mean } else { continue };
+        let square = model.node(square.node);
+        if !single_use(model, square)? {
+            continue;
+        }
+        let centered = if is_element_wise::<Square>(square)
+            || is_bin::<Mul>(square) && square.inputs[0] == square.inputs[1]
+        {
+            square.inputs[0]
+        } else {
+            continue;
+        };
+        if centered != node.inputs[centered_slot] {
+            continue;
+        }
+        let sub = model.node(centered.node);
+        if !is_bin::<Sub>(sub)
+            || sub.outputs[0].successors.iter().any(|s| s.node != square.id && s.node != node.id)
+            || model.output_outlets()?.contains(&centered)
+        {
+            continue;
+        }
+        let mean_node = model.node(sub.inputs[1].node);
+        if !single_use(model, mean_node)? {
+            continue;
+        }
+        let (x, mean_axes) = if let Some(mean) = mean(model, mean_node)? { mean } else { continue };
+        if x != sub.inputs[0]
+            || mean_axes != axes
+            || model.outlet_fact(x)?.shape != node.outputs[0].fact.shape
+        {
+            continue;
+        }
+        let mut patch = TypedModelPatch::default();
+        let x = patch.tap_model(model, x)?;
+        let wire = patch.wire_node(&node.name, LayerNorm::new(axes, epsilon), &[x])?;
+        patch.shunt_outside(model, node.id.into(), wire[0])?;
+        return Ok(Some(patch));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+
+    // mean and variance over the last axis, as decluttered from a ONNX ReduceMean/Sub/Pow/Sqrt/Div
+    fn expanded(square_as_mul: bool) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), [2, 4]))?;
+        let wire_mean = |model: &mut TypedModel, name: &str, input: OutletId| {
+            let sum = model.wire_node(
+                format!("{}.sum", name),
+                Reduce::new(tvec!(1), Reducer::Sum),
+                &[input],
+            )?;
+            model.wire_node(name, math::mul::unary(rctensor2(&[[0.25f32]])), &sum)
+        };
+        let mean = wire_mean(&mut model, "mean", x)?;
+        let centered = model.wire_node("centered", math::sub::bin_typed(), &[x, mean[0]])?[0];
+        let square = if square_as_mul {
+            model.wire_node("square", math::mul::bin_typed(), &[centered, centered])?
+        } else {
+            model.wire_node("square", math::square(), &[centered])?
+        };
+        let var = wire_mean(&mut model, "var", square[0])?;
+        let var = model.wire_node("eps", math::add::unary(rctensor2(&[[1e-5f32]])), &var)?;
+        let rsqrt = model.wire_node("rsqrt", math::rsqrt(), &var)?;
+        let norm = model.wire_node("norm", math::mul::bin_typed(), &[rsqrt[0], centered])?;
+        model.set_output_outlets(&norm)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn fuse_expanded_layer_norm() -> TractResult<()> {
+        for &square_as_mul in &[false, true] {
+            let model = expanded(square_as_mul)?;
+            let fused = model.clone().declutter()?;
+            assert_eq!(fused.nodes().len(), 2);
+            let op = fused.node(1).op_as::<LayerNorm>().unwrap();
+            assert_eq!(op.axes, tvec!(1));
+            assert_eq!(op.epsilon, 1e-5);
+            let input = tensor2(&[[1f32, 2., 3., 4.], [-1., 0., 0., 5.]]);
+            let expected = SimplePlan::new(model)?.run(tvec!(input.clone()))?;
+            let found = SimplePlan::new(fused)?.run(tvec!(input))?;
+            expected[0].close_enough(&found[0], true)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn keep_shared_intermediate() -> TractResult<()> {
+        let mut model = expanded(false)?;
+        let var = model.node_by_name("var")?.id;
+        let outputs = model.output_outlets()?.to_vec();
+        model.set_output_outlets(&[outputs[0], var.into()])?;
+        let model = model.declutter()?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<LayerNorm>()));
+        Ok(())
+    }
+}
@@ -4,11 +4,13 @@ use std::fmt::Debug;
 use tract_itertools::Itertools;
 
 pub mod change_axes;
+mod layer_norm;
 mod op_optim;
 mod prop_const;
 mod push_split_down;
 
 use self::change_axes::ChangeAxes;
+use self::layer_norm::FuseLayerNorm;
 use self::prop_const::PropConst;
 use self::push_split_down::PushSplitDown;
 use op_optim::OpOptim;
@@ -39,6 +41,7 @@ impl Optimizer {
             Box::new(OpOptim("declutter", TypedOp::declutter, 0)),
             Box::new(PropConst),
             Box::new(PushSplitDown),
+            Box::new(FuseLayerNorm),
             Box::new(ChangeAxes),
         ])
     }
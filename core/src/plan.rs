@@ -3,8 +3,10 @@ use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 
 use crate::internal::*;
+use crate::memory::{ArenaAllocator, MemoryPlan, ARENA_ALIGNMENT};
 use crate::model::order::eval_order_for_nodes;
 use crate::model::{Fact, Graph, OutletId};
+use tract_linalg::multithread::{multithread_tract_scope, Executor};
 
 #[derive(Default)]
 pub struct SessionState {
@@ -12,6 +14,8 @@ pub struct SessionState {
     pub resolved_symbols: SymbolValues,
     pub tensors: HashMap<String, Tensor>,
     pub cached_mmm_scratch_space: Option<Box<dyn tract_linalg::mmm::ScratchSpace>>,
+    /// Seed of the random operators without a fixed seed.
+    pub random_seed: Option<u64>,
 }
 
 impl Clone for SessionState {
@@ -21,6 +25,7 @@ impl Clone for SessionState {
             resolved_symbols: self.resolved_symbols.clone(),
             tensors: self.tensors.clone(),
             cached_mmm_scratch_space: None,
+            random_seed: self.random_seed,
         }
     }
 }
@@ -31,6 +36,25 @@ impl Debug for SessionState {
     }
 }
 
+/// Node scheduling strategy of a `SimplePlan`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlanExecution {
+    /// Run the nodes one after the other, following the plan `order`.
+    Sequential,
+    /// Run independent nodes concurrently on the rayon thread pool. Requires the
+    /// `multithread-plan` feature.
+    Parallel,
+    /// Run the nodes sequentially, placing intermediate values in a preallocated arena (see
+    /// `MemoryPlan`).
+    Arena,
+}
+
+impl Default for PlanExecution {
+    fn default() -> PlanExecution {
+        PlanExecution::Sequential
+    }
+}
+
 #[derive(Debug, Clone, Educe)]
 #[educe(Hash)]
 pub struct SimplePlan<F, O, M>
@@ -43,6 +67,11 @@ where
     pub outputs: Vec<OutletId>,
     pub order: Vec<usize>,
     pub flush_lists: Vec<TVec<usize>>,
+    pub precursors: Vec<TVec<usize>>,
+    pub execution: PlanExecution,
+    pub memory: Option<MemoryPlan>,
+    #[educe(Hash(ignore))]
+    pub executor: Option<Executor>,
     _casper: PhantomData<(F, O)>,
 }
 
@@ -91,15 +120,51 @@ where
                 flush_lists[flush_at].push(node)
             }
         }
+        let mut precursors: Vec<TVec<usize>> = vec![tvec!(); model.borrow().nodes().len()];
+        for &node in &order {
+            if inputs.contains(&node) {
+                continue;
+            }
+            let node_inputs = model.borrow().node(node).inputs.iter().map(|i| i.node);
+            let node_deps = deps.iter().filter(|d| d.0 == node).map(|d| d.1);
+            for prec in node_inputs.chain(node_deps) {
+                if !precursors[node].contains(&prec) {
+                    precursors[node].push(prec);
+                }
+            }
+        }
         Ok(SimplePlan {
             model,
             order,
             flush_lists,
+            precursors,
             outputs: outputs.to_vec(),
+            execution: PlanExecution::Sequential,
+            memory: None,
+            executor: None,
             _casper: PhantomData,
         })
     }
 
+    /// Run the matrix multiplications of this plan with `executor` instead of the process-wide
+    /// default one.
+    pub fn with_executor(self, executor: Executor) -> SimplePlan<F, O, M> {
+        SimplePlan { executor: Some(executor), ..self }
+    }
+
+    /// Pick the node scheduling strategy of this plan.
+    pub fn with_execution(self, execution: PlanExecution) -> TractResult<SimplePlan<F, O, M>> {
+        if execution == PlanExecution::Parallel && !cfg!(feature = "multithread-plan") {
+            bail!("Parallel plan execution requires tract-core multithread-plan feature")
+        }
+        let memory = if execution == PlanExecution::Arena {
+            Some(MemoryPlan::new(self.model(), &self.order, &self.flush_lists, &self.outputs)?)
+        } else {
+            None
+        };
+        Ok(SimplePlan { execution, memory, ..self })
+    }
+
     pub fn run(&self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
         let mut state = SimpleState::new(self)?;
         state.run(inputs)
@@ -122,6 +187,7 @@ where
     pub states: Vec<Option<Box<dyn OpState>>>,
     pub session_state: SessionState,
     pub values: Vec<Option<TVec<Arc<Tensor>>>>,
+    arena: Option<Arc<Tensor>>,
     _phantom: PhantomData<(M, F, O)>,
 }
 
@@ -141,7 +207,14 @@ where
             .iter()
             .map(|n: &Node<F, O>| n.op().state(&mut session, n.id))
             .collect::<TractResult<_>>()?;
-        Ok(SimpleState { plan, states, session_state: session, values, _phantom: PhantomData })
+        Ok(SimpleState {
+            plan,
+            states,
+            session_state: session,
+            values,
+            arena: None,
+            _phantom: PhantomData,
+        })
     }
 
     /// Reset wires state.
@@ -163,11 +236,211 @@ where
         Ok(())
     }
 
+    /// Seeds the random operators, making their outputs reproducible from this point.
+    pub fn set_random_seed(&mut self, seed: u64) -> TractResult<()> {
+        self.session_state.random_seed = Some(seed);
+        self.reset_op_states()
+    }
+
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
-        self.run_plan_with_eval(inputs, self::eval)
+        match self.plan.borrow().execution {
+            PlanExecution::Sequential => self.run_plan_with_eval(inputs, self::eval),
+            PlanExecution::Arena => self.run_plan_in_arena(inputs),
+            #[cfg(feature = "multithread-plan")]
+            PlanExecution::Parallel => self.run_plan_parallel(inputs),
+            #[cfg(not(feature = "multithread-plan"))]
+            PlanExecution::Parallel => {
+                bail!("Parallel plan execution requires tract-core multithread-plan feature")
+            }
+        }
+    }
+
+    /// Run the plan, serving the outputs allocations of stateless nodes from the arena.
+    ///
+    /// Only the node outputs allocated by the op through `Tensor::uninitialized_output_dt` are
+    /// placed in their slots, everything else ends up outside the arena, which is fine. Outputs
+    /// pointing to arena memory outside of their own slot (typically an op forwarding its input)
+    /// are copied out, as their slot may be reused.
+    fn run_plan_in_arena(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let plan = self.plan.clone();
+        let memory = plan.borrow().memory.as_ref().context("Plan has no memory plan")?;
+        if self.arena.as_ref().map(|a| a.len()) != Some(memory.arena_size) {
+            let arena = unsafe {
+                Tensor::uninitialized_aligned::<u8>(&[memory.arena_size], ARENA_ALIGNMENT)?
+            };
+            self.arena = Some(arena.into_arc_tensor());
+        }
+        let mut allocator = ArenaAllocator::new(self.arena.clone().unwrap());
+        self.run_plan_with_eval(inputs, |session, state, node, inputs| {
+            let slots = &memory.slots[node.id];
+            let mut outputs = if state.is_none() && slots.iter().any(|s| s.is_some()) {
+                allocator.reserve(node.id, slots);
+                with_tensor_allocator(&mut allocator, || eval(session, state, node, inputs))?
+            } else {
+                eval(session, state, node, inputs)?
+            };
+            for (ix, output) in outputs.iter_mut().enumerate() {
+                if allocator.is_in_arena(output)
+                    && !allocator.is_in_slot(output, memory.slot(OutletId::new(node.id, ix)))
+                {
+                    *output = output.deep_clone().into_arc_tensor();
+                }
+            }
+            TractResult::Ok(outputs)
+        })
+    }
+
+    /// Run the plan following its dependency graph instead of its linear order.
+    ///
+    /// Stateful nodes are run on the calling thread, as they need the session state, while
+    /// stateless nodes ready to go are dispatched to the rayon thread pool. Values are released
+    /// following the plan flush lists: the values flushed at a step go once all the steps up to
+    /// this one have been scheduled.
+    ///
+    /// While waiting for dispatched nodes, the calling thread helps running the pool jobs if it
+    /// is a worker of the pool, so running a plan from inside the pool does not deadlock.
+    #[cfg(feature = "multithread-plan")]
+    fn run_plan_parallel(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let result = if let Some(executor) = self.plan.borrow().executor.clone() {
+            multithread_tract_scope(executor, || self.do_run_plan_parallel(inputs))
+        } else {
+            self.do_run_plan_parallel(inputs)
+        };
+        self.reset_wires()?;
+        result
+    }
+
+    #[cfg(feature = "multithread-plan")]
+    fn do_run_plan_parallel(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut result = tvec!();
+        {
+            self.set_inputs(inputs)?;
+            let &mut SimpleState {
+                ref plan,
+                ref mut session_state,
+                ref mut states,
+                ref mut values,
+                ..
+            } = self;
+            let plan = plan.borrow();
+            let model = plan.model();
+            let mut missing = vec![0usize; model.nodes().len()];
+            let mut successors: Vec<TVec<usize>> = vec![tvec!(); model.nodes().len()];
+            let mut steps = vec![0usize; model.nodes().len()];
+            for (step, &n) in plan.order.iter().enumerate() {
+                steps[n] = step;
+                missing[n] = plan.precursors[n].len();
+                for &prec in &plan.precursors[n] {
+                    successors[prec].push(n);
+                }
+            }
+            let mut scheduled = vec![false; plan.order.len()];
+            let mut flushed_until = 0;
+            let mut ready: Vec<usize> =
+                plan.order.iter().rev().copied().filter(|&n| missing[n] == 0).collect();
+            let mut running = 0;
+            let (tx, rx) = std::sync::mpsc::channel::<(usize, TractResult<TVec<Arc<Tensor>>>)>();
+            rayon::in_place_scope(|scope| -> TractResult<()> {
+                loop {
+                    while let Some(n) = ready.pop() {
+                        let node = model.node(n);
+                        trace!("Scheduling node {}", node);
+                        let mut dying: TVec<(usize, TVec<Option<Arc<Tensor>>>)> = tvec!();
+                        scheduled[steps[n]] = true;
+                        while flushed_until < plan.order.len() && scheduled[flushed_until] {
+                            for flush in &plan.flush_lists[flushed_until] {
+                                trace!("  Scheduled {} can now flush {}", node, model.node(*flush));
+                                if let Some(vs) = values[*flush].take() {
+                                    dying.push((*flush, vs.into_iter().map(Some).collect()));
+                                }
+                            }
+                            flushed_until += 1;
+                        }
+                        let inputs = gather_inputs(model, node, values, &mut dying)?;
+                        if states[n].is_some() || (ready.is_empty() && running == 0) {
+                            let vs = eval(
+                                session_state,
+                                states[n].as_mut().map(|s| &mut **s),
+                                node,
+                                inputs,
+                            )?;
+                            values[n] = Some(vs);
+                            for &succ in &successors[n] {
+                                missing[succ] -= 1;
+                                if missing[succ] == 0 {
+                                    ready.push(succ);
+                                }
+                            }
+                        } else {
+                            let op = node.op();
+                            let tx = tx.clone();
+                            let executor = plan.executor.clone();
+                            running += 1;
+                            scope.spawn(move |_| {
+                                let vs = if let Some(executor) = executor {
+                                    multithread_tract_scope(executor, || op.eval(inputs))
+                                } else {
+                                    op.eval(inputs)
+                                };
+                                let _ = tx.send((n, vs));
+                            });
+                        }
+                    }
+                    if running == 0 {
+                        return Ok(());
+                    }
+                    let (n, vs) = loop {
+                        if let Ok(done) = rx.try_recv() {
+                            break done;
+                        }
+                        match rayon::yield_now() {
+                            // not a pool worker: the pool threads are free to run the nodes
+                            None => break rx.recv()?,
+                            Some(rayon::Yield::Executed) => (),
+                            Some(rayon::Yield::Idle) => std::thread::yield_now(),
+                        }
+                    };
+                    running -= 1;
+                    let vs = vs.with_context(|| format!("Evaluating {}", model.node(n)))?;
+                    values[n] = Some(vs);
+                    for &succ in &successors[n] {
+                        missing[succ] -= 1;
+                        if missing[succ] == 0 {
+                            ready.push(succ);
+                        }
+                    }
+                }
+            })?;
+            for output in &plan.outputs {
+                trace!("Extracting value {:?} ({})", output, model.node(output.node));
+                result.push(values[output.node].as_ref().unwrap()[output.slot].clone())
+            }
+        }
+        Ok(result)
     }
 
     pub fn run_plan_with_eval<Eval, E>(
+        &mut self,
+        inputs: TVec<Tensor>,
+        eval: Eval,
+    ) -> TractResult<TVec<Arc<Tensor>>>
+    where
+        Eval: for<'a, 'b, 'c> FnMut(
+            &'a mut SessionState,
+            Option<&'b mut (dyn OpState + 'static)>,
+            &'c Node<F, O>,
+            TVec<Arc<Tensor>>,
+        ) -> Result<TVec<Arc<Tensor>>, E>,
+        E: Into<anyhow::Error> + Send + Sync + 'static,
+    {
+        if let Some(executor) = self.plan.borrow().executor.clone() {
+            multithread_tract_scope(executor, || self.do_run_plan_with_eval(inputs, eval))
+        } else {
+            self.do_run_plan_with_eval(inputs, eval)
+        }
+    }
+
+    fn do_run_plan_with_eval<Eval, E>(
         &mut self,
         inputs: TVec<Tensor>,
         mut eval: Eval,
@@ -196,20 +469,14 @@ where
             for (step, n) in plan.order.iter().enumerate() {
                 let node = model.node(*n);
                 trace!("Running step {}, node {}", step, node);
-                let mut inputs: TVec<Arc<Tensor>> = tvec![];
-                for i in &node.inputs {
-                    trace!("  use input {:?}", i);
-                    let prec_node = model.node(i.node);
-                    let prec = values[i.node].as_ref().ok_or_else(|| {
-                        format_err!("Computing {}, precursor {} not done:", node, prec_node)
-                    })?;
-                    inputs.push(prec[i.slot].clone().into())
-                }
-
+                let mut dying: TVec<(usize, TVec<Option<Arc<Tensor>>>)> = tvec!();
                 for flush in &plan.flush_lists[step] {
                     trace!("  Ran {} can now flush {}", node, model.node(*flush));
-                    values[*flush] = None;
+                    if let Some(vs) = values[*flush].take() {
+                        dying.push((*flush, vs.into_iter().map(Some).collect()));
+                    }
                 }
+                let inputs = gather_inputs(model, node, values, &mut dying)?;
 
                 if cfg!(debug_assertions) {
                     let facts = model.node_input_facts(node.id)?;
@@ -426,6 +693,41 @@ where
     }
 }
 
+/// Collect the inputs of `node`.
+///
+/// `dying` holds the values taken out of `values` because `node` is their last consumer. They
+/// are moved into the inputs instead of cloned, so the op gets the only reference to them and
+/// can work in place.
+fn gather_inputs<F, O>(
+    model: &Graph<F, O>,
+    node: &Node<F, O>,
+    values: &[Option<TVec<Arc<Tensor>>>],
+    dying: &mut [(usize, TVec<Option<Arc<Tensor>>>)],
+) -> TractResult<TVec<Arc<Tensor>>>
+where
+    F: Fact + Hash + Clone + 'static,
+    O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
+{
+    let mut inputs: TVec<Arc<Tensor>> = tvec![];
+    for (ix, i) in node.inputs.iter().enumerate() {
+        trace!("  use input {:?}", i);
+        let input = if let Some(dying) = dying.iter_mut().find(|d| d.0 == i.node) {
+            if node.inputs[ix + 1..].contains(i) {
+                dying.1[i.slot].clone()
+            } else {
+                dying.1[i.slot].take()
+            }
+        } else {
+            values[i.node].as_ref().map(|vs| vs[i.slot].clone())
+        };
+        let input = input.ok_or_else(|| {
+            format_err!("Computing {}, precursor {} not done:", node, model.node(i.node))
+        })?;
+        inputs.push(input)
+    }
+    Ok(inputs)
+}
+
 pub fn eval<F, O>(
     session_state: &mut SessionState,
     mut state: Option<&mut (dyn OpState + 'static)>,
@@ -443,3 +745,88 @@ where
     .with_context(|| format!("Evaluating {}", node));
     r
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+
+    #[test]
+    fn last_use_moves_value() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[3]))?;
+        let two = model.add_const("two", tensor1(&[2f32]))?;
+        let mul = model.wire_node("mul", math::mul::bin_typed(), &[source, two])?;
+        let exp = model.wire_node("exp", math::exp(), &mul)?;
+        let add = model.wire_node("add", math::add::bin_typed(), &[exp[0], exp[0]])?;
+        model.set_output_outlets(&add)?;
+        let plan = SimplePlan::new(&model)?;
+        let mut state = SimpleState::new(&plan)?;
+        let mut counts = vec![];
+        state.run_plan_with_eval(
+            tvec!(tensor1(&[1f32, 2., 3.])),
+            |session, op_state, node, inputs| {
+                counts.push((
+                    node.name.clone(),
+                    inputs.iter().map(Arc::strong_count).collect::<Vec<_>>(),
+                ));
+                eval(session, op_state, node, inputs)
+            },
+        )?;
+        assert!(counts.contains(&("exp".to_string(), vec![1])));
+        assert!(counts.contains(&("add".to_string(), vec![2, 2])));
+        Ok(())
+    }
+
+    #[cfg(feature = "multithread-plan")]
+    fn branches_model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[3]))?;
+        let one = model.add_const("one", tensor1(&[1f32]))?;
+        let two = model.add_const("two", tensor1(&[2f32]))?;
+        let left = model.wire_node("left", math::add::bin_typed(), &[source, one])?;
+        let right = model.wire_node("right", math::mul::bin_typed(), &[source, two])?;
+        let both = model.wire_node("both", math::add::bin_typed(), &[left[0], right[0]])?;
+        model.set_output_outlets(&both)?;
+        Ok(model)
+    }
+
+    #[cfg(feature = "multithread-plan")]
+    #[test]
+    fn parallel_branches() -> TractResult<()> {
+        let plan = branches_model()?.into_runnable_with_execution(PlanExecution::Parallel)?;
+        for _ in 0..10 {
+            let result = plan.run(tvec!(tensor1(&[1f32, 2., 3.])))?;
+            assert_eq!(result[0], rctensor1(&[4f32, 7., 10.]));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "multithread-plan")]
+    #[test]
+    fn parallel_in_single_thread_pool() -> TractResult<()> {
+        let plan = branches_model()?.into_runnable_with_execution(PlanExecution::Parallel)?;
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build()?;
+        let result = pool.install(|| plan.run(tvec!(tensor1(&[1f32, 2., 3.]))))?;
+        assert_eq!(result[0], rctensor1(&[4f32, 7., 10.]));
+        Ok(())
+    }
+
+    #[cfg(feature = "multithread-plan")]
+    #[test]
+    fn parallel_failure_resets_wires() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), [1]))?;
+        let s = model.add_source("s", TypedFact::dt_shape(String::datum_type(), [1]))?;
+        let exp = model.wire_node("exp", math::exp(), &[x])?;
+        let parsed = model.wire_node("parsed", crate::ops::cast::cast(f32::datum_type()), &[s])?;
+        let add = model.wire_node("add", math::add::bin_typed(), &[exp[0], parsed[0]])?;
+        model.set_output_outlets(&add)?;
+        let plan = SimplePlan::new(&model)?.with_execution(PlanExecution::Parallel)?;
+        let mut state = SimpleState::new(&plan)?;
+        let result = state.run(tvec!(tensor1(&[0f32]), tensor1(&["abc".to_string()])));
+        assert!(result.is_err());
+        assert!(state.values.iter().all(|v| v.is_none()));
+        Ok(())
+    }
+}
@@ -0,0 +1,539 @@
+//! Post-training static quantization.
+//!
+//! A float model is first run on a few representative inputs to record the range of the values
+//! going through each of its outlets (see `Calibration`). These ranges are then turned into i8
+//! quantization parameters, and `quantize` rewrites convolutions, matrix products and additions
+//! to their quantized forms. The other operators keep working in f32: casts are inserted where
+//! quantized and float parts of the network meet, so the model inputs and outputs are left
+//! untouched.
+//!
+//! Alternatively, `quantize_dynamic` needs no calibration: it only quantizes the constant
+//! weights of matrix products, the activations being quantized on the fly with their own range.
+use crate::internal::*;
+use crate::ops::binary::{wire_with_rank_broadcast, TypedBinOp, UnaryOp};
+use crate::ops::cnn::ConvUnary;
+use crate::ops::math::{add, mul, Add};
+use crate::ops::matmul::mir_quant_unary::QMatMulUnary;
+use crate::ops::matmul::{MatMulQParams, MatMulUnary, QMatMul};
+use crate::ops::quant::DynamicQuantizeLinearI8;
+use tract_ndarray::{Axis, Zip};
+
+/// Value ranges observed on the f32 outlets of a model.
+#[derive(Clone, Debug, Default)]
+pub struct Calibration {
+    pub ranges: HashMap<OutletId, (f32, f32)>,
+}
+
+impl Calibration {
+    /// Run the model on each sample, accumulating the min and max of every f32 outlet.
+    pub fn new(
+        model: &TypedModel,
+        samples: impl IntoIterator<Item = TVec<Tensor>>,
+    ) -> TractResult<Calibration> {
+        let mut ranges: HashMap<OutletId, (f32, f32)> = HashMap::new();
+        let plan = SimplePlan::new(model)?;
+        let mut state = SimpleState::new(&plan)?;
+        for sample in samples {
+            state.run_plan_with_eval(
+                sample,
+                |session, op_state, node, inputs| -> TractResult<_> {
+                    let outputs = crate::plan::eval(session, op_state, node, inputs)?;
+                    for (slot, output) in outputs.iter().enumerate() {
+                        if output.datum_type() != f32::datum_type() || output.len() == 0 {
+                            continue;
+                        }
+                        let (min, max) = output
+                            .as_slice::<f32>()?
+                            .iter()
+                            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &x| {
+                                (min.min(x), max.max(x))
+                            });
+                        let range =
+                            ranges.entry(OutletId::new(node.id, slot)).or_insert((min, max));
+                        range.0 = range.0.min(min);
+                        range.1 = range.1.max(max);
+                    }
+                    Ok(outputs)
+                },
+            )?;
+        }
+        Ok(Calibration { ranges })
+    }
+
+    /// Asymmetric i8 quantization parameters covering the range observed on an outlet.
+    pub fn qparams(&self, outlet: OutletId) -> Option<QParams> {
+        self.ranges.get(&outlet).map(|&(min, max)| qparams_for_range(min, max))
+    }
+}
+
+fn qparams_for_range(min: f32, max: f32) -> QParams {
+    // zero has to be exactly representable (padding, relu, ...)
+    let min = min.min(0.0);
+    let mut max = max.max(0.0);
+    if max - min < f32::EPSILON {
+        max = min + 1.0;
+    }
+    let scale = (max - min) / 255.0;
+    let zero_point = (-128.0 - min / scale).round().max(-128.0).min(127.0) as i32;
+    QParams::ZpScale { zero_point, scale }
+}
+
+/// Symmetric per-tensor i8 quantization of weights.
+fn quantize_weights(weights: &Tensor) -> TractResult<(Arc<Tensor>, f32)> {
+    let weights = weights.cast_to::<f32>()?;
+    let max = weights.as_slice::<f32>()?.iter().fold(0f32, |max, x| max.max(x.abs()));
+    let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+    let dt = DatumType::QI8(QParams::ZpScale { zero_point: 0, scale });
+    Ok((weights.cast_to_dt(dt)?.into_owned().into_arc_tensor(), scale))
+}
+
+fn static_params(a: (i32, f32), b: (i32, f32), c: (i32, f32)) -> MatMulQParams {
+    MatMulQParams {
+        a0: AttrOrInput::Attr(rctensor0(a.0)),
+        a_scale: AttrOrInput::Attr(rctensor0(a.1)),
+        b0: AttrOrInput::Attr(rctensor0(b.0)),
+        b_scale: AttrOrInput::Attr(rctensor0(b.1)),
+        c0: AttrOrInput::Attr(rctensor0(c.0)),
+        c_scale: AttrOrInput::Attr(rctensor0(c.1)),
+    }
+}
+
+/// Rewrite a (decluttered) f32 model to run its convolutions, matrix products and additions in
+/// i8, using the ranges collected by the calibration.
+///
+/// Operators without a calibrated range for their input and output are left in f32.
+pub fn quantize(model: &TypedModel, calibration: &Calibration) -> TractResult<TypedModel> {
+    let mut quantizer = Quantizer {
+        source: model,
+        calibration,
+        target: TypedModel::default(),
+        wires: HashMap::new(),
+        native: HashMap::new(),
+    };
+    quantizer.run()?;
+    Ok(quantizer.target)
+}
+
+struct Quantizer<'a> {
+    source: &'a TypedModel,
+    calibration: &'a Calibration,
+    target: TypedModel,
+    /// Available forms of each source outlet in the target model.
+    wires: HashMap<(OutletId, DatumType), OutletId>,
+    /// Datum type each source outlet is natively computed in by the target model.
+    native: HashMap<OutletId, DatumType>,
+}
+
+impl<'a> Quantizer<'a> {
+    fn run(&mut self) -> TractResult<()> {
+        let inputs = self.source.input_outlets()?.to_vec();
+        let outputs = self.source.output_outlets()?.to_vec();
+        for &input in &inputs {
+            let node = self.source.node(input.node);
+            let fact = self.source.outlet_fact(input)?.clone();
+            let wire = self.target.add_source(&*node.name, fact)?;
+            self.bind(input, wire)?;
+        }
+        for id in self.source.eval_order()? {
+            let node = self.source.node(id);
+            if inputs.contains(&OutletId::new(id, 0)) {
+                continue;
+            }
+            // keep output names stable: the quantized node gives its name up to the
+            // final cast back to f32
+            let name = if outputs.contains(&OutletId::new(id, 0)) {
+                format!("{}.quantized", node.name)
+            } else {
+                node.name.clone()
+            };
+            let wire = if let Some(op) = node.op_as::<ConvUnary>() {
+                self.conv(node, &name, op)?
+            } else if let Some(op) = node.op_as::<MatMulUnary>() {
+                self.matmul(node, &name, op)?
+            } else if node.op_as::<TypedBinOp>().map(|op| op.0.is::<Add>()).unwrap_or(false) {
+                self.add(node, &name, None)?
+            } else if let Some(op) = node.op_as::<UnaryOp>().filter(|op| op.mini_op.is::<Add>()) {
+                self.add(node, &name, Some(&op.a))?
+            } else {
+                None
+            };
+            if let Some(wire) = wire {
+                self.bind(OutletId::new(id, 0), wire)?;
+            } else {
+                let inputs = node
+                    .inputs
+                    .iter()
+                    .map(|i| self.wire(*i, self.source.outlet_fact(*i)?.datum_type))
+                    .collect::<TractResult<TVec<_>>>()?;
+                let wires = self.target.wire_node(&*node.name, node.op.clone(), &inputs)?;
+                for (slot, wire) in wires.into_iter().enumerate() {
+                    self.bind(OutletId::new(id, slot), wire)?;
+                }
+            }
+        }
+        let mut target_outputs = tvec!();
+        for output in &outputs {
+            let dt = self.source.outlet_fact(*output)?.datum_type;
+            if self.native[output] != dt && output.slot == 0 {
+                let name = &self.source.node(output.node).name;
+                let wire = self.wires[&(*output, self.native[output])];
+                let wire = self.target.wire_node(&**name, crate::ops::cast::cast(dt), &[wire])?[0];
+                self.wires.insert((*output, dt), wire);
+            }
+            target_outputs.push(self.wire(*output, dt)?);
+        }
+        self.target.set_output_outlets(&target_outputs)?;
+        for (ix, output) in outputs.iter().enumerate() {
+            if let Some(label) = self.source.outlet_label(*output) {
+                self.target.set_outlet_label(target_outputs[ix], label.to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn bind(&mut self, source: OutletId, target: OutletId) -> TractResult<()> {
+        let dt = self.target.outlet_fact(target)?.datum_type;
+        self.native.insert(source, dt);
+        self.wires.insert((source, dt), target);
+        Ok(())
+    }
+
+    /// Get a source outlet in the target model as the requested datum type, casting as needed.
+    fn wire(&mut self, outlet: OutletId, dt: DatumType) -> TractResult<OutletId> {
+        if let Some(wire) = self.wires.get(&(outlet, dt)) {
+            return Ok(*wire);
+        }
+        let native = self.native[&outlet];
+        let kind = if native == f32::datum_type() {
+            "quant"
+        } else if dt == f32::datum_type() {
+            "dequant"
+        } else {
+            "requant"
+        };
+        let mut name = format!("{}.{}", self.source.node(outlet.node).name, kind);
+        if outlet.slot > 0 {
+            name = format!("{}_{}", name, outlet.slot);
+        }
+        let mut candidate = name.clone();
+        let mut ix = 1;
+        while self.target.nodes().iter().any(|n| n.name == candidate) {
+            candidate = format!("{}_{}", name, ix);
+            ix += 1;
+        }
+        let input = self.wires[&(outlet, native)];
+        let wire = self.target.wire_node(candidate, crate::ops::cast::cast(dt), &[input])?[0];
+        self.wires.insert((outlet, dt), wire);
+        Ok(wire)
+    }
+
+    /// Quantization parameters for the input and output of a single-wire f32 operator.
+    fn io_qparams(&self, node: &TypedNode) -> Option<(QParams, QParams)> {
+        if self.source.outlet_fact(node.inputs[0]).ok()?.datum_type != f32::datum_type()
+            || node.outputs[0].fact.datum_type != f32::datum_type()
+        {
+            return None;
+        }
+        Some((
+            self.calibration.qparams(node.inputs[0])?,
+            self.calibration.qparams(OutletId::new(node.id, 0))?,
+        ))
+    }
+
+    fn conv(
+        &mut self,
+        node: &TypedNode,
+        name: &str,
+        op: &ConvUnary,
+    ) -> TractResult<Option<OutletId>> {
+        let (b_qp, c_qp) = match self.io_qparams(node) {
+            Some(qps) if op.q_params.is_none() && node.inputs.len() == 1 => qps,
+            _ => return Ok(None),
+        };
+        let (kernel, a_scale) = quantize_weights(&op.kernel)?;
+        let (b0, b_scale) = b_qp.zp_scale();
+        let bias = if let Some(bias) = &op.bias {
+            let bias = bias.cast_to::<f32>()?;
+            let bias = bias
+                .as_slice::<f32>()?
+                .iter()
+                .map(|b| (b / (a_scale * b_scale)).round() as i32)
+                .collect::<Vec<_>>();
+            Some(rctensor1(&bias))
+        } else {
+            None
+        };
+        let c_dt = DatumType::QI8(c_qp);
+        let params = static_params((0, a_scale), (b0, b_scale), c_qp.zp_scale());
+        let op = ConvUnary { kernel, bias, q_params: Some((c_dt, params)), ..op.clone() };
+        let input = self.wire(node.inputs[0], DatumType::QI8(b_qp))?;
+        Ok(Some(self.target.wire_node(name, op, &[input])?[0]))
+    }
+
+    fn matmul(
+        &mut self,
+        node: &TypedNode,
+        name: &str,
+        op: &MatMulUnary,
+    ) -> TractResult<Option<OutletId>> {
+        let (b_qp, c_qp) = match self.io_qparams(node) {
+            Some(qps) => qps,
+            None => return Ok(None),
+        };
+        let (a, a_scale) = quantize_weights(&op.a)?;
+        let (b0, b_scale) = b_qp.zp_scale();
+        let b = self.wire(node.inputs[0], DatumType::QI8(b_qp))?;
+        let a = self.target.add_const(format!("{}.a", name), a)?;
+        let bias = self.target.add_const(format!("{}.bias", name), rctensor0(0i32))?;
+        let op = QMatMul {
+            a_trans: op.a_trans,
+            b_trans: op.b_trans,
+            c_trans: op.c_trans,
+            output_type: DatumType::QI8(c_qp),
+            params: static_params((0, a_scale), (b0, b_scale), c_qp.zp_scale()),
+        };
+        Ok(Some(self.target.wire_node(name, op, &[a, b, bias])?[0]))
+    }
+
+    /// Quantized additions require both operands to share the output quantization.
+    fn add(
+        &mut self,
+        node: &TypedNode,
+        name: &str,
+        konst: Option<&Arc<Tensor>>,
+    ) -> TractResult<Option<OutletId>> {
+        let output = OutletId::new(node.id, 0);
+        let c_qp = match self.calibration.qparams(output) {
+            Some(qp) if node.outputs[0].fact.datum_type == f32::datum_type() => qp,
+            _ => return Ok(None),
+        };
+        let c_dt = DatumType::QI8(c_qp);
+        for input in &node.inputs {
+            if self.source.outlet_fact(*input)?.datum_type != f32::datum_type() {
+                return Ok(None);
+            }
+        }
+        if konst.map(|k| k.datum_type() != f32::datum_type()).unwrap_or(false) {
+            return Ok(None);
+        }
+        let inputs =
+            node.inputs.iter().map(|i| self.wire(*i, c_dt)).collect::<TractResult<TVec<_>>>()?;
+        let wire = if let Some(konst) = konst {
+            let konst = konst.cast_to_dt(c_dt)?.into_owned().into_arc_tensor();
+            self.target.wire_node(name, add::unary(konst), &inputs)?[0]
+        } else {
+            self.target.wire_node(name, add::bin_typed(), &inputs)?[0]
+        };
+        Ok(Some(wire))
+    }
+}
+
+/// Rewrite the products of a (decluttered) f32 model by constant weights to run in i8.
+///
+/// Weights are quantized once, symmetrically with one scale per row, while the activations are
+/// quantized for each run by `DynamicQuantizeLinearI8`. The product is a `QMatMulUnary` with an
+/// i32 output, scaled back to f32.
+pub fn quantize_dynamic(model: &TypedModel) -> TractResult<TypedModel> {
+    let mut model = model.clone();
+    for id in model.eval_order()? {
+        let node = model.node(id);
+        if let Some(op) = node.op_as::<MatMulUnary>() {
+            if let Some(patch) = dynamic_matmul(&model, node, op)? {
+                patch.apply(&mut model)?;
+            }
+        }
+    }
+    model.compact()
+}
+
+/// Symmetric i8 quantization of weights with one scale for each row along `k_axis`. The scales
+/// tensor has the shape of the weights, minus `k_axis`.
+fn quantize_weights_rows(weights: &Tensor, k_axis: usize) -> TractResult<(Tensor, Tensor)> {
+    let weights = weights.to_array_view::<f32>()?;
+    let scales = weights.map_axis(Axis(k_axis), |row| {
+        let max = row.fold(0f32, |max, x| max.max(x.abs()));
+        if max > 0.0 {
+            max / 127.0
+        } else {
+            1.0
+        }
+    });
+    let mut quantized = tract_ndarray::ArrayD::<i8>::zeros(weights.shape());
+    Zip::from(&mut quantized)
+        .and(&weights)
+        .and_broadcast(&scales.view().insert_axis(Axis(k_axis)))
+        .for_each(|q, w, s| *q = (w / s).round().clamp(-127.0, 127.0) as i8);
+    Ok((quantized.into_tensor(), scales.into_tensor()))
+}
+
+fn dynamic_matmul(
+    model: &TypedModel,
+    node: &TypedNode,
+    op: &MatMulUnary,
+) -> TractResult<Option<TypedModelPatch>> {
+    if op.a.datum_type() != f32::datum_type()
+        || model.outlet_fact(node.inputs[0])?.datum_type != f32::datum_type()
+    {
+        return Ok(None);
+    }
+    let name = &*node.name;
+    let rank = op.a.rank();
+    let (a, mut a_scales) = quantize_weights_rows(&op.a, rank - 2 + !op.a_trans as usize)?;
+    // make the per-row scales broadcast along n in the product
+    a_scales.insert_axis(rank - 1 - op.c_trans as usize)?;
+
+    let mut patch = TypedModelPatch::default();
+    let input = patch.tap_model(model, node.inputs[0])?;
+    let b = patch.wire_node(format!("{}.quantize_b", name), DynamicQuantizeLinearI8, &[input])?;
+    let mut params = MatMulQParams::noop_static(i8::datum_type());
+    params.b0 = AttrOrInput::Input(1);
+    let qmm = QMatMulUnary::new(
+        a.into_arc_tensor(),
+        None,
+        op.a_trans,
+        op.b_trans,
+        op.c_trans,
+        i32::datum_type(),
+        params,
+    );
+    let wire = patch.wire_node(format!("{}.qmm", name), qmm, &[b[0], b[2]])?[0];
+    let wire = patch.wire_node(
+        format!("{}.dequantize", name),
+        crate::ops::cast::cast(f32::datum_type()),
+        &[wire],
+    )?[0];
+    let a_scales = patch.add_const(format!("{}.a_scales", name), a_scales)?;
+    let wire = patch.wire_node(format!("{}.a_scale", name), mul::bin_typed(), &[wire, a_scales])?;
+    let wire = wire_with_rank_broadcast(
+        &format!("{}.b_scale", name),
+        &mut patch,
+        mul::bin_typed(),
+        &[wire[0], b[1]],
+    )?;
+    patch.shunt_outside(model, node.id.into(), wire[0])?;
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::{KernelFormat, PaddingSpec, PoolSpec};
+    use crate::ops::nn::DataFormat;
+
+    fn tensor(shape: &[usize], seed: usize) -> Tensor {
+        let len = shape.iter().product::<usize>();
+        let data = (0..len).map(|i| ((i * 7 + seed) % 11) as f32 / 5.0 - 1.0).collect::<Vec<_>>();
+        tract_ndarray::ArrayD::from_shape_vec(shape, data).unwrap().into_tensor()
+    }
+
+    fn model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let s = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[1, 3, 6, 6]))?;
+        let conv = ConvUnary {
+            pool_spec: PoolSpec::new(
+                DataFormat::NCHW,
+                tvec!(3, 3),
+                PaddingSpec::SameUpper,
+                None,
+                None,
+                Some(4),
+            ),
+            kernel_fmt: KernelFormat::OIHW,
+            kernel: tensor(&[4, 3, 3, 3], 0).into_arc_tensor(),
+            group: 1,
+            bias: Some(tensor(&[4], 3).into_arc_tensor()),
+            q_params: None,
+        };
+        let conv = model.wire_node("conv", conv, &[s])?;
+        let zero = tensor0(0f32).broadcast_into_rank(4)?.into_arc_tensor();
+        let relu = model.wire_node("relu", crate::ops::math::max::unary(zero), &conv)?;
+        let add = model.wire_node("add", add::bin_typed(), &[conv[0], relu[0]])?;
+        let reshape = model.wire_node(
+            "reshape",
+            AxisOp::Reshape(2, tvec!(6.to_dim(), 6.to_dim()), tvec!(36.to_dim())),
+            &add,
+        )?;
+        let a = tensor(&[1, 5, 4], 1).into_arc_tensor();
+        let matmul =
+            model.wire_node("matmul", MatMulUnary::new(a, false, false, false), &reshape)?;
+        model.set_output_outlets(&matmul)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn qparams_covers_range_and_zero() {
+        let (zp, scale) = qparams_for_range(-1.0, 3.0).zp_scale();
+        assert!(((-128 - zp) as f32 * scale + 1.0).abs() <= scale);
+        assert!(((127 - zp) as f32 * scale - 3.0).abs() <= scale);
+        let (zp, _) = qparams_for_range(0.5, 3.0).zp_scale();
+        assert_eq!(zp, -128);
+    }
+
+    #[test]
+    fn quantize_conv_add_matmul() -> TractResult<()> {
+        let model = model()?;
+        let samples = (0..4).map(|seed| tvec!(tensor(&[1, 3, 6, 6], seed)));
+        let calibration = Calibration::new(&model, samples)?;
+        let quantized = quantize(&model, &calibration)?;
+        let ops = quantized.nodes().iter().map(|n| n.op().name().to_string()).collect::<Vec<_>>();
+        assert!(ops.contains(&"ConvUnary".to_string()));
+        assert!(ops.contains(&"QMatMul".to_string()));
+        let add = quantized.node_by_name("add")?;
+        assert!(add.outputs[0].fact.datum_type.is_quantized());
+        assert_eq!(quantized.node(quantized.output_outlets()?[0].node).name, "matmul");
+        assert_eq!(quantized.output_fact(0)?.datum_type, f32::datum_type());
+
+        let input = tensor(&[1, 3, 6, 6], 2);
+        let expected = model.into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+        let found = quantized.into_runnable()?.run(tvec!(input))?.remove(0);
+        let range = expected.as_slice::<f32>()?.iter().fold(0f32, |m, x| m.max(x.abs()));
+        for (e, f) in expected.as_slice::<f32>()?.iter().zip(found.as_slice::<f32>()?) {
+            assert!((e - f).abs() < range * 0.05, "expected {:?} found {:?}", expected, found);
+        }
+        Ok(())
+    }
+
+    fn dynamic_model(a: Tensor, a_trans: bool, c_trans: bool) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let s = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[2, 8, 3]))?;
+        let op = MatMulUnary::new(a.into_arc_tensor(), a_trans, false, c_trans);
+        let matmul = model.wire_node("matmul", op, &[s])?;
+        model.set_output_outlets(&matmul)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn quantize_dynamic_matmul() -> TractResult<()> {
+        for &(a_trans, c_trans) in &[(false, false), (true, false), (false, true)] {
+            let mut a = tensor(&[1, 5, 8], 1);
+            // rows with very different ranges
+            a.as_slice_mut::<f32>()?.iter_mut().take(8).for_each(|x| *x *= 100.0);
+            if a_trans {
+                a = a.permute_axes(&[0, 2, 1])?;
+            }
+            let model = dynamic_model(a, a_trans, c_trans)?;
+            let quantized = quantize_dynamic(&model)?;
+            assert!(quantized.nodes().iter().all(|n| !n.op_is::<MatMulUnary>()));
+            assert_eq!(quantized.output_fact(0)?.datum_type, f32::datum_type());
+
+            let input = tensor(&[2, 8, 3], 4);
+            let expected = model.into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+            let plain = quantized.clone().into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+            let optim = quantized.into_optimized()?.into_runnable()?.run(tvec!(input))?.remove(0);
+            let expected = expected.to_array_view::<f32>()?;
+            for found in &[plain, optim] {
+                let found = found.to_array_view::<f32>()?;
+                assert_eq!(expected.shape(), found.shape());
+                // tolerance relative to the magnitude of each row of a
+                let m_axis = 1 + c_trans as usize;
+                for (e, f) in expected.axis_iter(Axis(m_axis)).zip(found.axis_iter(Axis(m_axis))) {
+                    let range = e.fold(0f32, |m, x| m.max(x.abs()));
+                    for (e, f) in e.iter().zip(f.iter()) {
+                        assert!((e - f).abs() < range * 0.05, "expected {} found {}", e, f);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
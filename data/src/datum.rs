@@ -78,6 +78,31 @@ impl QParams {
     }
 }
 
+/// Quantization parameters varying along one axis of a tensor, typically the output channels of
+/// a convolution or matmul kernel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisQParams {
+    pub axis: usize,
+    pub zero_points: Vec<i32>,
+    pub scales: Vec<f32>,
+}
+
+impl Eq for AxisQParams {}
+
+impl Hash for AxisQParams {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.axis.hash(state);
+        self.zero_points.hash(state);
+        self.scales.iter().for_each(|s| s.to_bits().hash(state));
+    }
+}
+
+impl AxisQParams {
+    pub fn zp_scale(&self, ix: usize) -> (i32, f32) {
+        (self.zero_points[ix], self.scales[ix])
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub enum DatumType {
     Bool,
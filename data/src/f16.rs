@@ -2,6 +2,7 @@ use std::{fmt, ops};
 
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Default, PartialEq, PartialOrd, Debug)]
+#[repr(transparent)]
 pub struct f16(pub half::f16);
 
 macro_rules! binary_f16 {
@@ -301,6 +302,12 @@ impl ops::Mul<&f16> for f16 {
     }
 }
 
+impl ops::MulAssign<f16> for f16 {
+    fn mul_assign(&mut self, other: f16) {
+        *self = *self * other
+    }
+}
+
 impl ops::Div<f16> for f16 {
     type Output = f16;
     fn div(self, other: f16) -> f16 {
@@ -13,10 +13,11 @@ mod macros;
 pub type TVec<T> = smallvec::SmallVec<[T; 4]>;
 
 pub mod prelude {
-    pub use crate::datum::{round_ties_to_even, Blob, Datum, DatumType, QParams};
+    pub use crate::datum::{round_ties_to_even, AxisQParams, Blob, Datum, DatumType, QParams};
     pub use crate::dim::{Symbol, SymbolValues, TDim, ToDim};
     pub use crate::f16::*;
     pub use crate::tensor::litteral::*;
+    pub use crate::tensor::store::TensorStore;
     pub use crate::tensor::{natural_strides, IntoArcTensor, IntoTensor, Tensor};
     pub use crate::tvec;
     pub use crate::TVec;
@@ -31,6 +32,7 @@ pub mod internal {
     pub use crate::datum::ClampCast;
     pub use crate::dim::{DimLike, TDim, ToDim};
     pub use crate::prelude::*;
+    pub use crate::tensor::allocator::{with_tensor_allocator, TensorAllocator};
     pub use crate::tensor::view::TensorView;
     pub use ndarray as tract_ndarray;
     pub use smallvec as tract_smallvec;
@@ -8,6 +8,7 @@ use ndarray::prelude::*;
 #[cfg(feature = "serialize")]
 use serde::ser::{Serialize, Serializer};
 use std::alloc;
+use std::any::Any;
 use std::borrow::Cow;
 use std::fmt;
 use std::hash::Hash;
@@ -15,7 +16,9 @@ use std::mem::{align_of, size_of};
 use std::ops::Range;
 use std::sync::Arc;
 
+pub mod allocator;
 pub mod litteral;
+pub mod store;
 pub mod view;
 
 /// Tensor is a concrete tensor in tract.
@@ -25,6 +28,8 @@ pub struct Tensor {
     strides: TVec<isize>,
     layout: alloc::Layout,
     data: *mut u8,
+    /// Set when data lives in a buffer the tensor does not own.
+    foreign: Option<Arc<dyn Any + Send + Sync>>,
 }
 
 unsafe impl Send for Tensor {}
@@ -98,7 +103,7 @@ impl Drop for Tensor {
                     .for_each(|s| std::ptr::drop_in_place(s as *mut TDim));
             }
         }
-        if !self.data.is_null() && self.layout.size() > 0 {
+        if !self.data.is_null() && self.layout.size() > 0 && self.foreign.is_none() {
             unsafe { alloc::dealloc(self.data, self.layout) }
         }
     }
@@ -128,6 +133,25 @@ impl Tensor {
         dt: DatumType,
         shape: &[usize],
         alignment: usize,
+    ) -> anyhow::Result<Tensor> {
+        Self::uninitialized_in(None, dt, shape, alignment)
+    }
+
+    /// Create an uninitialized tensor meant to be the `output`-th output of the op being
+    /// evaluated, served by the current tensor allocator if any (see `with_tensor_allocator`).
+    pub unsafe fn uninitialized_output_dt(
+        output: usize,
+        dt: DatumType,
+        shape: &[usize],
+    ) -> anyhow::Result<Tensor> {
+        Self::uninitialized_in(Some(output), dt, shape, dt.alignment())
+    }
+
+    unsafe fn uninitialized_in(
+        output: Option<usize>,
+        dt: DatumType,
+        shape: &[usize],
+        alignment: usize,
     ) -> anyhow::Result<Tensor> {
         if dt == String::datum_type() {
             return Ok(ndarray::ArrayD::<String>::default(shape).into());
@@ -139,14 +163,21 @@ impl Tensor {
         assert!(dt.is_copy());
         let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
         let layout = alloc::Layout::from_size_align(bytes, alignment)?;
+        let mut foreign = None;
         let data = if bytes == 0 {
             std::ptr::null()
+        } else if let Some((ptr, owner)) =
+            output.and_then(|output| allocator::allocate(output, bytes, alignment))
+        {
+            foreign = Some(owner);
+            ptr
         } else {
             let ptr = alloc::alloc(layout);
             assert!(!ptr.is_null());
             ptr
         } as *mut u8;
-        let mut tensor = Tensor { strides: tvec!(), layout, dt, shape: shape.into(), data };
+        let mut tensor =
+            Tensor { strides: tvec!(), layout, dt, shape: shape.into(), data, foreign };
         #[cfg(debug_assertions)]
         {
             if dt == DatumType::F32 {
@@ -159,6 +190,36 @@ impl Tensor {
         Ok(tensor)
     }
 
+    /// Create a tensor pointing to a buffer it does not own.
+    ///
+    /// `data` must be valid for the tensor size and aligned for `dt`, and will stay alive as long
+    /// as `owner` does.
+    pub unsafe fn from_raw_dt_with_owner(
+        dt: DatumType,
+        shape: &[usize],
+        data: *mut u8,
+        owner: Arc<dyn Any + Send + Sync>,
+    ) -> anyhow::Result<Tensor> {
+        anyhow::ensure!(dt.is_copy(), "Can not borrow {:?} data", dt);
+        let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
+        let layout = alloc::Layout::from_size_align(bytes, dt.alignment())?;
+        let mut tensor = Tensor {
+            strides: tvec!(),
+            layout,
+            dt,
+            shape: shape.into(),
+            data,
+            foreign: Some(owner),
+        };
+        tensor.update_strides();
+        Ok(tensor)
+    }
+
+    /// Is the tensor data in a buffer it does not own ?
+    pub fn is_foreign(&self) -> bool {
+        self.foreign.is_some()
+    }
+
     pub fn stack_tensors(
         axis: usize,
         tensors: &[impl std::borrow::Borrow<Tensor>],
@@ -997,7 +1058,14 @@ impl Tensor {
             let shape = it.shape().into();
             let vec = it.into_raw_vec().into_boxed_slice();
             let data = Box::into_raw(vec) as *mut u8;
-            let mut t = Tensor { dt: T::datum_type(), shape, layout, data, strides: tvec!() };
+            let mut t = Tensor {
+                dt: T::datum_type(),
+                shape,
+                layout,
+                data,
+                strides: tvec!(),
+                foreign: None,
+            };
             t.update_strides();
             return t;
         }
@@ -1042,6 +1110,7 @@ impl Tensor {
                 data: data.as_ptr() as *mut u8,
                 shape: self.shape.clone(),
                 strides: self.strides.clone(),
+                foreign: None,
                 ..*self
             };
             std::mem::forget(data);
@@ -1052,6 +1121,7 @@ impl Tensor {
                 data: data.as_ptr() as *mut u8,
                 shape: self.shape.clone(),
                 strides: self.strides.clone(),
+                foreign: None,
                 ..*self
             };
             std::mem::forget(data);
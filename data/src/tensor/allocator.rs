@@ -0,0 +1,53 @@
+//! Hook to serve tensor allocations from caller-provided memory.
+use std::any::Any;
+use std::cell::Cell;
+use std::sync::Arc;
+
+/// A source of memory for the op outputs allocated on the current thread.
+///
+/// See `with_tensor_allocator` and `Tensor::uninitialized_output_dt`.
+pub trait TensorAllocator {
+    /// Return a buffer of `bytes` bytes aligned on `alignment` for the `output`-th output of the
+    /// op being evaluated, and the object owning it, or `None` to fall back on the system
+    /// allocator.
+    fn allocate(
+        &mut self,
+        output: usize,
+        bytes: usize,
+        alignment: usize,
+    ) -> Option<(*mut u8, Arc<dyn Any + Send + Sync>)>;
+}
+
+thread_local! {
+    static ALLOCATOR: Cell<Option<*mut (dyn TensorAllocator + 'static)>> = Cell::new(None);
+}
+
+/// Run `f` with `allocator` serving the `Tensor::uninitialized_output_dt` allocations of the
+/// current thread.
+pub fn with_tensor_allocator<R, F: FnOnce() -> R>(allocator: &mut dyn TensorAllocator, f: F) -> R {
+    // the pointer is only reachable from the thread local while f runs
+    let allocator: *mut (dyn TensorAllocator + '_) = allocator;
+    let allocator: *mut (dyn TensorAllocator + 'static) = unsafe { std::mem::transmute(allocator) };
+    struct Restore(Option<*mut (dyn TensorAllocator + 'static)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ALLOCATOR.with(|a| a.set(self.0))
+        }
+    }
+    let _restore = Restore(ALLOCATOR.with(|a| a.replace(Some(allocator))));
+    f()
+}
+
+pub(super) fn allocate(
+    output: usize,
+    bytes: usize,
+    alignment: usize,
+) -> Option<(*mut u8, Arc<dyn Any + Send + Sync>)> {
+    ALLOCATOR.with(|a| {
+        // taken out while in use, so an allocator allocating tensors itself is not reentered
+        let allocator = a.take()?;
+        let buffer = unsafe { (*allocator).allocate(output, bytes, alignment) };
+        a.set(Some(allocator));
+        buffer
+    })
+}
@@ -0,0 +1,151 @@
+//! Content-addressed store for immutable tensors.
+//!
+//! Models opting in (`Graph::share_tensors`) register their constants and pre-packed weights
+//! here so that models loaded several times in the same process end up pointing to the same
+//! buffers.
+use super::Tensor;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, Weak};
+
+lazy_static::lazy_static! {
+    static ref GLOBAL: TensorStore = TensorStore::default();
+}
+
+/// A set of tensors, indexed by their content.
+///
+/// The store only keeps weak references: a tensor is freed as soon as the last model using it
+/// is dropped.
+#[derive(Debug, Default)]
+pub struct TensorStore {
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    tensors: HashMap<u64, Vec<Weak<Tensor>>>,
+    /// number of buckets triggering the next sweep of the dead ones
+    sweep_at: usize,
+}
+
+impl Buckets {
+    fn sweep(&mut self) {
+        self.tensors.retain(|_, bucket| {
+            bucket.retain(|t| t.strong_count() > 0);
+            !bucket.is_empty()
+        });
+        self.sweep_at = 2 * self.tensors.len().max(16);
+    }
+}
+
+impl TensorStore {
+    /// The process-wide store.
+    pub fn global() -> &'static TensorStore {
+        &GLOBAL
+    }
+
+    /// Return a tensor identical to `tensor` if the store has one alive, or register `tensor`
+    /// and return it.
+    ///
+    /// Tensors borrowing their data (from a memory mapping, for instance) are returned as is:
+    /// hashing them would load all their pages.
+    pub fn share(&self, tensor: Arc<Tensor>) -> Arc<Tensor> {
+        if tensor.is_foreign() {
+            return tensor;
+        }
+        let mut hasher = DefaultHasher::new();
+        tensor.hash(&mut hasher);
+        let key = hasher.finish();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.tensors.len() >= buckets.sweep_at {
+            buckets.sweep();
+        }
+        let bucket = buckets.tensors.entry(key).or_default();
+        bucket.retain(|t| t.strong_count() > 0);
+        for candidate in bucket.iter().filter_map(|t| t.upgrade()) {
+            if Arc::ptr_eq(&candidate, &tensor) || same_content(&candidate, &tensor) {
+                return candidate;
+            }
+        }
+        bucket.push(Arc::downgrade(&tensor));
+        tensor
+    }
+
+    /// Number of tensors currently alive in the store.
+    pub fn len(&self) -> usize {
+        let buckets = self.buckets.lock().unwrap();
+        buckets.tensors.values().flatten().filter(|t| t.strong_count() > 0).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget about the tensors that have been dropped.
+    pub fn purge(&self) {
+        self.buckets.lock().unwrap().sweep()
+    }
+}
+
+fn same_content(a: &Tensor, b: &Tensor) -> bool {
+    if a.datum_type() != b.datum_type()
+        || a.shape() != b.shape()
+        || a.layout.align() != b.layout.align()
+    {
+        return false;
+    }
+    if a.datum_type().is_copy() {
+        // bitwise: keeps 0.0 and -0.0 apart, and lets NaNs be shared
+        unsafe { a.as_bytes() == b.as_bytes() }
+    } else {
+        a == b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn share_identical() {
+        let store = TensorStore::default();
+        let a = store.share(rctensor1(&[1f32, 2.0]));
+        let b = store.share(rctensor1(&[1f32, 2.0]));
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn do_not_share_different() {
+        let store = TensorStore::default();
+        let a = store.share(rctensor1(&[0f32]));
+        let b = store.share(rctensor1(&[-0f32]));
+        let c = store.share(rctensor1(&[0i32]));
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(store.len(), 3);
+    }
+
+    #[test]
+    fn weak_entries() {
+        let store = TensorStore::default();
+        let a = store.share(rctensor1(&[1u8, 2]));
+        assert_eq!(store.len(), 1);
+        std::mem::drop(a);
+        assert!(store.is_empty());
+        store.purge();
+        assert!(store.buckets.lock().unwrap().tensors.is_empty());
+    }
+
+    #[test]
+    fn dead_buckets_are_swept() {
+        let store = TensorStore::default();
+        for i in 0..1000 {
+            store.share(rctensor0(i));
+        }
+        assert!(store.is_empty());
+        assert!(store.buckets.lock().unwrap().tensors.len() <= 32);
+    }
+}
@@ -24,6 +24,7 @@ pub mod ops {
     pub mod cnn;
     pub mod downsample;
     pub mod dummy;
+    pub mod einsum;
     pub mod element_wise;
     pub mod expandable;
     pub mod identity;
@@ -0,0 +1,78 @@
+use crate::infer::*;
+use crate::internal::*;
+
+pub use tract_core::ops::einsum::{EinSum, Expr};
+
+/// Einsum as found in ONNX and TensorFlow: the equation can only be resolved once the ranks of
+/// the operands are known.
+#[derive(Debug, Clone, new, Hash)]
+pub struct EinSumInference {
+    pub equation: String,
+}
+
+impl_dyn_hash!(EinSumInference);
+
+impl Expansion for EinSumInference {
+    fn name(&self) -> Cow<str> {
+        "EinSumInference".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![self.equation.clone()])
+    }
+
+    op_hir!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(outputs, 1)?;
+        let n = inputs.len();
+        s.equals_all((0..n).map(|i| (&inputs[i].datum_type).bex()).collect())?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.given_all(inputs.iter().map(|i| &i.rank), move |s, ranks: Vec<i64>| {
+            let ranks = ranks.iter().map(|r| *r as usize).collect::<TVec<_>>();
+            let expr = Expr::parse(&self.equation, &ranks)?;
+            s.equals(&outputs[0].rank, expr.output.len() as i64)?;
+            s.given_all(inputs.iter().map(|i| &i.shape), move |s, shapes: Vec<TVec<TDim>>| {
+                let shapes = shapes.iter().map(|s| &**s).collect::<TVec<_>>();
+                s.equals(&outputs[0].shape, expr.output_shape(&shapes)?)
+            })
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let ranks = inputs
+            .iter()
+            .map(|i| Ok(target.outlet_fact(*i)?.rank()))
+            .collect::<TractResult<TVec<_>>>()?;
+        let expr = Expr::parse(&self.equation, &ranks)?;
+        target.wire_node(prefix, EinSum::new(expr), inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_infer_ellipsis() {
+        let mut op = expand(EinSumInference::new("...ij,...jk".to_string()));
+        let a = InferenceFact::dt_shape(DatumType::F32, shapefactoid!(2, 1, 3, 4));
+        let b = InferenceFact::dt_shape(DatumType::F32, shapefactoid!(5, 4, 6));
+        let ofact = InferenceFact::default();
+        let facts = op.infer_facts(tvec!(&a, &b), tvec!(&ofact), tvec!()).unwrap();
+        assert_eq!(
+            facts.1,
+            tvec!(InferenceFact::dt_shape(DatumType::F32, shapefactoid!(2, 5, 3, 6)))
+        );
+    }
+}
@@ -40,6 +40,15 @@ pub trait Expansion:
         inputs: &'p [TensorProxy],
         outputs: &'p [TensorProxy],
     ) -> InferenceResult;
+
+    #[allow(unused_variables)]
+    fn incorporate(
+        &self,
+        model: &InferenceModel,
+        node: &InferenceNode,
+    ) -> TractResult<Option<InferenceModelPatch>> {
+        Ok(None)
+    }
 }
 
 tract_core::dyn_clone::clone_trait_object!(Expansion);
@@ -120,6 +129,14 @@ impl InferenceRulesOp for Box<dyn Expansion> {
         self.as_ref().nboutputs()
     }
 
+    fn incorporate(
+        &self,
+        model: &InferenceModel,
+        node: &InferenceNode,
+    ) -> TractResult<Option<InferenceModelPatch>> {
+        self.as_ref().incorporate(model, node)
+    }
+
     as_op!();
 }
 
@@ -66,7 +66,12 @@ fn main() {
                         // clang at least (dunno about gcc) outputs .asm files in the
                         // root directory that we need to clean up so we don't pollute
                         // the build output/working directory
+                        let _ = fs::remove_file("avx512_mmm_f32_128x1.asm");
+                        let _ = fs::remove_file("avx512_mmm_f32_32x12.asm");
+                        let _ = fs::remove_file("avx512vnni_mmm_i8_16x16.asm");
+                        let _ = fs::remove_file("fma_mmm_f16_16x6.asm");
                         let _ = fs::remove_file("fma_mmm_f32_16x6.asm");
+                        let _ = fs::remove_file("fma_mmm_f64_8x6.asm");
                         let _ = fs::remove_file("fma_mmm_i8_8x8.asm");
                         let _ = fs::remove_file("fma_sigmoid_f32.asm");
                         let _ = fs::remove_file("fma_tanh_f32.asm");
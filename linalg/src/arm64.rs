@@ -8,6 +8,7 @@ use crate::frame::MatMatMul;
 use crate::frame::MatMatMulImpl;
 
 use tract_data::internal::DimLike;
+use tract_data::prelude::f16;
 
 fn is_cortex_a53() -> std::io::Result<bool> {
     let cpu_info = std::fs::read_to_string("/proc/cpuinfo")?;
@@ -16,6 +17,14 @@ fn is_cortex_a53() -> std::io::Result<bool> {
     Ok(a53)
 }
 
+fn has_fp16() -> std::io::Result<bool> {
+    let cpu_info = std::fs::read_to_string("/proc/cpuinfo")?;
+    let fp16 = cpu_info.split("\n").any(|line| {
+        line.starts_with("Features") && line.split_whitespace().any(|f| f == "asimdhp")
+    });
+    Ok(fp16)
+}
+
 pub fn plug(ops: &mut Ops) {
     if is_cortex_a53().unwrap_or(false) {
         log::info!("arm64simd activated for smmv (cortex A53)");
@@ -56,6 +65,12 @@ pub fn plug(ops: &mut Ops) {
         Box::new(|_, _, _| Box::new(MatMatMulImpl::<MatMatMulI8xI32x8x8, i32>::new()));
     ops.qmmv_i8_i32 = Box::new(|_, _| Box::new(MatMatMulImpl::<MatMatMulI8xI32x64x1, i32>::new()));
 
+    if has_fp16().unwrap_or(false) {
+        log::info!("arm64simd activated for mmm_f16 (fp16)");
+        ops.mmm_f16 = Box::new(|_, _, _| Box::new(MatMatMulImpl::<MatMatMulF16x16x8, f16>::new()));
+        ops.mmv_f16 = Box::new(|_, _| Box::new(MatMatMulImpl::<MatMatMulF16x16x8, f16>::new()));
+    }
+
     ops.sigmoid_f32 = Box::new(|| Box::new(ElementWiseImpl::<SigmoidF32x4n, f32>::new()));
     ops.tanh_f32 = Box::new(|| Box::new(ElementWiseImpl::<TanhF32x4n, f32>::new()));
 }
@@ -1,5 +1,6 @@
 use crate::frame::element_wise::ElementWiseKer;
 use crate::frame::mmm::*;
+use tract_data::prelude::f16;
 
 extern_kernel!(fn arm64simd_mmm_f32_16x4_a53(op: *const MatMatMulKerSpec<f32>) -> isize);
 extern_kernel!(fn arm64simd_mmm_f32_8x8_a53(op: *const MatMatMulKerSpec<f32>) -> isize);
@@ -9,6 +10,7 @@ extern_kernel!(fn arm64simd_mmm_f32_12x8_a53(op: *const MatMatMulKerSpec<f32>) -
 extern_kernel!(fn arm64simd_mmm_f32_12x8_gen(op: *const MatMatMulKerSpec<f32>) -> isize);
 extern_kernel!(fn arm64simd_mmm_f32_64x1_a53(op: *const MatMatMulKerSpec<f32>) -> isize);
 extern_kernel!(fn arm64simd_mmm_f32_64x1_gen(op: *const MatMatMulKerSpec<f32>) -> isize);
+extern_kernel!(fn arm64simd_mmm_f16_16x8(op: *const MatMatMulKerSpec<f16>) -> isize);
 extern_kernel!(fn arm64simd_mmm_i8_8x8(op: *const MatMatMulKerSpec<i32>) -> isize);
 extern_kernel!(fn arm64simd_mmm_i8_64x1(op: *const MatMatMulKerSpec<i32>) -> isize);
 extern_kernel!(fn arm64simd_sigmoid_f32_4n(ptr: *mut f32, count: usize) -> ());
@@ -24,6 +26,8 @@ MMMKernel!(MatMatMulF32x8x8<f32>, "arm64simd (generic)", arm64simd_mmm_f32_8x8_g
 MMMKernel!(MatMatMulF32x12x8<f32>, "arm64simd (generic)", arm64simd_mmm_f32_12x8_gen; 12, 8; 16, 16; 1, 1);
 MMMKernel!(MatMatMulF32x64x1<f32>, "arm64simd (generic)", arm64simd_mmm_f32_64x1_gen; 64, 1; 16, 16; 1, 1);
 
+MMMKernel!(MatMatMulF16x16x8<f16>, "arm64simd (fp16)", arm64simd_mmm_f16_16x8; 16, 8; 16, 16; 0, 0);
+
 MMMKernel!(MatMatMulI8x8x8<i32>, "arm64simd (generic)", arm64simd_mmm_i8_8x8; 8, 8; 16, 16; 0,0);
 MMMKernel!(MatMatMulI8x64x1<i32>, "arm64simd (generic)", arm64simd_mmm_i8_64x1; 64, 1; 16, 1; 0,0);
 
@@ -98,6 +102,11 @@ test_mmm_kernel_f32!(
     true
 );
 test_mmm_kernel_f32!(crate::arm64::arm64simd::MatMatMulF32x64x1, test_MatMatMulF32x64x1, true);
+test_mmm_kernel_f16!(
+    crate::arm64::arm64simd::MatMatMulF16x16x8,
+    test_MatMatMulF16x16x8,
+    crate::arm64::has_fp16().unwrap_or(false)
+);
 test_mmm_kernel_i8!(crate::arm64::arm64simd::MatMatMulI8x8x8, test_MatMatMulI8x8x8, true);
 test_mmm_kernel_i8!(crate::arm64::arm64simd::MatMatMulI8x64x1, test_MatMatMulI8x64x1, true);
 test_mmm_kernel_i8_i32!(
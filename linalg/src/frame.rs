@@ -1,6 +1,8 @@
 #[macro_use]
 pub mod element_wise;
 #[macro_use]
+pub mod layer_norm;
+#[macro_use]
 pub mod lut;
 #[macro_use]
 pub mod mmm;
@@ -0,0 +1,149 @@
+use num_traits::Float;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use tract_data::anyhow;
+use tract_data::prelude::{Datum, Tensor};
+
+/// Normalizes a vector in place to zero mean and unit variance: `(x - mean) / sqrt(var + eps)`.
+pub trait LayerNorm<T>: Send + Sync + Debug + dyn_clone::DynClone
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+{
+    fn run(&self, vec: &mut [T], epsilon: T) -> anyhow::Result<()>;
+}
+
+dyn_clone::clone_trait_object!(<T> LayerNorm<T> where T: Copy);
+
+#[derive(Debug, Clone, new)]
+pub struct LayerNormImpl<K, T>
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+    K: LayerNormKer<T> + Clone,
+{
+    phantom: PhantomData<(K, T)>,
+}
+
+impl<K, T> LayerNormImpl<K, T>
+where
+    T: Datum + Float + Debug + Send + Sync,
+    K: LayerNormKer<T> + Clone,
+{
+    /// Splits `vec` in an unaligned prefix, an aligned body with a multiple of `K::nr()` items and
+    /// a suffix. The prefix and suffix are processed through a scratch buffer, padded with `pad`.
+    unsafe fn split<F: FnMut(&mut [T])>(vec: &mut [T], pad: T, tmp: &mut [T], mut f: F) {
+        let mut via_tmp = |slice: &mut [T], f: &mut F| {
+            tmp.iter_mut().for_each(|t| *t = pad);
+            tmp[..slice.len()].copy_from_slice(slice);
+            f(tmp);
+            slice.copy_from_slice(&tmp[..slice.len()]);
+        };
+        let prefix_len = vec.as_ptr().align_offset(K::alignment_bytes()).min(vec.len());
+        if prefix_len > 0 {
+            via_tmp(&mut vec[..prefix_len], &mut f);
+        }
+        let aligned_len = (vec.len() - prefix_len) / K::nr() * K::nr();
+        if aligned_len > 0 {
+            f(&mut vec[prefix_len..][..aligned_len]);
+        }
+        if prefix_len + aligned_len < vec.len() {
+            via_tmp(&mut vec[prefix_len + aligned_len..], &mut f);
+        }
+    }
+}
+
+impl<K, T> LayerNorm<T> for LayerNormImpl<K, T>
+where
+    T: Datum + Float + Debug + Send + Sync,
+    K: LayerNormKer<T> + Clone,
+{
+    fn run(&self, vec: &mut [T], epsilon: T) -> anyhow::Result<()> {
+        if vec.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            let mut tmp_buffer =
+                Tensor::uninitialized_aligned::<T>(&[K::nr()], K::alignment_bytes())?;
+            let tmp = tmp_buffer.as_slice_mut_unchecked::<T>();
+            // statistics are computed on values shifted by the first one, so that a large mean
+            // does not swamp the variance. Padding with the pivot leaves the sums unchanged.
+            let pivot = vec[0];
+            let mut sum = T::zero();
+            let mut sum_squares = T::zero();
+            Self::split(vec, pivot, tmp, |slice| {
+                let (s, s2) = K::stats(slice, pivot);
+                sum = sum + s;
+                sum_squares = sum_squares + s2;
+            });
+            let len = T::from(vec.len()).unwrap();
+            let shifted_mean = sum / len;
+            let var = (sum_squares / len - shifted_mean * shifted_mean).max(T::zero());
+            let scale = (var + epsilon).sqrt().recip();
+            let shift = -(pivot + shifted_mean) * scale;
+            Self::split(vec, T::zero(), tmp, |slice| K::scale_shift(slice, scale, shift));
+        }
+        Ok(())
+    }
+}
+
+pub trait LayerNormKer<T>: Send + Sync + Debug + dyn_clone::DynClone + Clone
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+{
+    fn name() -> &'static str;
+    fn alignment_bytes() -> usize;
+    fn nr() -> usize;
+    /// Sums of `x - pivot` and `(x - pivot)²`. The length of `x` is a multiple of `nr()`.
+    fn stats(x: &[T], pivot: T) -> (T, T);
+    /// `x = x * scale + shift`. The length of `x` is a multiple of `nr()`.
+    fn scale_shift(x: &mut [T], scale: T, shift: T);
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use super::*;
+    use proptest::test_runner::TestCaseResult;
+
+    #[macro_export]
+    macro_rules! layer_norm_frame_tests {
+        ($cond:expr, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn layer_norm(xs in proptest::collection::vec(-25f32..25.0, 1..100)) {
+                    if $cond {
+                        crate::frame::layer_norm::test::test_layer_norm::<$ker>(&*xs).unwrap()
+                    }
+                }
+            }
+
+            #[test]
+            fn layer_norm_constant() {
+                if $cond {
+                    crate::frame::layer_norm::test::test_layer_norm::<$ker>(&[3.0; 13]).unwrap()
+                }
+            }
+
+            #[test]
+            fn layer_norm_large_mean() {
+                if $cond {
+                    let xs = (0..37).map(|i| 1000.0 + (i % 5) as f32).collect::<Vec<_>>();
+                    crate::frame::layer_norm::test::test_layer_norm::<$ker>(&xs).unwrap()
+                }
+            }
+        };
+    }
+
+    pub fn test_layer_norm<K: LayerNormKer<f32>>(values: &[f32]) -> TestCaseResult {
+        let op = LayerNormImpl::<K, f32>::new();
+        let mut found = values.to_vec();
+        op.run(&mut found, 1e-5).unwrap();
+        let len = values.len() as f64;
+        let mean = values.iter().map(|&x| x as f64).sum::<f64>() / len;
+        let var = values.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>() / len;
+        let expected = values
+            .iter()
+            .map(|&x| ((x as f64 - mean) / (var + 1e-5).sqrt()) as f32)
+            .collect::<Vec<_>>();
+        crate::test::check_close(&found, &expected)
+    }
+}
@@ -99,7 +99,10 @@ pub mod test {
                     fn return_c_prop(pb in any::<test::ReturnCProblem<$ker, $tc, $ti>>()) {
                         if $cond {
                             let got = pb.run();
-                            prop_assert!(got.iter().zip(pb.c.iter()).all(|(g,e)| (*g as f32 - *e as f32).abs() < 1e-7),
+                            prop_assert!(got.iter().zip(pb.c.iter()).all(|(g,e)| {
+                                use num_traits::AsPrimitive;
+                                (AsPrimitive::<f32>::as_(*g) - AsPrimitive::<f32>::as_(*e)).abs() < 1e-7
+                            }),
                             "got: {:?}\nexpected: {:?}", pb.run(), pb.c)
                         }
                     }
@@ -142,18 +145,32 @@ pub mod test {
 
                 #[test]
                 fn return_c_max() {
-                    if $cond {
+                    if $cond && test::tile_indices_fit::<$ker, $tc, $ti>() {
                         test::return_c_max::<$ker, $tc, $ti>()
                     }
                 }
 
                 #[test]
                 fn return_c_min() {
-                    if $cond {
+                    if $cond && test::tile_indices_fit::<$ker, $tc, $ti>() {
                         test::return_c_min::<$ker, $tc, $ti>()
                     }
                 }
 
+                #[test]
+                fn return_c_max_i8_range() {
+                    if $cond {
+                        test::return_c_max_i8_range::<$ker, $tc, $ti>()
+                    }
+                }
+
+                #[test]
+                fn return_c_min_i8_range() {
+                    if $cond {
+                        test::return_c_min_i8_range::<$ker, $tc, $ti>()
+                    }
+                }
+
                 #[test]
                 fn return_c_scalar_mul() {
                     if $cond {
@@ -579,6 +596,68 @@ pub mod test {
         }));
     }
 
+    /// Whether the item indices of a tile are representable as `TC` values, as `return_c_max`
+    /// and `return_c_min` assume.
+    pub fn tile_indices_fit<K, TC, TI>() -> bool
+    where
+        K: MatMatMulKer<TI>,
+        TI: Copy + Debug,
+    {
+        std::mem::size_of::<TC>() > 1 || K::mr() * K::nr() <= 128
+    }
+
+    pub fn return_c_max_i8_range<K, TC, TI>()
+    where
+        K: MatMatMulKer<TI>,
+        TC: Copy + PartialEq + 'static,
+        TI: Copy
+            + Add
+            + Mul<Output = TI>
+            + std::cmp::PartialOrd
+            + Zero
+            + Debug
+            + fmt::Display
+            + PartialEq
+            + 'static
+            + AsPrimitive<TC>,
+        usize: AsPrimitive<TC> + AsPrimitive<TI>,
+    {
+        let len = K::mr() * K::nr();
+        // tiles may be bigger than 128 items
+        let v: Vec<TC> = (0..len).map(|f| (f % 128).as_()).collect();
+        let found = fused_ops::<K, TC, TI>(&*v, &[FusedKerSpec::Max(5.as_())]);
+        assert!(found.iter().enumerate().all(|(ix, &a)| {
+            let ix: TI = (ix % 128).as_();
+            a == if ix > 5.as_() { ix.as_() } else { 5.as_() }
+        }));
+    }
+
+    pub fn return_c_min_i8_range<K, TC, TI>()
+    where
+        K: MatMatMulKer<TI>,
+        TC: Copy + PartialEq + 'static,
+        TI: Copy
+            + Add
+            + Mul<Output = TI>
+            + std::cmp::PartialOrd
+            + Zero
+            + Debug
+            + fmt::Display
+            + PartialEq
+            + 'static
+            + AsPrimitive<TC>,
+        usize: AsPrimitive<TC> + AsPrimitive<TI>,
+    {
+        let len = K::mr() * K::nr();
+        // tiles may be bigger than 128 items
+        let v: Vec<TC> = (0..len).map(|f| (f % 128).as_()).collect();
+        let found = fused_ops::<K, TC, TI>(&*v, &[FusedKerSpec::Min(5.as_())]);
+        assert!(found.iter().enumerate().all(|(ix, &a)| {
+            let ix: TI = (ix % 128).as_();
+            a == if ix < 5.as_() { ix.as_() } else { 5.as_() }
+        }));
+    }
+
     pub fn return_c_scalar_add<K, TC, TI>()
     where
         K: MatMatMulKer<TI>,
@@ -691,14 +770,14 @@ pub mod test {
     impl<K, TC, TI> Arbitrary for ReturnCProblem<K, TC, TI>
     where
         K: MatMatMulKer<TI>,
-        TC: Copy + Debug + 'static + Arbitrary,
+        TC: crate::test::LADatum,
         TI: Copy + Debug,
     {
         type Parameters = ();
         type Strategy = BoxedStrategy<Self>;
         fn arbitrary_with(_p: ()) -> Self::Strategy {
             let len = K::mr() * K::nr();
-            proptest::collection::vec(any::<TC>(), len..=len)
+            proptest::collection::vec(TC::strat(), len..=len)
                 .prop_map(|c| ReturnCProblem { c, boo: std::marker::PhantomData })
                 .boxed()
         }
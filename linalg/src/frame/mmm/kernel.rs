@@ -55,6 +55,73 @@ macro_rules! test_mmm_kernel_f32 {
     };
 }
 
+#[macro_export]
+macro_rules! test_mmm_kernel_f64 {
+    ($k: ty, $id: ident, $cond: expr) => {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod $id {
+            mmm_kernel_tests!($cond, $k, f64, f64, f64, f64);
+            mmm_frame_tests!($cond, $k, f64, f64, f64, f64);
+            mmm_kernel_fuse_tests!($cond, $k, f64, f64);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_mmm_kernel_f16 {
+    ($k: ty, $id: ident, $cond: expr) => {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod $id {
+            mmm_kernel_tests!(
+                $cond,
+                $k,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16
+            );
+            mmm_frame_tests!(
+                $cond,
+                $k,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16
+            );
+            mmm_kernel_fuse_tests!($cond, $k, tract_data::prelude::f16, tract_data::prelude::f16);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_mmm_kernel_f16_f32 {
+    ($k: ty, $id: ident, $cond: expr) => {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod $id {
+            mmm_kernel_tests!(
+                $cond,
+                $k,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                f32,
+                f32
+            );
+            mmm_frame_tests!(
+                $cond,
+                $k,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                f32,
+                f32
+            );
+            mmm_kernel_fuse_tests!($cond, $k, f32, f32);
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! test_mmm_kernel_i8 {
     ($k: ty, $id: ident, $cond: expr) => {
@@ -201,9 +268,9 @@ pub mod test {
                     if $cond {
                         let mut pa = vec!(<$ta>::zero(); <$ker>::mr() * 3);
                         let len = pa.len() - 1;
-                        pa[len] = 1 as _;
+                        pa[len] = num_traits::One::one();
                         let pb = PackedOffsetsProblem::<$ker, $ta, $tb, $tc, $ti>::new(pa,
-                            vec!(0 as _, 1 as _),
+                            vec!(<$tb>::zero(), num_traits::One::one()),
                             vec!(0usize; <$ker>::nr()),
                             vec!(1usize, 0, 0),
                             true);
@@ -546,7 +613,7 @@ pub mod test {
         K: MatMatMulKer<TI>,
         TA: Copy + One + AsPrimitive<TI> + Datum,
         TB: Copy + One + AsPrimitive<TI> + Datum,
-        TC: Copy + PartialEq + Zero + 'static + Debug,
+        TC: crate::test::LADatum,
         TI: Copy + Add + Zero + Mul<Output = TI> + Debug + fmt::Display + 'static + AsPrimitive<TC>,
         usize: AsPrimitive<TA> + AsPrimitive<TB>,
     {
@@ -582,7 +649,13 @@ pub mod test {
                     .as_()
             })
             .collect();
-        assert_eq!(v, expected);
+        if TC::datum_type() == DatumType::F16 {
+            // f16 kernels may accumulate in single precision, so the sums do not round like the
+            // half precision reference
+            crate::test::check_close(&v, &expected).unwrap();
+        } else {
+            assert_eq!(v, expected);
+        }
     }
 
     pub fn packed_vec<K, TA, TB, TC, TI>(k: usize)
@@ -1,6 +1,7 @@
 use super::ScratchSpaceFusedNonLinear;
 use super::*;
 use crate::frame::Packer;
+use crate::multithread::Executor;
 use num_traits::{AsPrimitive, Zero};
 use std::fmt;
 use std::fmt::Debug;
@@ -252,116 +253,136 @@ where
         non_linear: &[FusedSpec],
     ) -> anyhow::Result<()> {
         use anyhow::Context;
-        let mr = K::mr();
-        let nr = K::nr();
         let scratch = scratch
             .downcast_mut::<ScratchSpaceFusedNonLinear<TI>>()
             .context("Wrong scratch space type")?;
-        let ref linear = LinearSpec::k(k);
-        for ia in 0..m / mr {
-            let ref a = a.panel_a(ia);
-            if K::nr() == 1 && n == 1 {
-                let ref b = b.panel_b(0);
-                self.prefetch(a, b);
-                scratch.clear();
-                let non_linear = scratch.for_tile::<K>(&non_linear, ia, 0, c);
-                let ref direct_c = c.tile_c(ia, 0);
-                let err = K::kernel(&MatMatMulKerSpec {
-                    a: a as _,
-                    b: b as _,
-                    c: direct_c as _,
-                    linear,
-                    non_linear,
-                });
-                debug_assert_eq!(err, 0, "Kernel return error {}", err);
-            } else {
-                for ib in 0..n / nr {
-                    let ref b = b.panel_b(ib);
-                    self.prefetch(a, b);
-                    scratch.clear();
-                    let ref direct_c = c.tile_c(ia, ib);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, ia, ib, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: a as _,
-                        b: b as _,
-                        c: direct_c as _,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                }
-                if n % nr != 0 {
-                    let ref b = b.panel_b(n / nr);
-                    self.prefetch(a, b);
-                    scratch.clear();
-                    let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, ia, n / nr, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: a as _,
-                        b: b as _,
-                        c: &tmpc,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                    c.set_from_tile(ia, n / nr, mr, n % nr, &tmpc);
+        match crate::multithread::current_tract_executor() {
+            Executor::SingleThread => {
+                self.run_tiles_sequential(m, k, n, scratch, a, b, c, non_linear)
+            }
+            #[cfg(feature = "multithread-mm")]
+            Executor::MultiThread(pool) => {
+                if Self::tiles_count(m, n) < 2 {
+                    self.run_tiles_sequential(m, k, n, scratch, a, b, c, non_linear)
+                } else {
+                    self.run_tiles_parallel(&pool, m, k, n, a, b, c, non_linear)
                 }
             }
         }
-        if m % mr != 0 {
-            let ref panel_a = a.panel_a(m / mr);
-            if K::nr() == 1 && n == 1 {
-                let ref b = b.panel_b(0);
-                self.prefetch(panel_a, b);
-                scratch.clear();
-                let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                let non_linear = scratch.for_tile::<K>(&non_linear, m / mr, 0, c);
-                let err = K::kernel(&MatMatMulKerSpec {
-                    a: panel_a as _,
-                    b: b as _,
-                    c: &tmpc,
-                    linear,
-                    non_linear,
-                });
-                debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                c.set_from_tile(m / mr, 0, m % mr, nr, &tmpc);
-            } else {
-                for ib in 0..n / nr {
-                    let ref b = b.panel_b(ib);
-                    self.prefetch(panel_a, b);
-                    scratch.clear();
-                    let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, m / mr, ib, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: panel_a as _,
-                        b: b as _,
-                        c: &tmpc,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                    c.set_from_tile(m / mr, ib, m % mr, nr, &tmpc);
-                }
-                if n % nr != 0 {
-                    let ref b = b.panel_b(n / nr);
-                    self.prefetch(panel_a, b);
-                    scratch.clear();
-                    let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
-                    let non_linear = scratch.for_tile::<K>(&non_linear, m / mr, n / nr, c);
-                    let err = K::kernel(&MatMatMulKerSpec {
-                        a: panel_a as _,
-                        b: b as _,
-                        c: &tmpc,
-                        linear,
-                        non_linear,
-                    });
-                    debug_assert_eq!(err, 0, "Kernel return error {}", err);
-                    c.set_from_tile(m / mr, n / nr, m % mr, n % nr, &tmpc);
-                }
+    }
+}
+
+impl<K, TI> MatMatMulImpl<K, TI>
+where
+    TI: Datum + Copy + Add + Mul<Output = TI> + Zero + Debug + 'static + Neg<Output = TI>,
+    K: MatMatMulKer<TI> + 'static,
+    i32: AsPrimitive<TI>,
+    usize: AsPrimitive<TI>,
+{
+    #[inline]
+    fn tiles_count(m: usize, n: usize) -> usize {
+        ((m + K::mr() - 1) / K::mr()) * ((n + K::nr() - 1) / K::nr())
+    }
+
+    unsafe fn run_tiles_sequential(
+        &self,
+        m: usize,
+        k: usize,
+        n: usize,
+        scratch: &mut ScratchSpaceFusedNonLinear<TI>,
+        a: &MatrixStore,
+        b: &MatrixStore,
+        c: &MatrixStore,
+        non_linear: &[FusedSpec],
+    ) -> anyhow::Result<()> {
+        let ref linear = LinearSpec::k(k);
+        for ia in 0..(m + K::mr() - 1) / K::mr() {
+            for ib in 0..(n + K::nr() - 1) / K::nr() {
+                self.run_tile(m, n, scratch, ia, ib, linear, a, b, c, non_linear);
             }
         }
         Ok(())
     }
+
+    #[cfg(feature = "multithread-mm")]
+    unsafe fn run_tiles_parallel(
+        &self,
+        pool: &rayon::ThreadPool,
+        m: usize,
+        k: usize,
+        n: usize,
+        a: &MatrixStore,
+        b: &MatrixStore,
+        c: &MatrixStore,
+        non_linear: &[FusedSpec],
+    ) -> anyhow::Result<()> {
+        use rayon::prelude::*;
+        // stores and fused specs are plain pointers to the tensors the caller keeps alive for
+        // the whole run: tiles write to disjoint regions of c, so sharing them is sound.
+        struct Shared<'a, 's, 't, 'f>(
+            &'a MatrixStore<'s, 't>,
+            &'a MatrixStore<'s, 't>,
+            &'a MatrixStore<'s, 't>,
+            &'a [FusedSpec<'f>],
+        );
+        unsafe impl Sync for Shared<'_, '_, '_, '_> {}
+        let shared = Shared(a, b, c, non_linear);
+        let ref linear = LinearSpec::k(k);
+        let n_tiles = (n + K::nr() - 1) / K::nr();
+        pool.install(|| {
+            (0..Self::tiles_count(m, n)).into_par_iter().for_each_init(
+                || ScratchSpaceFusedNonLinear::<TI>::default(),
+                |scratch, tile| {
+                    let Shared(a, b, c, non_linear) = &shared;
+                    let (ia, ib) = (tile / n_tiles, tile % n_tiles);
+                    self.run_tile(m, n, scratch, ia, ib, linear, a, b, c, non_linear)
+                },
+            )
+        });
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn run_tile(
+        &self,
+        m: usize,
+        n: usize,
+        scratch: &mut ScratchSpaceFusedNonLinear<TI>,
+        ia: usize,
+        ib: usize,
+        linear: &LinearSpec,
+        a: &MatrixStore,
+        b: &MatrixStore,
+        c: &MatrixStore,
+        non_linear: &[FusedSpec],
+    ) {
+        let mr = K::mr();
+        let nr = K::nr();
+        let ref a = a.panel_a(ia);
+        let ref b = b.panel_b(ib);
+        self.prefetch(a, b);
+        scratch.clear();
+        let non_linear = scratch.for_tile::<K>(&non_linear, ia, ib, c);
+        if ia < m / mr && ib < n / nr {
+            let ref direct_c = c.tile_c(ia, ib);
+            let err = K::kernel(&MatMatMulKerSpec {
+                a: a as _,
+                b: b as _,
+                c: direct_c as _,
+                linear,
+                non_linear,
+            });
+            debug_assert_eq!(err, 0, "Kernel return error {}", err);
+        } else {
+            let tmpc = scratch.tmp_tile_c(c.item_size(), mr, nr);
+            let err =
+                K::kernel(&MatMatMulKerSpec { a: a as _, b: b as _, c: &tmpc, linear, non_linear });
+            debug_assert_eq!(err, 0, "Kernel return error {}", err);
+            let height = if ia < m / mr { mr } else { m % mr };
+            let width = if ib < n / nr { nr } else { n % nr };
+            c.set_from_tile(ia, ib, height, width, &tmpc);
+        }
+    }
 }
 
 impl<K, TI> fmt::Display for MatMatMulImpl<K, TI>
@@ -191,23 +191,24 @@ impl<'s, 't> MatrixStore<'s, 't> {
 
     #[inline]
     pub(super) unsafe fn set_from_tile(
-        &mut self,
+        &self,
         down: usize,
         right: usize,
         height: usize,
         width: usize,
         tile: &Tile)
     {
-        if self.item_size() == 1 {
-            self.set_from_tile_t::<i8>(down, right, height, width, tile)
-        } else {
-            self.set_from_tile_t::<i32>(down, right, height, width, tile)
+        match self.item_size() {
+            1 => self.set_from_tile_t::<i8>(down, right, height, width, tile),
+            2 => self.set_from_tile_t::<i16>(down, right, height, width, tile),
+            8 => self.set_from_tile_t::<i64>(down, right, height, width, tile),
+            _ => self.set_from_tile_t::<i32>(down, right, height, width, tile),
         }
     }
 
     #[inline]
     unsafe fn set_from_tile_t<T: Datum + Copy>(
-        &mut self,
+        &self,
         down: usize,
         right: usize,
         height: usize,
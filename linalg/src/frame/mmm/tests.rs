@@ -68,6 +68,22 @@ macro_rules! mmm_frame_tests {
                 }
             }
 
+            #[cfg(feature = "multithread-mm")]
+            #[test]
+            fn mat_mul_multithread() {
+                if $cond {
+                    let (m, k, n) = (37, 5, 23);
+                    let a: Vec<i32> = (0..m * k).map(|i| (i % 7) as i32 - 3).collect();
+                    let b: Vec<i32> = (0..k * n).map(|i| (i % 5) as i32 - 2).collect();
+                    let a = tensor1(&a).into_shape(&[m, k]).unwrap().cast_to::<$ta>().unwrap().into_owned();
+                    let b = tensor1(&b).into_shape(&[k, n]).unwrap().cast_to::<$tb>().unwrap().into_owned();
+                    let executor = $crate::multithread::Executor::multithread(4).unwrap();
+                    $crate::multithread::multithread_tract_scope(executor, || {
+                        test_mat_mat_mul_prep::<$ker, $ta, $tb, $tc, $ti>(m, k, n, &a, &b).unwrap()
+                    })
+                }
+            }
+
             #[test]
             fn mat_mul_1_2_1() {
                 if $cond {
@@ -131,8 +147,8 @@ macro_rules! mmm_frame_tests {
                         kt: 1,
                         stride: 1,
                         dilation: 1,
-                        filters: tensor2(&[[2 as $ta]]),
-                        data: tensor2(&[[-65i32 as $tb]]),
+                        filters: tensor2(&[[2i32]]).cast_to::<$ta>().unwrap().into_owned(),
+                        data: tensor2(&[[-65i32]]).cast_to::<$tb>().unwrap().into_owned(),
                         phantom: std::marker::PhantomData,
                     };
                     let expected = pb.expected::<$tc, $ti>();
@@ -28,6 +28,10 @@ impl Packer {
         self.r
     }
 
+    pub fn end_padding_record(&self) -> usize {
+        self.end_padding_record
+    }
+
     pub fn len<D: DimLike>(&self, n: D) -> D {
         (n.div_ceil(self.r) * (self.k + self.end_padding_record)) * self.r
     }
@@ -1,9 +1,11 @@
+pub mod layer_norm;
 pub mod lut;
 pub mod mmm;
 pub mod rounding;
 pub mod sigmoid;
 pub mod tanh;
 
+pub use self::layer_norm::SLayerNorm8;
 pub use self::lut::GenericLut8;
 pub use self::mmm::GenericMmm4x1;
 pub use self::mmm::GenericMmm4x4;
@@ -0,0 +1,44 @@
+use crate::frame::layer_norm::LayerNormKer;
+
+#[derive(Clone, Debug)]
+pub struct SLayerNorm8;
+
+impl LayerNormKer<f32> for SLayerNorm8 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        32
+    }
+
+    fn nr() -> usize {
+        8
+    }
+
+    fn stats(x: &[f32], pivot: f32) -> (f32, f32) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        // independent accumulators per lane, so the loop vectorizes
+        let mut sum = [0f32; 8];
+        let mut sum_squares = [0f32; 8];
+        for chunk in x.chunks_exact(8) {
+            for i in 0..8 {
+                let d = chunk[i] - pivot;
+                sum[i] += d;
+                sum_squares[i] += d * d;
+            }
+        }
+        (sum.iter().sum(), sum_squares.iter().sum())
+    }
+
+    fn scale_shift(x: &mut [f32], scale: f32, shift: f32) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        x.iter_mut().for_each(|px| *px = *px * scale + shift)
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    layer_norm_frame_tests!(true, crate::generic::layer_norm::SLayerNorm8);
+}
@@ -773,7 +773,7 @@ where
                 ab[i].as_mut()[j] += *value;
             }
         }
-    } else if TI::datum_type() == i32::datum_type() && tile.item_size == 1 {
+    } else if tile.item_size == std::mem::size_of::<TC>() {
         for i in 0usize..ab.len() {
             for j in 0usize..ab[0].as_mut().len() {
                 let value: *const TC = tile
@@ -789,19 +789,28 @@ where
 }
 
 test_mmm_kernel_f32!(crate::generic::mmm::GenericMmm4x4<f32, f32, f32, f32>, test_GenericMmm4x4_f32, true);
+test_mmm_kernel_f64!(crate::generic::mmm::GenericMmm4x4<f64, f64, f64, f64>, test_GenericMmm4x4_f64, true);
 test_mmm_kernel_i8!(crate::generic::mmm::GenericMmm4x4<i8, i8, i8, i32>, test_GenericMmm4x4_i8, true);
 test_mmm_kernel_u8!(crate::generic::mmm::GenericMmm4x4<u8, u8, u8, i32>, test_GenericMmm4x4_u8, true);
 test_mmm_kernel_i8_i32!(crate::generic::mmm::GenericMmm4x4<i8, i8, i32, i32>, test_GenericMmm4x4_i8_i32, true);
 test_mmm_kernel_i8_u8_i32!(crate::generic::mmm::GenericMmm4x4<i8, u8, i32, i32>, test_GenericMmm4x4_i8_u8_i32, true);
+test_mmm_kernel_f16!(crate::generic::mmm::GenericMmm4x4<tract_data::prelude::f16, tract_data::prelude::f16, tract_data::prelude::f16, tract_data::prelude::f16>, test_GenericMmm4x4_f16, true);
+test_mmm_kernel_f16_f32!(crate::generic::mmm::GenericMmm4x4<tract_data::prelude::f16, tract_data::prelude::f16, f32, f32>, test_GenericMmm4x4_f16_f32, true);
 
 test_mmm_kernel_f32!(crate::generic::mmm::GenericMmm4x1<f32, f32, f32, f32>, test_GenericMmm4x1_f32, true);
+test_mmm_kernel_f64!(crate::generic::mmm::GenericMmm4x1<f64, f64, f64, f64>, test_GenericMmm4x1_f64, true);
 test_mmm_kernel_i8!(crate::generic::mmm::GenericMmm4x1<i8, i8, i8, i32>, test_GenericMmm4x1_i8, true);
 test_mmm_kernel_u8!(crate::generic::mmm::GenericMmm4x1<u8, u8, u8, i32>, test_GenericMmm4x1_u8, true);
 test_mmm_kernel_i8_i32!(crate::generic::mmm::GenericMmm4x1<i8, i8, i32, i32>, test_GenericMmm4x1_i8_i32, true);
 test_mmm_kernel_i8_u8_i32!(crate::generic::mmm::GenericMmm4x1<i8, u8, i32, i32>, test_GenericMmm4x1_i8_u8_i32, true);
+test_mmm_kernel_f16!(crate::generic::mmm::GenericMmm4x1<tract_data::prelude::f16, tract_data::prelude::f16, tract_data::prelude::f16, tract_data::prelude::f16>, test_GenericMmm4x1_f16, true);
+test_mmm_kernel_f16_f32!(crate::generic::mmm::GenericMmm4x1<tract_data::prelude::f16, tract_data::prelude::f16, f32, f32>, test_GenericMmm4x1_f16_f32, true);
 
 test_mmm_kernel_f32!(crate::generic::mmm::GenericMmmTest3x2<f32, f32, f32, f32>, test_GenericMmmTest3x2_f32, true);
+test_mmm_kernel_f64!(crate::generic::mmm::GenericMmmTest3x2<f64, f64, f64, f64>, test_GenericMmmTest3x2_f64, true);
 test_mmm_kernel_i8!(crate::generic::mmm::GenericMmmTest3x2<i8, i8, i8, i32>, test_GenericMmmTest3x2_i8, true);
 test_mmm_kernel_u8!(crate::generic::mmm::GenericMmmTest3x2<u8, u8, u8, i32>, test_GenericMmmTest3x2_u8, true);
 test_mmm_kernel_i8_i32!(crate::generic::mmm::GenericMmmTest3x2<i8, i8, i32, i32>, test_GenericMmmTest3x2_i8_i32, true);
 test_mmm_kernel_i8_u8_i32!(crate::generic::mmm::GenericMmmTest3x2<i8, u8, i32, i32>, test_GenericMmmTest3x2_i8_u8_i32, true);
+test_mmm_kernel_f16!(crate::generic::mmm::GenericMmmTest3x2<tract_data::prelude::f16, tract_data::prelude::f16, tract_data::prelude::f16, tract_data::prelude::f16>, test_GenericMmmTest3x2_f16, true);
+test_mmm_kernel_f16_f32!(crate::generic::mmm::GenericMmmTest3x2<tract_data::prelude::f16, tract_data::prelude::f16, f32, f32>, test_GenericMmmTest3x2_f16_f32, true);
@@ -1,4 +1,5 @@
 use crate::frame::mmm::*;
+use tract_data::prelude::f16;
 
 pub trait ScaleShiftAndRound {
     fn q_scale(self, mult: i32, shift: usize, policy: RoundingPolicy) -> Self;
@@ -10,6 +11,18 @@ impl ScaleShiftAndRound for f32 {
     }
 }
 
+impl ScaleShiftAndRound for f64 {
+    fn q_scale(self, mult: i32, shift: usize, _policy: RoundingPolicy) -> Self {
+        self * mult as f64 * 2. * 2f64.powi(-(shift as i32))
+    }
+}
+
+impl ScaleShiftAndRound for f16 {
+    fn q_scale(self, mult: i32, shift: usize, policy: RoundingPolicy) -> Self {
+        f16::from(self.0.to_f32().q_scale(mult, shift, policy))
+    }
+}
+
 impl ScaleShiftAndRound for i32 {
     fn q_scale(self, mult: i32, shift: usize, policy: RoundingPolicy) -> Self {
         use RoundingPolicy::*;
@@ -16,6 +16,7 @@ include!(concat!(env!("OUT_DIR"), "/extern_kernel_macro.rs"));
 #[macro_use]
 pub mod frame;
 mod generic;
+pub mod multithread;
 pub use generic::ScaleShiftAndRound;
 #[cfg(target_arch = "x86_64")]
 pub mod x86_64_fma;
@@ -26,7 +27,7 @@ pub mod arm64;
 #[cfg(any(target_arch = "arm", target_arch = "armv7"))]
 pub mod arm32;
 
-pub use self::frame::{element_wise, lut, mmm};
+pub use self::frame::{element_wise, layer_norm, lut, mmm};
 
 use tract_data::prelude::*;
 
@@ -37,6 +38,23 @@ pub struct Ops {
             + Sync,
     >,
     mmv_f32: Box<dyn Fn(Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    mmm_f64: Box<
+        dyn Fn(Option<usize>, Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul>
+            + Send
+            + Sync,
+    >,
+    mmv_f64: Box<dyn Fn(Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    mmm_f16: Box<
+        dyn Fn(Option<usize>, Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul>
+            + Send
+            + Sync,
+    >,
+    mmv_f16: Box<dyn Fn(Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    mmm_f16_f32: Box<
+        dyn Fn(Option<usize>, Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul>
+            + Send
+            + Sync,
+    >,
     qmmm_i8_i32: Box<
         dyn Fn(Option<usize>, Option<usize>, Option<usize>) -> Box<dyn mmm::MatMatMul>
             + Send
@@ -66,6 +84,7 @@ pub struct Ops {
     >,
     pub sigmoid_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
     pub tanh_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
+    pub layer_norm_f32: Box<dyn Fn() -> Box<dyn layer_norm::LayerNorm<f32>> + Send + Sync>,
     pub lut_u8: Box<dyn Fn(&[u8]) -> Box<dyn lut::Lut> + Send + Sync>,
     pub(crate) prefetch: Option<&'static (dyn Fn(*const u8, usize) + Sync + Send)>,
 }
@@ -85,6 +104,13 @@ impl Ops {
             (F32, F32, F32) => {
                 Some(if n == Some(1) { (self.mmv_f32)(m, k) } else { (self.mmm_f32)(m, k, n) })
             }
+            (F64, F64, F64) => {
+                Some(if n == Some(1) { (self.mmv_f64)(m, k) } else { (self.mmm_f64)(m, k, n) })
+            }
+            (F16, F16, F16) => {
+                Some(if n == Some(1) { (self.mmv_f16)(m, k) } else { (self.mmm_f16)(m, k, n) })
+            }
+            (F16, F16, F32) => Some((self.mmm_f16_f32)(m, k, n)),
             (I8, I8, I32) => Some(if n == Some(1) {
                 (self.qmmv_i8_i32)(m, k)
             } else {
@@ -111,6 +137,21 @@ pub fn generic() -> Ops {
         mmv_f32: Box::new(|_, _| {
             Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x1<f32, f32, f32, f32>, f32>::new())
         }),
+        mmm_f64: Box::new(|_, _, _| {
+            Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x4<f64, f64, f64, f64>, f64>::new())
+        }),
+        mmv_f64: Box::new(|_, _| {
+            Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x1<f64, f64, f64, f64>, f64>::new())
+        }),
+        mmm_f16: Box::new(|_, _, _| {
+            Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x4<f16, f16, f16, f16>, f16>::new())
+        }),
+        mmv_f16: Box::new(|_, _| {
+            Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x1<f16, f16, f16, f16>, f16>::new())
+        }),
+        mmm_f16_f32: Box::new(|_, _, _| {
+            Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x4<f16, f16, f32, f32>, f32>::new())
+        }),
         qmmm_i8_i32: Box::new(|_, _, _| {
             Box::new(mmm::MatMatMulImpl::<generic::GenericMmm4x4<i8, i8, i32, i32>, i32>::new())
         }),
@@ -138,6 +179,9 @@ pub fn generic() -> Ops {
         tanh_f32: Box::new(|| {
             Box::new(element_wise::ElementWiseImpl::<generic::STanh4, f32>::new())
         }),
+        layer_norm_f32: Box::new(|| {
+            Box::new(layer_norm::LayerNormImpl::<generic::SLayerNorm8, f32>::new())
+        }),
         lut_u8: Box::new(|table: &[u8]| Box::new(lut::LutImpl::<generic::GenericLut8>::new(table))),
         prefetch: None,
     }
@@ -202,6 +246,27 @@ mod test {
         }
     }
 
+    impl LADatum for f64 {
+        fn strat() -> BoxedStrategy<Self> {
+            (-1000isize..1000).prop_map(|i| i as f64 / 1000.0).boxed()
+        }
+        fn close(&self, other: &Self) -> bool {
+            (self - other).abs() < 0.001
+        }
+    }
+
+    impl LADatum for tract_data::prelude::f16 {
+        fn strat() -> BoxedStrategy<Self> {
+            // multiples of 1/8: products and short sums stay exact in half precision
+            (-8isize..8).prop_map(|i| (i as f32 / 8.0).into()).boxed()
+        }
+        fn close(&self, other: &Self) -> bool {
+            // kernels may accumulate in single precision, so allow for half precision rounding
+            let (a, b) = (self.0.to_f32(), other.0.to_f32());
+            a == b || (a - b).abs() < 0.001 + 0.01 * b.abs()
+        }
+    }
+
     impl LADatum for u8 {
         fn strat() -> BoxedStrategy<Self> {
             any::<u8>().boxed()
@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+#[cfg(feature = "multithread-mm")]
+use std::sync::Arc;
+use std::sync::Mutex;
+
+#[cfg(feature = "multithread-mm")]
+use tract_data::anyhow;
+
+/// Strategy used by the MatMatMul implementations to walk their tiles.
+#[derive(Debug, Clone)]
+pub enum Executor {
+    SingleThread,
+    #[cfg(feature = "multithread-mm")]
+    MultiThread(Arc<rayon::ThreadPool>),
+}
+
+impl Default for Executor {
+    fn default() -> Executor {
+        Executor::SingleThread
+    }
+}
+
+impl Executor {
+    /// Build an executor backed by a dedicated thread pool of `n` threads.
+    #[cfg(feature = "multithread-mm")]
+    pub fn multithread(n: usize) -> anyhow::Result<Executor> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .thread_name(|ix| format!("tract-mmm-{}", ix))
+            .num_threads(n)
+            .build()?;
+        Ok(Executor::MultiThread(Arc::new(pool)))
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref DEFAULT_EXECUTOR: Mutex<Executor> = Mutex::new(Executor::SingleThread);
+}
+
+thread_local! {
+    static TLS_EXECUTOR_OVERRIDE: RefCell<Option<Executor>> = RefCell::new(None);
+}
+
+/// Executor in use for the current thread: the innermost `multithread_tract_scope` one if
+/// any, the process-wide default otherwise.
+pub fn current_tract_executor() -> Executor {
+    if let Some(executor) = TLS_EXECUTOR_OVERRIDE.with(|global| global.borrow().clone()) {
+        executor
+    } else {
+        DEFAULT_EXECUTOR.lock().unwrap().clone()
+    }
+}
+
+/// Set the process-wide executor.
+pub fn set_default_executor(executor: Executor) {
+    *DEFAULT_EXECUTOR.lock().unwrap() = executor;
+}
+
+/// Run `f` with `executor` overriding the default one on the current thread.
+pub fn multithread_tract_scope<R, F: FnOnce() -> R>(executor: Executor, f: F) -> R {
+    let previous = TLS_EXECUTOR_OVERRIDE.with(|tls| tls.replace(Some(executor)));
+    struct Restore(Option<Executor>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            TLS_EXECUTOR_OVERRIDE.with(|tls| *tls.borrow_mut() = previous);
+        }
+    }
+    let _restore = Restore(previous);
+    f()
+}
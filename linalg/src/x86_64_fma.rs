@@ -1,6 +1,7 @@
 use crate::frame::ElementWiseImpl;
 use crate::frame::MatMatMulImpl;
 use crate::Ops;
+use tract_data::prelude::f16;
 
 pub mod mmm;
 pub mod sigmoid;
@@ -17,6 +18,13 @@ pub fn plug(ops: &mut Ops) {
         ops.tanh_f32 = Box::new(|| Box::new(ElementWiseImpl::<tanh::TanhF32, f32>::new()));
         log::info!("mmm_f32, sigmoid_f32, tanh_f32: x86_64/fma activated");
     }
+    if is_x86_feature_detected!("fma") && is_x86_feature_detected!("avx2") {
+        ops.mmm_f64 =
+            Box::new(|_, _, _| Box::new(MatMatMulImpl::<mmm::MatMatMulF64x8x6, f64>::new()));
+        ops.mmv_f64 =
+            Box::new(|_, _| Box::new(MatMatMulImpl::<mmm::MatMatMulF64x8x6, f64>::new()));
+        log::info!("mmm_f64: x86_64/fma activated");
+    }
     if is_x86_feature_detected!("avx2") {
         ops.qmmm_i8_i8 =
             Box::new(|_, _, _| Box::new(MatMatMulImpl::<mmm::MatMatMulI8x8x8, i32>::new()));
@@ -25,4 +33,30 @@ pub fn plug(ops: &mut Ops) {
         });
         log::info!("mmm_i8_i8 and mmm_i8_i32: x86_64/avx2 activated");
     }
+    if is_x86_feature_detected!("avx512f") {
+        ops.mmm_f32 = Box::new(|_, _, _| {
+            Box::new(MatMatMulImpl::<mmm::MatMatMulF32x32x12, f32>::new())
+        });
+        ops.mmv_f32 =
+            Box::new(|_, _| Box::new(MatMatMulImpl::<mmm::MatMatMulF32x128x1, f32>::new()));
+        log::info!("mmm_f32: x86_64/avx512f activated");
+    }
+    if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512vnni") {
+        ops.qmmm_i8_i8 =
+            Box::new(|_, _, _| Box::new(MatMatMulImpl::<mmm::MatMatMulI8x16x16, i32>::new()));
+        ops.qmmm_i8_i32 = Box::new(|_, _, _| {
+            Box::new(MatMatMulImpl::<mmm::MatMatMulI8xI32x16x16, i32>::new())
+        });
+        log::info!("mmm_i8_i8 and mmm_i8_i32: x86_64/avx512vnni activated");
+    }
+    if is_x86_feature_detected!("f16c")
+        && is_x86_feature_detected!("fma")
+        && is_x86_feature_detected!("avx2")
+    {
+        ops.mmm_f16 =
+            Box::new(|_, _, _| Box::new(MatMatMulImpl::<mmm::MatMatMulF16x16x6, f16>::new()));
+        ops.mmv_f16 =
+            Box::new(|_, _| Box::new(MatMatMulImpl::<mmm::MatMatMulF16x16x6, f16>::new()));
+        log::info!("mmm_f16: x86_64/f16c activated");
+    }
 }
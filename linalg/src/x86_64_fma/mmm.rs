@@ -1,14 +1,46 @@
 use crate::frame::mmm::*;
+use tract_data::prelude::f16;
 
+extern_kernel!(fn avx512_mmm_f32_32x12(op: *const MatMatMulKerSpec<f32>) -> isize);
+extern_kernel!(fn avx512_mmm_f32_128x1(op: *const MatMatMulKerSpec<f32>) -> isize);
+extern_kernel!(fn avx512vnni_mmm_i8_16x16(op: *const MatMatMulKerSpec<i32>) -> isize);
+extern_kernel!(fn fma_mmm_f16_16x6(op: *const MatMatMulKerSpec<f16>) -> isize);
 extern_kernel!(fn fma_mmm_f32_16x6(op: *const MatMatMulKerSpec<f32>) -> isize);
 extern_kernel!(fn fma_mmm_f32_64x1(op: *const MatMatMulKerSpec<f32>) -> isize);
+extern_kernel!(fn fma_mmm_f64_8x6(op: *const MatMatMulKerSpec<f64>) -> isize);
 extern_kernel!(fn fma_mmm_i8_8x8(op: *const MatMatMulKerSpec<i32>) -> isize);
 
+MMMKernel!(MatMatMulF32x32x12<f32>, "avx512f", avx512_mmm_f32_32x12; 32, 12; 64, 4; 0, 0);
+MMMKernel!(MatMatMulF32x128x1<f32>, "avx512f", avx512_mmm_f32_128x1; 128, 1; 64, 4; 0, 0);
+MMMKernel!(MatMatMulI8x16x16<i32>, "avx512vnni", avx512vnni_mmm_i8_16x16; 16, 16; 64, 64; 0, 0);
+MMMKernel!(MatMatMulI8xI32x16x16<i32>, "avx512vnni", avx512vnni_mmm_i8_16x16; 16, 16; 64, 64; 0, 0);
+MMMKernel!(MatMatMulF16x16x6<f16>, "f16c", fma_mmm_f16_16x6; 16, 6; 32, 2; 0, 0);
 MMMKernel!(MatMatMulF32x16x6<f32>, "fma", fma_mmm_f32_16x6; 16, 6; 32, 4; 0, 0);
 MMMKernel!(MatMatMulF32x64x1<f32>, "fma", fma_mmm_f32_64x1; 64, 1; 32, 4; 0, 0);
+MMMKernel!(MatMatMulF64x8x6<f64>, "fma", fma_mmm_f64_8x6; 8, 6; 32, 8; 0, 0);
 MMMKernel!(MatMatMulI8x8x8<i32>, "avx2", fma_mmm_i8_8x8; 8, 8; 32, 4; 0, 0);
 MMMKernel!(MatMatMulI8xI32x8x8<i32>, "avx2", fma_mmm_i8_8x8; 8, 8; 32, 4; 0, 0);
 
+test_mmm_kernel_f32!(
+    crate::x86_64_fma::mmm::MatMatMulF32x32x12,
+    test_MatMatMulF32x32x12,
+    is_x86_feature_detected!("avx512f")
+);
+
+test_mmm_kernel_f32!(
+    crate::x86_64_fma::mmm::MatMatMulF32x128x1,
+    test_MatMatMulF32x128x1,
+    is_x86_feature_detected!("avx512f")
+);
+
+test_mmm_kernel_f16!(
+    crate::x86_64_fma::mmm::MatMatMulF16x16x6,
+    test_MatMatMulF16x16x6,
+    is_x86_feature_detected!("f16c")
+        && is_x86_feature_detected!("fma")
+        && is_x86_feature_detected!("avx2")
+);
+
 test_mmm_kernel_f32!(
     crate::x86_64_fma::mmm::MatMatMulF32x16x6,
     test_MatMatMulF32x16x6,
@@ -21,6 +53,12 @@ test_mmm_kernel_f32!(
     is_x86_feature_detected!("fma")
 );
 
+test_mmm_kernel_f64!(
+    crate::x86_64_fma::mmm::MatMatMulF64x8x6,
+    test_MatMatMulF64x8x6,
+    is_x86_feature_detected!("fma")
+);
+
 test_mmm_kernel_i8!(
     crate::x86_64_fma::mmm::MatMatMulI8x8x8,
     test_MatMatMulI8x8x8,
@@ -32,3 +70,15 @@ test_mmm_kernel_i8_i32!(
     test_MatMatMulI8xI32x8x8,
     is_x86_feature_detected!("avx2")
 );
+
+test_mmm_kernel_i8!(
+    crate::x86_64_fma::mmm::MatMatMulI8x16x16,
+    test_MatMatMulI8x16x16,
+    is_x86_feature_detected!("avx512vnni")
+);
+
+test_mmm_kernel_i8_i32!(
+    crate::x86_64_fma::mmm::MatMatMulI8xI32x16x16,
+    test_MatMatMulI8xI32x16x16,
+    is_x86_feature_detected!("avx512vnni")
+);
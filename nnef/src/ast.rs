@@ -21,18 +21,39 @@ impl ProtoModel {
 #[derive(Clone, Debug, PartialEq)]
 pub enum QuantFormat {
     Linear { params: QParams, bits: i8, signed: bool },
+    LinearPerAxis { params: AxisQParams, bits: i8, signed: bool },
 }
 
 impl QuantFormat {
-    pub fn datum_type(&self) -> DatumType {
+    pub fn datum_type(&self) -> TractResult<DatumType> {
         match self {
-            QuantFormat::Linear { params, bits, signed } => match (bits, signed) {
-                (8, true) => DatumType::QI8(*params),
-                (8, false) => DatumType::QU8(*params),
-                (32, true) => DatumType::I32,
-                (32, false) => DatumType::U32,
-                _ => todo!(),
-            },
+            QuantFormat::Linear { params, bits, signed } => {
+                Self::bits_datum_type(*bits, *signed, Some(*params))
+            }
+            // per-axis parameters do not fit in the datum type, they live on the fact
+            QuantFormat::LinearPerAxis { bits, signed, .. } => {
+                Self::bits_datum_type(*bits, *signed, None)
+            }
+        }
+    }
+
+    /// Storage type of `bits` wide values, quantized with `params` if they are 8 bits wide.
+    fn bits_datum_type(bits: i8, signed: bool, params: Option<QParams>) -> TractResult<DatumType> {
+        Ok(match (bits, signed, params) {
+            (8, true, Some(params)) => DatumType::QI8(params),
+            (8, false, Some(params)) => DatumType::QU8(params),
+            (8, true, None) => DatumType::I8,
+            (8, false, None) => DatumType::U8,
+            (32, true, _) => DatumType::I32,
+            (32, false, _) => DatumType::U32,
+            _ => bail!("Unsupported quantization bits {}", bits),
+        })
+    }
+
+    pub fn axis_qparams(&self) -> Option<&AxisQParams> {
+        match self {
+            QuantFormat::LinearPerAxis { params, .. } => Some(params),
+            _ => None,
         }
     }
 }
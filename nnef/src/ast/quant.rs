@@ -1,8 +1,10 @@
 use std::str::FromStr;
 
-use nom::branch::permutation;
+use nom::branch::{alt, permutation};
 use nom::character::complete::digit1;
-use nom::combinator::map_res;
+use nom::combinator::{map, map_res, recognize};
+use nom::error::{Error, ErrorKind};
+use nom::sequence::{delimited, pair};
 use tract_core::internal::*;
 
 use nom::{bytes::complete::*, multi::*};
@@ -31,7 +33,19 @@ fn quantization(i: &str) -> IResult<&str, (String, QuantFormat)> {
 }
 
 fn integer_numeric<T: FromStr>(i: &str) -> IResult<&str, T> {
-    map_res(digit1, |s: &str| s.parse::<T>())(i)
+    map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<T>())(i)
+}
+
+// <scalars> ::= <f> | "[" <f> ("," <f>)* "]"
+fn scalars<'s, T, F>(f: F) -> impl Fn(&'s str) -> IResult<&'s str, Vec<T>>
+where
+    F: Fn(&'s str) -> IResult<&'s str, T> + Copy,
+{
+    move |i: &str| {
+        alt((map(f, |x| vec![x]), delimited(stag("["), separated_list1(stag(","), f), stag("]"))))(
+            i,
+        )
+    }
 }
 
 // <qparam> ::= "<identifier>": <qparam>
@@ -39,30 +53,42 @@ fn qparam(i: &str) -> IResult<&str, QuantFormat> {
     let (i, id) =
         nom::branch::alt((stag("linear_quantize"), stag("zero_point_linear_quantize")))(i)?;
     let (i, _) = stag("(")(i)?;
-    let (i, params, bits, signed) = match &*id {
+    let (i, format) = match &*id {
         "linear_quantize" => {
             let (i, (bits, max, min)) =
                 permutation((arg("bits", integer_numeric), arg("max", float), arg("min", float)))(
                     i,
                 )?;
 
-            (i, QParams::MinMax { min, max }, bits, true)
+            (i, QuantFormat::Linear { params: QParams::MinMax { min, max }, bits, signed: true })
         }
         "zero_point_linear_quantize" => {
-            let (i, (zero_point, scale, bits, signed, _)) = permutation((
-                arg("zero_point", integer_numeric),
-                arg("scale", float),
+            let (i, (zero_points, scales, bits, signed, _, axis)) = permutation((
+                arg("zero_point", scalars(integer_numeric)),
+                arg("scale", scalars(float)),
                 arg("bits", integer_numeric),
                 arg("signed", logical_literal),
                 opt(arg("symmetric", logical_literal)),
+                opt(arg("axis", integer_numeric)),
             ))(i)?;
-            (i, QParams::ZpScale { zero_point, scale }, bits, signed)
+            // per-axis parameters come as arrays, along with the axis they apply to
+            match axis {
+                Some(axis) if zero_points.len() == scales.len() => {
+                    let params = AxisQParams { axis, zero_points, scales };
+                    (i, QuantFormat::LinearPerAxis { params, bits, signed })
+                }
+                None if zero_points.len() == 1 && scales.len() == 1 => {
+                    let params = QParams::ZpScale { zero_point: zero_points[0], scale: scales[0] };
+                    (i, QuantFormat::Linear { params, bits, signed })
+                }
+                _ => return Err(nom::Err::Error(Error::new(i, ErrorKind::Verify))),
+            }
         }
         _ => unreachable!(),
     };
 
     let (i, _) = stag(")")(i)?;
-    Ok((i, QuantFormat::Linear { params, bits, signed }))
+    Ok((i, format))
 }
 // <arg>(<id>, <f>) ::= <id> "=" <f> ","
 fn arg<'s, T, F>(name: &'static str, f: F) -> impl Fn(&'s str) -> IResult<&'s str, T>
@@ -86,10 +112,17 @@ pub(crate) fn write_quant_format(
     match format {
         QuantFormat::Linear {
             params: QParams::ZpScale {zero_point, scale}, bits, signed
-        } => writeln!(w, "{}: zero_point_linear_quantize(zero_point = {}, scale = {:.9}, bits = {}, signed = {}, symmetric = {});", name, zero_point, scale, bits, signed, zero_point == 0)?,
+        } => writeln!(w, "\"{}\": zero_point_linear_quantize(zero_point = {}, scale = {:.9}, bits = {}, signed = {}, symmetric = {});", name, zero_point, scale, bits, signed, zero_point == 0)?,
         QuantFormat::Linear {
             params: QParams::MinMax {min, max}, bits, signed: _
-        } => writeln!(w, "{}: linear_quantize(max = {:.9}, min = {:.9}, bits = {});", name, max, min, bits)?,
+        } => writeln!(w, "\"{}\": linear_quantize(max = {:.9}, min = {:.9}, bits = {});", name, max, min, bits)?,
+        QuantFormat::LinearPerAxis {
+            params: AxisQParams { axis, zero_points, scales }, bits, signed
+        } => writeln!(w, "\"{}\": zero_point_linear_quantize(zero_point = [{}], scale = [{}], bits = {}, signed = {}, symmetric = {}, axis = {});",
+            name,
+            zero_points.iter().map(|zp| zp.to_string()).collect::<Vec<_>>().join(", "),
+            scales.iter().map(|s| format!("{:.9}", s)).collect::<Vec<_>>().join(", "),
+            bits, signed, zero_points.iter().all(|zp| *zp == 0), axis)?,
     }
     Ok(())
 }
@@ -128,6 +161,56 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_negative_zero_point() {
+        assert_eq!(
+            p(qparam, "zero_point_linear_quantize(zero_point = -12, scale = 0.5, bits = 8, signed = true)"),
+            QuantFormat::Linear {
+                params: QParams::ZpScale { zero_point: -12, scale: 0.5 },
+                bits: 8,
+                signed: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_unsupported_bits() {
+        let format = p(
+            qparam,
+            "zero_point_linear_quantize(zero_point = 0, scale = 0.5, bits = 16, signed = true)",
+        );
+        assert!(format.datum_type().is_err());
+    }
+
+    #[test]
+    fn test_per_axis() {
+        assert_eq!(
+            p(qparam, "zero_point_linear_quantize(zero_point = [0, -3], scale = [0.5, 0.25], bits = 8, signed = true, symmetric = false, axis = 0)"),
+            QuantFormat::LinearPerAxis {
+                params: AxisQParams { axis: 0, zero_points: vec![0, -3], scales: vec![0.5, 0.25] },
+                bits: 8,
+                signed: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_per_axis_roundtrip() {
+        let format = QuantFormat::LinearPerAxis {
+            params: AxisQParams {
+                axis: 1,
+                zero_points: vec![1, 2, 3],
+                scales: vec![0.5, 1.0, 2.0],
+            },
+            bits: 8,
+            signed: false,
+        };
+        let mut buf = vec![];
+        write_quant_format(&mut buf, "t".to_string(), format.clone()).unwrap();
+        let parsed = parse_quantization(std::str::from_utf8(&buf).unwrap()).unwrap();
+        assert_eq!(parsed, vec!(("t".to_string(), format)));
+    }
+
     #[test]
     fn test_quantization() {
         assert_eq!(
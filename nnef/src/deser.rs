@@ -12,10 +12,11 @@ pub struct ModelBuilder<'a> {
 
 impl<'mb> ModelBuilder<'mb> {
     pub fn new(framework: &'mb Nnef, proto_model: &'mb ProtoModel) -> ModelBuilder<'mb> {
+        let model = TypedModel { share_tensors: framework.share_tensors, ..TypedModel::default() };
         ModelBuilder {
             registries: vec!["tract_nnef".to_string()],
             framework,
-            model: TypedModel::default(),
+            model,
             naming_scopes: vec![],
             scopes: vec![],
             proto_model,
@@ -84,15 +85,14 @@ impl<'mb> ModelBuilder<'mb> {
         // todo: can i relax the outlet id constraint ?
         for assignment in body {
             let identifiers = assignment.left.to_identifiers()?;
-            let datum_types = identifiers
+            let quant_formats = identifiers
                 .iter()
-                .map(|s| {
-                    self.proto_model
-                        .quantization
-                        .as_ref()
-                        .and_then(|qm| qm.get(*s).map(|q| q.datum_type()))
-                })
+                .map(|s| self.proto_model.quantization.as_ref().and_then(|qm| qm.get(*s)).cloned())
                 .collect::<Vec<_>>();
+            let datum_types = quant_formats
+                .iter()
+                .map(|q| q.as_ref().map(|q| q.datum_type()).transpose())
+                .collect::<TractResult<Vec<_>>>()?;
             self.naming_scopes.push(identifiers[0].to_string());
             let values = if identifiers.len() == 1 {
                 let value: OutletId = assignment
@@ -124,11 +124,15 @@ impl<'mb> ModelBuilder<'mb> {
             for (id, outlet) in identifiers.iter().zip(values.iter()) {
                 self.scopes.last_mut().unwrap().insert(id.to_string(), Value::Wire(*outlet));
             }
-            for (qparam, value) in datum_types.into_iter().zip(values.iter()) {
-                if let Some(qparam) = qparam {
+            for (format, value) in quant_formats.into_iter().zip(values.iter()) {
+                if let Some(format) = format {
                     let node = self.model.node_mut(value.node);
                     let output_fact = &mut node.outputs[value.slot].fact;
-                    output_fact.datum_type = qparam;
+                    output_fact.datum_type = format.datum_type()?;
+                    if let Some(qp) = format.axis_qparams() {
+                        output_fact.axis_qparams = Some(Arc::new(qp.clone()));
+                        output_fact.consistent()?;
+                    }
                 }
             }
             self.naming_scopes.pop();
@@ -2,7 +2,7 @@ use crate::ast::quant::write_quant_format;
 use crate::ast::{ProtoModel, QuantFormat};
 use crate::internal::*;
 use std::io::Read;
-#[cfg(target_family="unix")]
+#[cfg(target_family = "unix")]
 use std::os::unix::prelude::OsStrExt;
 use std::path::Path;
 
@@ -13,11 +13,20 @@ pub fn stdlib() -> Vec<FragmentDef> {
 pub struct Nnef {
     pub stdlib: Vec<FragmentDef>,
     pub registries: Vec<Registry>,
+    /// Memory-map the tensors when loading a model from a directory.
+    pub mmap: bool,
+    /// Register the tensors of the loaded models in the process-wide `TensorStore`.
+    pub share_tensors: bool,
 }
 
 impl Nnef {
     pub fn new() -> Nnef {
-        Nnef { stdlib: stdlib(), registries: vec![crate::ops::tract_nnef()] }
+        Nnef {
+            stdlib: stdlib(),
+            registries: vec![crate::ops::tract_nnef()],
+            mmap: false,
+            share_tensors: false,
+        }
     }
 
     pub fn with_registry(mut self, registry: Registry) -> Nnef {
@@ -30,6 +39,28 @@ impl Nnef {
         self
     }
 
+    /// Allow dumping and loading codegen operators, with their kernel choices and pre-packed
+    /// weights. Such models are tied to the kernels of the platform they were optimized on.
+    pub fn with_tract_lir(mut self) -> Self {
+        self.registries.push(crate::ops::tract_lir());
+        self
+    }
+
+    /// Borrow tensor data from memory mappings of the `.dat` files instead of reading them
+    /// when loading a model from a directory.
+    #[cfg(feature = "mapr")]
+    pub fn with_mmap(mut self) -> Self {
+        self.mmap = true;
+        self
+    }
+
+    /// Let the loaded models share identical constants and pre-packed weights with the other
+    /// models of the process opting in.
+    pub fn with_shared_tensors(mut self) -> Self {
+        self.share_tensors = true;
+        self
+    }
+
     pub fn translate(
         &self,
         proto_model: &ProtoModel,
@@ -145,6 +176,13 @@ impl tract_core::prelude::Framework<ProtoModel, TypedModel> for Nnef {
                 .skip(path.components().count())
                 .collect::<std::path::PathBuf>();
             let mut stream = std::fs::File::open(entry.path())?;
+            #[cfg(feature = "mapr")]
+            if self.mmap && !is_hidden(&subpath) && is_tensor(&subpath) {
+                let tensor = crate::tensors::read_tensor_mmap(&stream)
+                    .with_context(|| format!("Mapping {:?}", entry.path()))?;
+                tensors.push((tensor_id(&subpath)?, tensor.into_arc_tensor()));
+                continue;
+            }
             read_stream(&subpath, &mut stream, &mut text, &mut tensors, &mut quantization)?;
         }
         let text = text.ok_or_else(|| format_err!("Model must contain graph.nnef at top level"))?;
@@ -197,23 +235,16 @@ fn read_stream<R: std::io::Read>(
     tensors: &mut Vec<(String, Arc<Tensor>)>,
     quantization: &mut Option<HashMap<String, QuantFormat>>,
 ) -> TractResult<()> {
-    // ignore path with any component starting with "." (because OSX's tar is weird)
-    #[cfg(target_family="unix")]
-    if path.components().any(|name| name.as_os_str().as_bytes().get(0) == Some(&b'.')) {
-        return Ok(())
+    if is_hidden(path) {
+        return Ok(());
     }
     if path.file_name().map(|n| n == "graph.nnef").unwrap_or(false) {
         let mut t = String::new();
         reader.read_to_string(&mut t)?;
         *text = Some(t);
-    } else if path.extension().map(|e| e == "dat").unwrap_or(false) {
-        let mut path = path.to_path_buf();
-        path.set_extension("");
-        let id = path
-            .to_str()
-            .ok_or_else(|| format_err!("Badly encoded filename for tensor: {:?}", path))?;
+    } else if is_tensor(path) {
         let tensor = crate::tensors::read_tensor(reader)?;
-        tensors.push((id.to_string(), tensor.into_arc_tensor()));
+        tensors.push((tensor_id(path)?, tensor.into_arc_tensor()));
     } else if path.file_name().map(|n| n == "graph.quant").unwrap_or(false) {
         let mut t = String::new();
         reader.read_to_string(&mut t)?;
@@ -226,3 +257,26 @@ fn read_stream<R: std::io::Read>(
     }
     Ok(())
 }
+
+// ignore path with any component starting with "." (because OSX's tar is weird)
+#[allow(unused_variables)]
+fn is_hidden(path: &std::path::Path) -> bool {
+    #[cfg(target_family = "unix")]
+    if path.components().any(|name| name.as_os_str().as_bytes().get(0) == Some(&b'.')) {
+        return true;
+    }
+    false
+}
+
+fn is_tensor(path: &std::path::Path) -> bool {
+    path.extension().map(|e| e == "dat").unwrap_or(false)
+}
+
+fn tensor_id(path: &std::path::Path) -> TractResult<String> {
+    let mut path = path.to_path_buf();
+    path.set_extension("");
+    let id = path
+        .to_str()
+        .ok_or_else(|| format_err!("Badly encoded filename for tensor: {:?}", path))?;
+    Ok(id.to_string())
+}
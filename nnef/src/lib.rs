@@ -27,7 +27,7 @@ pub mod internal {
     pub use crate::framework::Nnef;
     pub use crate::prelude::*;
     pub use crate::registry::*;
-    pub use crate::ser::{invocation, logical, numeric, string, IntoAst};
+    pub use crate::ser::{invocation, ints, logical, numeric, string, IntoAst};
     pub use std::any::TypeId;
     pub use tract_core::internal::*;
 }
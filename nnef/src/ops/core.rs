@@ -3,15 +3,27 @@ use tract_core::ops;
 
 mod broadcast;
 mod cast;
+mod cumsum;
 mod downsample;
+mod einsum;
 mod gather;
+mod grid_sample;
+mod layer_norm;
+mod max_unpool;
+mod non_max_suppression;
 mod one_hot;
 mod qconv;
 mod qmatmul;
+mod range;
 mod reduce;
-mod scan;
+mod reverse_sequence;
+mod roi_align;
+pub(crate) mod scan;
 mod scatter;
 mod source;
+mod topk;
+mod trilu;
+mod unique;
 
 pub fn register(registry: &mut Registry) {
     registry.register_unit_element_wise("tract_core_tan", &ops::math::Tan {});
@@ -40,13 +52,25 @@ pub fn register(registry: &mut Registry) {
     );
     broadcast::register(registry);
     cast::register(registry);
+    cumsum::register(registry);
     downsample::register(registry);
+    einsum::register(registry);
     gather::register(registry);
+    grid_sample::register(registry);
+    layer_norm::register(registry);
+    max_unpool::register(registry);
+    non_max_suppression::register(registry);
     one_hot::register(registry);
     qconv::register(registry);
     qmatmul::register(registry);
+    range::register(registry);
     reduce::register(registry);
+    reverse_sequence::register(registry);
+    roi_align::register(registry);
     scatter::register(registry);
     scan::register(registry);
     source::register(registry);
+    topk::register(registry);
+    trilu::register(registry);
+    unique::register(registry);
 }
@@ -20,6 +20,10 @@ fn cast_parameters() -> Vec<Parameter> {
 fn cast_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
     let op = node.op_as::<ElementWiseOp>().unwrap().0.downcast_ref::<Cast>().unwrap();
     let input = ast.mapping[&node.inputs[0]].clone();
+    // quantized types are carried by the quantization file
+    if op.to.is_quantized() {
+        return Ok(Some(invocation("tract_core_cast", &[input], &[])));
+    }
     Ok(Some(invocation(
         "tract_core_cast",
         &[input],
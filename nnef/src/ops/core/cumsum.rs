@@ -0,0 +1,42 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::math::CumSum;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<CumSum>(), cumsum_dump);
+    registry.register_primitive(
+        "tract_core_cumsum",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Logical.named("exclusive").default(false),
+            TypeName::Logical.named("reverse").default(false),
+        ],
+        cumsum_load,
+    );
+}
+
+fn cumsum_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<CumSum>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_core_cumsum",
+        &[input],
+        &[
+            ("axis", numeric(op.axis)),
+            ("exclusive", logical(op.exclusive)),
+            ("reverse", logical(op.reverse)),
+        ],
+    )))
+}
+
+fn cumsum_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as(builder, "axis")?;
+    let exclusive = invocation.named_arg_as(builder, "exclusive")?;
+    let reverse = invocation.named_arg_as(builder, "reverse")?;
+    builder.wire(CumSum { axis, exclusive, reverse }, &[input])
+}
@@ -0,0 +1,35 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::einsum::{EinSum, Expr};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<EinSum>(), einsum_dump);
+    registry.register_primitive(
+        "tract_core_einsum",
+        &[TypeName::Scalar.tensor().array().named("inputs"), TypeName::String.named("expr")],
+        einsum_load,
+    );
+}
+
+fn einsum_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<EinSum>().unwrap();
+    let inputs = node.inputs.iter().map(|i| (*ast.mapping[i]).clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_core_einsum",
+        &[array(&inputs).into()],
+        &[("expr", string(op.expr.to_string()))],
+    )))
+}
+
+fn einsum_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let inputs: TVec<OutletId> = invocation.named_arg_as(builder, "inputs")?;
+    let expr: String = invocation.named_arg_as(builder, "expr")?;
+    let ranks = inputs
+        .iter()
+        .map(|i| Ok(builder.model.outlet_fact(*i)?.rank()))
+        .collect::<TractResult<TVec<_>>>()?;
+    builder.wire(EinSum::new(Expr::parse(&expr, &ranks)?), &inputs)
+}
@@ -0,0 +1,64 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::{GridPadding, GridSample, Interpolation};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<GridSample>(), grid_sample_dump);
+    registry.register_primitive(
+        "tract_core_grid_sample",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Scalar.tensor().named("grid"),
+            TypeName::String.named("mode").default("bilinear"),
+            TypeName::String.named("padding_mode").default("zeros"),
+            TypeName::Logical.named("align_corners").default(false),
+        ],
+        grid_sample_load,
+    );
+}
+
+fn grid_sample_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<GridSample>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    let mode = match op.interpolation {
+        Interpolation::Bilinear => "bilinear",
+        Interpolation::Nearest => "nearest",
+    };
+    let padding_mode = match op.padding {
+        GridPadding::Zeros => "zeros",
+        GridPadding::Border => "border",
+        GridPadding::Reflection => "reflection",
+    };
+    Ok(Some(invocation(
+        "tract_core_grid_sample",
+        &inputs,
+        &[
+            ("mode", string(mode)),
+            ("padding_mode", string(padding_mode)),
+            ("align_corners", logical(op.align_corners)),
+        ],
+    )))
+}
+
+fn grid_sample_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let grid = invocation.named_arg_as(builder, "grid")?;
+    let mode: String = invocation.named_arg_as(builder, "mode")?;
+    let interpolation = match &*mode {
+        "bilinear" => Interpolation::Bilinear,
+        "nearest" => Interpolation::Nearest,
+        _ => bail!("Unsupported grid sample mode: {}", mode),
+    };
+    let padding_mode: String = invocation.named_arg_as(builder, "padding_mode")?;
+    let padding = match &*padding_mode {
+        "zeros" => GridPadding::Zeros,
+        "border" => GridPadding::Border,
+        "reflection" => GridPadding::Reflection,
+        _ => bail!("Unsupported grid sample padding mode: {}", padding_mode),
+    };
+    let align_corners = invocation.named_arg_as(builder, "align_corners")?;
+    builder.wire(GridSample { interpolation, padding, align_corners }, &[input, grid])
+}
@@ -0,0 +1,54 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::cnn::MaxUnpool;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<MaxUnpool>(), max_unpool_dump);
+    registry.register_primitive(
+        "tract_core_max_unpool",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.tensor().named("indices"),
+            TypeName::Integer.tensor().named("output_shape"),
+            TypeName::Integer.array().named("kernel_shape"),
+            TypeName::Integer.array().named("strides"),
+            TypeName::Integer.array().named("pads_before"),
+            TypeName::Integer.array().named("pads_after"),
+        ],
+        max_unpool_load,
+    );
+}
+
+fn max_unpool_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<MaxUnpool>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_core_max_unpool",
+        &inputs,
+        &[
+            ("kernel_shape", ints(&op.kernel_shape)),
+            ("strides", ints(&op.strides)),
+            ("pads_before", ints(&op.pads.iter().map(|p| p.0).collect::<TVec<_>>())),
+            ("pads_after", ints(&op.pads.iter().map(|p| p.1).collect::<TVec<_>>())),
+        ],
+    )))
+}
+
+fn max_unpool_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let mut inputs: TVec<OutletId> = tvec!(
+        invocation.named_arg_as(builder, "input")?,
+        invocation.named_arg_as(builder, "indices")?
+    );
+    if invocation.get_named_arg("output_shape").is_some() {
+        inputs.push(invocation.named_arg_as(builder, "output_shape")?);
+    }
+    let kernel_shape = invocation.named_arg_as(builder, "kernel_shape")?;
+    let strides = invocation.named_arg_as(builder, "strides")?;
+    let before: TVec<usize> = invocation.named_arg_as(builder, "pads_before")?;
+    let after: TVec<usize> = invocation.named_arg_as(builder, "pads_after")?;
+    let pads = before.into_iter().zip(after).collect();
+    builder.wire(MaxUnpool { kernel_shape, strides, pads }, &inputs)
+}
@@ -0,0 +1,46 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::{BoxRepr, NonMaxSuppression};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<NonMaxSuppression>(), nms_dump);
+    registry.register_primitive(
+        "tract_core_non_max_suppression",
+        &[
+            TypeName::Scalar.tensor().named("boxes"),
+            TypeName::Scalar.tensor().named("scores"),
+            TypeName::Integer.tensor().named("max_output_boxes_per_class"),
+            TypeName::Scalar.tensor().named("iou_threshold"),
+            TypeName::Scalar.tensor().named("score_threshold"),
+            TypeName::Logical.named("center_point_box").default(false),
+        ],
+        nms_load,
+    );
+}
+
+fn nms_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<NonMaxSuppression>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_core_non_max_suppression",
+        &inputs,
+        &[("center_point_box", logical(op.box_repr == BoxRepr::CenterWidthHeight))],
+    )))
+}
+
+fn nms_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let boxes = invocation.named_arg_as(builder, "boxes")?;
+    let scores = invocation.named_arg_as(builder, "scores")?;
+    let max = invocation.named_arg_as(builder, "max_output_boxes_per_class")?;
+    let iou = invocation.named_arg_as(builder, "iou_threshold")?;
+    let score = invocation.named_arg_as(builder, "score_threshold")?;
+    let box_repr = if invocation.named_arg_as(builder, "center_point_box")? {
+        BoxRepr::CenterWidthHeight
+    } else {
+        BoxRepr::TwoPoints
+    };
+    builder.wire(NonMaxSuppression::new(box_repr), &[boxes, scores, max, iou, score])
+}
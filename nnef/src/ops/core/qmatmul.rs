@@ -46,7 +46,13 @@ pub fn qparams_to_rvalues(
         ($a:ident, $typ:ty) => {
             match &params.$a {
                 AttrOrInput::Attr(t) => {
-                    numeric(t.cast_to_dt(<$typ>::datum_type())?.to_scalar::<$typ>()?)
+                    let t = t.cast_to_dt(<$typ>::datum_type())?;
+                    if t.rank() == 0 {
+                        numeric(t.to_scalar::<$typ>()?)
+                    } else {
+                        // per-axis parameters
+                        array(t.as_slice::<$typ>()?.iter().map(numeric).collect::<Vec<_>>())
+                    }
                 }
                 AttrOrInput::Input(i) => (*ast_mapping[&node_inputs[*i]]).clone(),
             }
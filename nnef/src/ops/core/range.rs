@@ -0,0 +1,31 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::Range;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Range>(), range_dump);
+    registry.register_primitive(
+        "tract_core_range",
+        &[
+            TypeName::Scalar.tensor().named("start"),
+            TypeName::Scalar.tensor().named("end"),
+            TypeName::Scalar.tensor().named("step"),
+        ],
+        range_load,
+    );
+}
+
+fn range_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation("tract_core_range", &inputs, &[])))
+}
+
+fn range_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let start = invocation.named_arg_as(builder, "start")?;
+    let end = invocation.named_arg_as(builder, "end")?;
+    let step = invocation.named_arg_as(builder, "step")?;
+    builder.wire(Range::default(), &[start, end, step])
+}
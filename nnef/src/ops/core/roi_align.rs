@@ -0,0 +1,67 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::{RoiAlign, RoiPooling};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<RoiAlign>(), roi_align_dump);
+    registry.register_primitive(
+        "tract_core_roi_align",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Scalar.tensor().named("rois"),
+            TypeName::Integer.tensor().named("batch_indices"),
+            TypeName::String.named("mode").default("avg"),
+            TypeName::Integer.named("output_height"),
+            TypeName::Integer.named("output_width"),
+            TypeName::Integer.named("sampling_ratio").default(0),
+            TypeName::Scalar.named("spatial_scale").default(1.0),
+            TypeName::Logical.named("half_pixel").default(true),
+        ],
+        roi_align_load,
+    );
+}
+
+fn roi_align_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<RoiAlign>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    let mode = match op.pooling {
+        RoiPooling::Avg => "avg",
+        RoiPooling::Max => "max",
+    };
+    Ok(Some(invocation(
+        "tract_core_roi_align",
+        &inputs,
+        &[
+            ("mode", string(mode)),
+            ("output_height", numeric(op.output_height)),
+            ("output_width", numeric(op.output_width)),
+            ("sampling_ratio", numeric(op.sampling_ratio)),
+            ("spatial_scale", numeric(op.spatial_scale)),
+            ("half_pixel", logical(op.half_pixel)),
+        ],
+    )))
+}
+
+fn roi_align_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let rois = invocation.named_arg_as(builder, "rois")?;
+    let batch_indices = invocation.named_arg_as(builder, "batch_indices")?;
+    let mode: String = invocation.named_arg_as(builder, "mode")?;
+    let pooling = match &*mode {
+        "avg" => RoiPooling::Avg,
+        "max" => RoiPooling::Max,
+        _ => bail!("Unsupported roi align mode: {}", mode),
+    };
+    let op = RoiAlign {
+        pooling,
+        output_height: invocation.named_arg_as(builder, "output_height")?,
+        output_width: invocation.named_arg_as(builder, "output_width")?,
+        sampling_ratio: invocation.named_arg_as(builder, "sampling_ratio")?,
+        spatial_scale: invocation.named_arg_as(builder, "spatial_scale")?,
+        half_pixel: invocation.named_arg_as(builder, "half_pixel")?,
+    };
+    builder.wire(op, &[input, rois, batch_indices])
+}
@@ -8,49 +8,58 @@ use tract_itertools::Itertools;
 
 pub fn register(registry: &mut Registry) {
     registry.register_dumper(TypeId::of::<ops::scan::Scan>(), ser_scan);
-    registry.register_primitive(
-        "tract_core_scan",
-        &[
-            TypeName::String.named("body"),
-            ast::TypeSpec::Tuple(vec![
-                TypeName::String.spec(),   // body param name
-                TypeName::Scalar.tensor(), // input
-                TypeName::Integer.spec(),  // axis
-                TypeName::Integer.spec(),  // step
-            ])
-            .array()
-            .named("scan"),
-            ast::TypeSpec::Tuple(vec![
-                TypeName::String.spec(),   // body param name
-                TypeName::Scalar.tensor(), // input
-            ])
-            .array()
-            .named("full"),
-            ast::TypeSpec::Tuple(vec![
-                TypeName::String.spec(),   // body param name
-                TypeName::Scalar.tensor(), // initializer
-                TypeName::String.spec(),   // body result name
-            ])
-            .array()
-            .named("state"),
-            ast::TypeSpec::Tuple(vec![
-                TypeName::String.spec(),  // body param name
-                TypeName::String.spec(),  // "full" or "last"
-                TypeName::Integer.spec(), // axis
-            ])
-            .array()
-            .named("output"),
-            TypeName::Integer.spec()    // if present, assumes B is first axis in all inputs
-                    .named("seq_length")
-                    .default(-1),
-            TypeName::Integer.spec().named("skip").default(0), // needed for pulse
-        ],
-        de_scan,
-    );
+    registry.register_primitive("tract_core_scan", &scan_parameters(), de_scan);
+}
+
+pub(crate) fn scan_parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::String.named("body"),
+        ast::TypeSpec::Tuple(vec![
+            TypeName::String.spec(),   // body param name
+            TypeName::Scalar.tensor(), // input
+            TypeName::Integer.spec(),  // axis
+            TypeName::Integer.spec(),  // step
+        ])
+        .array()
+        .named("scan"),
+        ast::TypeSpec::Tuple(vec![
+            TypeName::String.spec(),   // body param name
+            TypeName::Scalar.tensor(), // input
+        ])
+        .array()
+        .named("full"),
+        ast::TypeSpec::Tuple(vec![
+            TypeName::String.spec(),   // body param name
+            TypeName::Scalar.tensor(), // initializer
+            TypeName::String.spec(),   // body result name
+        ])
+        .array()
+        .named("state"),
+        ast::TypeSpec::Tuple(vec![
+            TypeName::String.spec(),  // body param name
+            TypeName::String.spec(),  // "full" or "last"
+            TypeName::Integer.spec(), // axis
+        ])
+        .array()
+        .named("output"),
+        TypeName::Integer.spec()    // if present, assumes B is first axis in all inputs
+            .named("seq_length")
+            .default(-1),
+        TypeName::Integer.spec().named("skip").default(0), // needed for pulse
+    ]
 }
 
 fn ser_scan(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
     let op = node.op().downcast_ref::<Scan>().unwrap();
+    ser_scan_op(ast, node, op, "tract_core_scan")
+}
+
+pub(crate) fn ser_scan_op(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &Scan,
+    id: &str,
+) -> TractResult<Option<Arc<RValue>>> {
     let (mut body, body_tensors) = crate::ser::to_fragment_def(ast, &op.body)?;
     body.decl.id = format!("scan_body_{}", ast.fragments.len());
     let mut scan = vec![];
@@ -121,7 +130,7 @@ fn ser_scan(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValu
         };
     }
     let invoke = invocation(
-        "tract_core_scan",
+        id,
         &[],
         &[
             ("body", string(&body.decl.id)),
@@ -140,6 +149,14 @@ fn de_scan(
     builder: &mut ModelBuilder,
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
+    let (op, inputs) = de_scan_op(builder, invocation)?;
+    builder.wire(op, &*inputs)
+}
+
+pub(crate) fn de_scan_op(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<(Scan, TVec<OutletId>)> {
     let fragment_name: String = invocation.named_arg_as(builder, "body")?;
     let fragment = builder
         .proto_model
@@ -149,6 +166,7 @@ fn de_scan(
         .find(|n| n.decl.id == fragment_name)
         .ok_or_else(|| format_err!("Cound not find fragment `{}'", fragment_name))?;
     let mut body = ModelBuilder::new(builder.framework, builder.proto_model);
+    body.registries = builder.registries.clone();
     body.scopes.push(HashMap::new());
     let mut outer_inputs: TVec<OutletId> = tvec!();
     let mut input_mapping = vec![];
@@ -235,5 +253,5 @@ fn de_scan(
     }
     let skip: usize = invocation.named_arg_as(builder, "skip")?;
     let op = Scan::new(body.model, input_mapping, output_mapping, None, skip)?;
-    builder.wire(op, &*outer_inputs)
+    Ok((op, outer_inputs))
 }
@@ -0,0 +1,40 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::array::Unique;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Unique>(), unique_dump);
+    registry.register_primitive(
+        "tract_core_unique",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Logical.named("sorted").default(true),
+        ],
+        unique_load,
+    );
+}
+
+fn unique_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Unique>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let mut named = vec![("sorted", logical(op.sorted))];
+    if let Some(axis) = op.axis {
+        named.push(("axis", numeric(axis)));
+    }
+    Ok(Some(invocation("tract_core_unique", &[input], &named)))
+}
+
+fn unique_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let axis = if invocation.get_named_arg("axis").is_some() {
+        Some(invocation.named_arg_as(builder, "axis")?)
+    } else {
+        None
+    };
+    let sorted = invocation.named_arg_as(builder, "sorted")?;
+    builder.wire(Unique::new(axis, sorted), &[input])
+}
@@ -0,0 +1,83 @@
+//! Serialization of codegen (lir) operators.
+//!
+//! These operators embed platform specific choices (kernel, packing layouts, pre-packed
+//! weights). A model dumped with this registry can only be reloaded on a platform picking the
+//! same kernels, and is meant to be run as is, without going through optimisation again.
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::cnn::PaddingSpec;
+use tract_core::ops::nn::DataFormat;
+use tract_core::tract_linalg::frame::Packer;
+
+mod cnn;
+mod element_wise;
+mod matmul;
+mod scan;
+
+pub fn register(registry: &mut Registry) {
+    cnn::register(registry);
+    element_wise::register(registry);
+    matmul::register(registry);
+    scan::register(registry);
+}
+
+fn data_format_to_string(fmt: DataFormat) -> RValue {
+    string(format!("{:?}", fmt))
+}
+
+fn data_format_from_string(s: &str) -> TractResult<DataFormat> {
+    Ok(match s {
+        "NCHW" => DataFormat::NCHW,
+        "NHWC" => DataFormat::NHWC,
+        "CHW" => DataFormat::CHW,
+        "HWC" => DataFormat::HWC,
+        _ => bail!("Unknown data format {}", s),
+    })
+}
+
+fn padding_to_tuple(padding: &PaddingSpec) -> RValue {
+    let (kind, before, after, ceil) = match padding {
+        PaddingSpec::Explicit(before, after, ceil) => ("explicit", &**before, &**after, *ceil),
+        PaddingSpec::Valid => ("valid", &[][..], &[][..], false),
+        PaddingSpec::SameUpper => ("same_upper", &[][..], &[][..], false),
+        PaddingSpec::SameLower => ("same_lower", &[][..], &[][..], false),
+    };
+    tuple_4(string(kind), ints(before), ints(after), logical(ceil))
+}
+
+fn padding_from_tuple(
+    (kind, before, after, ceil): (String, TVec<usize>, TVec<usize>, bool),
+) -> TractResult<PaddingSpec> {
+    Ok(match &*kind {
+        "explicit" => PaddingSpec::Explicit(before, after, ceil),
+        "valid" => PaddingSpec::Valid,
+        "same_upper" => PaddingSpec::SameUpper,
+        "same_lower" => PaddingSpec::SameLower,
+        _ => bail!("Unknown padding {}", kind),
+    })
+}
+
+fn packer_to_ints(packer: &Packer) -> RValue {
+    ints(&[packer.k(), packer.panel_width(), packer.alignment(), packer.end_padding_record()])
+}
+
+fn packer_from_ints(packer: &[usize]) -> TractResult<Packer> {
+    if packer.len() != 4 {
+        bail!("Expected packer as [k, r, alignment, end_padding], got {:?}", packer)
+    }
+    Ok(Packer::new(packer[0], packer[1], packer[2], packer[3]))
+}
+
+/// Tensors read from a NNEF archive are only aligned for their datum type: kernels want
+/// stronger alignments for their packed operands.
+fn realign(tensor: Arc<Tensor>, alignment: usize) -> TractResult<Arc<Tensor>> {
+    unsafe {
+        if tensor.as_bytes().as_ptr() as usize % alignment == 0 {
+            return Ok(tensor);
+        }
+        let mut aligned =
+            Tensor::uninitialized_aligned_dt(tensor.datum_type(), tensor.shape(), alignment)?;
+        aligned.as_bytes_mut().copy_from_slice(tensor.as_bytes());
+        Ok(aligned.into_arc_tensor())
+    }
+}
@@ -0,0 +1,191 @@
+use crate::ast;
+use crate::ser::*;
+use tract_core::ops::cnn::conv::{DepthWise, Im2Col, QSumB};
+use tract_core::ops::cnn::{PatchSpec, PoolSpec};
+
+use super::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Im2Col>(), ser_im2col);
+    registry.register_primitive(
+        "tract_lir_im2col",
+        &[
+            TypeName::Scalar.tensor().array().named("inputs"),
+            TypeName::String.named("data_format"),
+            TypeName::Integer.array().named("kernel_shape"),
+            padding_parameter(),
+            TypeName::Integer.array().named("dilations"),
+            TypeName::Integer.array().named("strides"),
+            TypeName::Integer.named("output_channels").default(-1),
+            TypeName::Integer.named("group"),
+            TypeName::Integer.array().named("packer"),
+        ],
+        de_im2col,
+    );
+    registry.register_dumper(TypeId::of::<QSumB>(), ser_qsumb);
+    registry.register_primitive(
+        "tract_lir_qsumb",
+        &[
+            TypeName::Integer.tensor().named("input"),
+            TypeName::Integer.named("r"),
+            TypeName::Integer.named("n"),
+            TypeName::Integer.named("k"),
+        ],
+        de_qsumb,
+    );
+    registry.register_dumper(TypeId::of::<DepthWise>(), ser_depth_wise);
+    registry.register_primitive(
+        "tract_lir_depth_wise",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::String.named("data_format"),
+            TypeName::Integer.array().named("output_shape"),
+            TypeName::Integer.named("input_inner_stride"),
+            TypeName::Integer.named("output_inner_stride"),
+            TypeName::Integer.array().named("kernel_shape"),
+            TypeName::Integer.array().named("strides"),
+            TypeName::Integer.array().named("dilations"),
+            padding_parameter(),
+            TypeName::Scalar.tensor().named("kernel"),
+            TypeName::Scalar.tensor().named("bias"),
+        ],
+        de_depth_wise,
+    );
+}
+
+fn padding_parameter() -> Parameter {
+    ast::TypeSpec::Tuple(vec![
+        TypeName::String.spec(), // "explicit", "valid", "same_upper" or "same_lower"
+        TypeName::Integer.array(), // explicit before
+        TypeName::Integer.array(), // explicit after
+        TypeName::Logical.spec(), // explicit ceil mode
+    ])
+    .named("padding")
+}
+
+fn ser_im2col(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Im2Col>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].as_ref().clone()).collect::<Vec<_>>();
+    let spec = &op.pool_spec;
+    Ok(Some(invocation(
+        "tract_lir_im2col",
+        &[],
+        &[
+            ("inputs", array(inputs)),
+            ("data_format", data_format_to_string(spec.data_format)),
+            ("kernel_shape", ints(&spec.kernel_shape)),
+            ("padding", padding_to_tuple(&spec.padding)),
+            ("dilations", ints(spec.dilations.as_deref().unwrap_or(&[]))),
+            ("strides", ints(spec.strides.as_deref().unwrap_or(&[]))),
+            (
+                "output_channels",
+                numeric(spec.output_channel_override.map(|c| c as i64).unwrap_or(-1)),
+            ),
+            ("group", numeric(op.group)),
+            ("packer", packer_to_ints(op.b_pack())),
+        ],
+    )))
+}
+
+fn de_im2col(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let inputs: TVec<OutletId> = invocation.named_arg_as(builder, "inputs")?;
+    let data_format =
+        data_format_from_string(&invocation.named_arg_as::<String>(builder, "data_format")?)?;
+    let kernel_shape = invocation.named_arg_as(builder, "kernel_shape")?;
+    let padding = padding_from_tuple(invocation.named_arg_as(builder, "padding")?)?;
+    let dilations: TVec<usize> = invocation.named_arg_as(builder, "dilations")?;
+    let strides: TVec<usize> = invocation.named_arg_as(builder, "strides")?;
+    let output_channels: i64 = invocation.named_arg_as(builder, "output_channels")?;
+    let pool_spec = PoolSpec {
+        data_format,
+        kernel_shape,
+        padding,
+        dilations: Some(dilations).filter(|d| d.len() > 0),
+        strides: Some(strides).filter(|s| s.len() > 0),
+        output_channel_override: Some(output_channels as usize).filter(|_| output_channels >= 0),
+    };
+    let group = invocation.named_arg_as(builder, "group")?;
+    let packer = packer_from_ints(&invocation.named_arg_as::<TVec<usize>>(builder, "packer")?)?;
+    let input_shape = builder.model.outlet_fact(inputs[0])?.shape.clone();
+    let op = Im2Col::with_b_pack(pool_spec, group, &input_shape, packer)?;
+    builder.wire(op, &inputs)
+}
+
+fn ser_qsumb(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<QSumB>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_lir_qsumb",
+        &[input],
+        &[("r", numeric(op.r)), ("n", numeric(op.n.to_usize()?)), ("k", numeric(op.k))],
+    )))
+}
+
+fn de_qsumb(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let r = invocation.named_arg_as(builder, "r")?;
+    let n = invocation.named_arg_as(builder, "n")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    builder.wire(QSumB { r, n, k }, &[input])
+}
+
+fn ser_depth_wise(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<DepthWise>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let spec = &op.patch.spec;
+    let kernel = ast.konst_variable(format!("{}.kernel", node.name), &op.kernel_chw)?;
+    let mut named = vec![
+        ("data_format", data_format_to_string(op.input_shape.fmt)),
+        ("output_shape", ints(&op.output_shape.shape)),
+        ("input_inner_stride", numeric(spec.input_inner_stride)),
+        ("output_inner_stride", numeric(spec.output_inner_stride)),
+        ("kernel_shape", ints(&spec.kernel_shape)),
+        ("strides", ints(&spec.strides)),
+        ("dilations", ints(&spec.dilations)),
+        ("padding", padding_to_tuple(&spec.padding)),
+        ("kernel", kernel.as_ref().clone()),
+    ];
+    if let Some(bias) = &op.bias {
+        let bias = ast.konst_variable(format!("{}.bias", node.name), bias)?;
+        named.push(("bias", bias.as_ref().clone()));
+    }
+    Ok(Some(invocation("tract_lir_depth_wise", &[input], &named)))
+}
+
+fn de_depth_wise(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let data_format =
+        data_format_from_string(&invocation.named_arg_as::<String>(builder, "data_format")?)?;
+    let input_shape = builder.model.outlet_fact(input)?.shape.as_concrete().map(|s| s.to_vec());
+    let input_shape = data_format.shape(
+        input_shape.context("Depthwise convolution requires a concrete input shape")?.into(),
+    )?;
+    let output_shape =
+        data_format.shape(invocation.named_arg_as::<TVec<usize>>(builder, "output_shape")?)?;
+    let patch = PatchSpec {
+        input_shape: input_shape.hw_dims().into(),
+        input_inner_stride: invocation.named_arg_as(builder, "input_inner_stride")?,
+        output_inner_stride: invocation.named_arg_as(builder, "output_inner_stride")?,
+        kernel_shape: invocation.named_arg_as(builder, "kernel_shape")?,
+        strides: invocation.named_arg_as(builder, "strides")?,
+        dilations: invocation.named_arg_as(builder, "dilations")?,
+        padding: padding_from_tuple(invocation.named_arg_as(builder, "padding")?)?,
+    }
+    .into_patch();
+    let kernel = invocation.named_arg_as(builder, "kernel")?;
+    let bias = if invocation.get_named_arg("bias").is_some() {
+        Some(invocation.named_arg_as(builder, "bias")?)
+    } else {
+        None
+    };
+    builder.wire(DepthWise::new(patch, input_shape, output_shape, kernel, bias), &[input])
+}
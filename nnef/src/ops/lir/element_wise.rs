@@ -0,0 +1,97 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::binary::MergeOpUnicast;
+use tract_core::ops::element_wise::ElementWiseOp;
+use tract_core::ops::quant::{lookup_table, LookupTable};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_element_wise(
+        "tract_lir_lookup_table",
+        TypeId::of::<LookupTable>(),
+        ser_lookup_table,
+        vec![TypeName::Integer.tensor().named("input"), TypeName::Integer.tensor().named("table")],
+        de_lookup_table,
+    );
+    registry.register_dumper(TypeId::of::<MergeOpUnicast>(), ser_merge_op_unicast);
+    registry.register_primitive(
+        "tract_lir_merge_op_unicast",
+        &[
+            TypeName::Scalar.tensor().named("a"),
+            TypeName::Scalar.tensor().named("b"),
+            TypeName::String.named("op"),
+            TypeName::Logical.named("flipped").default(false),
+        ],
+        de_merge_op_unicast,
+    );
+}
+
+fn ser_lookup_table(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<ElementWiseOp>().unwrap().0.downcast_ref::<LookupTable>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let table = ast.konst_variable(format!("{}.table", node.name), &rctensor1(op.table.table()))?;
+    Ok(Some(invocation("tract_lir_lookup_table", &[input, table], &[])))
+}
+
+fn de_lookup_table(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let table: Arc<Tensor> = invocation.named_arg_as(builder, "table")?;
+    let op = lookup_table((tract_core::tract_linalg::ops().lut_u8)(table.as_slice::<u8>()?));
+    builder.wire(op, &[input])
+}
+
+fn ser_merge_op_unicast(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<MergeOpUnicast>().unwrap();
+    let type_id = op.0.as_ref().type_id();
+    let (name, flipped) = if let Some(bin) = ast
+        .framework
+        .registries
+        .iter()
+        .flat_map(|r| r.binary_ops.iter())
+        .find(|bin| bin.1.as_ref().type_id() == type_id)
+    {
+        (&bin.0, false)
+    } else if let Some(bin) = ast
+        .framework
+        .registries
+        .iter()
+        .flat_map(|r| r.binary_ops.iter())
+        .find(|bin| bin.2.as_ref().map(|op| op.as_ref().type_id()) == Some(type_id))
+    {
+        (&bin.0, true)
+    } else {
+        return Ok(None);
+    };
+    let a = ast.mapping[&node.inputs[0]].clone();
+    let b = ast.mapping[&node.inputs[1]].clone();
+    Ok(Some(invocation(
+        "tract_lir_merge_op_unicast",
+        &[a, b],
+        &[("op", string(name)), ("flipped", logical(flipped))],
+    )))
+}
+
+fn de_merge_op_unicast(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let a = invocation.named_arg_as(builder, "a")?;
+    let b = invocation.named_arg_as(builder, "b")?;
+    let name: String = invocation.named_arg_as(builder, "op")?;
+    let flipped: bool = invocation.named_arg_as(builder, "flipped")?;
+    let bin = builder
+        .framework
+        .registries
+        .iter()
+        .flat_map(|r| r.binary_ops.iter())
+        .find(|bin| bin.0 == name)
+        .with_context(|| format!("No binary operator named {}", name))?;
+    let mini_op = if flipped {
+        bin.2.clone().with_context(|| format!("Binary operator {} has no flipped form", name))?
+    } else {
+        bin.1.clone()
+    };
+    builder.wire(MergeOpUnicast(mini_op), &[a, b])
+}
@@ -0,0 +1,364 @@
+use crate::ast;
+use crate::deser::Value;
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::change_axes::AxisOp;
+use tract_core::ops::matmul::lir_unary::*;
+use tract_core::ops::matmul::pack::MatMatMulPack;
+use tract_core::tract_linalg::mmm::{MatMatMul, MatrixStoreSpec, RoundingPolicy};
+use tract_ndarray::ArrayD;
+
+use super::{packer_from_ints, packer_to_ints, realign};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<LirMatMulUnary>(), ser_lir_matmul);
+    registry.register_primitive(
+        "tract_lir_matmul",
+        &[
+            TypeName::Scalar.tensor().array().named("inputs"),
+            TypeName::String.named("kernel"),
+            TypeName::String.named("c_datum_type"),
+            TypeName::Integer.named("m"),
+            TypeName::Integer.named("k"),
+            TypeName::Integer.named("n"),
+            TypeName::Integer.named("c_m_axis"),
+            TypeName::Integer.named("c_n_axis"),
+            TypeName::Integer.array().named("c_shape"),
+            TypeName::Integer.array().named("c_final_shape"),
+            TypeName::Integer.array().named("micro_ops_shape"),
+            TypeName::Scalar.tensor().array().named("packed_a"),
+            ast::TypeSpec::Tuple(vec![
+                TypeName::String.spec(),           // spec kind
+                TypeName::Scalar.tensor().array(), // attributes, or input slots
+            ])
+            .array()
+            .array()
+            .named("fused"),
+            TypeName::String.named("b_storage"),
+            ast::TypeSpec::Tuple(vec![
+                TypeName::Integer.tensor(), // row byte offsets
+                TypeName::Integer.tensor(), // col byte offsets
+            ])
+            .named("b_offsets"),
+            ast::TypeSpec::Tuple(vec![
+                TypeName::String.spec(),
+                TypeName::Integer.array(),
+                TypeName::Integer.array(),
+                TypeName::Integer.array(),
+            ])
+            .array()
+            .named("reshape_post"),
+        ],
+        de_lir_matmul,
+    );
+    registry.register_dumper(TypeId::of::<MatMatMulPack>(), ser_pack);
+    registry.register_primitive(
+        "tract_lir_pack",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.array().named("packer"),
+            TypeName::Logical.named("trans"),
+            TypeName::Integer.array().named("output_shape"),
+        ],
+        de_pack,
+    );
+}
+
+fn concrete_shape<'a>(node: &TypedNode, shape: &'a ShapeFact) -> TractResult<&'a [usize]> {
+    shape.as_concrete().with_context(|| format!("Can not serialize {} with symbolic shape", node))
+}
+
+fn ser_lir_matmul(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<LirMatMulUnary>().unwrap();
+    let geometry = match &op.geometry {
+        MatMulGeometry::Concrete(geometry) => geometry,
+        MatMulGeometry::Symbolic(_) => {
+            bail!("Can not serialize {} with symbolic geometry", node)
+        }
+    };
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].as_ref().clone()).collect::<Vec<_>>();
+    let mut packed_a = vec![];
+    let mut fused = vec![];
+    for (ix, (pa, specs)) in op.micro_ops.iter().enumerate() {
+        let name = format!("{}.packed_a.{}", node.name, ix);
+        packed_a.push(ast.konst_variable(name, pa)?.as_ref().clone());
+        let specs = specs
+            .iter()
+            .enumerate()
+            .map(|(spec_ix, spec)| {
+                ser_fused_spec(ast, &format!("{}.fused.{}.{}", node.name, ix, spec_ix), spec)
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        fused.push(array(specs));
+    }
+    let reshape_post =
+        op.reshape_post.iter().map(ser_axis_op).collect::<TractResult<Vec<RValue>>>()?;
+    let mut named = vec![
+        ("inputs", array(inputs)),
+        ("kernel", string(op.mmm.to_string())),
+        ("c_datum_type", string(format!("{:?}", op.c_fact.datum_type))),
+        ("m", numeric(geometry.m)),
+        ("k", numeric(geometry.k)),
+        ("n", numeric(geometry.n)),
+        ("c_m_axis", numeric(op.c_m_axis)),
+        ("c_n_axis", numeric(op.c_n_axis)),
+        ("c_shape", ints(concrete_shape(node, &op.c_fact.shape)?)),
+        ("c_final_shape", ints(concrete_shape(node, &op.c_final_shape)?)),
+        ("micro_ops_shape", ints(op.micro_ops.shape())),
+        ("packed_a", array(packed_a)),
+        ("fused", array(fused)),
+        ("reshape_post", array(reshape_post)),
+    ];
+    match &geometry.b_storage {
+        MatrixStoreSpec::Packed { .. } => named.push(("b_storage", string("packed"))),
+        MatrixStoreSpec::OffsetsAndPtrs { row_byte_offsets, col_byte_offsets, .. } => {
+            let offsets = |offsets: &[isize]| {
+                rctensor1(&offsets.iter().map(|&o| o as i64).collect::<Vec<_>>())
+            };
+            let rows = ast.konst_variable(
+                format!("{}.b_row_byte_offsets", node.name),
+                &offsets(row_byte_offsets),
+            )?;
+            let cols = ast.konst_variable(
+                format!("{}.b_col_byte_offsets", node.name),
+                &offsets(col_byte_offsets),
+            )?;
+            named.push(("b_storage", string("offsets")));
+            named.push(("b_offsets", tuple_2(rows.as_ref().clone(), cols.as_ref().clone())));
+        }
+        other => bail!("Can not serialize {} with B storage {}", node, other),
+    }
+    Ok(Some(invocation("tract_lir_matmul", &[], &named)))
+}
+
+fn ser_fused_spec(ast: &mut IntoAst, name: &str, spec: &ProtoFusedSpec) -> TractResult<RValue> {
+    use ProtoFusedSpec::*;
+    let mut attr = |ix: usize, v: &AttrOrInput| -> TractResult<RValue> {
+        Ok(match v {
+            AttrOrInput::Attr(t) => {
+                ast.konst_variable(format!("{}.{}", name, ix), t)?.as_ref().clone()
+            }
+            AttrOrInput::Input(slot) => numeric(slot),
+        })
+    };
+    let (kind, args) = match spec {
+        Min(v) => ("min", vec![attr(0, v)?]),
+        Max(v) => ("max", vec![attr(0, v)?]),
+        PerRowMul(v) => ("per_row_mul", vec![attr(0, v)?]),
+        PerRowAdd(v) => ("per_row_add", vec![attr(0, v)?]),
+        PerColMul(v) => ("per_col_mul", vec![attr(0, v)?]),
+        PerColAdd(v) => ("per_col_add", vec![attr(0, v)?]),
+        AddRowColProducts(row, col) => ("add_row_col_products", vec![attr(0, row)?, attr(1, col)?]),
+        ScalarMul(v) => ("scalar_mul", vec![attr(0, v)?]),
+        ScalarAdd(v) => ("scalar_add", vec![attr(0, v)?]),
+        AddUnicast(v) => ("add_unicast", vec![attr(0, v)?]),
+        QScale(shift, policy, mult) => {
+            ("q_scale", vec![numeric(shift), string(format!("{:?}", policy)), numeric(mult)])
+        }
+    };
+    Ok(tuple_2(string(kind), array(args)))
+}
+
+fn ser_axis_op(op: &AxisOp) -> TractResult<RValue> {
+    let dims = |dims: &[TDim]| -> TractResult<RValue> {
+        Ok(ints(&dims.iter().map(|d| d.to_usize()).collect::<TractResult<TVec<_>>>()?))
+    };
+    Ok(match op {
+        AxisOp::Add(axis) => tuple_4(string("add"), ints(&[*axis]), ints(&[]), ints(&[])),
+        AxisOp::Rm(axis) => tuple_4(string("rm"), ints(&[*axis]), ints(&[]), ints(&[])),
+        AxisOp::Move(from, to) => {
+            tuple_4(string("move"), ints(&[*from, *to]), ints(&[]), ints(&[]))
+        }
+        AxisOp::Reshape(at, from, to) => {
+            tuple_4(string("reshape"), ints(&[*at]), dims(from)?, dims(to)?)
+        }
+    })
+}
+
+fn de_lir_matmul(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let inputs: TVec<OutletId> = invocation.named_arg_as(builder, "inputs")?;
+    let kernel: String = invocation.named_arg_as(builder, "kernel")?;
+    let c_dt: DatumType = invocation.named_arg_as::<String>(builder, "c_datum_type")?.parse()?;
+    let m: usize = invocation.named_arg_as(builder, "m")?;
+    let k: usize = invocation.named_arg_as(builder, "k")?;
+    let n: usize = invocation.named_arg_as(builder, "n")?;
+    let c_m_axis: usize = invocation.named_arg_as(builder, "c_m_axis")?;
+    let c_n_axis: usize = invocation.named_arg_as(builder, "c_n_axis")?;
+    let c_shape: TVec<usize> = invocation.named_arg_as(builder, "c_shape")?;
+    let c_final_shape: TVec<usize> = invocation.named_arg_as(builder, "c_final_shape")?;
+    let micro_ops_shape: TVec<usize> = invocation.named_arg_as(builder, "micro_ops_shape")?;
+    let packed_a: TVec<Arc<Tensor>> = invocation.named_arg_as(builder, "packed_a")?;
+    let fused: TVec<TVec<(String, TVec<Value>)>> = invocation.named_arg_as(builder, "fused")?;
+    let reshape_post: TVec<(String, TVec<usize>, TVec<usize>, TVec<usize>)> =
+        invocation.named_arg_as(builder, "reshape_post")?;
+
+    let a_dt = packed_a.get(0).context("Expected at least one packed A")?.datum_type();
+    let b_dt = builder.model.outlet_fact(inputs[0])?.datum_type;
+    let mmm = select_mmm(a_dt, b_dt, c_dt, m, k, n, &kernel)?;
+    let a_pack = mmm.a_pack(k);
+    let packed_a = packed_a
+        .into_iter()
+        .map(|pa| {
+            if pa.len() != a_pack.len(m) {
+                bail!("Packed A has {} items, expected {} for {}", pa.len(), a_pack.len(m), mmm);
+            }
+            Ok(builder.model.share_tensor(realign(pa, a_pack.alignment())?))
+        })
+        .collect::<TractResult<Vec<_>>>()?;
+    let fused = fused
+        .iter()
+        .map(|specs| specs.iter().map(|spec| de_fused_spec(builder, spec)).collect())
+        .collect::<TractResult<Vec<Vec<ProtoFusedSpec>>>>()?;
+    if packed_a.len() != fused.len() {
+        bail!("Got {} packed A, but {} fused spec lists", packed_a.len(), fused.len());
+    }
+    let micro_ops =
+        ArrayD::from_shape_vec(&*micro_ops_shape, packed_a.into_iter().zip(fused).collect())?;
+
+    let b_storage = match &*invocation.named_arg_as::<String>(builder, "b_storage")? {
+        "packed" => unsafe { mmm.b_packed(b_dt.size_of(), k) },
+        "offsets" => {
+            let (rows, cols): (Arc<Tensor>, Arc<Tensor>) =
+                invocation.named_arg_as(builder, "b_offsets")?;
+            let offsets = |t: &Tensor| -> TractResult<Vec<isize>> {
+                Ok(t.cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&o| o as isize).collect())
+            };
+            MatrixStoreSpec::OffsetsAndPtrs {
+                row_byte_offsets: offsets(&rows)?,
+                col_byte_offsets: offsets(&cols)?,
+                nr: mmm.nr(),
+            }
+        }
+        other => bail!("Unknown B storage {}", other),
+    };
+    let reshape_post = reshape_post.iter().map(de_axis_op).collect::<TractResult<Vec<_>>>()?;
+    let op = LirMatMulUnary {
+        c_fact: TypedFact::dt_shape(c_dt, &*c_shape),
+        c_m_axis,
+        c_n_axis,
+        micro_ops,
+        c_final_shape: c_final_shape.into(),
+        geometry: MatMulGeometry::Concrete(ConcreteMatMulGeometry { m, k, n, b_storage }),
+        mmm,
+        reshape_post,
+    };
+    builder.wire(op, &inputs)
+}
+
+/// Pick the kernel the model was compiled with, trying the same hints as the optimizer.
+fn select_mmm(
+    a_dt: DatumType,
+    b_dt: DatumType,
+    c_dt: DatumType,
+    m: usize,
+    k: usize,
+    n: usize,
+    kernel: &str,
+) -> TractResult<Box<dyn MatMatMul>> {
+    let mut candidates = vec![];
+    for k in &[Some(k), None] {
+        if let Some(mmm) =
+            tract_core::tract_linalg::ops().mmm(a_dt, b_dt, c_dt, Some(m), *k, Some(n))
+        {
+            if mmm.to_string() == kernel {
+                return Ok(mmm);
+            }
+            candidates.push(mmm.to_string());
+        }
+    }
+    bail!(
+        "Model was compiled for kernel {}, but this platform offers {}",
+        kernel,
+        if candidates.len() > 0 { candidates.join(" or ") } else { "none".to_string() }
+    )
+}
+
+fn de_fused_spec(
+    builder: &mut ModelBuilder,
+    (kind, args): &(String, TVec<Value>),
+) -> TractResult<ProtoFusedSpec> {
+    use ProtoFusedSpec::*;
+    let mut attrs = args
+        .iter()
+        .map(|arg| {
+            Ok(match arg {
+                Value::Dim(slot) => AttrOrInput::Input(slot.to_usize()?),
+                other => AttrOrInput::Attr(other.to::<Arc<Tensor>>(builder)?),
+            })
+        })
+        .collect::<TractResult<TVec<_>>>()?
+        .into_iter();
+    let mut attr = || attrs.next().with_context(|| format!("Missing argument for {}", kind));
+    Ok(match &**kind {
+        "min" => Min(attr()?),
+        "max" => Max(attr()?),
+        "per_row_mul" => PerRowMul(attr()?),
+        "per_row_add" => PerRowAdd(attr()?),
+        "per_col_mul" => PerColMul(attr()?),
+        "per_col_add" => PerColAdd(attr()?),
+        "add_row_col_products" => AddRowColProducts(attr()?, attr()?),
+        "scalar_mul" => ScalarMul(attr()?),
+        "scalar_add" => ScalarAdd(attr()?),
+        "add_unicast" => AddUnicast(attr()?),
+        "q_scale" => {
+            if args.len() != 3 {
+                bail!("q_scale expects shift, policy and multiplier");
+            }
+            let shift = args[0].to::<usize>(builder)?;
+            let policy = match &*args[1].to::<String>(builder)? {
+                "Native" => RoundingPolicy::Native,
+                "Zero" => RoundingPolicy::Zero,
+                "Away" => RoundingPolicy::Away,
+                "MinusInf" => RoundingPolicy::MinusInf,
+                "PlusInf" => RoundingPolicy::PlusInf,
+                "Even" => RoundingPolicy::Even,
+                "Odd" => RoundingPolicy::Odd,
+                other => bail!("Unknown rounding policy {}", other),
+            };
+            let mult = args[2].to::<i64>(builder)? as i32;
+            QScale(shift, policy, mult)
+        }
+        other => bail!("Unknown fused spec {}", other),
+    })
+}
+
+fn de_axis_op(
+    (kind, axes, from, to): &(String, TVec<usize>, TVec<usize>, TVec<usize>),
+) -> TractResult<AxisOp> {
+    let dims = |dims: &[usize]| dims.iter().map(|d| d.to_dim()).collect::<TVec<_>>();
+    Ok(match (&**kind, &**axes) {
+        ("add", &[axis]) => AxisOp::Add(axis),
+        ("rm", &[axis]) => AxisOp::Rm(axis),
+        ("move", &[from, to]) => AxisOp::Move(from, to),
+        ("reshape", &[at]) => AxisOp::Reshape(at, dims(from), dims(to)),
+        _ => bail!("Invalid axis op {} {:?}", kind, axes),
+    })
+}
+
+fn ser_pack(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<MatMatMulPack>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_lir_pack",
+        &[input],
+        &[
+            ("packer", packer_to_ints(&op.packer)),
+            ("trans", logical(op.trans)),
+            ("output_shape", ints(&op.output_shape)),
+        ],
+    )))
+}
+
+fn de_pack(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let packer = packer_from_ints(&invocation.named_arg_as::<TVec<usize>>(builder, "packer")?)?;
+    let trans = invocation.named_arg_as(builder, "trans")?;
+    let output_shape = invocation.named_arg_as(builder, "output_shape")?;
+    builder.wire(MatMatMulPack { packer, trans, output_shape }, &[input])
+}
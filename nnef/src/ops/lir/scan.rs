@@ -0,0 +1,28 @@
+use crate::internal::*;
+use crate::ops::core::scan::{de_scan_op, scan_parameters, ser_scan_op};
+use tract_core::ops::scan::{LirScan, Scan};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<LirScan>(), ser_lir_scan);
+    registry.register_primitive("tract_lir_scan", &scan_parameters(), de_lir_scan);
+}
+
+fn ser_lir_scan(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<LirScan>().unwrap();
+    let scan = Scan::new(
+        op.plan.model().clone(),
+        op.input_mapping.clone(),
+        op.output_mapping.clone(),
+        None,
+        op.skip,
+    )?;
+    ser_scan_op(ast, node, &scan, "tract_lir_scan")
+}
+
+fn de_lir_scan(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let (op, inputs) = de_scan_op(builder, invocation)?;
+    builder.wire(op.to_codegen_op(false)?, &inputs)
+}
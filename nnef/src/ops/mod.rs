@@ -1,6 +1,7 @@
 use crate::internal::*;
 
 pub(super) mod core;
+pub(super) mod lir;
 pub(super) mod nnef;
 
 pub use nnef::tract_nnef;
@@ -10,3 +11,9 @@ pub fn tract_core() -> Registry {
     core::register(&mut reg);
     reg
 }
+
+pub fn tract_lir() -> Registry {
+    let mut reg = Registry::new("tract_lir");
+    lir::register(&mut reg);
+    reg
+}
@@ -311,7 +311,12 @@ pub fn conv_or_deconv(
     use ops::cnn::{ConvUnary, KernelFormat};
 
     let input: OutletId = invocation.named_arg_as(builder, "input")?;
-    let kernel: Arc<Tensor> = invocation.named_arg_as(builder, "filter")?;
+    let kernel: OutletId = invocation.named_arg_as(builder, "filter")?;
+    let kernel_fact = builder.model.outlet_fact(kernel)?.clone();
+    let kernel = kernel_fact
+        .konst
+        .clone()
+        .ok_or_else(|| format_err!("Convolution filter must be a constant"))?;
     let input_fact = builder.model.outlet_fact(input)?.clone();
     if input_fact.rank() != kernel.rank() {
         bail!(
@@ -328,16 +333,17 @@ pub fn conv_or_deconv(
         invocation.dt_from_quant_file.get(0).cloned().flatten().unwrap_or(DatumType::F32);
     let quantized = input_fact.datum_type.is_quantized()
         || kernel.datum_type().is_quantized()
+        || kernel_fact.axis_qparams.is_some()
         || output_dt.is_quantized();
     let (b0, b_scale) = input_fact.datum_type.zp_scale();
-    let (a0, a_scale) = kernel.datum_type().zp_scale();
+    let (a0, a_scale) = operand_qparams(&kernel_fact, 0)?;
     let (c0, c_scale) = output_dt.qparams().map(|q| q.zp_scale()).unwrap_or((b0, b_scale));
     let qparams = if quantized {
         Some((
             output_dt,
             MatMulQParams {
-                a0: AttrOrInput::Attr(Arc::new(a0.into())),
-                a_scale: AttrOrInput::Attr(Arc::new(a_scale.into())),
+                a0,
+                a_scale,
                 b0: AttrOrInput::Attr(Arc::new(b0.into())),
                 b_scale: AttrOrInput::Attr(Arc::new(b_scale.into())),
                 c0: AttrOrInput::Attr(Arc::new(c0.into())),
@@ -520,6 +526,23 @@ pub fn reduce(
     bail!("Normalization only works with float items and known dimensions");
 }
 
+/// Zero point and scale of a conv or matmul operand: scalars from its quantized datum type, or
+/// vectors when the graph.quant file gives per-axis parameters along `axis`.
+fn operand_qparams(fact: &TypedFact, axis: usize) -> TractResult<(AttrOrInput, AttrOrInput)> {
+    if let Some(qp) = &fact.axis_qparams {
+        if qp.axis != axis {
+            bail!("Per-axis quantization is only supported on axis {}, got {:?}", axis, qp);
+        }
+        Ok((
+            AttrOrInput::Attr(rctensor1(&qp.zero_points)),
+            AttrOrInput::Attr(rctensor1(&qp.scales)),
+        ))
+    } else {
+        let (zero_point, scale) = fact.datum_type.zp_scale();
+        Ok((AttrOrInput::Attr(rctensor0(zero_point)), AttrOrInput::Attr(rctensor0(scale))))
+    }
+}
+
 /*
  * fragment matmul( A: tensor<scalar>, B: tensor<scalar>, transposeA: logical = false, transposeB: logical = false ) -> ( C: tensor<scalar> );
  */
@@ -533,10 +556,11 @@ pub fn matmul(
     let b_trans = invocation.named_arg_as(builder, "transposeB")?;
     if let Some(Some(dt)) = &invocation.dt_from_quant_file.get(0) {
         if let Some(qparams) = dt.qparams() {
-            let (a0, a_scale) =
-                builder.model.node(a.node).outputs[a.slot].fact.datum_type.zp_scale();
-            let (b0, b_scale) =
-                builder.model.node(b.node).outputs[b.slot].fact.datum_type.zp_scale();
+            let a_fact = builder.model.outlet_fact(a)?.clone();
+            let b_fact = builder.model.outlet_fact(b)?.clone();
+            let rank = a_fact.rank();
+            let (a0, a_scale) = operand_qparams(&a_fact, rank - 2 + a_trans as usize)?;
+            let (b0, b_scale) = operand_qparams(&b_fact, rank - 1 - b_trans as usize)?;
 
             let (c0, c_scale) = qparams.zp_scale();
             //FIXME: bias is not specified in the nnef format, but whether the bias is a bias or an add later changes the quantized behaviour
@@ -553,10 +577,10 @@ pub fn matmul(
                     c_trans: false,
                     output_type: DatumType::QI8(qparams),
                     params: MatMulQParams {
-                        a0: AttrOrInput::Attr(Arc::new(a0.into())),
-                        a_scale: AttrOrInput::Attr(Arc::new(a_scale.into())),
-                        b0: AttrOrInput::Attr(Arc::new(b0.into())),
-                        b_scale: AttrOrInput::Attr(Arc::new(b_scale.into())),
+                        a0,
+                        a_scale,
+                        b0,
+                        b_scale,
                         c0: AttrOrInput::Attr(Arc::new(c0.into())),
                         c_scale: AttrOrInput::Attr(Arc::new(c_scale.into())),
                     },
@@ -222,6 +222,7 @@ pub fn conv_or_deconv(
     group: usize,
     deconv: bool,
     adjustments: Option<&[usize]>,
+    weights_qparams: Option<AxisQParams>,
 ) -> TractResult<Option<Arc<RValue>>> {
     let ci = pool_spec
         .data_format
@@ -234,8 +235,12 @@ pub fn conv_or_deconv(
     let mut kernel_shape = tvec!(co, ci / group);
     kernel_shape.extend(pool_spec.kernel_shape.iter().copied());
     weights.set_shape(&*kernel_shape)?;
+    let weights_dt = weights.datum_type();
     let weigths =
         ast.konst_variable(format!("{}_weigths", node.name), &weights.into_arc_tensor())?;
+    if let Some(params) = weights_qparams {
+        ast.axis_quantization(&weigths, weights_dt, params)?;
+    }
     wire = ast.force_assign(format!("{}_input", node.name), &wire);
     let conv_fragment =
         conv_or_deconv_fragment(ast, pool_spec.data_format, pool_spec.rank(), deconv);
@@ -262,7 +267,46 @@ pub fn conv(
         return Ok(None);
     }
     let weights = op.kernel_as_group_o_ihw()?.into_tensor();
-    conv_or_deconv(ast, node, &op.pool_spec, weights, &op.bias, op.group, false, None)
+    let weights_qparams = if let Some((_, qp)) = &op.q_params {
+        per_axis_qparams(&qp.a0, &qp.a_scale, 0)?
+    } else {
+        None
+    };
+    conv_or_deconv(
+        ast,
+        node,
+        &op.pool_spec,
+        weights,
+        &op.bias,
+        op.group,
+        false,
+        None,
+        weights_qparams,
+    )
+}
+
+/// Per-axis quantization parameters of a conv or matmul operand, when its zero point or scale
+/// is a vector.
+fn per_axis_qparams(
+    zero_point: &AttrOrInput,
+    scale: &AttrOrInput,
+    axis: usize,
+) -> TractResult<Option<AxisQParams>> {
+    if let (AttrOrInput::Attr(zero_point), AttrOrInput::Attr(scale)) = (zero_point, scale) {
+        let len = zero_point.len().max(scale.len());
+        if len > 1 {
+            let zero_point = zero_point.cast_to::<i32>()?;
+            let zero_point = zero_point.as_slice::<i32>()?;
+            let scale = scale.cast_to::<f32>()?;
+            let scale = scale.as_slice::<f32>()?;
+            return Ok(Some(AxisQParams {
+                axis,
+                zero_points: (0..len).map(|i| zero_point[i % zero_point.len()]).collect(),
+                scales: (0..len).map(|i| scale[i % scale.len()]).collect(),
+            }));
+        }
+    }
+    Ok(None)
 }
 
 pub fn deconv(
@@ -285,6 +329,7 @@ pub fn deconv(
         op.group,
         true,
         Some(&op.adjustments),
+        None,
     )
 }
 
@@ -474,6 +519,19 @@ pub fn qmatmul(
     }
     let a = ast.force_assign(format!("{}_a", node.name), &ast.mapping[&node.inputs[0]].clone());
     let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[1]].clone());
+    let rank = node.outputs[0].fact.rank();
+    for (input, alias, zero_point, scale, axis) in &[
+        (0, &a, &op.params.a0, &op.params.a_scale, rank - 2 + op.a_trans as usize),
+        (1, &b, &op.params.b0, &op.params.b_scale, rank - 1 - op.b_trans as usize),
+    ] {
+        if let Some(params) = per_axis_qparams(zero_point, scale, *axis)? {
+            let dt = ast.model.outlet_fact(node.inputs[*input])?.datum_type;
+            // prefer the variable itself, so that its datum type gets overriden
+            let id = ast.mapping[&node.inputs[*input]].clone();
+            let id = if let RValue::Identifier(_) = &*id { id } else { (*alias).clone() };
+            ast.axis_quantization(&id, dt, params)?;
+        }
+    }
     let c = if op.c_trans {
         invocation(
             "matmul",
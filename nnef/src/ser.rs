@@ -237,7 +237,7 @@ impl<'a> IntoAst<'a> {
                     if let Some(params) = outlet.fact.datum_type.qparams() {
                         let quant_format = QuantFormat::Linear {
                             params,
-                            bits: outlet.fact.datum_type.size_of() as i8,
+                            bits: 8 * outlet.fact.datum_type.size_of() as i8,
                             signed: outlet.fact.datum_type.is_signed(),
                         };
                         self.quantization.insert(name.to_string(), quant_format);
@@ -316,7 +316,11 @@ impl<'a> IntoAst<'a> {
         tensor: &Arc<Tensor>,
         force_variable: bool,
     ) -> TractResult<Arc<RValue>> {
-        if !force_variable && tensor.is_uniform() && tensor.len() > 0 {
+        if !force_variable
+            && tensor.is_uniform()
+            && tensor.len() > 0
+            && !tensor.datum_type().is_quantized()
+        {
             if tensor.datum_type() == String::datum_type() {
                 return Ok(string(tensor.to_scalar::<String>().unwrap()).into());
             } else if tensor.datum_type() == DatumType::F32 {
@@ -342,9 +346,37 @@ impl<'a> IntoAst<'a> {
             })
             .into(),
         );
+        if let Some(params) = tensor.datum_type().qparams() {
+            let quant_format = QuantFormat::Linear {
+                params,
+                bits: 8 * tensor.datum_type().size_of() as i8,
+                signed: tensor.datum_type().is_signed(),
+            };
+            self.quantization.insert(id.clone(), quant_format);
+        }
         Ok(ident(id).into())
     }
 
+    /// Record per-axis quantization parameters for an identifier in the graph.quant file.
+    pub fn axis_quantization(
+        &mut self,
+        id: &RValue,
+        datum_type: DatumType,
+        params: AxisQParams,
+    ) -> TractResult<()> {
+        if let RValue::Identifier(id) = id {
+            let quant_format = QuantFormat::LinearPerAxis {
+                params,
+                bits: 8 * datum_type.size_of() as i8,
+                signed: datum_type.is_signed(),
+            };
+            self.quantization.insert(id.clone(), quant_format);
+            Ok(())
+        } else {
+            bail!("Per-axis quantization can only apply to an identifier, got {:?}", id)
+        }
+    }
+
     fn assignment(&mut self, name: impl Into<String>, right: Arc<RValue>) {
         let name = name.into();
         if &*right == &ident(&name) {
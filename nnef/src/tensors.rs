@@ -19,7 +19,7 @@ struct Header {
     padding: [u32; 11],
 }
 
-pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
+fn read_header<R: std::io::Read>(reader: &mut R) -> TractResult<(DatumType, TVec<usize>)> {
     unsafe {
         let mut header: Header = std::mem::zeroed();
         let buffer: &mut [u8; 128] = std::mem::transmute(&mut header);
@@ -36,7 +36,9 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
         let shape: TVec<usize> =
             header.dims[0..header.rank as usize].iter().map(|d| *d as _).collect();
         let len = shape.iter().product::<usize>();
+        // strings are variable-length: their size is not checked
         if header.bits_per_item != 0xFFFFFFFF
+            && header.bits_per_item != 0xFFFF
             && len * (header.bits_per_item as usize / 8) != header.data_size_bytes as usize
         {
             bail!(
@@ -68,26 +70,61 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
                 header.bits_per_item
             ),
         };
+        Ok((dt, shape))
+    }
+}
+
+pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
+    unsafe {
+        let (dt, shape) = read_header(&mut reader)?;
         if dt.is_copy() {
             let mut tensor = Tensor::uninitialized_dt(dt, &shape)?;
             reader.read_exact(tensor.as_bytes_mut())?;
             Ok(tensor)
         } else if dt == DatumType::String {
-            let mut tensor = Tensor::zero_dt(dt, &shape)?;
-            for item in tensor.as_slice_mut_unchecked::<String>() {
+            let count = shape.iter().product();
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
                 let len: u32 = reader.read_u32::<LE>()?;
                 let mut bytes = Vec::with_capacity(len as usize);
                 bytes.set_len(len as usize);
                 reader.read_exact(&mut bytes)?;
-                *item = String::from_utf8(bytes)?;
+                items.push(String::from_utf8(bytes)?);
             }
-            Ok(tensor)
+            tensor1(&items).into_shape(&shape)
         } else {
             todo!()
         }
     }
 }
 
+/// Load a tensor from a `.dat` file, borrowing its data from a private mapping of the file
+/// instead of copying it.
+///
+/// Pages are loaded lazily and shared with other processes mapping the same file. Tensors
+/// with non-copy items (strings) are read the usual way.
+#[cfg(feature = "mapr")]
+pub fn read_tensor_mmap(file: &std::fs::File) -> TractResult<Tensor> {
+    unsafe {
+        // copy-on-write mapping: tract may mutate a tensor it holds the only reference to
+        let mut mmap = mapr::MmapOptions::new().map_copy(file)?;
+        let (dt, shape) = read_header(&mut &mmap[..])?;
+        if !dt.is_copy() {
+            return read_tensor(&mmap[..]);
+        }
+        let len = shape.iter().product::<usize>() * dt.size_of();
+        if mmap.len() < 128 + len {
+            bail!(
+                "Truncated tensor file: expected {} bytes of data, got {}",
+                len,
+                mmap.len() - 128
+            );
+        }
+        let data = mmap.as_mut_ptr().add(128);
+        Tensor::from_raw_dt_with_owner(dt, &shape, data, Arc::new(mmap))
+    }
+}
+
 pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResult<()> {
     unsafe {
         let mut header: Header = std::mem::zeroed();
@@ -138,4 +175,40 @@ mod test {
     fn header_is_128_bytes() {
         assert_eq!(std::mem::size_of::<Header>(), 128);
     }
+
+    #[test]
+    fn string_tensor() -> TractResult<()> {
+        let tensor = tensor1(&["tract".to_string(), "".to_string(), "nnef".to_string()]);
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, &tensor)?;
+        assert_eq!(read_tensor(&*buffer)?, tensor);
+        Ok(())
+    }
+
+    #[test]
+    fn string_tensor_stops_at_item_count() -> TractResult<()> {
+        let tensor = tensor1(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, &tensor)?;
+        let len = buffer.len();
+        buffer.extend_from_slice(&[0xff; 64]);
+        let mut reader = &*buffer;
+        assert_eq!(read_tensor(&mut reader)?, tensor);
+        assert_eq!(reader.len(), buffer.len() - len);
+        Ok(())
+    }
+
+    #[cfg(feature = "mapr")]
+    #[test]
+    fn mmap_tensor() -> TractResult<()> {
+        let path = std::env::temp_dir().join(format!("tract-nnef-mmap-{}.dat", std::process::id()));
+        let tensor = tensor2(&[[1f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        write_tensor(&mut std::fs::File::create(&path)?, &tensor)?;
+        let mapped = read_tensor_mmap(&std::fs::File::open(&path)?);
+        std::fs::remove_file(&path)?;
+        let mapped = mapped?;
+        assert!(mapped.is_foreign());
+        assert_eq!(mapped, tensor);
+        Ok(())
+    }
 }
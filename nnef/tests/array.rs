@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::array::{Range, ReverseSequence, Trilu, Unique};
+use tract_nnef::tract_core::ops::cnn::MaxUnpool;
+use tract_nnef::tract_core::ops::math::CumSum;
+
+fn nnef() -> Nnef {
+    tract_nnef::nnef().with_tract_core()
+}
+
+#[test]
+fn cumsum_trilu_reverse_sequence_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [3, 4]))?;
+    let cumsum = CumSum { axis: 1, exclusive: true, reverse: true };
+    let wire = model.wire_node("cumsum", cumsum, &[input])?[0];
+    let k = model.add_const("k", tensor0(1i64))?;
+    let wire = model.wire_node("trilu", Trilu { upper: false }, &[wire, k])?[0];
+    let lens = model.add_const("lens", tensor1(&[4i64, 2, 3]))?;
+    let reverse = ReverseSequence { batch_axis: 0, time_axis: 1 };
+    let wire = model.wire_node("reverse", reverse, &[wire, lens])?;
+    model.set_output_outlets(&wire)?;
+
+    let reloaded = roundtrip(&nnef(), &model)?;
+    let cumsum = reloaded.node_by_name("cumsum")?.op_as::<CumSum>().unwrap();
+    assert!(cumsum.exclusive && cumsum.reverse);
+    assert!(!reloaded.node_by_name("trilu")?.op_as::<Trilu>().unwrap().upper);
+    assert!(reloaded.node_by_name("reverse")?.op_is::<ReverseSequence>());
+    check_same_outputs(model, reloaded.into_optimized()?, tvec!(tensor(&[3, 4], 0)))
+}
+
+#[test]
+fn range_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let bounds = ["start", "end", "step"]
+        .iter()
+        .map(|name| model.add_source(*name, TypedFact::dt_scalar(i64::datum_type())))
+        .collect::<TractResult<TVec<_>>>()?;
+    let range = model.wire_node("range", Range::default(), &bounds)?;
+    model.set_output_outlets(&range)?;
+
+    let reloaded = roundtrip(&nnef(), &model)?;
+    assert!(reloaded.node_by_name("range")?.op_is::<Range>());
+    let inputs = tvec!(tensor0(10i64), tensor0(1i64), tensor0(-3i64));
+    check_same_outputs(model, reloaded.into_optimized()?, inputs)
+}
+
+#[test]
+fn unique_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [3, 2]))?;
+    let unique = model.wire_node("unique", Unique::new(Some(0), false), &[input])?;
+    model.set_output_outlets(&unique)?;
+
+    let reloaded = roundtrip(&nnef(), &model)?;
+    let unique = reloaded.node_by_name("unique")?.op_as::<Unique>().unwrap();
+    assert_eq!((unique.axis, unique.sorted), (Some(0), false));
+    let input = tensor2(&[[1f32, 2.], [0., 1.], [1., 2.]]);
+    check_same_outputs(model, reloaded.into_optimized()?, tvec!(input))
+}
+
+#[test]
+fn max_unpool_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [1, 1, 2, 2]))?;
+    let indices =
+        model.add_source("indices", TypedFact::dt_shape(i64::datum_type(), [1, 1, 2, 2]))?;
+    let op =
+        MaxUnpool { kernel_shape: tvec!(2, 2), strides: tvec!(2, 2), pads: tvec!((0, 0), (0, 1)) };
+    let unpool = model.wire_node("unpool", op, &[input, indices])?;
+    model.set_output_outlets(&unpool)?;
+
+    let reloaded = roundtrip(&nnef(), &model)?;
+    let op = reloaded.node_by_name("unpool")?.op_as::<MaxUnpool>().unwrap();
+    assert_eq!(op.pads, tvec!((0, 0), (0, 1)));
+    let inputs =
+        tvec!(tensor(&[1, 1, 2, 2], 0), tensor1(&[0i64, 2, 7, 11]).into_shape(&[1, 1, 2, 2])?);
+    check_same_outputs(model, reloaded.into_optimized()?, inputs)
+}
@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops;
+use tract_nnef::tract_core::ops::cnn::*;
+use tract_nnef::tract_core::ops::nn::DataFormat;
+
+/// Deterministic values in `[-1, 1]`, `seed` shifting the pattern.
+pub fn tensor(shape: &[usize], seed: usize) -> Tensor {
+    let len = shape.iter().product::<usize>();
+    let data = (0..len).map(|i| ((i * 7 + seed) % 11) as f32 / 5.0 - 1.0).collect::<Vec<_>>();
+    tract_ndarray::ArrayD::from_shape_vec(shape, data).unwrap().into_tensor()
+}
+
+pub fn conv(group: usize, input_channels: usize, output_channels: usize) -> ConvUnary {
+    ConvUnary {
+        pool_spec: PoolSpec::new(
+            DataFormat::NCHW,
+            tvec!(3, 3),
+            PaddingSpec::SameUpper,
+            None,
+            None,
+            Some(output_channels),
+        ),
+        kernel_fmt: KernelFormat::OIHW,
+        kernel: tensor(&[output_channels, input_channels / group, 3, 3], 0).into_arc_tensor(),
+        group,
+        bias: Some(tensor(&[output_channels], 3).into_arc_tensor()),
+        q_params: None,
+    }
+}
+
+/// A `[1, 3, 6, 6]` input through a conv, a relu added to the conv output, and a matmul.
+pub fn conv_relu_add_matmul() -> TractResult<TypedModel> {
+    let mut model = TypedModel::default();
+    let s = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [1, 3, 6, 6]))?;
+    let conv = model.wire_node("conv", conv(1, 3, 4), &[s])?;
+    let zero = tensor0(0f32).broadcast_into_rank(4)?.into_arc_tensor();
+    let relu = model.wire_node("relu", ops::math::max::unary(zero), &conv)?;
+    let add = model.wire_node("add", ops::math::add::bin_typed(), &[conv[0], relu[0]])?;
+    let reshape = model.wire_node(
+        "reshape",
+        ops::change_axes::AxisOp::Reshape(2, tvec!(6.to_dim(), 6.to_dim()), tvec!(36.to_dim())),
+        &add,
+    )?;
+    let matmul = ops::matmul::MatMulUnary::new(tensor(&[1, 5, 4], 1).into(), false, false, false);
+    let wire = model.wire_node("matmul", matmul, &reshape)?;
+    model.set_output_outlets(&wire)?;
+    Ok(model)
+}
+
+/// Dumps the model to NNEF and reads it back.
+pub fn roundtrip(nnef: &Nnef, model: &TypedModel) -> TractResult<TypedModel> {
+    let mut buffer = vec![];
+    nnef.write(model, &mut buffer)?;
+    nnef.model_for_read(&mut &*buffer)
+}
+
+/// Runs both models on the same inputs and checks that all their outputs are close.
+pub fn check_same_outputs(
+    expected: TypedModel,
+    found: TypedModel,
+    inputs: TVec<Tensor>,
+) -> TractResult<()> {
+    let expected = expected.into_runnable()?.run(inputs.clone())?;
+    let found = found.into_runnable()?.run(inputs)?;
+    if expected.len() != found.len() {
+        bail!("Expected {} outputs, found {}", expected.len(), found.len())
+    }
+    for (expected, found) in expected.iter().zip(found.iter()) {
+        expected.close_enough(found, true)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::array::TopK;
+use tract_nnef::tract_core::ops::nn::{BoxRepr, NonMaxSuppression};
+
+#[test]
+fn topk_and_nms_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let boxes = model.add_source("boxes", TypedFact::dt_shape(f32::datum_type(), [1, 4, 4]))?;
+    let scores = model.add_source("scores", TypedFact::dt_shape(f32::datum_type(), [1, 1, 4]))?;
+    let k = model.add_const("k", tensor1(&[3i64]))?;
+    let top = model.wire_node("top", TopK::new(2, true, true), &[scores, k])?;
+    let max = model.add_const("max", tensor0(2i64))?;
+    let iou = model.add_const("iou", tensor0(0.5f32))?;
+    let score = model.add_const("score", tensor0(0.1f32))?;
+    let nms = NonMaxSuppression::new(BoxRepr::CenterWidthHeight);
+    let selected = model.wire_node("nms", nms, &[boxes, scores, max, iou, score])?;
+    model.set_output_outlets(&[top[0], top[1], selected[0]])?;
+
+    let reloaded = roundtrip(&tract_nnef::nnef().with_tract_core(), &model)?;
+    assert!(reloaded.nodes().iter().any(|n| n.op_as::<TopK>().is_some()));
+    let nms = reloaded.nodes().iter().find_map(|n| n.op_as::<NonMaxSuppression>()).unwrap();
+    assert_eq!(nms.box_repr, BoxRepr::CenterWidthHeight);
+
+    let boxes = tensor3(&[[
+        [0.5f32, 0.5, 1.0, 1.0],
+        [0.5, 0.6, 1.0, 1.0],
+        [0.5, 10.5, 1.0, 1.0],
+        [0.5, 100.5, 1.0, 1.0],
+    ]]);
+    let scores = tensor3(&[[[0.9f32, 0.95, 0.05, 0.3]]]);
+    let inputs = tvec!(boxes, scores);
+    let found = reloaded.clone().into_runnable()?.run(inputs.clone())?;
+    assert_eq!(*found[1], tensor3(&[[[1i64, 0, 3]]]));
+    assert_eq!(*found[2], tensor2(&[[0i64, 0, 1], [0, 0, 3]]));
+    check_same_outputs(model, reloaded.into_optimized()?, inputs)
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::einsum::{EinSum, Expr};
+
+#[test]
+fn einsum_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), &[2, 3, 3]))?;
+    let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[3, 4]))?;
+    let diag = EinSum::new(Expr::parse("bii->bi", &[3])?);
+    let diag = model.wire_node("diag", diag, &[a])?[0];
+    let product = EinSum::new(Expr::parse("bj,jk->kb", &[2, 2])?);
+    let product = model.wire_node("product", product, &[diag, b])?;
+    model.set_output_outlets(&product)?;
+
+    let reloaded = roundtrip(&tract_nnef::nnef().with_tract_core(), &model)?;
+    let ops = reloaded.nodes().iter().filter_map(|n| n.op_as::<EinSum>()).collect::<Vec<_>>();
+    assert_eq!(ops.len(), 2);
+    assert_eq!(ops[1].expr.to_string(), "bj,jk->kb");
+
+    let inputs = tvec!(tensor(&[2, 3, 3], 0), tensor(&[3, 4], 1));
+    check_same_outputs(model, reloaded.into_optimized()?, inputs)
+}
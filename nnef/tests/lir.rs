@@ -0,0 +1,72 @@
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops;
+use tract_nnef::tract_core::ops::scan::*;
+
+fn check_roundtrip(model: TypedModel, input: Tensor) -> TractResult<()> {
+    let optimized = model.into_optimized()?;
+    let nnef = tract_nnef::nnef().with_tract_core().with_tract_lir();
+    let reloaded = roundtrip(&nnef, &optimized)?.compact()?;
+    let ops = |model: &TypedModel| {
+        model
+            .nodes()
+            .iter()
+            .filter(|n| !n.op_is::<ops::konst::Const>())
+            .map(|n| n.op().name().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ops(&optimized), ops(&reloaded));
+    check_same_outputs(optimized, reloaded, tvec!(input))
+}
+
+#[test]
+fn conv_relu_add_matmul() -> TractResult<()> {
+    check_roundtrip(common::conv_relu_add_matmul()?, tensor(&[1, 3, 6, 6], 0))
+}
+
+#[test]
+fn depth_wise() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let s = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[1, 4, 6, 6]))?;
+    let wire = model.wire_node("conv", conv(4, 4, 4), &[s])?;
+    model.set_output_outlets(&wire)?;
+    check_roundtrip(model, tensor(&[1, 4, 6, 6], 0))
+}
+
+#[test]
+fn scan() -> TractResult<()> {
+    let mut body = TypedModel::default();
+    let x = body.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[1, 4]))?;
+    let h = body.add_source("h", TypedFact::dt_shape(f32::datum_type(), &[1, 4]))?;
+    let sum = body.wire_node("sum", ops::math::add::bin_typed(), &[x, h])?;
+    let matmul = ops::matmul::MatMulUnary::new(tensor(&[4, 4], 0).into(), false, true, true);
+    let wire = body.wire_node("matmul", matmul, &sum)?;
+    body.set_output_outlets(&wire)?;
+
+    let mut model = TypedModel::default();
+    let s = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[5, 4]))?;
+    let scan = Scan::new(
+        body,
+        vec![
+            InputMapping::Scan { slot: 0, axis: 0, chunk: 1 },
+            InputMapping::State {
+                initializer: StateInitializer::Value(Tensor::zero::<f32>(&[1, 4])?.into()),
+            },
+        ],
+        vec![OutputMapping {
+            full_slot: Some(0),
+            axis: 0,
+            chunk: 1,
+            full_dim_hint: None,
+            last_value_slot: None,
+            state: true,
+        }],
+        None,
+        0,
+    )?;
+    let wire = model.wire_node("scan", scan, &[s])?;
+    model.set_output_outlets(&wire)?;
+    check_roundtrip(model, tensor(&[5, 4], 0))
+}
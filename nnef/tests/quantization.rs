@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops;
+use tract_nnef::tract_core::ops::cnn::*;
+use tract_nnef::tract_core::ops::matmul::MatMulQParams;
+use tract_nnef::tract_core::ops::nn::DataFormat;
+use tract_nnef::tract_core::quantization::{quantize, Calibration};
+
+#[test]
+fn quantized_conv_add_matmul_roundtrip() -> TractResult<()> {
+    let model = conv_relu_add_matmul()?;
+    let samples = (0..4).map(|seed| tvec!(tensor(&[1, 3, 6, 6], seed)));
+    let calibration = Calibration::new(&model, samples)?;
+    let quantized = quantize(&model, &calibration)?;
+
+    let reloaded = roundtrip(&tract_nnef::nnef().with_tract_core(), &quantized)?;
+    assert!(reloaded
+        .nodes()
+        .iter()
+        .any(|n| n.op_is::<ConvUnary>() && n.outputs[0].fact.datum_type.is_quantized()));
+
+    check_same_outputs(quantized, reloaded.into_optimized()?, tvec!(tensor(&[1, 3, 6, 6], 2)))
+}
+
+#[test]
+fn per_channel_conv_matmul_roundtrip() -> TractResult<()> {
+    let qi8 = |zero_point, scale| DatumType::QI8(QParams::ZpScale { zero_point, scale });
+    let per_channel = |a0: &[i32], a_scale: &[f32], b: (i32, f32), c: (i32, f32)| MatMulQParams {
+        a0: AttrOrInput::Attr(rctensor1(a0)),
+        a_scale: AttrOrInput::Attr(rctensor1(a_scale)),
+        b0: AttrOrInput::Attr(rctensor0(b.0)),
+        b_scale: AttrOrInput::Attr(rctensor0(b.1)),
+        c0: AttrOrInput::Attr(rctensor0(c.0)),
+        c_scale: AttrOrInput::Attr(rctensor0(c.1)),
+    };
+    let weights = (0..24).map(|i| ((i * 7) % 19) as i8 - 9).collect::<Vec<_>>();
+    let mut model = TypedModel::default();
+    let s = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[1, 3, 6]))?;
+    let s = model.wire_node("input.quant", ops::cast::cast(qi8(3, 0.05)), &[s])?[0];
+    let conv = ConvUnary {
+        pool_spec: PoolSpec::new(
+            DataFormat::NCHW,
+            tvec!(2),
+            PaddingSpec::Valid,
+            None,
+            None,
+            Some(4),
+        ),
+        kernel_fmt: KernelFormat::OIHW,
+        kernel: tensor1(&weights).into_shape(&[4, 3, 2])?.into_arc_tensor(),
+        group: 1,
+        bias: None,
+        q_params: Some((
+            qi8(-2, 0.02),
+            per_channel(&[0, 1, -1, 2], &[0.01, 0.02, 0.015, 0.005], (3, 0.05), (-2, 0.02)),
+        )),
+    };
+    let conv = model.wire_node("conv", conv, &[s])?;
+    let a = model.add_const("a", tensor1(&weights[..12]).into_shape(&[1, 3, 4])?)?;
+    let bias = model.add_const("bias", rctensor0(0i32))?;
+    let qmm = ops::matmul::QMatMul::new(
+        false,
+        false,
+        false,
+        qi8(1, 0.01),
+        per_channel(&[1, 0, -3], &[0.02, 0.01, 0.03], (-2, 0.02), (1, 0.01)),
+    );
+    let wire = model.wire_node("matmul", qmm, &[a, conv[0], bias])?;
+    model.set_output_outlets(&wire)?;
+
+    let reloaded = roundtrip(&tract_nnef::nnef().with_tract_core(), &model)?;
+    let reloaded_conv = reloaded.node_by_name("conv")?.op_as::<ConvUnary>().unwrap();
+    assert_eq!(
+        reloaded_conv.q_params.as_ref().unwrap().1.a_scale,
+        AttrOrInput::Attr(rctensor1(&[0.01f32, 0.02, 0.015, 0.005]))
+    );
+
+    check_same_outputs(model, reloaded.into_optimized()?, tvec!(tensor(&[1, 3, 6], 2)))
+}
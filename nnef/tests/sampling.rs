@@ -0,0 +1,55 @@
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::nn::{GridPadding, GridSample, Interpolation};
+use tract_nnef::tract_core::ops::nn::{RoiAlign, RoiPooling};
+
+#[test]
+fn grid_sample_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [1, 2, 3, 3]))?;
+    let grid = model.add_source("grid", TypedFact::dt_shape(f32::datum_type(), [1, 2, 2, 2]))?;
+    let op = GridSample {
+        interpolation: Interpolation::Bilinear,
+        padding: GridPadding::Reflection,
+        align_corners: true,
+    };
+    let sampled = model.wire_node("sample", op, &[input, grid])?;
+    model.set_output_outlets(&sampled)?;
+
+    let reloaded = roundtrip(&tract_nnef::nnef().with_tract_core(), &model)?;
+    let op = reloaded.node_by_name("sample")?.op_as::<GridSample>().unwrap();
+    assert_eq!(op.padding, GridPadding::Reflection);
+    assert!(op.align_corners);
+    let grid = tensor(&[1, 2, 2, 2], 1).into_array::<f32>()?.mapv(|x| x * 1.5).into_tensor();
+    let inputs = tvec!(tensor(&[1, 2, 3, 3], 0), grid);
+    check_same_outputs(model, reloaded.into_optimized()?, inputs)
+}
+
+#[test]
+fn roi_align_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [2, 1, 4, 4]))?;
+    let rois = model.add_source("rois", TypedFact::dt_shape(f32::datum_type(), [2, 4]))?;
+    let indices = model.add_const("indices", tensor1(&[1i64, 0]))?;
+    let op = RoiAlign {
+        pooling: RoiPooling::Max,
+        output_height: 2,
+        output_width: 3,
+        sampling_ratio: 2,
+        spatial_scale: 0.5,
+        half_pixel: false,
+    };
+    let pooled = model.wire_node("roi", op, &[input, rois, indices])?;
+    model.set_output_outlets(&pooled)?;
+
+    let reloaded = roundtrip(&tract_nnef::nnef().with_tract_core(), &model)?;
+    let op = reloaded.node_by_name("roi")?.op_as::<RoiAlign>().unwrap();
+    assert_eq!(op.pooling, RoiPooling::Max);
+    assert_eq!((op.output_height, op.output_width, op.sampling_ratio), (2, 3, 2));
+    assert_eq!(op.spatial_scale, 0.5);
+    assert!(!op.half_pixel);
+    let rois = tensor2(&[[0f32, 0., 6., 6.], [1., 2., 7., 5.]]);
+    check_same_outputs(model, reloaded.into_optimized()?, tvec!(tensor(&[2, 1, 4, 4], 0), rois))
+}
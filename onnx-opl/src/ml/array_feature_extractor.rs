@@ -0,0 +1,68 @@
+use tract_core::ops::array::Gather;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_ml_array_feature_extractor", &parameters(), load);
+    registry.register_dumper(TypeId::of::<ArrayFeatureExtractor>(), dump);
+}
+
+/// Selects features along the last axis of the input, by index.
+#[derive(Clone, Debug, Hash)]
+pub struct ArrayFeatureExtractor;
+
+impl_dyn_hash!(ArrayFeatureExtractor);
+
+impl ArrayFeatureExtractor {
+    fn gather(&self, rank: usize) -> Gather {
+        Gather::new(rank - 1)
+    }
+}
+
+impl Op for ArrayFeatureExtractor {
+    fn name(&self) -> Cow<str> {
+        "ArrayFeatureExtractor".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for ArrayFeatureExtractor {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.gather(inputs[0].rank()).eval(inputs)
+    }
+}
+
+impl TypedOp for ArrayFeatureExtractor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        self.gather(inputs[0].rank()).output_facts(inputs)
+    }
+
+    as_op!();
+}
+
+fn parameters() -> Vec<Parameter> {
+    vec![TypeName::Scalar.tensor().named("input"), TypeName::Integer.tensor().named("indices")]
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let indices = ast.mapping[&node.inputs[1]].clone();
+    Ok(Some(invocation("tract_onnx_ml_array_feature_extractor", &[input, indices], &[])))
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let indices = invocation.named_arg_as(builder, "indices")?;
+    builder.wire(ArrayFeatureExtractor, &[input, indices])
+}
@@ -0,0 +1,215 @@
+use tract_ndarray::{concatenate, Array2, Axis, Ix1, Ix2};
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_onnx_ml_linear_classifier",
+        &parameters_classifier(),
+        load_classifier,
+    );
+    registry.register_primitive(
+        "tract_onnx_ml_linear_regressor",
+        &parameters_regressor(),
+        load_regressor,
+    );
+    registry.register_dumper(TypeId::of::<LinearClassifier>(), dump_classifier);
+    registry.register_dumper(TypeId::of::<LinearRegressor>(), dump_regressor);
+}
+
+/// `X . coefficients^T + intercepts`, for a `[N, features]` input, `[rows, features]`
+/// coefficients and `[rows]` intercepts.
+fn linear(input: &Tensor, coefficients: &Tensor, intercepts: &Tensor) -> TractResult<Array2<f32>> {
+    let input = input.cast_to::<f32>()?;
+    let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+    let coefficients = coefficients.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+    let intercepts = intercepts.to_array_view::<f32>()?.into_dimensionality::<Ix1>()?;
+    Ok(input.dot(&coefficients.t()) + intercepts)
+}
+
+/// Linear classification scores, one column per class.
+///
+/// A single row of coefficients on a `binary` classifier scores the second class against the
+/// first: the scores are the negated and the plain linear value.
+#[derive(Clone, Debug, Hash)]
+pub struct LinearClassifier {
+    pub coefficients: Arc<Tensor>,
+    pub intercepts: Arc<Tensor>,
+    pub binary: bool,
+}
+
+impl_dyn_hash!(LinearClassifier);
+
+impl LinearClassifier {
+    pub fn n_scores(&self) -> usize {
+        if self.binary {
+            2
+        } else {
+            self.coefficients.shape()[0]
+        }
+    }
+}
+
+impl Op for LinearClassifier {
+    fn name(&self) -> Cow<str> {
+        "LinearClassifier".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{} classes, binary: {}", self.n_scores(), self.binary)])
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for LinearClassifier {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let scores = linear(&input, &self.coefficients, &self.intercepts)?;
+        let scores = if self.binary {
+            let negated = -&scores;
+            concatenate(Axis(1), &[negated.view(), scores.view()])?
+        } else {
+            scores
+        };
+        Ok(tvec!(scores.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LinearClassifier {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &[n.clone(), self.n_scores().into()])))
+    }
+
+    as_op!();
+}
+
+/// Linear regression, one column per target.
+#[derive(Clone, Debug, Hash)]
+pub struct LinearRegressor {
+    pub coefficients: Arc<Tensor>,
+    pub intercepts: Arc<Tensor>,
+}
+
+impl_dyn_hash!(LinearRegressor);
+
+impl Op for LinearRegressor {
+    fn name(&self) -> Cow<str> {
+        "LinearRegressor".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{} targets", self.coefficients.shape()[0])])
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for LinearRegressor {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let targets = linear(&input, &self.coefficients, &self.intercepts)?;
+        Ok(tvec!(targets.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LinearRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        let targets = self.coefficients.shape()[0];
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &[n.clone(), targets.into()])))
+    }
+
+    as_op!();
+}
+
+fn parameters_classifier() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("coefficients"),
+        TypeName::Scalar.tensor().named("intercepts"),
+        TypeName::Logical.named("binary"),
+    ]
+}
+
+fn dump_classifier(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<LinearClassifier>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let coefs = ast.konst_variable(format!("{}_coefficients", node.name), &op.coefficients)?;
+    let intercepts = ast.konst_variable(format!("{}_intercepts", node.name), &op.intercepts)?;
+    Ok(Some(invocation(
+        "tract_onnx_ml_linear_classifier",
+        &[input, coefs, intercepts],
+        &[("binary", logical(op.binary))],
+    )))
+}
+
+fn load_classifier(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let coefficients = invocation.named_arg_as(builder, "coefficients")?;
+    let intercepts = invocation.named_arg_as(builder, "intercepts")?;
+    let binary = invocation.named_arg_as(builder, "binary")?;
+    builder.wire(LinearClassifier { coefficients, intercepts, binary }, &[input])
+}
+
+fn parameters_regressor() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("coefficients"),
+        TypeName::Scalar.tensor().named("intercepts"),
+    ]
+}
+
+fn dump_regressor(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<LinearRegressor>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let coefs = ast.konst_variable(format!("{}_coefficients", node.name), &op.coefficients)?;
+    let intercepts = ast.konst_variable(format!("{}_intercepts", node.name), &op.intercepts)?;
+    Ok(Some(invocation("tract_onnx_ml_linear_regressor", &[input, coefs, intercepts], &[])))
+}
+
+fn load_regressor(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let coefficients = invocation.named_arg_as(builder, "coefficients")?;
+    let intercepts = invocation.named_arg_as(builder, "intercepts")?;
+    builder.wire(LinearRegressor { coefficients, intercepts }, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn binary_scores() -> TractResult<()> {
+        let op = LinearClassifier {
+            coefficients: rctensor2(&[[1f32, -1.]]),
+            intercepts: rctensor1(&[0.5f32]),
+            binary: true,
+        };
+        let output = op.eval(tvec!(rctensor2(&[[2i64, 1], [0, 2]])))?;
+        assert_eq!(*output[0], tensor2(&[[-1.5f32, 1.5], [1.5, -1.5]]));
+        Ok(())
+    }
+}
@@ -1,12 +1,26 @@
 use tract_nnef::internal::*;
 
+pub mod array_feature_extractor;
 pub mod category_mapper;
+pub mod linear;
+pub mod one_hot_encoder;
+pub mod preprocessing;
+pub mod svm;
 pub mod tree;
 pub mod tree_ensemble_classifier;
+pub mod tree_ensemble_regressor;
+pub mod zip_map;
 
 pub use category_mapper::{DirectLookup, ReverseLookup};
 
 pub fn register(registry: &mut Registry) {
+    array_feature_extractor::register(registry);
     category_mapper::register(registry);
+    linear::register(registry);
+    one_hot_encoder::register(registry);
+    preprocessing::register(registry);
+    svm::register(registry);
     tree_ensemble_classifier::register(registry);
+    tree_ensemble_regressor::register(registry);
+    zip_map::register(registry);
 }
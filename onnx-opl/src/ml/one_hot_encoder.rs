@@ -0,0 +1,96 @@
+use tract_ndarray::ArrayD;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_ml_one_hot_encoder", &parameters(), load);
+    registry.register_dumper(TypeId::of::<OneHotEncoder>(), dump);
+}
+
+/// One-hot encodes the input over a new last axis, one column per category.
+///
+/// Values that are not a known category are encoded as a row of zeros.
+#[derive(Clone, Debug, Hash)]
+pub struct OneHotEncoder {
+    pub categories: Arc<Tensor>,
+}
+
+impl_dyn_hash!(OneHotEncoder);
+
+impl OneHotEncoder {
+    fn encode<T: Datum + PartialEq>(&self, input: &Tensor) -> TractResult<ArrayD<f32>> {
+        let categories = self.categories.as_slice::<T>()?;
+        let mut shape = input.shape().to_vec();
+        shape.push(categories.len());
+        let mut output = ArrayD::<f32>::zeros(shape);
+        let output_slice = output.as_slice_mut().unwrap();
+        for (ix, x) in input.as_slice::<T>()?.iter().enumerate() {
+            if let Some(cat) = categories.iter().position(|c| c == x) {
+                output_slice[ix * categories.len() + cat] = 1.0;
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl Op for OneHotEncoder {
+    fn name(&self) -> Cow<str> {
+        "OneHotEncoder".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{} categories", self.categories.len())])
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for OneHotEncoder {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.cast_to_dt(self.categories.datum_type())?;
+        let output = if self.categories.datum_type() == String::datum_type() {
+            self.encode::<String>(&input)?
+        } else {
+            self.encode::<i64>(&input)?
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for OneHotEncoder {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut shape = inputs[0].shape.to_tvec();
+        shape.push(self.categories.len().to_dim());
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape)))
+    }
+
+    as_op!();
+}
+
+fn parameters() -> Vec<Parameter> {
+    vec![TypeName::Scalar.tensor().named("input"), TypeName::Scalar.tensor().named("categories")]
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<OneHotEncoder>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let categories = ast.konst_variable(format!("{}_categories", node.name), &op.categories)?;
+    Ok(Some(invocation("tract_onnx_ml_one_hot_encoder", &[input, categories], &[])))
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let categories = invocation.named_arg_as(builder, "categories")?;
+    builder.wire(OneHotEncoder { categories }, &[input])
+}
@@ -0,0 +1,299 @@
+use tract_ndarray::Axis;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_onnx_ml_normalizer",
+        &parameters_normalizer(),
+        load_normalizer,
+    );
+    registry.register_primitive("tract_onnx_ml_scaler", &parameters_scaler(), load_scaler);
+    registry.register_primitive("tract_onnx_ml_imputer", &parameters_imputer(), load_imputer);
+    registry.register_dumper(TypeId::of::<Normalizer>(), dump_normalizer);
+    registry.register_dumper(TypeId::of::<Scaler>(), dump_scaler);
+    registry.register_dumper(TypeId::of::<Imputer>(), dump_imputer);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Norm {
+    Max,
+    L1,
+    L2,
+}
+
+impl Norm {
+    pub fn parse(s: &str) -> TractResult<Norm> {
+        match s {
+            "MAX" => Ok(Norm::Max),
+            "L1" => Ok(Norm::L1),
+            "L2" => Ok(Norm::L2),
+            _ => bail!("Invalid norm: {}", s),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Norm::Max => "MAX",
+            Norm::L1 => "L1",
+            Norm::L2 => "L2",
+        }
+    }
+}
+
+/// Normalizes each row of the input, along its last axis, by its max, L1 or L2 norm.
+#[derive(Clone, Debug, Hash)]
+pub struct Normalizer {
+    pub norm: Norm,
+}
+
+impl_dyn_hash!(Normalizer);
+
+impl Op for Normalizer {
+    fn name(&self) -> Cow<str> {
+        "Normalizer".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("norm: {}", self.norm.as_str())])
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for Normalizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut output = input.cast_to::<f32>()?.into_owned();
+        let mut view = output.to_array_view_mut::<f32>()?;
+        let axis = Axis(view.ndim() - 1);
+        for mut row in view.lanes_mut(axis) {
+            let norm = match self.norm {
+                Norm::Max => row.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+                Norm::L1 => row.iter().map(|x| x.abs()).sum(),
+                Norm::L2 => row.iter().map(|x| x * x).sum::<f32>().sqrt(),
+            };
+            row.mapv_inplace(|x| x / norm);
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Normalizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), inputs[0].shape.clone())))
+    }
+
+    as_op!();
+}
+
+/// `(X - offset) * scale`, with `offset` and `scale` broadcast along the last axis.
+#[derive(Clone, Debug, Hash)]
+pub struct Scaler {
+    pub offset: Arc<Tensor>,
+    pub scale: Arc<Tensor>,
+}
+
+impl_dyn_hash!(Scaler);
+
+impl Op for Scaler {
+    fn name(&self) -> Cow<str> {
+        "Scaler".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for Scaler {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?;
+        let offset = self.offset.to_array_view::<f32>()?;
+        let scale = self.scale.to_array_view::<f32>()?;
+        let output = (&input - &offset) * scale;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Scaler {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), inputs[0].shape.clone())))
+    }
+
+    as_op!();
+}
+
+/// Replaces the `replaced` values (NaN included) by `imputed`, broadcast along the last axis.
+#[derive(Clone, Debug, Hash)]
+pub struct Imputer {
+    pub imputed: Arc<Tensor>,
+    pub replaced: Arc<Tensor>,
+}
+
+impl_dyn_hash!(Imputer);
+
+impl Imputer {
+    fn eval_t<T: Datum + Copy + PartialEq>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let imputed = self.imputed.cast_to::<T>()?;
+        let imputed = imputed.to_array_view::<T>()?;
+        let replaced = *self.replaced.cast_to::<T>()?.to_scalar::<T>()?;
+        // NaN is the only value not equal to itself
+        #[allow(clippy::eq_op)]
+        let is_replaced = |x: T| x == replaced || (replaced != replaced && x != x);
+        let mut output = input.clone();
+        let mut view = output.to_array_view_mut::<T>()?;
+        let axis = Axis(view.ndim() - 1);
+        for mut row in view.lanes_mut(axis) {
+            for (ix, x) in row.iter_mut().enumerate() {
+                if is_replaced(*x) {
+                    *x = imputed[ix % imputed.len()];
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl Op for Imputer {
+    fn name(&self) -> Cow<str> {
+        "Imputer".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for Imputer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = dispatch_numbers!(Self::eval_t(input.datum_type())(self, &input))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Imputer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, inputs[0].shape.clone())))
+    }
+
+    as_op!();
+}
+
+fn parameters_normalizer() -> Vec<Parameter> {
+    vec![TypeName::Scalar.tensor().named("input"), TypeName::String.named("norm")]
+}
+
+fn dump_normalizer(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Normalizer>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_onnx_ml_normalizer",
+        &[input],
+        &[("norm", string(op.norm.as_str()))],
+    )))
+}
+
+fn load_normalizer(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let norm: String = invocation.named_arg_as(builder, "norm")?;
+    builder.wire(Normalizer { norm: Norm::parse(&norm)? }, &[input])
+}
+
+fn parameters_scaler() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("offset"),
+        TypeName::Scalar.tensor().named("scale"),
+    ]
+}
+
+fn dump_scaler(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Scaler>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let offset = ast.konst_variable(format!("{}_offset", node.name), &op.offset)?;
+    let scale = ast.konst_variable(format!("{}_scale", node.name), &op.scale)?;
+    Ok(Some(invocation("tract_onnx_ml_scaler", &[input, offset, scale], &[])))
+}
+
+fn load_scaler(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let offset = invocation.named_arg_as(builder, "offset")?;
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    builder.wire(Scaler { offset, scale }, &[input])
+}
+
+fn parameters_imputer() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("imputed"),
+        TypeName::Scalar.tensor().named("replaced"),
+    ]
+}
+
+fn dump_imputer(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Imputer>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let imputed = ast.konst_variable(format!("{}_imputed", node.name), &op.imputed)?;
+    let replaced = ast.konst_variable(format!("{}_replaced", node.name), &op.replaced)?;
+    Ok(Some(invocation("tract_onnx_ml_imputer", &[input, imputed, replaced], &[])))
+}
+
+fn load_imputer(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let imputed = invocation.named_arg_as(builder, "imputed")?;
+    let replaced = invocation.named_arg_as(builder, "replaced")?;
+    builder.wire(Imputer { imputed, replaced }, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn l2_normalizer() -> TractResult<()> {
+        let op = Normalizer { norm: Norm::L2 };
+        let output = op.eval(tvec!(rctensor2(&[[3i64, 4], [0, 2]])))?;
+        assert_eq!(*output[0], tensor2(&[[0.6f32, 0.8], [0., 1.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn integer_imputer() -> TractResult<()> {
+        let op = Imputer { imputed: rctensor1(&[7i64]), replaced: rctensor0(-1i64) };
+        let output = op.eval(tvec!(rctensor2(&[[-1i64, 4], [3, -1]])))?;
+        assert_eq!(*output[0], tensor2(&[[7i64, 4], [3, 7]]));
+        Ok(())
+    }
+}
@@ -0,0 +1,518 @@
+use tract_ndarray::{Array1, Array2, ArrayView1, Axis, Ix1, Ix2};
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_onnx_ml_svm_classifier",
+        &parameters_classifier(),
+        load_classifier,
+    );
+    registry.register_primitive(
+        "tract_onnx_ml_svm_regressor",
+        &parameters_regressor(),
+        load_regressor,
+    );
+    registry.register_dumper(TypeId::of::<SvmClassifier>(), dump_classifier);
+    registry.register_dumper(TypeId::of::<SvmRegressor>(), dump_regressor);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KernelType {
+    Linear,
+    Poly,
+    Rbf,
+    Sigmoid,
+}
+
+impl KernelType {
+    pub fn parse(s: &str) -> TractResult<KernelType> {
+        match s {
+            "LINEAR" => Ok(KernelType::Linear),
+            "POLY" => Ok(KernelType::Poly),
+            "RBF" => Ok(KernelType::Rbf),
+            "SIGMOID" => Ok(KernelType::Sigmoid),
+            _ => bail!("Invalid kernel type: {}", s),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KernelType::Linear => "LINEAR",
+            KernelType::Poly => "POLY",
+            KernelType::Rbf => "RBF",
+            KernelType::Sigmoid => "SIGMOID",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct Kernel {
+    pub kernel_type: KernelType,
+    #[educe(Hash(method = "hash_f32"))]
+    pub gamma: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub coef0: f32,
+    pub degree: i32,
+}
+
+impl Kernel {
+    fn eval(&self, a: &ArrayView1<f32>, b: &ArrayView1<f32>) -> f32 {
+        match self.kernel_type {
+            KernelType::Linear => a.dot(b),
+            KernelType::Poly => (self.gamma * a.dot(b) + self.coef0).powi(self.degree),
+            KernelType::Rbf => {
+                let d2 = a.iter().zip(b.iter()).map(|(a, b)| (a - b) * (a - b)).sum::<f32>();
+                (-self.gamma * d2).exp()
+            }
+            KernelType::Sigmoid => (self.gamma * a.dot(b) + self.coef0).tanh(),
+        }
+    }
+
+    /// Kernel values between each row of `input` and each row of `vectors`.
+    fn eval_rows(&self, input: &ArrayView1<f32>, vectors: &Tensor) -> TractResult<Array1<f32>> {
+        let vectors = vectors.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        Ok(vectors.outer_iter().map(|v| self.eval(input, &v)).collect())
+    }
+}
+
+fn sigmoid_probability(score: f32, a: f32, b: f32) -> f32 {
+    let f_apb = score * a + b;
+    let p = if f_apb >= 0.0 {
+        (-f_apb).exp() / (1.0 + (-f_apb).exp())
+    } else {
+        1.0 / (1.0 + f_apb.exp())
+    };
+    p.clamp(1e-7, 1.0 - 1e-7)
+}
+
+/// Couples pairwise class probabilities `r` into per-class probabilities, as libsvm does.
+fn multiclass_probability(r: &Array2<f32>) -> Array1<f32> {
+    let k = r.nrows();
+    let mut q = Array2::<f32>::zeros((k, k));
+    for t in 0..k {
+        for j in 0..t {
+            q[(t, t)] += r[(j, t)] * r[(j, t)];
+            q[(t, j)] = q[(j, t)];
+        }
+        for j in t + 1..k {
+            q[(t, t)] += r[(j, t)] * r[(j, t)];
+            q[(t, j)] = -r[(j, t)] * r[(t, j)];
+        }
+    }
+    let mut p = Array1::from_elem(k, 1.0 / k as f32);
+    let eps = 0.005 / k as f32;
+    for _ in 0..100.max(k) {
+        let mut qp = q.dot(&p);
+        let mut pqp = p.dot(&qp);
+        if qp.iter().all(|qp| (qp - pqp).abs() < eps) {
+            break;
+        }
+        for t in 0..k {
+            let diff = (pqp - qp[t]) / q[(t, t)];
+            p[t] += diff;
+            pqp = (pqp + diff * (diff * q[(t, t)] + 2.0 * qp[t])) / (1.0 + diff) / (1.0 + diff);
+            for j in 0..k {
+                qp[j] = (qp[j] + diff * q[(t, j)]) / (1.0 + diff);
+                p[j] /= 1.0 + diff;
+            }
+        }
+    }
+    p
+}
+
+/// Support vector classification, as trained by libsvm.
+///
+/// With `vectors_per_class`, `support_vectors` are grouped by class and `coefficients` is the
+/// `[classes - 1, support_vectors]` matrix of one-versus-one dual coefficients. Without it, the
+/// model is linear: `support_vectors` holds one weight row per class.
+///
+/// Outputs the index of the winning class and the scores: class probabilities if `prob_a` and
+/// `prob_b` are set, one-versus-one decision values otherwise, per class scores for linear models.
+#[derive(Clone, Debug, Hash)]
+pub struct SvmClassifier {
+    pub kernel: Kernel,
+    pub n_classes: usize,
+    pub support_vectors: Arc<Tensor>,
+    pub vectors_per_class: Option<TVec<usize>>,
+    pub coefficients: Arc<Tensor>,
+    pub rho: Arc<Tensor>,
+    pub prob_a: Option<Arc<Tensor>>,
+    pub prob_b: Option<Arc<Tensor>>,
+}
+
+impl_dyn_hash!(SvmClassifier);
+
+impl SvmClassifier {
+    pub fn n_scores(&self) -> usize {
+        if self.vectors_per_class.is_none() || self.prob_a.is_some() {
+            self.n_classes
+        } else {
+            self.n_classes * (self.n_classes - 1) / 2
+        }
+    }
+
+    fn eval_one(
+        &self,
+        input: &ArrayView1<f32>,
+        scores: &mut [f32],
+        kernels: &mut Array1<f32>,
+    ) -> TractResult<usize> {
+        let rho = self.rho.as_slice::<f32>()?;
+        let vectors_per_class = if let Some(vpc) = &self.vectors_per_class {
+            vpc
+        } else {
+            // linear model: one kernel value per class
+            *kernels = self.kernel.eval_rows(input, &self.support_vectors)?;
+            for (ix, score) in scores.iter_mut().enumerate() {
+                *score = kernels[ix] + rho[ix];
+            }
+            let winner = scores
+                .iter()
+                .enumerate()
+                .fold((0, f32::MIN), |best, (ix, &s)| if s > best.1 { (ix, s) } else { best })
+                .0;
+            return Ok(winner);
+        };
+        *kernels = self.kernel.eval_rows(input, &self.support_vectors)?;
+        let coefficients = self
+            .coefficients
+            .to_array_view::<f32>()?
+            .into_shape((self.n_classes - 1, self.support_vectors.shape()[0]))?;
+        let starts: TVec<usize> = vectors_per_class
+            .iter()
+            .scan(0, |start, &count| {
+                *start += count;
+                Some(*start - count)
+            })
+            .collect();
+        let mut votes = tvec!(0usize; self.n_classes);
+        let mut decisions = tvec!();
+        for i in 0..self.n_classes {
+            for j in i + 1..self.n_classes {
+                let mut sum = rho[decisions.len()];
+                for k in starts[i]..starts[i] + vectors_per_class[i] {
+                    sum += coefficients[(j - 1, k)] * kernels[k];
+                }
+                for k in starts[j]..starts[j] + vectors_per_class[j] {
+                    sum += coefficients[(i, k)] * kernels[k];
+                }
+                votes[if sum > 0.0 { i } else { j }] += 1;
+                decisions.push(sum);
+            }
+        }
+        if let (Some(prob_a), Some(prob_b)) = (&self.prob_a, &self.prob_b) {
+            let prob_a = prob_a.as_slice::<f32>()?;
+            let prob_b = prob_b.as_slice::<f32>()?;
+            let mut pairwise = Array2::<f32>::zeros((self.n_classes, self.n_classes));
+            let mut pair = 0;
+            for i in 0..self.n_classes {
+                for j in i + 1..self.n_classes {
+                    let p = sigmoid_probability(decisions[pair], prob_a[pair], prob_b[pair]);
+                    pairwise[(i, j)] = p;
+                    pairwise[(j, i)] = 1.0 - p;
+                    pair += 1;
+                }
+            }
+            let probabilities = multiclass_probability(&pairwise);
+            scores.copy_from_slice(probabilities.as_slice().unwrap());
+            let winner = scores
+                .iter()
+                .enumerate()
+                .fold((0, f32::MIN), |best, (ix, &s)| if s > best.1 { (ix, s) } else { best })
+                .0;
+            Ok(winner)
+        } else {
+            scores.copy_from_slice(&decisions);
+            let winner = votes
+                .iter()
+                .enumerate()
+                .fold((0, 0), |best, (ix, &v)| if v > best.1 { (ix, v) } else { best })
+                .0;
+            Ok(winner)
+        }
+    }
+}
+
+impl Op for SvmClassifier {
+    fn name(&self) -> Cow<str> {
+        "SvmClassifier".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{:?}, {} classes", self.kernel, self.n_classes)])
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for SvmClassifier {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let n = input.nrows();
+        let mut winners = Array1::<i32>::zeros(n);
+        let mut scores = Array2::<f32>::zeros((n, self.n_scores()));
+        let mut kernels = Array1::zeros(0);
+        for (ix, row) in input.outer_iter().enumerate() {
+            let mut scores = scores.index_axis_mut(Axis(0), ix);
+            winners[ix] = self.eval_one(&row, scores.as_slice_mut().unwrap(), &mut kernels)? as i32;
+        }
+        Ok(tvec!(winners.into_arc_tensor(), scores.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SvmClassifier {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        Ok(tvec!(
+            TypedFact::dt_shape(i32::datum_type(), std::slice::from_ref(n)),
+            TypedFact::dt_shape(f32::datum_type(), &[n.clone(), self.n_scores().into()])
+        ))
+    }
+
+    as_op!();
+}
+
+/// Support vector regression, or one class classification with `one_class`.
+///
+/// Without support vectors, the model is linear and `coefficients` is the weight vector.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct SvmRegressor {
+    pub kernel: Kernel,
+    pub support_vectors: Arc<Tensor>,
+    pub coefficients: Arc<Tensor>,
+    #[educe(Hash(method = "hash_f32"))]
+    pub rho: f32,
+    pub one_class: bool,
+}
+
+impl_dyn_hash!(SvmRegressor);
+
+impl Op for SvmRegressor {
+    fn name(&self) -> Cow<str> {
+        "SvmRegressor".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{:?}", self.kernel)])
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for SvmRegressor {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let coefficients = self.coefficients.to_array_view::<f32>()?;
+        let coefficients = coefficients.into_dimensionality::<Ix1>()?;
+        let mut output = Array2::<f32>::zeros((input.nrows(), 1));
+        for (row, output) in input.outer_iter().zip(output.iter_mut()) {
+            let sum = if self.support_vectors.len() == 0 {
+                self.kernel.eval(&row, &coefficients)
+            } else {
+                self.kernel.eval_rows(&row, &self.support_vectors)?.dot(&coefficients)
+            } + self.rho;
+            *output = if !self.one_class {
+                sum
+            } else if sum > 0.0 {
+                1.0
+            } else {
+                -1.0
+            };
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SvmRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &[n.clone(), 1.to_dim()])))
+    }
+
+    as_op!();
+}
+
+fn kernel_parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::String.named("kernel_type"),
+        TypeName::Scalar.named("gamma"),
+        TypeName::Scalar.named("coef0"),
+        TypeName::Integer.named("degree"),
+    ]
+}
+
+fn dump_kernel(kernel: &Kernel) -> Vec<(&'static str, RValue)> {
+    vec![
+        ("kernel_type", string(kernel.kernel_type.as_str())),
+        ("gamma", numeric(kernel.gamma)),
+        ("coef0", numeric(kernel.coef0)),
+        ("degree", numeric(kernel.degree)),
+    ]
+}
+
+fn load_kernel(builder: &mut ModelBuilder, invocation: &ResolvedInvocation) -> TractResult<Kernel> {
+    let kernel_type: String = invocation.named_arg_as(builder, "kernel_type")?;
+    let gamma = invocation.named_arg_as(builder, "gamma")?;
+    let coef0 = invocation.named_arg_as(builder, "coef0")?;
+    let degree = invocation.named_arg_as::<i64>(builder, "degree")? as i32;
+    Ok(Kernel { kernel_type: KernelType::parse(&kernel_type)?, gamma, coef0, degree })
+}
+
+fn parameters_classifier() -> Vec<Parameter> {
+    let mut params = vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("support_vectors"),
+        TypeName::Scalar.tensor().named("coefficients"),
+        TypeName::Scalar.tensor().named("rho"),
+        TypeName::Integer.named("n_classes"),
+        TypeName::Integer.array().named("vectors_per_class"),
+        TypeName::Scalar.tensor().named("prob_a"),
+        TypeName::Scalar.tensor().named("prob_b"),
+    ];
+    params.extend(kernel_parameters());
+    params
+}
+
+fn dump_classifier(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<SvmClassifier>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let sv = ast.konst_variable(format!("{}_support_vectors", node.name), &op.support_vectors)?;
+    let coefs = ast.konst_variable(format!("{}_coefficients", node.name), &op.coefficients)?;
+    let rho = ast.konst_variable(format!("{}_rho", node.name), &op.rho)?;
+    let mut named = vec![("n_classes", numeric(op.n_classes))];
+    if let Some(vpc) = &op.vectors_per_class {
+        named.push(("vectors_per_class", ints(vpc)));
+    }
+    if let (Some(prob_a), Some(prob_b)) = (&op.prob_a, &op.prob_b) {
+        let prob_a = ast.konst_variable(format!("{}_prob_a", node.name), prob_a)?;
+        let prob_b = ast.konst_variable(format!("{}_prob_b", node.name), prob_b)?;
+        named.push(("prob_a", prob_a.as_ref().clone()));
+        named.push(("prob_b", prob_b.as_ref().clone()));
+    }
+    named.extend(dump_kernel(&op.kernel));
+    Ok(Some(invocation("tract_onnx_ml_svm_classifier", &[input, sv, coefs, rho], &named)))
+}
+
+fn load_classifier(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let support_vectors = invocation.named_arg_as(builder, "support_vectors")?;
+    let coefficients = invocation.named_arg_as(builder, "coefficients")?;
+    let rho = invocation.named_arg_as(builder, "rho")?;
+    let n_classes = invocation.named_arg_as(builder, "n_classes")?;
+    let vectors_per_class = if invocation.get_named_arg("vectors_per_class").is_some() {
+        Some(invocation.named_arg_as(builder, "vectors_per_class")?)
+    } else {
+        None
+    };
+    let (prob_a, prob_b) = if invocation.get_named_arg("prob_a").is_some() {
+        (
+            Some(invocation.named_arg_as(builder, "prob_a")?),
+            Some(invocation.named_arg_as(builder, "prob_b")?),
+        )
+    } else {
+        (None, None)
+    };
+    let kernel = load_kernel(builder, invocation)?;
+    let op = SvmClassifier {
+        kernel,
+        n_classes,
+        support_vectors,
+        vectors_per_class,
+        coefficients,
+        rho,
+        prob_a,
+        prob_b,
+    };
+    builder.wire(op, &[input])
+}
+
+fn parameters_regressor() -> Vec<Parameter> {
+    let mut params = vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("support_vectors"),
+        TypeName::Scalar.tensor().named("coefficients"),
+        TypeName::Scalar.named("rho"),
+        TypeName::Logical.named("one_class"),
+    ];
+    params.extend(kernel_parameters());
+    params
+}
+
+fn dump_regressor(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<SvmRegressor>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let sv = ast.konst_variable(format!("{}_support_vectors", node.name), &op.support_vectors)?;
+    let coefs = ast.konst_variable(format!("{}_coefficients", node.name), &op.coefficients)?;
+    let mut named = vec![("rho", numeric(op.rho)), ("one_class", logical(op.one_class))];
+    named.extend(dump_kernel(&op.kernel));
+    Ok(Some(invocation("tract_onnx_ml_svm_regressor", &[input, sv, coefs], &named)))
+}
+
+fn load_regressor(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let support_vectors = invocation.named_arg_as(builder, "support_vectors")?;
+    let coefficients = invocation.named_arg_as(builder, "coefficients")?;
+    let rho = invocation.named_arg_as(builder, "rho")?;
+    let one_class = invocation.named_arg_as(builder, "one_class")?;
+    let kernel = load_kernel(builder, invocation)?;
+    builder.wire(SvmRegressor { kernel, support_vectors, coefficients, rho, one_class }, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pairwise_coupling_of_symmetric_classes() {
+        let r = tract_ndarray::arr2(&[[0.0f32, 0.5, 0.5], [0.5, 0.0, 0.5], [0.5, 0.5, 0.0]]);
+        let p = multiclass_probability(&r);
+        assert!(p.iter().all(|p| (p - 1.0 / 3.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn one_versus_one_votes() -> TractResult<()> {
+        // one support vector per class, on a line
+        let op = SvmClassifier {
+            kernel: Kernel { kernel_type: KernelType::Linear, gamma: 0.0, coef0: 0.0, degree: 0 },
+            n_classes: 3,
+            support_vectors: rctensor2(&[[-1f32], [0.], [1.]]),
+            vectors_per_class: Some(tvec!(1, 1, 1)),
+            coefficients: rctensor2(&[[1f32, -1., -1.], [1., 1., -1.]]),
+            rho: rctensor1(&[0f32, 0., 0.]),
+            prob_a: None,
+            prob_b: None,
+        };
+        let output = op.eval(tvec!(rctensor2(&[[-2f32], [2.]])))?;
+        assert_eq!(*output[0], tensor1(&[0i32, 2]));
+        assert_eq!(output[1].shape(), &[2, 3]);
+        Ok(())
+    }
+}
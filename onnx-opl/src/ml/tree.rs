@@ -230,21 +230,36 @@ impl AggregateFn for AvgFn {
     }
 }
 
+// the first score replaces the zero the total starts from
 #[derive(Clone, Copy, Default, Debug)]
-pub struct MaxFn;
+pub struct MaxFn {
+    seen: bool,
+}
 
 impl AggregateFn for MaxFn {
     fn aggregate(&mut self, score: f32, total: &mut f32) {
-        *total = total.max(score);
+        *total = if self.seen { total.max(score) } else { score };
+        self.seen = true;
+    }
+
+    fn post_aggregate(&mut self, _total: &mut f32) {
+        self.seen = false;
     }
 }
 
 #[derive(Clone, Copy, Default, Debug)]
-pub struct MinFn;
+pub struct MinFn {
+    seen: bool,
+}
 
 impl AggregateFn for MinFn {
     fn aggregate(&mut self, score: f32, total: &mut f32) {
-        *total = total.min(score);
+        *total = if self.seen { total.min(score) } else { score };
+        self.seen = true;
+    }
+
+    fn post_aggregate(&mut self, _total: &mut f32) {
+        self.seen = false;
     }
 }
 
@@ -61,7 +61,7 @@ impl TypedOp for TreeEnsembleClassifier {
     as_op!();
 }
 
-fn parameters() -> Vec<Parameter> {
+pub(crate) fn parameters() -> Vec<Parameter> {
     vec![
         TypeName::Scalar.tensor().named("input"),
         TypeName::Scalar.tensor().named("trees"),
@@ -75,22 +75,31 @@ fn parameters() -> Vec<Parameter> {
 
 fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
     let op = node.op_as::<TreeEnsembleClassifier>().context("wrong op")?;
+    dump_ensemble(ast, node, &op.ensemble, "tract_onnx_ml_tree_ensemble_classifier")
+}
+
+pub(crate) fn dump_ensemble(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    ensemble: &TreeEnsemble,
+    primitive: &str,
+) -> TractResult<Option<Arc<RValue>>> {
     let input = ast.mapping[&node.inputs[0]].clone();
-    let trees = ast.konst_variable(format!("{}_trees", node.name), &op.ensemble.data.trees)?;
-    let nodes = ast.konst_variable(format!("{}_nodes", node.name), &op.ensemble.data.nodes)?;
-    let leaves = ast.konst_variable(format!("{}_leaves", node.name), &op.ensemble.data.leaves)?;
-    let agg = match op.ensemble.aggregate_fn {
+    let trees = ast.konst_variable(format!("{}_trees", node.name), &ensemble.data.trees)?;
+    let nodes = ast.konst_variable(format!("{}_nodes", node.name), &ensemble.data.nodes)?;
+    let leaves = ast.konst_variable(format!("{}_leaves", node.name), &ensemble.data.leaves)?;
+    let agg = match ensemble.aggregate_fn {
         Aggregate::Min => "MIN",
         Aggregate::Max => "MAX",
         Aggregate::Sum => "SUM",
         Aggregate::Avg => "AVERAGE",
     };
     Ok(Some(invocation(
-        "tract_onnx_ml_tree_ensemble_classifier",
+        primitive,
         &[input, trees, nodes, leaves],
         &[
-            ("max_used_feature", numeric(op.ensemble.max_used_feature)),
-            ("n_classes", numeric(op.ensemble.n_classes)),
+            ("max_used_feature", numeric(ensemble.max_used_feature)),
+            ("n_classes", numeric(ensemble.n_classes)),
             ("aggregate_fn", string(agg)),
         ],
     )))
@@ -100,6 +109,14 @@ fn load(
     builder: &mut ModelBuilder,
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
+    let (input, ensemble) = load_ensemble(builder, invocation)?;
+    builder.wire(TreeEnsembleClassifier { ensemble }, &[input])
+}
+
+pub(crate) fn load_ensemble(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<(OutletId, TreeEnsemble)> {
     let input = invocation.named_arg_as(builder, "input")?;
     let trees = invocation.named_arg_as(builder, "trees")?;
     let nodes = invocation.named_arg_as(builder, "nodes")?;
@@ -109,7 +126,5 @@ fn load(
     let aggregate_fn: String = invocation.named_arg_as(builder, "aggregate_fn")?;
     let aggregate_fn = parse_aggregate(&aggregate_fn)?;
     let data = TreeEnsembleData { trees, nodes, leaves };
-    let ensemble = TreeEnsemble { data, n_classes, max_used_feature, aggregate_fn };
-    let op = TreeEnsembleClassifier { ensemble };
-    builder.wire(op, &[input])
+    Ok((input, TreeEnsemble { data, n_classes, max_used_feature, aggregate_fn }))
 }
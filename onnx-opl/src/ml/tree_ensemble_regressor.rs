@@ -0,0 +1,111 @@
+use super::tree::TreeEnsemble;
+use super::tree_ensemble_classifier::{dump_ensemble, load_ensemble, parameters};
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_ml_tree_ensemble_regressor", &parameters(), load);
+    registry.register_dumper(TypeId::of::<TreeEnsembleRegressor>(), dump);
+}
+
+/// Raw tree ensemble regression: one column per target, before base values and post transform.
+#[derive(Debug, Clone, Hash)]
+pub struct TreeEnsembleRegressor {
+    pub ensemble: TreeEnsemble,
+}
+
+impl_dyn_hash!(TreeEnsembleRegressor);
+
+impl Op for TreeEnsembleRegressor {
+    fn name(&self) -> Cow<str> {
+        "TreeEnsembleRegressor".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for TreeEnsembleRegressor {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?;
+        let targets = self.ensemble.eval(input)?;
+        Ok(tvec!(targets.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for TreeEnsembleRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = &inputs[0].shape[0];
+        Ok(tvec!(TypedFact::dt_shape(
+            f32::datum_type(),
+            &[n.clone(), self.ensemble.n_classes().into()]
+        )))
+    }
+
+    as_op!();
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<TreeEnsembleRegressor>().context("wrong op")?;
+    dump_ensemble(ast, node, &op.ensemble, "tract_onnx_ml_tree_ensemble_regressor")
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let (input, ensemble) = load_ensemble(builder, invocation)?;
+    builder.wire(TreeEnsembleRegressor { ensemble }, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ml::tree::*;
+
+    // two single-branch trees on feature 0, with negative leaves so that max and min do not start
+    // from zero
+    fn ensemble(aggregate_fn: Aggregate) -> TreeEnsemble {
+        let leaf = |start: u32| [start, start + 1, 0, 0, 0];
+        let nodes = rctensor2(&[
+            [0, 1, 2, 0f32.to_bits(), Cmp::LessEqual as u32],
+            leaf(0),
+            leaf(1),
+            [0, 4, 5, 1f32.to_bits(), Cmp::LessEqual as u32],
+            leaf(2),
+            leaf(3),
+        ]);
+        let leaves = rctensor2(&[
+            [0, (-1f32).to_bits()],
+            [0, (-2f32).to_bits()],
+            [0, (-3f32).to_bits()],
+            [0, (-4f32).to_bits()],
+        ]);
+        let data = TreeEnsembleData { trees: rctensor1(&[0u32, 3]), nodes, leaves };
+        TreeEnsemble::build(data, 0, 1, aggregate_fn).unwrap()
+    }
+
+    #[test]
+    fn aggregates() -> TractResult<()> {
+        let input = rctensor2(&[[-1f32], [0.5], [2.]]);
+        for &(aggregate_fn, expected) in &[
+            (Aggregate::Sum, [-4f32, -5., -6.]),
+            (Aggregate::Avg, [-2., -2.5, -3.]),
+            (Aggregate::Max, [-1., -2., -2.]),
+            (Aggregate::Min, [-3., -3., -4.]),
+        ] {
+            let op = TreeEnsembleRegressor { ensemble: ensemble(aggregate_fn) };
+            let output = op.eval(tvec!(input.clone()))?;
+            assert_eq!(*output[0], tensor1(&expected).into_shape(&[3, 1])?);
+        }
+        Ok(())
+    }
+}
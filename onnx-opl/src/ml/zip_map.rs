@@ -0,0 +1,68 @@
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_ml_zip_map", &parameters(), load);
+    registry.register_dumper(TypeId::of::<ZipMap>(), dump);
+}
+
+/// tract has no map nor sequence types: ZipMap forwards the `[N, C]` probability matrix, whose
+/// columns follow the order of the class labels.
+#[derive(Clone, Debug, Hash)]
+pub struct ZipMap;
+
+impl_dyn_hash!(ZipMap);
+
+impl Op for ZipMap {
+    fn name(&self) -> Cow<str> {
+        "ZipMap".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    op_as_typed_op!();
+}
+
+impl EvalOp for ZipMap {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        Ok(inputs)
+    }
+}
+
+impl TypedOp for ZipMap {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
+    }
+
+    as_op!();
+}
+
+fn parameters() -> Vec<Parameter> {
+    vec![TypeName::Scalar.tensor().named("input")]
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation("tract_onnx_ml_zip_map", &[input], &[])))
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    builder.wire(ZipMap, &[input])
+}
@@ -0,0 +1,15 @@
+use tract_nnef::internal::*;
+
+pub mod string_normalizer;
+pub mod tfidf_vectorizer;
+pub mod tokenizer;
+
+pub use string_normalizer::StringNormalizer;
+pub use tfidf_vectorizer::TfIdfVectorizer;
+pub use tokenizer::Tokenizer;
+
+pub fn register(registry: &mut Registry) {
+    string_normalizer::register(registry);
+    tfidf_vectorizer::register(registry);
+    tokenizer::register(registry);
+}
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_string_normalizer", &parameters(), load);
+    registry.register_dumper(TypeId::of::<StringNormalizer>(), dump);
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum CaseChange {
+    Lower,
+    Upper,
+    None,
+}
+
+impl CaseChange {
+    pub fn parse(s: &str) -> TractResult<CaseChange> {
+        match s {
+            "LOWER" => Ok(CaseChange::Lower),
+            "UPPER" => Ok(CaseChange::Upper),
+            "NONE" => Ok(CaseChange::None),
+            _ => bail!("Invalid case change action: {}", s),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaseChange::Lower => "LOWER",
+            CaseChange::Upper => "UPPER",
+            CaseChange::None => "NONE",
+        }
+    }
+
+    fn apply(&self, s: &str) -> String {
+        match self {
+            CaseChange::Lower => s.to_lowercase(),
+            CaseChange::Upper => s.to_uppercase(),
+            CaseChange::None => s.to_string(),
+        }
+    }
+}
+
+/// Filters stopwords out of a `[C]` or `[1, C]` string tensor, then changes the case of the
+/// remaining strings.
+///
+/// With stopwords, the number of strings left is only known at runtime: it is `len` in the output
+/// fact. When everything is filtered out, or the input is empty, the output is a single empty
+/// string.
+#[derive(Clone, Debug, Hash)]
+pub struct StringNormalizer {
+    pub case_change: CaseChange,
+    pub case_sensitive: bool,
+    pub stopwords: TVec<String>,
+    pub len: TDim,
+}
+
+impl_dyn_hash!(StringNormalizer);
+
+impl StringNormalizer {
+    pub fn new(
+        case_change: CaseChange,
+        case_sensitive: bool,
+        stopwords: TVec<String>,
+    ) -> StringNormalizer {
+        StringNormalizer { case_change, case_sensitive, stopwords, len: Symbol::new('S').into() }
+    }
+
+    fn fold(&self, s: &str) -> String {
+        if self.case_sensitive {
+            s.to_string()
+        } else {
+            s.to_lowercase()
+        }
+    }
+}
+
+impl Op for StringNormalizer {
+    fn name(&self) -> Cow<str> {
+        "StringNormalizer".into()
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for StringNormalizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let stopwords: HashSet<String> = self.stopwords.iter().map(|s| self.fold(s)).collect();
+        let mut output: Vec<String> = input
+            .as_slice::<String>()?
+            .iter()
+            .filter(|s| !stopwords.contains(&self.fold(s)))
+            .map(|s| self.case_change.apply(s))
+            .collect();
+        if output.is_empty() {
+            output.push(String::new());
+        }
+        let mut shape: TVec<usize> = input.shape().into();
+        *shape.last_mut().unwrap() = output.len();
+        Ok(tvec!(tensor1(&output).into_shape(&shape)?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for StringNormalizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let rank = inputs[0].rank();
+        if inputs[0].datum_type != String::datum_type()
+            || rank == 0
+            || rank > 2
+            || (rank == 2 && inputs[0].shape[0] != 1.to_dim())
+        {
+            bail!("StringNormalizer expects a [C] or [1, C] string tensor, got {:?}", inputs[0])
+        }
+        let mut fact = inputs[0].without_value();
+        // without stopwords, only an empty input changes size (to a single empty string)
+        let len = match fact.shape[rank - 1].to_usize() {
+            Ok(c) if self.stopwords.is_empty() => c.max(1).to_dim(),
+            _ => self.len.clone(),
+        };
+        fact.shape.set(rank - 1, len);
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+}
+
+fn parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::String.named("case_change"),
+        TypeName::Logical.named("case_sensitive"),
+        TypeName::String.array().named("stopwords"),
+    ]
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<StringNormalizer>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_onnx_string_normalizer",
+        &[input],
+        &[
+            ("case_change", string(op.case_change.as_str())),
+            ("case_sensitive", logical(op.case_sensitive)),
+            ("stopwords", RValue::Array(op.stopwords.iter().map(string).collect())),
+        ],
+    )))
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let case_change: String = invocation.named_arg_as(builder, "case_change")?;
+    let case_sensitive = invocation.named_arg_as(builder, "case_sensitive")?;
+    let stopwords = invocation.named_arg_as(builder, "stopwords")?;
+    let op = StringNormalizer::new(CaseChange::parse(&case_change)?, case_sensitive, stopwords);
+    builder.wire(op, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stopwords_and_case() -> TractResult<()> {
+        let op = StringNormalizer::new(CaseChange::Upper, false, tvec!("the".to_string()));
+        let input = tensor2(&[["The", "quick", "fox"]].map(|r| r.map(|s| s.to_string())));
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        let expected = tensor2(&[["QUICK", "FOX"]].map(|r| r.map(|s| s.to_string())));
+        assert_eq!(*output[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn all_filtered_out() -> TractResult<()> {
+        let op = StringNormalizer::new(CaseChange::None, true, tvec!("a".to_string()));
+        let output = op.eval(tvec!(rctensor1(&["a".to_string(), "a".to_string()])))?;
+        assert_eq!(*output[0], tensor1(&[String::new()]));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input_without_stopwords() -> TractResult<()> {
+        let op = StringNormalizer::new(CaseChange::Lower, true, tvec!());
+        let input = tensor1::<String>(&[]).into_shape(&[1, 0])?;
+        let fact = op.output_facts(&[&TypedFact::from(input.clone())])?.remove(0);
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        assert_eq!(output[0].shape(), &[1, 1]);
+        assert_eq!(fact.shape.as_concrete(), Some(output[0].shape()));
+        let symbolic = TypedFact::dt_shape(String::datum_type(), [Symbol::new('C').to_dim()]);
+        let fact = op.output_facts(&[&symbolic])?.remove(0);
+        assert_eq!(fact.shape[0], op.len);
+        Ok(())
+    }
+}
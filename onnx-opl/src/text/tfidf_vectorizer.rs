@@ -0,0 +1,338 @@
+use std::hash::{Hash, Hasher};
+use tract_ndarray::prelude::*;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_tfidf_vectorizer", &parameters(), load);
+    registry.register_dumper(TypeId::of::<TfIdfVectorizer>(), dump);
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TfIdfMode {
+    Tf,
+    Idf,
+    TfIdf,
+}
+
+impl TfIdfMode {
+    pub fn parse(s: &str) -> TractResult<TfIdfMode> {
+        match s {
+            "TF" => Ok(TfIdfMode::Tf),
+            "IDF" => Ok(TfIdfMode::Idf),
+            "TFIDF" => Ok(TfIdfMode::TfIdf),
+            _ => bail!("Invalid TfIdfVectorizer mode: {}", s),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TfIdfMode::Tf => "TF",
+            TfIdfMode::Idf => "IDF",
+            TfIdfMode::TfIdf => "TFIDF",
+        }
+    }
+}
+
+/// Counts the n-grams of a pool found in each row of a `[C]` or `[N, C]` tensor of strings or
+/// integers, and scatters the (weighted) counts to `[N, output_len]` columns.
+///
+/// The pool is laid out as in ONNX: `ngram_counts[n - 1]` is the offset in `pool` of the first
+/// n-gram of length `n`, and `ngram_indexes[i]` is the output column of the i-th n-gram.
+#[derive(Clone, Debug)]
+pub struct TfIdfVectorizer {
+    pub mode: TfIdfMode,
+    pub min_gram_length: usize,
+    pub max_gram_length: usize,
+    pub max_skip_count: usize,
+    pub pool: Arc<Tensor>,
+    pub ngram_counts: TVec<usize>,
+    pub ngram_indexes: TVec<usize>,
+    pub weights: Option<Arc<Tensor>>,
+    // pool items, as ids in `vocabulary` for string pools
+    ngrams: HashMap<TVec<i64>, usize>,
+    vocabulary: HashMap<String, i64>,
+}
+
+impl_dyn_hash!(TfIdfVectorizer);
+
+impl Hash for TfIdfVectorizer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mode.hash(state);
+        self.min_gram_length.hash(state);
+        self.max_gram_length.hash(state);
+        self.max_skip_count.hash(state);
+        self.pool.hash(state);
+        self.ngram_counts.hash(state);
+        self.ngram_indexes.hash(state);
+        self.weights.hash(state);
+    }
+}
+
+impl TfIdfVectorizer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mode: TfIdfMode,
+        min_gram_length: usize,
+        max_gram_length: usize,
+        max_skip_count: usize,
+        pool: Arc<Tensor>,
+        ngram_counts: TVec<usize>,
+        ngram_indexes: TVec<usize>,
+        weights: Option<Arc<Tensor>>,
+    ) -> TractResult<TfIdfVectorizer> {
+        if min_gram_length == 0 || min_gram_length > max_gram_length {
+            bail!("Invalid n-gram lengths: {}..={}", min_gram_length, max_gram_length)
+        }
+        if pool.rank() != 1 {
+            bail!("Expected a 1D n-gram pool, got {:?}", pool)
+        }
+        let mut vocabulary = HashMap::default();
+        let items: Vec<i64> = if pool.datum_type() == String::datum_type() {
+            pool.as_slice::<String>()?
+                .iter()
+                .map(|s| {
+                    let next = vocabulary.len() as i64;
+                    *vocabulary.entry(s.clone()).or_insert(next)
+                })
+                .collect()
+        } else {
+            pool.cast_to::<i64>()?.as_slice::<i64>()?.to_vec()
+        };
+        let mut ngrams = HashMap::default();
+        for (ix, &start) in ngram_counts.iter().enumerate() {
+            let n = ix + 1;
+            let end = ngram_counts.get(ix + 1).copied().unwrap_or(items.len());
+            if start > end || (end - start) % n != 0 {
+                bail!("Inconsistent n-gram counts {:?} for a pool of {}", ngram_counts, items.len())
+            }
+            for ngram in items[start..end].chunks(n) {
+                let id = ngrams.len();
+                ngrams.insert(ngram.into(), id);
+            }
+        }
+        if ngram_indexes.len() != ngrams.len() {
+            bail!("Expected {} n-gram indexes, got {}", ngrams.len(), ngram_indexes.len())
+        }
+        if let Some(weights) = &weights {
+            if weights.len() != ngram_indexes.len() {
+                bail!("Expected {} weights, got {}", ngram_indexes.len(), weights.len())
+            }
+        }
+        Ok(TfIdfVectorizer {
+            mode,
+            min_gram_length,
+            max_gram_length,
+            max_skip_count,
+            pool,
+            ngram_counts,
+            ngram_indexes,
+            weights,
+            ngrams,
+            vocabulary,
+        })
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.ngram_indexes.iter().max().map(|m| m + 1).unwrap_or(0)
+    }
+
+    fn tokens(&self, input: &Tensor) -> TractResult<Vec<Option<i64>>> {
+        if input.datum_type() == String::datum_type() {
+            Ok(input
+                .as_slice::<String>()?
+                .iter()
+                .map(|s| self.vocabulary.get(s).copied())
+                .collect())
+        } else {
+            Ok(input.cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&i| Some(i)).collect())
+        }
+    }
+
+    fn count(&self, row: &[Option<i64>], counts: &mut [f32]) {
+        let mut ngram = TVec::new();
+        for skip in 0..=self.max_skip_count {
+            let step = skip + 1;
+            // unigrams do not depend on the skip count: only gather them once
+            let min = if skip == 0 { self.min_gram_length } else { self.min_gram_length.max(2) };
+            for start in 0..row.len() {
+                ngram.clear();
+                for n in 1..=self.max_gram_length {
+                    match row.get(start + (n - 1) * step) {
+                        Some(Some(token)) => ngram.push(*token),
+                        _ => break,
+                    }
+                    if n >= min {
+                        if let Some(&id) = self.ngrams.get(&ngram) {
+                            counts[self.ngram_indexes[id]] += 1.0;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Op for TfIdfVectorizer {
+    fn name(&self) -> Cow<str> {
+        "TfIdfVectorizer".into()
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for TfIdfVectorizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let tokens = self.tokens(&input)?;
+        let rows = if input.rank() == 2 { input.shape()[0] } else { 1 };
+        let columns = *input.shape().last().unwrap();
+        let mut output = Array2::<f32>::zeros((rows, self.output_len()));
+        if columns > 0 {
+            for (row, mut counts) in tokens.chunks(columns).zip(output.outer_iter_mut()) {
+                self.count(row, counts.as_slice_mut().unwrap());
+            }
+        }
+        let weights = self.weights.as_ref().map(|w| w.as_slice::<f32>()).transpose()?;
+        if self.mode != TfIdfMode::Tf {
+            let mut column_weights = vec![1f32; self.output_len()];
+            if let Some(weights) = weights {
+                for (&ix, &w) in self.ngram_indexes.iter().zip(weights) {
+                    column_weights[ix] = w;
+                }
+            }
+            for mut counts in output.outer_iter_mut() {
+                for (c, w) in counts.iter_mut().zip(&column_weights) {
+                    *c = match self.mode {
+                        TfIdfMode::Idf if *c > 0.0 => *w,
+                        TfIdfMode::Idf => 0.0,
+                        _ => *c * w,
+                    };
+                }
+            }
+        }
+        let mut output = output.into_tensor();
+        if input.rank() == 1 {
+            output.remove_axis(0)?;
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for TfIdfVectorizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut shape: TVec<TDim> = match inputs[0].rank() {
+            1 => tvec!(),
+            2 => tvec!(inputs[0].shape[0].clone()),
+            _ => bail!("TfIdfVectorizer expects a [C] or [N, C] input, got {:?}", inputs[0]),
+        };
+        shape.push(self.output_len().into());
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape)))
+    }
+
+    as_op!();
+}
+
+fn parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::Scalar.tensor().named("pool"),
+        TypeName::String.named("mode"),
+        TypeName::Integer.named("min_gram_length"),
+        TypeName::Integer.named("max_gram_length"),
+        TypeName::Integer.named("max_skip_count"),
+        TypeName::Integer.array().named("ngram_counts"),
+        TypeName::Integer.array().named("ngram_indexes"),
+        TypeName::Scalar.tensor().named("weights"),
+    ]
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<TfIdfVectorizer>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let pool = ast.konst_variable(format!("{}_pool", node.name), &op.pool)?;
+    let mut named = vec![
+        ("mode", string(op.mode.as_str())),
+        ("min_gram_length", numeric(op.min_gram_length)),
+        ("max_gram_length", numeric(op.max_gram_length)),
+        ("max_skip_count", numeric(op.max_skip_count)),
+        ("ngram_counts", ints(&op.ngram_counts)),
+        ("ngram_indexes", ints(&op.ngram_indexes)),
+    ];
+    if let Some(weights) = &op.weights {
+        let weights = ast.konst_variable(format!("{}_weights", node.name), weights)?;
+        named.push(("weights", weights.as_ref().clone()));
+    }
+    Ok(Some(invocation("tract_onnx_tfidf_vectorizer", &[input, pool], &named)))
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let mode: String = invocation.named_arg_as(builder, "mode")?;
+    let weights = if invocation.get_named_arg("weights").is_some() {
+        Some(invocation.named_arg_as(builder, "weights")?)
+    } else {
+        None
+    };
+    let op = TfIdfVectorizer::new(
+        TfIdfMode::parse(&mode)?,
+        invocation.named_arg_as(builder, "min_gram_length")?,
+        invocation.named_arg_as(builder, "max_gram_length")?,
+        invocation.named_arg_as(builder, "max_skip_count")?,
+        invocation.named_arg_as(builder, "pool")?,
+        invocation.named_arg_as(builder, "ngram_counts")?,
+        invocation.named_arg_as(builder, "ngram_indexes")?,
+        weights,
+    )?;
+    builder.wire(op, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // ONNX backend test `tf_batch_uniandbigrams_skip5`
+    #[test]
+    fn uni_and_bigrams_with_skips() -> TractResult<()> {
+        let op = TfIdfVectorizer::new(
+            TfIdfMode::Tf,
+            1,
+            2,
+            5,
+            rctensor1(&[2i64, 3, 5, 4, 5, 6, 7, 8, 6, 7]),
+            tvec!(0, 4),
+            tvec!(0, 1, 2, 3, 4, 5, 6),
+            None,
+        )?;
+        let input = tensor2(&[[1i32, 1, 3, 3, 3, 7], [8, 6, 7, 5, 6, 8]]);
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        let expected = tensor2(&[[0f32, 3., 0., 0., 0., 0., 0.], [0., 0., 1., 0., 1., 1., 1.]]);
+        assert_eq!(*output[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn string_idf() -> TractResult<()> {
+        let words = |ws: &[&str]| ws.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let op = TfIdfVectorizer::new(
+            TfIdfMode::Idf,
+            1,
+            2,
+            0,
+            rctensor1(&words(&["a", "b", "a", "b"])),
+            tvec!(0, 2),
+            tvec!(1, 0, 2),
+            Some(rctensor1(&[0.5f32, 2., 4.])),
+        )?;
+        let output = op.eval(tvec!(rctensor1(&words(&["b", "a", "b", "c"]))))?;
+        assert_eq!(*output[0], tensor1(&[2f32, 0.5, 4.]));
+        Ok(())
+    }
+}
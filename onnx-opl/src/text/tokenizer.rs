@@ -0,0 +1,210 @@
+use regex::Regex;
+use std::hash::{Hash, Hasher};
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive("tract_onnx_tokenizer", &parameters(), load);
+    registry.register_dumper(TypeId::of::<Tokenizer>(), dump);
+}
+
+#[derive(Clone, Debug)]
+pub enum Tokens {
+    /// Tokens are what lies between matches of any of the separator expressions. A single empty
+    /// separator splits strings into characters.
+    Separators(TVec<String>, Regex),
+    /// Tokens are the matches of the expression.
+    Expression(String, Regex),
+}
+
+impl Tokens {
+    pub fn separators(separators: TVec<String>) -> TractResult<Tokens> {
+        let alternatives: Vec<String> = separators.iter().map(|s| format!("(?:{})", s)).collect();
+        let regex = Regex::new(&alternatives.join("|"))?;
+        Ok(Tokens::Separators(separators, regex))
+    }
+
+    pub fn expression(expression: String) -> TractResult<Tokens> {
+        let regex = Regex::new(&expression)?;
+        Ok(Tokens::Expression(expression, regex))
+    }
+
+    fn tokenize<'s>(&self, s: &'s str) -> Vec<&'s str> {
+        match self {
+            Tokens::Separators(seps, _) if seps.len() == 1 && seps[0].is_empty() => {
+                s.char_indices().map(|(ix, c)| &s[ix..ix + c.len_utf8()]).collect()
+            }
+            Tokens::Separators(_, regex) => regex.split(s).filter(|t| !t.is_empty()).collect(),
+            Tokens::Expression(_, regex) => regex.find_iter(s).map(|m| m.as_str()).collect(),
+        }
+    }
+}
+
+impl Hash for Tokens {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Tokens::Separators(seps, _) => (0u8, seps).hash(state),
+            Tokens::Expression(exp, _) => (1u8, exp).hash(state),
+        }
+    }
+}
+
+/// Splits each string of a `[N]` or `[N, C]` tensor into tokens, laid out along a new last axis
+/// and right-padded with `pad_value` to the longest token list.
+///
+/// Tokens shorter than `min_char_num` characters are dropped. With `mark`, each token list is
+/// surrounded by the `\u{2}` and `\u{3}` markers.
+#[derive(Clone, Debug, Hash)]
+pub struct Tokenizer {
+    pub tokens: Tokens,
+    pub mark: bool,
+    pub min_char_num: usize,
+    pub pad_value: String,
+    pub len: TDim,
+}
+
+impl_dyn_hash!(Tokenizer);
+
+impl Tokenizer {
+    pub fn new(tokens: Tokens, mark: bool, min_char_num: usize, pad_value: String) -> Tokenizer {
+        Tokenizer { tokens, mark, min_char_num, pad_value, len: Symbol::new('T').into() }
+    }
+}
+
+impl Op for Tokenizer {
+    fn name(&self) -> Cow<str> {
+        "Tokenizer".into()
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Tokenizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let tokenized: Vec<Vec<&str>> = input
+            .as_slice::<String>()?
+            .iter()
+            .map(|s| {
+                let mut tokens: Vec<&str> = self
+                    .tokens
+                    .tokenize(s)
+                    .into_iter()
+                    .filter(|t| t.chars().count() >= self.min_char_num)
+                    .collect();
+                if self.mark {
+                    tokens.insert(0, "\u{2}");
+                    tokens.push("\u{3}");
+                }
+                tokens
+            })
+            .collect();
+        let len = tokenized.iter().map(|t| t.len()).max().unwrap_or(0);
+        let mut output = Vec::with_capacity(tokenized.len() * len);
+        for tokens in tokenized {
+            let padding = len - tokens.len();
+            output.extend(tokens.into_iter().map(|t| t.to_string()));
+            output.resize(output.len() + padding, self.pad_value.clone());
+        }
+        let mut shape: TVec<usize> = input.shape().into();
+        shape.push(len);
+        Ok(tvec!(tensor1(&output).into_shape(&shape)?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Tokenizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != String::datum_type() || inputs[0].rank() > 2 {
+            bail!("Tokenizer expects a [N] or [N, C] string tensor, got {:?}", inputs[0])
+        }
+        let mut shape: TVec<TDim> = inputs[0].shape.iter().collect();
+        shape.push(self.len.clone());
+        Ok(tvec!(TypedFact::dt_shape(String::datum_type(), shape)))
+    }
+
+    as_op!();
+}
+
+fn parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("input"),
+        TypeName::String.array().named("separators"),
+        TypeName::String.named("expression"),
+        TypeName::Logical.named("mark"),
+        TypeName::Integer.named("min_char_num"),
+        TypeName::String.named("pad_value"),
+    ]
+}
+
+fn dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Tokenizer>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let tokens = match &op.tokens {
+        Tokens::Separators(seps, _) => {
+            ("separators", RValue::Array(seps.iter().map(string).collect()))
+        }
+        Tokens::Expression(exp, _) => ("expression", string(exp)),
+    };
+    Ok(Some(invocation(
+        "tract_onnx_tokenizer",
+        &[input],
+        &[
+            tokens,
+            ("mark", logical(op.mark)),
+            ("min_char_num", numeric(op.min_char_num)),
+            ("pad_value", string(&op.pad_value)),
+        ],
+    )))
+}
+
+fn load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let tokens = if invocation.get_named_arg("expression").is_some() {
+        Tokens::expression(invocation.named_arg_as(builder, "expression")?)?
+    } else {
+        Tokens::separators(invocation.named_arg_as(builder, "separators")?)?
+    };
+    let op = Tokenizer::new(
+        tokens,
+        invocation.named_arg_as(builder, "mark")?,
+        invocation.named_arg_as(builder, "min_char_num")?,
+        invocation.named_arg_as(builder, "pad_value")?,
+    );
+    builder.wire(op, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn separators() -> TractResult<()> {
+        let tokens = Tokens::separators(tvec!(" ".to_string(), ",".to_string()))?;
+        let op = Tokenizer::new(tokens, false, 2, "#".to_string());
+        let output = op.eval(tvec!(rctensor1(&strings(&["ab, cd e", "xyz"]))))?;
+        let expected = tensor1(&strings(&["ab", "cd", "xyz", "#"])).into_shape(&[2, 2])?;
+        assert_eq!(*output[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn expression_with_marks() -> TractResult<()> {
+        let tokens = Tokens::expression("[a-z]+".to_string())?;
+        let op = Tokenizer::new(tokens, true, 1, "".to_string());
+        let output = op.eval(tvec!(rctensor1(&strings(&["a1bc"]))))?;
+        let expected = tensor1(&strings(&["\u{2}", "a", "bc", "\u{3}"])).into_shape(&[1, 4])?;
+        assert_eq!(*output[0], expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+#[path = "../../nnef/tests/common/mod.rs"]
+mod common;
+
+use common::*;
+use tract_nnef::internal::*;
+use tract_onnx_opl::ml::svm::{Kernel, KernelType, SvmClassifier, SvmRegressor};
+use tract_onnx_opl::ml::tree::{Aggregate, Cmp, TreeEnsemble, TreeEnsembleData};
+use tract_onnx_opl::ml::tree_ensemble_regressor::TreeEnsembleRegressor;
+use tract_onnx_opl::WithOnnx;
+
+fn nnef() -> Nnef {
+    tract_nnef::nnef().with_onnx()
+}
+
+#[test]
+fn svm_roundtrip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [4, 2]))?;
+    let kernel = Kernel { kernel_type: KernelType::Rbf, gamma: 0.5, coef0: 0.0, degree: 0 };
+    let classifier = SvmClassifier {
+        kernel: kernel.clone(),
+        n_classes: 3,
+        support_vectors: rctensor2(&[[-1f32, 0.], [0., 1.], [1., 0.]]),
+        vectors_per_class: Some(tvec!(1, 1, 1)),
+        coefficients: rctensor2(&[[1f32, -1., -1.], [1., 1., -1.]]),
+        rho: rctensor1(&[0.1f32, 0., -0.1]),
+        prob_a: None,
+        prob_b: None,
+    };
+    let classes = model.wire_node("classifier", classifier, &[input])?;
+    let regressor = SvmRegressor {
+        kernel,
+        support_vectors: rctensor2(&[[-1f32, 0.], [1., 1.]]),
+        coefficients: rctensor1(&[0.5f32, -2.]),
+        rho: 0.25,
+        one_class: false,
+    };
+    let regression = model.wire_node("regressor", regressor, &[input])?;
+    model.set_output_outlets(&[classes[0], classes[1], regression[0]])?;
+
+    let reloaded = roundtrip(&nnef(), &model)?;
+    let classifier = reloaded.node_by_name("classifier")?.op_as::<SvmClassifier>().unwrap();
+    assert_eq!(classifier.kernel.kernel_type, KernelType::Rbf);
+    assert_eq!(classifier.vectors_per_class, Some(tvec!(1, 1, 1)));
+    let regressor = reloaded.node_by_name("regressor")?.op_as::<SvmRegressor>().unwrap();
+    assert_eq!(regressor.rho, 0.25);
+    check_same_outputs(model, reloaded.into_optimized()?, tvec!(tensor(&[4, 2], 0)))
+}
+
+#[test]
+fn tree_ensemble_regressor_roundtrip() -> TractResult<()> {
+    // one tree splitting on feature 1
+    let nodes = rctensor2(&[
+        [1, 1, 2, 0.5f32.to_bits(), Cmp::LessEqual as u32 | 1 << 8],
+        [0, 1, 0, 0, 0],
+        [1, 2, 0, 0, 0],
+    ]);
+    let leaves = rctensor2(&[[0, (-1f32).to_bits()], [0, 3f32.to_bits()]]);
+    let data = TreeEnsembleData { trees: rctensor1(&[0u32]), nodes, leaves };
+    let ensemble = TreeEnsemble::build(data, 1, 1, Aggregate::Max)?;
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), [4, 2]))?;
+    let regression = model.wire_node("regressor", TreeEnsembleRegressor { ensemble }, &[input])?;
+    model.set_output_outlets(&regression)?;
+
+    let reloaded = roundtrip(&nnef(), &model)?;
+    let op = reloaded.node_by_name("regressor")?.op_as::<TreeEnsembleRegressor>().unwrap();
+    assert_eq!(op.ensemble.aggregate_fn, Aggregate::Max);
+    check_same_outputs(model, reloaded.into_optimized()?, tvec!(tensor(&[4, 2], 0)))
+}
@@ -37,6 +37,7 @@ pub struct ParsingContext<'a> {
     pub onnx_operator_set_version: i64,
     pub framework: &'a Onnx,
     pub model: &'a pb::ModelProto,
+    pub model_dir: Option<&'a path::Path>,
     pub parent_graphs: Vec<&'a pb::GraphProto>,
 }
 
@@ -51,13 +52,16 @@ impl<'a> ParsingContext<'a> {
     pub fn parse_graph(&self, graph: &pb::GraphProto) -> TractResult<ParseResult> {
         let mut ctx = self.clone();
         ctx.parent_graphs.push(graph);
-        let mut model = InferenceModel::default();
+        let mut model = InferenceModel {
+            share_tensors: self.framework.share_tensors,
+            ..InferenceModel::default()
+        };
         let mut unresolved_inputs = vec![];
         let mut closures_to_wire = vec![];
         let mut initializers: HashMap<&str, Tensor> = graph
             .initializer
             .iter()
-            .map(|init| Ok((&*init.name, init.try_into()?)))
+            .map(|init| Ok((&*init.name, crate::tensor::load_tensor(init, self.model_dir)?)))
             .collect::<TractResult<_>>()?;
         for (k, v) in initializers.iter() {
             trace!("Initializer: {} {:?}", k, v);
@@ -158,6 +162,11 @@ impl<'a> ParsingContext<'a> {
             } else {
                 InferenceFact::default()
             };
+            if !outlets_by_name.contains_key(&*output.name) {
+                let id = model.add_source(output.name.clone(), InferenceFact::default())?;
+                unresolved_inputs.push(output.name.to_string());
+                outlets_by_name.insert(output.name.to_string(), id);
+            }
             let outlet = outlets_by_name[&*output.name];
             outputs.push(outlet);
             model.set_outlet_label(outlet, output.name.clone())?;
@@ -196,10 +205,29 @@ impl OnnxOpRegister {
 #[derive(Clone, Default)]
 pub struct Onnx {
     pub op_register: OnnxOpRegister,
+    /// Register the tensors of the loaded models in the process-wide `TensorStore`.
+    pub share_tensors: bool,
 }
 
 impl Onnx {
+    /// Let the loaded models share identical constants and pre-packed weights with the other
+    /// models of the process opting in.
+    pub fn with_shared_tensors(mut self) -> Self {
+        self.share_tensors = true;
+        self
+    }
+
     pub fn parse(&self, proto: &pb::ModelProto) -> TractResult<ParseResult> {
+        self.parse_with_model_dir(proto, None)
+    }
+
+    /// Parse a model. `model_dir` is the directory tensors with external data are resolved
+    /// from.
+    pub fn parse_with_model_dir(
+        &self,
+        proto: &pb::ModelProto,
+        model_dir: Option<&path::Path>,
+    ) -> TractResult<ParseResult> {
         let onnx_operator_set_version = proto
             .opset_import
             .iter()
@@ -218,11 +246,20 @@ impl Onnx {
         let ctx = ParsingContext {
             framework: self,
             model: proto,
+            model_dir,
             parent_graphs: vec![],
             onnx_operator_set_version,
         };
         ctx.parse_graph(graph)
     }
+
+    fn model_for_parse_result(&self, result: ParseResult) -> TractResult<InferenceModel> {
+        let ParseResult { model, unresolved_inputs, .. } = result;
+        if unresolved_inputs.len() > 0 {
+            bail!("Could not resolve inputs at top-level: {:?}", unresolved_inputs)
+        }
+        Ok(model)
+    }
 }
 
 impl Framework<pb::ModelProto, InferenceModel> for Onnx {
@@ -242,10 +279,13 @@ impl Framework<pb::ModelProto, InferenceModel> for Onnx {
     }
 
     fn model_for_proto_model(&self, proto: &pb::ModelProto) -> TractResult<InferenceModel> {
-        let ParseResult { model, unresolved_inputs, .. } = self.parse(proto)?;
-        if unresolved_inputs.len() > 0 {
-            bail!("Could not resolve inputs at top-level: {:?}", unresolved_inputs)
-        }
-        Ok(model)
+        self.model_for_parse_result(self.parse(proto)?)
+    }
+
+    fn model_for_path(&self, p: impl AsRef<path::Path>) -> TractResult<InferenceModel> {
+        let proto = self
+            .proto_model_for_path(p.as_ref())
+            .with_context(|| format!("Could not load {:?}", p.as_ref()))?;
+        self.model_for_parse_result(self.parse_with_model_dir(&proto, p.as_ref().parent())?)
     }
 }
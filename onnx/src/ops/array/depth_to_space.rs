@@ -0,0 +1,221 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::change_axes::perm_to_ops;
+
+use crate::model::ParsingContext;
+use crate::pb::*;
+
+pub fn depth_to_space(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let blocksize = node.get_attr("blocksize")?;
+    let crd = match node.get_attr_opt("mode")?.unwrap_or("DCR") {
+        "DCR" => false,
+        "CRD" => true,
+        mode => bail!("Unsupported DepthToSpace mode: {}", mode),
+    };
+    Ok((expand(DepthToSpace { blocksize, crd }), vec![]))
+}
+
+pub fn space_to_depth(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let blocksize = node.get_attr("blocksize")?;
+    Ok((expand(SpaceToDepth { blocksize }), vec![]))
+}
+
+fn wire_permute(
+    prefix: &str,
+    model: &mut TypedModel,
+    perm: &[usize],
+    mut wire: OutletId,
+) -> TractResult<OutletId> {
+    for (ix, op) in perm_to_ops(perm).into_iter().enumerate() {
+        wire = model.wire_node(format!("{}.{}-{}", prefix, op.name(), ix), op, &[wire])?[0];
+    }
+    Ok(wire)
+}
+
+/// Moves blocks of channels to the spatial dims of a NCHW input, as a reshape, permutation,
+/// reshape sequence: the channel axis is split as (block, block, depth) in DCR mode, as
+/// (depth, block, block) in CRD mode.
+#[derive(Debug, Clone, Hash)]
+struct DepthToSpace {
+    blocksize: usize,
+    crd: bool,
+}
+
+impl_dyn_hash!(DepthToSpace);
+
+impl Expansion for DepthToSpace {
+    fn name(&self) -> Cow<str> {
+        "DepthToSpace".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.given(&inputs[0].shape, move |s, shape| {
+            let b = self.blocksize;
+            let shape = tvec!(
+                shape[0].clone(),
+                shape[1].clone() / (b * b),
+                shape[2].clone() * b,
+                shape[3].clone() * b
+            );
+            s.equals(&outputs[0].shape, ShapeFactoid::from(shape))
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let b = self.blocksize.to_dim();
+        let shape = model.outlet_fact(inputs[0])?.shape.to_tvec();
+        let (c, h, w) = (shape[1].clone(), shape[2].clone(), shape[3].clone());
+        let depth = c.clone() / (self.blocksize * self.blocksize);
+        let (split, perm) = if self.crd {
+            (tvec!(depth, b.clone(), b.clone()), [0, 1, 4, 2, 5, 3])
+        } else {
+            (tvec!(b.clone(), b.clone(), depth), [0, 3, 4, 1, 5, 2])
+        };
+        let wire = model.wire_node(
+            format!("{}.split", prefix),
+            AxisOp::Reshape(1, tvec!(c), split),
+            inputs,
+        )?[0];
+        let wire = wire_permute(prefix, model, &perm, wire)?;
+        model.wire_node(
+            format!("{}.merge", prefix),
+            AxisOp::Reshape(
+                2,
+                tvec!(h.clone(), b.clone(), w.clone(), b.clone()),
+                tvec!(h * &b, w * &b),
+            ),
+            &[wire],
+        )
+    }
+}
+
+/// Moves spatial blocks of a NCHW input to the channel axis, as a reshape, permutation, reshape
+/// sequence.
+#[derive(Debug, Clone, Hash)]
+struct SpaceToDepth {
+    blocksize: usize,
+}
+
+impl_dyn_hash!(SpaceToDepth);
+
+impl Expansion for SpaceToDepth {
+    fn name(&self) -> Cow<str> {
+        "SpaceToDepth".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.given(&inputs[0].shape, move |s, shape| {
+            let b = self.blocksize;
+            let shape = tvec!(
+                shape[0].clone(),
+                shape[1].clone() * (b * b),
+                shape[2].clone() / b,
+                shape[3].clone() / b
+            );
+            s.equals(&outputs[0].shape, ShapeFactoid::from(shape))
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let b = self.blocksize.to_dim();
+        let shape = model.outlet_fact(inputs[0])?.shape.to_tvec();
+        let (c, h, w) = (shape[1].clone(), shape[2].clone(), shape[3].clone());
+        let (h_blocks, w_blocks) = (h.clone() / self.blocksize, w.clone() / self.blocksize);
+        let wire = model.wire_node(
+            format!("{}.split", prefix),
+            AxisOp::Reshape(2, tvec!(h, w), tvec!(h_blocks, b.clone(), w_blocks, b.clone())),
+            inputs,
+        )?[0];
+        let wire = wire_permute(prefix, model, &[0, 3, 5, 1, 2, 4], wire)?;
+        model.wire_node(
+            format!("{}.merge", prefix),
+            AxisOp::Reshape(1, tvec!(b.clone(), b.clone(), c.clone()), tvec!(c * &b * &b)),
+            &[wire],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(op: impl Expansion, input: Tensor) -> TractResult<Tensor> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape_from_tensor(&input))?;
+        let y = model.wire_node("op", expand(op), &[x])?;
+        model.set_output_outlets(&y)?;
+        let mut result = SimplePlan::new(model.into_typed()?)?.run(tvec!(input))?;
+        Ok(result.remove(0).into_tensor())
+    }
+
+    fn input() -> Tensor {
+        tensor1(&(0..16).map(|i| i as f32).collect::<Vec<_>>()).into_shape(&[1, 4, 2, 2]).unwrap()
+    }
+
+    #[test]
+    fn depth_to_space_dcr() -> TractResult<()> {
+        let output = run(DepthToSpace { blocksize: 2, crd: false }, input())?;
+        let expected =
+            tensor1(&[0f32, 4., 1., 5., 8., 12., 9., 13., 2., 6., 3., 7., 10., 14., 11., 15.]);
+        assert_eq!(output, expected.into_shape(&[1, 1, 4, 4])?);
+        Ok(())
+    }
+
+    #[test]
+    fn depth_to_space_modes() -> TractResult<()> {
+        let input =
+            tensor1(&(0..8).map(|i| i as f32).collect::<Vec<_>>()).into_shape(&[1, 8, 1, 1])?;
+        let output = run(DepthToSpace { blocksize: 2, crd: true }, input.clone())?;
+        assert_eq!(output, input.clone().into_shape(&[1, 2, 2, 2])?);
+        let output = run(DepthToSpace { blocksize: 2, crd: false }, input)?;
+        let expected = tensor1(&[0f32, 2., 4., 6., 1., 3., 5., 7.]).into_shape(&[1, 2, 2, 2])?;
+        assert_eq!(output, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn space_to_depth_inverts_depth_to_space() -> TractResult<()> {
+        let space = run(DepthToSpace { blocksize: 2, crd: false }, input())?;
+        assert_eq!(run(SpaceToDepth { blocksize: 2 }, space)?, input());
+        Ok(())
+    }
+}
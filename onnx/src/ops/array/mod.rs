@@ -1,10 +1,16 @@
 mod compress;
+mod depth_to_space;
 mod nonzero;
 mod one_hot;
 mod pad;
+mod range;
+mod reverse_sequence;
 mod slice;
 mod split;
 mod squeeze;
+mod topk;
+mod trilu;
+mod unique;
 mod unsqueeze;
 
 use tract_hir::internal::*;
@@ -19,6 +25,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("ConstantLike", constant_like);
     reg.insert("ConstantOfShape", constant_of_shape);
     reg.insert("Expand", |_, _| Ok((expand(array::MultiBroadcastTo::default()), vec![])));
+    reg.insert("DepthToSpace", depth_to_space::depth_to_space);
     reg.insert("EyeLike", eye_like);
     reg.insert("Flatten", flatten);
     reg.insert("Gather", gather);
@@ -27,17 +34,23 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("NonZero", |_, _| Ok((Box::new(nonzero::NonZero::non_zero()), vec![])));
     reg.insert("OneHot", one_hot::one_hot);
     reg.insert("Pad", pad::pad);
+    reg.insert("Range", |_, _| Ok((expand(range::Range::default()), vec![])));
     reg.insert("Reshape", |_, _| Ok((expand(array::Reshape::default()), vec![])));
+    reg.insert("ReverseSequence", reverse_sequence::reverse_sequence);
     reg.insert("Scatter", scatter_elements);
     reg.insert("ScatterElements", scatter_elements);
     reg.insert("ScatterND", |_, _| Ok((Box::new(array::ScatterNd), vec![])));
     reg.insert("Shape", |_, _| Ok((expand(array::Shape::new(DatumType::I64)), vec![])));
     reg.insert("Size", |_, _| Ok((expand(array::Size::new(DatumType::I64)), vec![])));
     reg.insert("Slice", slice::slice);
+    reg.insert("SpaceToDepth", depth_to_space::space_to_depth);
     reg.insert("Split", split::split);
     reg.insert("Squeeze", squeeze::squeeze);
     reg.insert("Tile", |_, _| Ok((expand(array::Tile::default()), vec![])));
+    reg.insert("TopK", topk::topk);
     reg.insert("Transpose", transpose);
+    reg.insert("Trilu", trilu::trilu);
+    reg.insert("Unique", unique::unique);
     reg.insert("Unsqueeze", unsqueeze::unsqueeze);
 }
 
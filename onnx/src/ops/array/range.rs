@@ -0,0 +1,170 @@
+use tract_hir::internal::*;
+
+/// Range start, end and step may have different types (typically `TDim` and `i64` when computed
+/// from shapes): they are cast to their common super type, and the output is cast back to the
+/// ONNX type.
+///
+/// `len` is set at incorporation when the inputs are only known at runtime.
+#[derive(Debug, Clone, Default, Hash)]
+pub struct Range {
+    len: Option<TDim>,
+}
+
+impl_dyn_hash!(Range);
+
+impl Range {
+    fn output_type(dts: &[DatumType]) -> TractResult<DatumType> {
+        DatumType::super_type_for(dts.iter().copied())
+            .with_context(|| format!("No super type for Range inputs {:?}", dts))?;
+        Ok(dts.iter().copied().find(|dt| *dt != TDim::datum_type()).unwrap_or(DatumType::TDim))
+    }
+}
+
+impl Expansion for Range {
+    fn name(&self) -> Cow<str> {
+        "Range".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 3)?;
+        check_output_arity(outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.rank, 0)?;
+        }
+        s.equals(&outputs[0].rank, 1)?;
+        s.given_all(inputs.iter().map(|i| &i.datum_type), move |s, dts| {
+            s.equals(&outputs[0].datum_type, Self::output_type(&dts)?)
+        })?;
+        if let Some(len) = &self.len {
+            return s.equals(&outputs[0].shape[0], len.clone());
+        }
+        s.given_all(inputs.iter().map(|i| &i.value), move |s, values| {
+            let dt = DatumType::super_type_for(values.iter().map(|v| v.datum_type()))
+                .with_context(|| "No super type for Range inputs")?;
+            let facts = values
+                .iter()
+                .map(|v| Ok(TypedFact::from(v.cast_to_dt(dt)?.into_owned().into_arc_tensor())))
+                .collect::<TractResult<TVec<_>>>()?;
+            let facts = tract_core::ops::array::Range::default()
+                .output_facts(&facts.iter().collect::<TVec<_>>())?;
+            s.equals(&outputs[0].shape[0], facts[0].shape[0].clone())
+        })
+    }
+
+    fn incorporate(
+        &self,
+        model: &InferenceModel,
+        node: &InferenceNode,
+    ) -> TractResult<Option<InferenceModelPatch>> {
+        if self.len.is_some() {
+            return Ok(None);
+        }
+        for input in &node.inputs {
+            if model.outlet_fact(*input)?.value.concretize().is_none() {
+                let op = Range { len: Some(Symbol::new('R').into()) };
+                return Ok(Some(InferenceModelPatch::replace_single_op(
+                    model,
+                    node,
+                    &node.inputs,
+                    expand(op),
+                )?));
+            }
+        }
+        Ok(None)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let dts = inputs
+            .iter()
+            .map(|i| Ok(model.outlet_fact(*i)?.datum_type))
+            .collect::<TractResult<TVec<_>>>()?;
+        let dt = DatumType::super_type_for(dts.iter().copied())
+            .with_context(|| format!("No super type for Range inputs {:?}", dts))?;
+        let mut wires = tvec!();
+        for (ix, input) in inputs.iter().enumerate() {
+            let mut wire = *input;
+            if dts[ix] != dt {
+                wire = model.wire_node(
+                    format!("{}.cast-{}", prefix, ix),
+                    tract_core::ops::cast::cast(dt),
+                    &[wire],
+                )?[0];
+            }
+            wires.push(wire);
+        }
+        let op = if let Some(len) = &self.len {
+            tract_core::ops::array::Range { len: len.clone() }
+        } else {
+            tract_core::ops::array::Range::default()
+        };
+        let mut wire = model.wire_node(prefix, op, &wires)?[0];
+        let output_dt = Self::output_type(&dts)?;
+        if output_dt != dt {
+            wire = model.wire_node(
+                format!("{}.cast-output", prefix),
+                tract_core::ops::cast::cast(output_dt),
+                &[wire],
+            )?[0];
+        }
+        Ok(tvec!(wire))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn symbolic_len_from_shape() -> TractResult<()> {
+        use tract_hir::ops::array::{Gather, Shape};
+        let mut model = InferenceModel::default();
+        let n = Symbol::new('n');
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [n.to_dim()]))?;
+        let shape = model.wire_node("shape", expand(Shape::new(DatumType::I64)), &[x])?;
+        let zero = model.add_const("zero", tensor0(0i64))?;
+        let end = model.wire_node("end", expand(Gather::new(0)), &[shape[0], zero])?;
+        let start = model.add_const("start", tensor0(0i64))?;
+        let step = model.add_const("step", tensor0(2i64))?;
+        let range = model.wire_node("range", expand(Range::default()), &[start, end[0], step])?;
+        model.set_output_outlets(&range)?;
+        let typed = model.into_typed()?;
+        let fact = typed.outlet_fact(typed.output_outlets()?[0])?;
+        assert_eq!(fact.shape[0], n.to_dim().div_ceil(2));
+        let result = SimplePlan::new(typed)?.run(tvec!(tensor1(&[0f32; 5])))?;
+        assert_eq!(*result[0], tensor1(&[0i64, 2, 4]));
+        Ok(())
+    }
+
+    #[test]
+    fn runtime_len_from_inputs() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let inputs = ["start", "limit", "delta"]
+            .iter()
+            .map(|name| {
+                model.add_source(*name, InferenceFact::dt_shape(f32::datum_type(), shapefactoid!()))
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        let range = model.wire_node("range", expand(Range::default()), &inputs)?;
+        model.set_output_outlets(&range)?;
+        model.analyse(false)?;
+        let model = model.incorporate()?;
+        assert!(model.missing_type_shape()?.is_empty());
+        let optimized = model.into_typed()?.declutter()?.optimize()?;
+        let result =
+            SimplePlan::new(optimized)?.run(tvec!(tensor0(1f32), tensor0(10f32), tensor0(2f32)))?;
+        assert_eq!(*result[0], tensor1(&[1f32, 3., 5., 7., 9.]));
+        Ok(())
+    }
+}
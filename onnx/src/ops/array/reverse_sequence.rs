@@ -0,0 +1,57 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::pb::*;
+
+pub fn reverse_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let batch_axis = node.get_attr_opt("batch_axis")?.unwrap_or(1);
+    let time_axis = node.get_attr_opt("time_axis")?.unwrap_or(0);
+    Ok((expand(ReverseSequence { batch_axis, time_axis }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct ReverseSequence {
+    batch_axis: usize,
+    time_axis: usize,
+}
+
+impl_dyn_hash!(ReverseSequence);
+
+impl Expansion for ReverseSequence {
+    fn name(&self) -> Cow<str> {
+        "ReverseSequence".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], &inputs[0].shape[self.batch_axis])?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let op = tract_core::ops::array::ReverseSequence {
+            batch_axis: self.batch_axis,
+            time_axis: self.time_axis,
+        };
+        model.wire_node(prefix, op, inputs)
+    }
+}
@@ -0,0 +1,116 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::pb::*;
+
+pub fn topk(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(-1);
+    let largest = node.get_attr_opt("largest")?.unwrap_or(true);
+    let sorted = node.get_attr_opt("sorted")?.unwrap_or(true);
+    let k = if ctx.onnx_operator_set_version < 10 { Some(node.get_attr("k")?) } else { None };
+    Ok((expand(TopK { axis, largest, sorted, k }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct TopK {
+    axis: i64,
+    largest: bool,
+    sorted: bool,
+    k: Option<usize>,
+}
+
+impl_dyn_hash!(TopK);
+
+impl TopK {
+    fn resolve_axis(&self, rank: usize) -> usize {
+        if self.axis < 0 {
+            (self.axis + rank as i64) as usize
+        } else {
+            self.axis as usize
+        }
+    }
+}
+
+impl Expansion for TopK {
+    fn name(&self) -> Cow<str> {
+        "TopK".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1 + self.k.is_none() as usize)?;
+        check_output_arity(outputs, 2)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&outputs[1].datum_type, i64::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].rank, &outputs[1].rank)?;
+        s.equals(&outputs[0].shape, &outputs[1].shape)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            let axis = self.resolve_axis(rank as usize);
+            for ix in 0..rank as usize {
+                if ix != axis {
+                    s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+                }
+            }
+            if let Some(k) = self.k {
+                s.equals(&outputs[0].shape[axis], k.to_dim())
+            } else {
+                s.given(&inputs[1].value, move |s, k| {
+                    let k = k.cast_to::<i64>()?.as_slice::<i64>()?[0];
+                    s.equals(&outputs[0].shape[axis], k.to_dim())
+                })
+            }
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let axis = self.resolve_axis(model.outlet_fact(inputs[0])?.rank());
+        let k = if let Some(k) = self.k {
+            model.add_const(format!("{}.k", prefix), tensor0(k as i64))?
+        } else {
+            inputs[1]
+        };
+        let op = tract_core::ops::array::TopK::new(axis, self.largest, self.sorted);
+        model.wire_node(prefix, op, &[inputs[0], k])
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn k_as_attribute() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let input =
+            model.add_source("input", InferenceFact::dt_shape(f32::datum_type(), [2, 3]))?;
+        let op = TopK { axis: -1, largest: false, sorted: true, k: Some(2) };
+        let outputs = model.wire_node("topk", expand(op), &[input])?;
+        model.set_output_outlets(&outputs)?;
+        let model = model.into_typed()?;
+        assert_eq!(model.output_fact(0)?.shape.to_tvec(), tvec!(2.to_dim(), 2.to_dim()));
+        let result =
+            SimplePlan::new(model)?.run(tvec!(tensor2(&[[3f32, 1., 2.], [4., 6., 5.]])))?;
+        assert_eq!(*result[0], tensor2(&[[1f32, 2.], [4., 5.]]));
+        assert_eq!(*result[1], tensor2(&[[1i64, 2], [0, 2]]));
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::pb::*;
+
+pub fn trilu(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let upper = node.get_attr_opt("upper")?.unwrap_or(true);
+    Ok((expand(Trilu { upper, has_k: node.input.len() == 2 }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Trilu {
+    upper: bool,
+    has_k: bool,
+}
+
+impl_dyn_hash!(Trilu);
+
+impl Expansion for Trilu {
+    fn name(&self) -> Cow<str> {
+        "Trilu".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1 + self.has_k as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        if self.has_k {
+            s.equals(&inputs[1].rank, 0)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let k = if self.has_k {
+            inputs[1]
+        } else {
+            model.add_const(format!("{}.k", prefix), tensor0(0i64))?
+        };
+        model.wire_node(
+            prefix,
+            tract_core::ops::array::Trilu { upper: self.upper },
+            &[inputs[0], k],
+        )
+    }
+}
@@ -0,0 +1,101 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::pb::*;
+
+pub fn unique(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?;
+    let sorted = node.get_attr_opt("sorted")?.unwrap_or(true);
+    let len = Symbol::new('U').into();
+    Ok((expand(Unique { axis, sorted, outputs: node.output.len(), len }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Unique {
+    axis: Option<i64>,
+    sorted: bool,
+    outputs: usize,
+    len: TDim,
+}
+
+impl_dyn_hash!(Unique);
+
+impl Unique {
+    fn to_core(&self, rank: usize) -> tract_core::ops::array::Unique {
+        let axis = self.axis.map(|axis| if axis < 0 { axis + rank as i64 } else { axis } as usize);
+        tract_core::ops::array::Unique { axis, sorted: self.sorted, len: self.len.clone() }
+    }
+}
+
+impl Expansion for Unique {
+    fn name(&self) -> Cow<str> {
+        "Unique".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, self.outputs)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        for output in &outputs[1..] {
+            s.equals(&output.datum_type, i64::datum_type())?;
+            s.equals(&output.rank, 1)?;
+        }
+        s.given(&inputs[0].shape, move |s, shape| {
+            let op = self.to_core(shape.len());
+            let fact = TypedFact::dt_shape(f32::datum_type(), shape);
+            let facts = op.output_facts(&[&fact])?;
+            for (output, fact) in outputs.iter().zip(facts.iter()) {
+                s.equals(&output.shape, ShapeFactoid::from(fact.shape.to_tvec()))?;
+            }
+            Ok(())
+        })
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.outputs)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let op = self.to_core(model.outlet_fact(inputs[0])?.rank());
+        let mut wires = model.wire_node(prefix, op, inputs)?;
+        wires.truncate(self.outputs);
+        Ok(wires)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn typed_with_runtime_len() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(i64::datum_type(), [6]))?;
+        let op = Unique { axis: None, sorted: false, outputs: 4, len: Symbol::new('U').into() };
+        let outputs = model.wire_node("unique", expand(op), &[x])?;
+        model.set_output_outlets(&outputs)?;
+        model.analyse(false)?;
+        assert!(model.missing_type_shape()?.is_empty());
+        let result =
+            SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor1(&[3i64, 1, 3, 2, 1, 3])))?;
+        assert_eq!(*result[0], tensor1(&[3i64, 1, 2]));
+        assert_eq!(*result[2], tensor1(&[0i64, 1, 0, 2, 1, 0]));
+        assert_eq!(*result[3], tensor1(&[3i64, 2, 1]));
+        Ok(())
+    }
+}
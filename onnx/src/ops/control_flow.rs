@@ -0,0 +1,617 @@
+use crate::model::{OnnxOpRegister, ParseResult, ParsingContext};
+use crate::pb::*;
+use tract_core::ops::control_flow;
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("If", if_then_else);
+    reg.insert("Loop", while_loop);
+}
+
+fn if_then_else(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let then_graph: &GraphProto = node.get_attr("then_branch")?;
+    let else_graph: &GraphProto = node.get_attr("else_branch")?;
+    let ParseResult { model: then_body, unresolved_inputs: then_closures, .. } =
+        ctx.parse_graph(then_graph)?;
+    let ParseResult { model: else_body, unresolved_inputs: else_closures, .. } =
+        ctx.parse_graph(else_graph)?;
+    let mut closures: Vec<String> = vec![];
+    for closure in then_closures.iter().chain(else_closures.iter()) {
+        if !closures.contains(closure) {
+            closures.push(closure.clone());
+        }
+    }
+    // outer input 0 is the condition, closures come next
+    let mapping = |names: &[String]| -> Vec<usize> {
+        names.iter().map(|name| 1 + closures.iter().position(|c| c == name).unwrap()).collect()
+    };
+    let then_input_mapping = mapping(&then_closures);
+    let else_input_mapping = mapping(&else_closures);
+    Ok((Box::new(If { then_body, then_input_mapping, else_body, else_input_mapping }), closures))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct If {
+    pub then_body: InferenceModel,
+    then_input_mapping: Vec<usize>,
+    pub else_body: InferenceModel,
+    else_input_mapping: Vec<usize>,
+}
+
+impl_dyn_hash!(If);
+
+impl If {
+    fn to_core(&self) -> TractResult<control_flow::If> {
+        control_flow::If::new(
+            self.then_body.clone().into_typed()?,
+            self.then_input_mapping.clone(),
+            self.else_body.clone().into_typed()?,
+            self.else_input_mapping.clone(),
+        )
+    }
+
+    fn branch(&self, cond: bool) -> (&InferenceModel, &[usize]) {
+        if cond {
+            (&self.then_body, &self.then_input_mapping)
+        } else {
+            (&self.else_body, &self.else_input_mapping)
+        }
+    }
+}
+
+impl Op for If {
+    fn name(&self) -> Cow<str> {
+        "If".into()
+    }
+
+    op_onnx!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for If {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(IfState(self.to_core()?))))
+    }
+}
+
+/// Typed branches are built once, when the state is created.
+#[derive(Debug, Clone)]
+struct IfState(control_flow::If);
+
+impl OpState for IfState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        _op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        self.0.eval(inputs)
+    }
+}
+
+impl InferenceOp for If {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        inputs[0].unify_with(&InferenceFact::dt_shape(bool::datum_type(), shapefactoid!()))?;
+        loop {
+            let mut changed = false;
+            for (ix, &slot) in self.then_input_mapping.iter().enumerate() {
+                changed |= inputs[slot].unify_with_mut(self.then_body.input_fact_mut(ix)?)?;
+            }
+            for (ix, &slot) in self.else_input_mapping.iter().enumerate() {
+                changed |= inputs[slot].unify_with_mut(self.else_body.input_fact_mut(ix)?)?;
+            }
+            let cond = inputs[0].value.concretize().map(|c| c.cast_to_scalar::<bool>());
+            match cond.transpose()? {
+                Some(true) => {
+                    for (ix, output) in outputs.iter_mut().enumerate() {
+                        changed |= output.unify_with_mut(self.then_body.output_fact_mut(ix)?)?;
+                    }
+                    changed |= self.then_body.analyse(false).context("analysing then branch")?;
+                }
+                Some(false) => {
+                    for (ix, output) in outputs.iter_mut().enumerate() {
+                        changed |= output.unify_with_mut(self.else_body.output_fact_mut(ix)?)?;
+                    }
+                    changed |= self.else_body.analyse(false).context("analysing else branch")?;
+                }
+                None => {
+                    // branches may disagree on dimensions: only the datum type, the rank and
+                    // the dimensions they agree on are shared with the output
+                    for (ix, output) in outputs.iter_mut().enumerate() {
+                        let then_fact = self.then_body.output_fact_mut(ix)?;
+                        let else_fact = self.else_body.output_fact_mut(ix)?;
+                        changed |= Factoid::unify_all(&mut [
+                            &mut output.datum_type,
+                            &mut then_fact.datum_type,
+                            &mut else_fact.datum_type,
+                        ])?;
+                        let rank = [&output.shape, &then_fact.shape, &else_fact.shape]
+                            .iter()
+                            .find(|s| !s.is_open())
+                            .map(|s| s.dims().count());
+                        if let Some(rank) = rank {
+                            let dims = tvec!(GenericFactoid::Any; rank);
+                            for shape in
+                                [&mut output.shape, &mut then_fact.shape, &mut else_fact.shape]
+                            {
+                                changed |= shape.unify_with(&ShapeFactoid::closed(dims.clone()))?;
+                            }
+                            for axis in 0..rank {
+                                let then_dim =
+                                    then_fact.shape.dim(axis).and_then(|d| d.concretize());
+                                let else_dim =
+                                    else_fact.shape.dim(axis).and_then(|d| d.concretize());
+                                match (then_dim, else_dim) {
+                                    (Some(t), Some(e)) if t == e => {
+                                        changed |= output.shape.set_dim(axis, t)
+                                    }
+                                    _ => (),
+                                }
+                            }
+                        }
+                    }
+                    changed |= self.then_body.analyse(false).context("analysing then branch")?;
+                    changed |= self.else_body.analyse(false).context("analysing else branch")?;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+        if let Some(cond) = &target.outlet_fact(inputs[0])?.konst {
+            let (body, body_mapping) = self.branch(cond.cast_to_scalar::<bool>()?);
+            let body = body.clone().into_typed()?;
+            let body_inputs = body_mapping.iter().map(|&slot| inputs[slot]).collect::<TVec<_>>();
+            return control_flow::inline_model(target, &node.name, &body, &body_inputs);
+        }
+        target.wire_node(&*node.name, self.to_core()?, &inputs)
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.then_body.output_outlets()?.len())
+    }
+
+    as_op!();
+}
+
+fn while_loop(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let graph: &GraphProto = node.get_attr("body")?;
+    let ParseResult { model: body, unresolved_inputs, .. } = ctx.parse_graph(graph)?;
+    let has_max_trip_count = node.input.first().map(|s| !s.is_empty()).unwrap_or(false);
+    let has_cond = node.input.get(1).map(|s| !s.is_empty()).unwrap_or(false);
+    let states = node.input.len().saturating_sub(2);
+    let body_inputs = body.input_outlets()?.len() - unresolved_inputs.len();
+    if body_inputs != 2 + states {
+        bail!("Loop body expects {} inputs, node provides {} states", body_inputs, states)
+    }
+    Ok((Box::new(Loop { body, states, has_max_trip_count, has_cond }), unresolved_inputs))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct Loop {
+    pub body: InferenceModel,
+    states: usize,
+    has_max_trip_count: bool,
+    has_cond: bool,
+}
+
+impl_dyn_hash!(Loop);
+
+impl Loop {
+    fn optional_inputs(&self) -> usize {
+        self.has_max_trip_count as usize + self.has_cond as usize
+    }
+
+    fn to_core(&self) -> TractResult<control_flow::Loop> {
+        control_flow::Loop::new(self.body.clone().into_typed()?, self.states)
+    }
+}
+
+impl Op for Loop {
+    fn name(&self) -> Cow<str> {
+        "Loop".into()
+    }
+
+    op_onnx!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for Loop {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(LoopState(self.to_core()?))))
+    }
+}
+
+/// The typed body is built once, when the state is created.
+#[derive(Debug, Clone)]
+struct LoopState(control_flow::Loop);
+
+impl OpState for LoopState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op.downcast_ref::<Loop>().context("Wrong op")?;
+        if !op.has_max_trip_count {
+            inputs.insert(0, rctensor0(i64::MAX));
+        }
+        if !op.has_cond {
+            inputs.insert(1, rctensor0(true));
+        }
+        self.0.eval(inputs)
+    }
+}
+
+impl InferenceOp for Loop {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        let i64_scalar = InferenceFact::dt_shape(i64::datum_type(), shapefactoid!());
+        let bool_scalar = InferenceFact::dt_shape(bool::datum_type(), shapefactoid!());
+        if self.has_max_trip_count {
+            inputs[0].unify_with(&i64_scalar)?;
+        }
+        if self.has_cond {
+            inputs[self.has_max_trip_count as usize].unify_with(&bool_scalar)?;
+        }
+        self.body.input_fact_mut(0)?.unify_with(&i64_scalar)?;
+        self.body.input_fact_mut(1)?.unify_with(&bool_scalar)?;
+        self.body.output_fact_mut(0)?.unify_with(&bool_scalar)?;
+        let first_state = self.optional_inputs();
+        let closures = inputs.len() - first_state - self.states;
+        let scans = outputs.len() - self.states;
+        loop {
+            let mut changed = false;
+            for ix in 0..self.states {
+                changed |=
+                    inputs[first_state + ix].unify_with_mut(self.body.input_fact_mut(2 + ix)?)?;
+                // states can change shape from one iteration to the next, not type or rank:
+                // the final state only has the dimensions that the body keeps
+                let mut dt = self.body.input_fact(2 + ix)?.datum_type;
+                changed |= dt.unify_with_mut(&mut self.body.output_fact_mut(1 + ix)?.datum_type)?;
+                changed |= dt.unify_with_mut(&mut self.body.input_fact_mut(2 + ix)?.datum_type)?;
+                changed |= outputs[ix].datum_type.unify_with_mut(&mut dt)?;
+                let input = self.body.input_fact(2 + ix)?;
+                let output = self.body.output_fact(1 + ix)?;
+                if let Some(rank) = output.shape.rank().concretize() {
+                    let dims = (0..rank as usize)
+                        .map(|axis| {
+                            let dim = output.shape.dim(axis).unwrap();
+                            if dim.concretize().is_some()
+                                && input.shape.dim(axis) == Some(dim.clone())
+                            {
+                                dim
+                            } else {
+                                GenericFactoid::Any
+                            }
+                        })
+                        .collect();
+                    changed |= outputs[ix].shape.unify_with(&ShapeFactoid::closed(dims))?;
+                }
+            }
+            for ix in 0..closures {
+                changed |= inputs[first_state + self.states + ix]
+                    .unify_with_mut(self.body.input_fact_mut(2 + self.states + ix)?)?;
+            }
+            for ix in 0..scans {
+                let inner = self.body.output_fact_mut(1 + self.states + ix)?;
+                let outer = &mut outputs[self.states + ix];
+                changed |= outer.datum_type.unify_with_mut(&mut inner.datum_type)?;
+                if let Some(rank) = inner.shape.rank().concretize() {
+                    let mut dims = tvec!(GenericFactoid::Any);
+                    dims.extend((0..rank as usize).map(|axis| inner.shape.dim(axis).unwrap()));
+                    changed |= outer.shape.unify_with(&ShapeFactoid::closed(dims))?;
+                }
+            }
+            changed |= self.body.analyse(false).context("analysing loop body")?;
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let mut inputs = node.inputs.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+        if !self.has_max_trip_count {
+            let max =
+                target.add_const(format!("{}.max_trip_count", node.name), rctensor0(i64::MAX))?;
+            inputs.insert(0, max);
+        }
+        if !self.has_cond {
+            let cond = target.add_const(format!("{}.cond", node.name), rctensor0(true))?;
+            inputs.insert(1, cond);
+        }
+        target.wire_node(&*node.name, self.to_core()?, &inputs)
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.body.output_outlets()?.len() - 1)
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pb::tensor_proto::DataType;
+    use crate::pb::tensor_shape_proto::{dimension, Dimension};
+
+    fn value_info(name: &str, dt: DataType, shape: &[i64]) -> ValueInfoProto {
+        let dim = shape
+            .iter()
+            .map(|&d| Dimension {
+                value: Some(dimension::Value::DimValue(d)),
+                ..Dimension::default()
+            })
+            .collect();
+        let tensor =
+            type_proto::Tensor { elem_type: dt as i32, shape: Some(TensorShapeProto { dim }) };
+        ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(tensor)),
+                ..TypeProto::default()
+            }),
+            ..ValueInfoProto::default()
+        }
+    }
+
+    fn value_info_without_shape(name: &str, dt: DataType) -> ValueInfoProto {
+        let tensor = type_proto::Tensor { elem_type: dt as i32, shape: None };
+        ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(tensor)),
+                ..TypeProto::default()
+            }),
+            ..ValueInfoProto::default()
+        }
+    }
+
+    fn node(op: &str, inputs: &[&str], outputs: &[&str]) -> NodeProto {
+        NodeProto {
+            op_type: op.to_string(),
+            name: outputs[0].to_string(),
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: outputs.iter().map(|s| s.to_string()).collect(),
+            ..NodeProto::default()
+        }
+    }
+
+    fn graph(
+        node: Vec<NodeProto>,
+        input: Vec<ValueInfoProto>,
+        output: Vec<ValueInfoProto>,
+    ) -> GraphProto {
+        GraphProto { node, input, output, ..GraphProto::default() }
+    }
+
+    fn with_graph(mut node: NodeProto, name: &str, graph: GraphProto) -> NodeProto {
+        node.attribute.push(AttributeProto {
+            name: name.to_string(),
+            r#type: attribute_proto::AttributeType::Graph as i32,
+            g: Some(graph),
+            ..AttributeProto::default()
+        });
+        node
+    }
+
+    fn model(graph: GraphProto) -> TractResult<TypedModel> {
+        let proto = ModelProto { graph: Some(graph), ..ModelProto::default() };
+        crate::onnx().model_for_proto_model(&proto)?.into_optimized()
+    }
+
+    // then: x + y, else: y (directly from the outer graph)
+    fn if_model() -> TractResult<(GraphProto, NodeProto)> {
+        let then_branch = graph(
+            vec![node("Add", &["x", "y"], &["sum"])],
+            vec![],
+            vec![value_info("sum", DataType::Float, &[2])],
+        );
+        let else_branch = graph(vec![], vec![], vec![value_info("y", DataType::Float, &[2])]);
+        let if_node = node("If", &["cond"], &["z"]);
+        let if_node = with_graph(if_node, "then_branch", then_branch);
+        let if_node = with_graph(if_node, "else_branch", else_branch);
+        let outer = graph(
+            vec![],
+            vec![value_info("x", DataType::Float, &[2]), value_info("y", DataType::Float, &[2])],
+            vec![value_info("z", DataType::Float, &[2])],
+        );
+        Ok((outer, if_node))
+    }
+
+    #[test]
+    fn if_with_dynamic_condition() -> TractResult<()> {
+        let (mut outer, if_node) = if_model()?;
+        outer.input.insert(0, value_info("cond", DataType::Bool, &[]));
+        outer.node.push(if_node);
+        let plan = SimplePlan::new(model(outer)?)?;
+        let x = tensor1(&[1f32, 2.]);
+        let y = tensor1(&[3f32, 4.]);
+        let result = plan.run(tvec!(tensor0(true), x.clone(), y.clone()))?;
+        assert_eq!(*result[0], tensor1(&[4f32, 6.]));
+        let result = plan.run(tvec!(tensor0(false), x, y))?;
+        assert_eq!(*result[0], tensor1(&[3f32, 4.]));
+        Ok(())
+    }
+
+    #[test]
+    fn if_with_const_condition() -> TractResult<()> {
+        let (mut outer, if_node) = if_model()?;
+        let cond = crate::pb::TensorProto {
+            name: "cond".to_string(),
+            data_type: DataType::Bool as i32,
+            int32_data: vec![1],
+            ..crate::pb::TensorProto::default()
+        };
+        outer.initializer.push(cond);
+        outer.node.push(if_node);
+        let model = model(outer)?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<control_flow::If>()));
+        let result =
+            SimplePlan::new(model)?.run(tvec!(tensor1(&[1f32, 2.]), tensor1(&[3f32, 4.])))?;
+        assert_eq!(*result[0], tensor1(&[4f32, 6.]));
+        Ok(())
+    }
+
+    // then: x, else: w, with different lengths
+    #[test]
+    fn if_with_different_branch_shapes() -> TractResult<()> {
+        let then_branch = graph(vec![], vec![], vec![value_info("x", DataType::Float, &[2])]);
+        let else_branch = graph(vec![], vec![], vec![value_info("w", DataType::Float, &[3])]);
+        let if_node = node("If", &["cond"], &["z"]);
+        let if_node = with_graph(if_node, "then_branch", then_branch);
+        let if_node = with_graph(if_node, "else_branch", else_branch);
+        let outer = graph(
+            vec![if_node],
+            vec![
+                value_info("cond", DataType::Bool, &[]),
+                value_info("x", DataType::Float, &[2]),
+                value_info("w", DataType::Float, &[3]),
+            ],
+            vec![value_info_without_shape("z", DataType::Float)],
+        );
+        let plan = SimplePlan::new(model(outer)?)?;
+        let x = tensor1(&[1f32, 2.]);
+        let w = tensor1(&[3f32, 4., 5.]);
+        let result = plan.run(tvec!(tensor0(true), x.clone(), w.clone()))?;
+        assert_eq!(*result[0], x);
+        let result = plan.run(tvec!(tensor0(false), x, w.clone()))?;
+        assert_eq!(*result[0], w);
+        Ok(())
+    }
+
+    // acc = acc + x, M times, scanning acc
+    #[test]
+    fn loop_with_trip_count() -> TractResult<()> {
+        let body = graph(
+            vec![
+                node("Identity", &["cond_in"], &["cond_out"]),
+                node("Add", &["acc_in", "x"], &["acc_out"]),
+                node("Identity", &["acc_out"], &["scan"]),
+            ],
+            vec![
+                value_info("i", DataType::Int64, &[]),
+                value_info("cond_in", DataType::Bool, &[]),
+                value_info("acc_in", DataType::Float, &[1]),
+            ],
+            vec![
+                value_info("cond_out", DataType::Bool, &[]),
+                value_info("acc_out", DataType::Float, &[1]),
+                value_info("scan", DataType::Float, &[1]),
+            ],
+        );
+        let loop_node =
+            with_graph(node("Loop", &["m", "", "acc"], &["acc_final", "scans"]), "body", body);
+        let outer = graph(
+            vec![loop_node],
+            vec![
+                value_info("m", DataType::Int64, &[]),
+                value_info("acc", DataType::Float, &[1]),
+                value_info("x", DataType::Float, &[1]),
+            ],
+            vec![
+                value_info("acc_final", DataType::Float, &[1]),
+                value_info("scans", DataType::Float, &[0, 1]),
+            ],
+        );
+        let plan = SimplePlan::new(model(outer)?)?;
+        let result = plan.run(tvec!(tensor0(3i64), tensor1(&[1f32]), tensor1(&[2f32])))?;
+        assert_eq!(*result[0], tensor1(&[7f32]));
+        assert_eq!(*result[1], tensor2(&[[3f32], [5.], [7.]]));
+        Ok(())
+    }
+
+    // acc = concat(acc, x), M times: the state grows
+    #[test]
+    fn loop_with_growing_state() -> TractResult<()> {
+        let mut concat = node("Concat", &["acc_in", "x"], &["acc_out"]);
+        concat.attribute.push(AttributeProto {
+            name: "axis".to_string(),
+            r#type: attribute_proto::AttributeType::Int as i32,
+            i: 0,
+            ..AttributeProto::default()
+        });
+        let body = graph(
+            vec![node("Identity", &["cond_in"], &["cond_out"]), concat],
+            vec![
+                value_info("i", DataType::Int64, &[]),
+                value_info("cond_in", DataType::Bool, &[]),
+                value_info("acc_in", DataType::Float, &[1]),
+            ],
+            vec![
+                value_info("cond_out", DataType::Bool, &[]),
+                value_info_without_shape("acc_out", DataType::Float),
+            ],
+        );
+        let loop_node = with_graph(node("Loop", &["m", "", "acc"], &["acc_final"]), "body", body);
+        let outer = graph(
+            vec![loop_node],
+            vec![
+                value_info("m", DataType::Int64, &[]),
+                value_info("acc", DataType::Float, &[1]),
+                value_info("x", DataType::Float, &[1]),
+            ],
+            vec![value_info_without_shape("acc_final", DataType::Float)],
+        );
+        let plan = SimplePlan::new(model(outer)?)?;
+        let result = plan.run(tvec!(tensor0(3i64), tensor1(&[0f32]), tensor1(&[1f32])))?;
+        assert_eq!(*result[0], tensor1(&[0f32, 1., 1., 1.]));
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use crate::model::{optional_inputs, OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_core::ops::fft::WindowKind;
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("DFT", dft);
+    reg.insert("STFT", stft);
+    reg.insert("HannWindow", |c, n| window(c, n, WindowKind::Hann));
+    reg.insert("HammingWindow", |c, n| window(c, n, WindowKind::Hamming));
+    reg.insert("BlackmanWindow", |c, n| window(c, n, WindowKind::Blackman));
+    reg.insert("MelWeightMatrix", mel_weight_matrix);
+}
+
+fn output_datatype(node: &NodeProto) -> TractResult<DatumType> {
+    Ok(node.get_attr_opt("output_datatype")?.unwrap_or(DatumType::F32))
+}
+
+fn dft(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(1i64);
+    let inverse = node.get_attr_opt("inverse")?.unwrap_or(false);
+    let onesided = node.get_attr_opt("onesided")?.unwrap_or(false);
+    let optional_dft_length_input = optional_inputs(node).nth(1).unwrap();
+    Ok((expand(Dft { axis, inverse, onesided, optional_dft_length_input }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Dft {
+    axis: i64,
+    inverse: bool,
+    onesided: bool,
+    optional_dft_length_input: Option<usize>,
+}
+
+impl_dyn_hash!(Dft);
+
+impl Dft {
+    /// Negative axes count from the end, including the trailing real/imaginary axis, which can
+    /// not be transformed.
+    fn resolve_axis(&self, rank: usize) -> TractResult<usize> {
+        let axis = if self.axis < 0 { self.axis + rank as i64 } else { self.axis };
+        if axis < 0 || axis + 1 >= rank as i64 {
+            bail!("Invalid DFT axis {} for an input of rank {}", self.axis, rank)
+        }
+        Ok(axis as usize)
+    }
+
+    fn bins(&self, n: TDim) -> TDim {
+        if self.onesided {
+            n / 2 + 1
+        } else {
+            n
+        }
+    }
+}
+
+impl Expansion for Dft {
+    fn name(&self) -> Cow<str> {
+        "DFT".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1 + self.optional_dft_length_input.is_some() as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            let rank = rank as usize;
+            let axis = self.resolve_axis(rank)?;
+            for ix in 0..rank - 1 {
+                if ix != axis {
+                    s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+                }
+            }
+            s.equals(&outputs[0].shape[rank - 1], 2.to_dim())?;
+            if let Some(ix) = self.optional_dft_length_input {
+                s.given(&inputs[ix].value, move |s, n| {
+                    let n = n.cast_to_scalar::<i64>()?.to_dim();
+                    s.equals(&outputs[0].shape[axis], self.bins(n))
+                })
+            } else {
+                s.given(&inputs[0].shape[axis], move |s, n| {
+                    s.equals(&outputs[0].shape[axis], self.bins(n))
+                })
+            }
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let length = if let Some(ix) = self.optional_dft_length_input {
+            if let Some(n) = &model.outlet_fact(inputs[ix])?.konst {
+                Some(n.cast_to_scalar::<i64>()? as usize)
+            } else {
+                bail!("DFT dft_length must be known at compile time")
+            }
+        } else {
+            None
+        };
+        let op = tract_core::ops::fft::Dft {
+            axis: self.resolve_axis(rank)?,
+            inverse: self.inverse,
+            onesided: self.onesided,
+            length,
+        };
+        model.wire_node(prefix, op, &inputs[0..1])
+    }
+}
+
+fn stft(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let onesided = node.get_attr_opt("onesided")?.unwrap_or(true);
+    let mut options = optional_inputs(node).skip(2);
+    let op = Stft {
+        onesided,
+        optional_window_input: options.next().unwrap(),
+        optional_frame_length_input: options.next().unwrap(),
+    };
+    Ok((expand(op), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Stft {
+    onesided: bool,
+    optional_window_input: Option<usize>,
+    optional_frame_length_input: Option<usize>,
+}
+
+impl_dyn_hash!(Stft);
+
+impl Stft {
+    fn to_core(
+        &self,
+        frame_step: &Tensor,
+        window: Option<Arc<Tensor>>,
+        frame_length: Option<&Tensor>,
+    ) -> TractResult<tract_core::ops::fft::Stft> {
+        let frame_length = if let Some(frame_length) = frame_length {
+            frame_length.cast_to_scalar::<i64>()? as usize
+        } else if let Some(window) = &window {
+            window.len()
+        } else {
+            bail!("STFT requires a window or a frame_length")
+        };
+        Ok(tract_core::ops::fft::Stft {
+            frame_step: frame_step.cast_to_scalar::<i64>()? as usize,
+            frame_length,
+            onesided: self.onesided,
+            window,
+        })
+    }
+}
+
+impl Expansion for Stft {
+    fn name(&self) -> Cow<str> {
+        "STFT".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(
+            inputs,
+            2 + self.optional_window_input.is_some() as usize
+                + self.optional_frame_length_input.is_some() as usize,
+        )?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[3], 2.to_dim())?;
+        let frames_and_bins = move |s: &mut Solver<'r>,
+                                    len: TDim,
+                                    frame_step: Arc<Tensor>,
+                                    frame_length: Arc<Tensor>| {
+            let op = self.to_core(&frame_step, None, Some(&frame_length))?;
+            s.equals(&outputs[0].shape[1], op.frames(&len))?;
+            s.equals(&outputs[0].shape[2], op.bins().to_dim())
+        };
+        if let Some(ix) = self.optional_frame_length_input {
+            s.given_3(&inputs[0].shape[1], &inputs[1].value, &inputs[ix].value, frames_and_bins)
+        } else if let Some(ix) = self.optional_window_input {
+            s.given_3(
+                &inputs[0].shape[1],
+                &inputs[1].value,
+                &inputs[ix].shape[0],
+                move |s, len, frame_step, window_len: TDim| {
+                    frames_and_bins(s, len, frame_step, rctensor0(window_len.to_i64()?))
+                },
+            )
+        } else {
+            bail!("STFT requires a window or a frame_length")
+        }
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let konst = |ix: usize, name: &str| {
+            model
+                .outlet_fact(inputs[ix])?
+                .konst
+                .clone()
+                .with_context(|| format!("STFT {} must be known at compile time", name))
+        };
+        let frame_step = konst(1, "frame_step")?;
+        let window = self.optional_window_input.map(|ix| konst(ix, "window")).transpose()?;
+        let frame_length =
+            self.optional_frame_length_input.map(|ix| konst(ix, "frame_length")).transpose()?;
+        let op = self.to_core(&frame_step, window, frame_length.as_deref())?;
+        model.wire_node(prefix, op, &inputs[0..1])
+    }
+}
+
+fn window(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+    kind: WindowKind,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let periodic = node.get_attr_opt("periodic")?.unwrap_or(true);
+    let datum_type = output_datatype(node)?;
+    Ok((expand(Window(tract_core::ops::fft::Window { kind, periodic, datum_type })), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Window(tract_core::ops::fft::Window);
+
+impl_dyn_hash!(Window);
+
+impl Expansion for Window {
+    fn name(&self) -> Cow<str> {
+        self.0.name()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].rank, 0)?;
+        s.equals(&outputs[0].datum_type, self.0.datum_type)?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.given(&inputs[0].value, move |s, size| {
+            s.equals(&outputs[0].shape[0], size.cast_to_scalar::<i64>()?.to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+fn mel_weight_matrix(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let datum_type = output_datatype(node)?;
+    Ok((expand(MelWeightMatrix(tract_core::ops::fft::MelWeightMatrix { datum_type })), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct MelWeightMatrix(tract_core::ops::fft::MelWeightMatrix);
+
+impl_dyn_hash!(MelWeightMatrix);
+
+impl Expansion for MelWeightMatrix {
+    fn name(&self) -> Cow<str> {
+        "MelWeightMatrix".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 5)?;
+        check_output_arity(outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.rank, 0)?;
+        }
+        s.equals(&outputs[0].datum_type, self.0.datum_type)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.given_2(&inputs[0].value, &inputs[1].value, move |s, num_mel_bins, dft_length| {
+            let dft_length = dft_length.cast_to_scalar::<i64>()?;
+            s.equals(&outputs[0].shape[0], (dft_length / 2 + 1).to_dim())?;
+            s.equals(&outputs[0].shape[1], num_mel_bins.cast_to_scalar::<i64>()?.to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dft_negative_axis() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let signal =
+            model.add_source("signal", InferenceFact::dt_shape(f32::datum_type(), [2, 4, 2]))?;
+        let op = Dft { axis: -2, inverse: false, onesided: false, optional_dft_length_input: None };
+        let dft = model.wire_node("dft", expand(op), &[signal])?;
+        model.set_output_outlets(&dft)?;
+        let plan = SimplePlan::new(model.into_typed()?)?;
+        let signal = tensor3(&[[[1f32, 0.], [0., 0.], [0., 0.], [0., 0.]]; 2]);
+        let output = plan.run(tvec!(signal))?;
+        assert_eq!(*output[0], tensor3(&[[[1f32, 0.]; 4]; 2]));
+        let op = Dft { axis: -1, inverse: false, onesided: false, optional_dft_length_input: None };
+        assert!(op.resolve_axis(3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn streaming_friendly_stft() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let signal =
+            model.add_source("signal", InferenceFact::dt_shape(f32::datum_type(), [1, 9, 1]))?;
+        let step = model.add_const("step", tensor0(2i64))?;
+        let window = model.add_const("window", tensor1(&[1f32, 1., 1., 1.]))?;
+        let op = Stft {
+            onesided: true,
+            optional_window_input: Some(2),
+            optional_frame_length_input: None,
+        };
+        let stft = model.wire_node("stft", expand(op), &[signal, step, window])?;
+        model.set_output_outlets(&stft)?;
+        let model = model.into_typed()?;
+        assert_eq!(model.outlet_fact(stft[0])?.shape.as_concrete(), Some(&[1usize, 3, 3, 2][..]));
+        let plan = SimplePlan::new(model)?;
+        let signal = tensor3(&[[[1f32], [1.], [1.], [1.], [0.], [0.], [0.], [0.], [0.]]]);
+        let output = plan.run(tvec!(signal))?;
+        assert_eq!(output[0].as_slice::<f32>()?[0..2], [4., 0.]);
+        assert_eq!(output[0].as_slice::<f32>()?[6..8], [2., 0.]);
+        Ok(())
+    }
+}
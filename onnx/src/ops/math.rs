@@ -6,6 +6,7 @@ use tract_hir::ops;
 use tract_hir::ops::binary::Nary;
 
 mod clip;
+mod cumsum;
 mod gemm;
 mod mat_mul_integer;
 mod pow;
@@ -30,6 +31,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Floor", |_, _| Ok((Box::new(ops::math::floor()), vec![])));
     reg.insert("Round", |_, _| Ok((Box::new(ops::math::round_half_to_even()), vec![])));
     reg.insert("Clip", clip::clip);
+    reg.insert("CumSum", cumsum::cumsum);
 
     reg.insert("Cos", |_, _| Ok((Box::new(ops::math::cos()), vec![])));
     reg.insert("Sin", |_, _| Ok((Box::new(ops::math::sin()), vec![])));
@@ -63,6 +65,15 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("MatMulInteger", mat_mul_integer::mat_mul_integer);
     reg.insert("QLinearMatMul", mat_mul_integer::q_linear_mat_mul);
     reg.insert("Gemm", gemm::gemm);
+    reg.insert("Einsum", einsum);
+}
+
+fn einsum(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let equation: String = node.get_attr("equation")?;
+    Ok((expand(ops::einsum::EinSumInference::new(equation)), vec![]))
 }
 
 fn isinf(
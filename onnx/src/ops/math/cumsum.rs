@@ -0,0 +1,63 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_hir::internal::*;
+
+pub fn cumsum(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let exclusive = node.get_attr_opt("exclusive")?.unwrap_or(false);
+    let reverse = node.get_attr_opt("reverse")?.unwrap_or(false);
+    Ok((expand(CumSum { exclusive, reverse }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct CumSum {
+    exclusive: bool,
+    reverse: bool,
+}
+
+impl_dyn_hash!(CumSum);
+
+impl Expansion for CumSum {
+    fn name(&self) -> Cow<str> {
+        "CumSum".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 0)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = if let Some(axis) = &model.outlet_fact(inputs[1])?.konst {
+            axis.cast_to_scalar::<i64>()?
+        } else {
+            bail!("CumSum axis must be known at compile time")
+        };
+        let axis = if axis < 0 { axis + rank } else { axis } as usize;
+        let op = tract_core::ops::math::CumSum {
+            axis,
+            exclusive: self.exclusive,
+            reverse: self.reverse,
+        };
+        model.wire_node(prefix, op, &inputs[0..1])
+    }
+}
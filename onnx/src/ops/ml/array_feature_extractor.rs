@@ -0,0 +1,46 @@
+use crate::model::OnnxOpRegister;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::array_feature_extractor;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("ArrayFeatureExtractor", |_, _| Ok((expand(ArrayFeatureExtractor), vec![])));
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ArrayFeatureExtractor;
+
+impl_dyn_hash!(ArrayFeatureExtractor);
+
+impl Expansion for ArrayFeatureExtractor {
+    fn name(&self) -> Cow<str> {
+        "ArrayFeatureExtractor".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].datum_type, i64::datum_type())?;
+        s.given_2(&inputs[0].shape, &inputs[1].shape, move |s, data, indices| {
+            let mut shape = data[..data.len() - 1].to_vec();
+            shape.extend(indices.iter().cloned());
+            s.equals(&outputs[0].shape, ShapeFactoid::from(shape))
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, array_feature_extractor::ArrayFeatureExtractor, inputs)
+    }
+}
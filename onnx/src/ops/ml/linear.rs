@@ -0,0 +1,188 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::linear;
+
+use super::{post_transform, wire_argmax_labels, wire_post_transform, PostTransform};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("LinearClassifier", linear_classifier);
+    reg.insert("LinearRegressor", linear_regressor);
+}
+
+fn linear_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let ints = node.get_attr_opt_slice::<i64>("classlabels_ints")?;
+    let strs = node.get_attr_opt_tvec::<&str>("classlabels_strings")?;
+    let class_labels = match (ints, strs) {
+        (Some(n), None) => rctensor1(n),
+        (None, Some(n)) => rctensor1(&n.iter().map(|d| d.to_string()).collect::<Vec<_>>()),
+        _ => bail!("exactly one of 'classlabels_ints' and 'classlabels_strings' must be set"),
+    };
+    let intercepts = node.get_attr_opt_vec::<f32>("intercepts")?;
+    let rows = intercepts.as_ref().map(|i| i.len()).unwrap_or_else(|| class_labels.len());
+    let (coefficients, intercepts) = linear_parameters(node, rows, intercepts)?;
+    // a single row of coefficients on two classes scores the second class against the first
+    let binary = rows == 1 && class_labels.len() == 2;
+    let op = linear::LinearClassifier { coefficients, intercepts, binary };
+    let post_transform = post_transform(node)?;
+    Ok((expand(LinearClassifier { op, class_labels, post_transform }), vec![]))
+}
+
+fn linear_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let targets = node.get_attr_opt("targets")?.unwrap_or(1);
+    let intercepts = node.get_attr_opt_vec::<f32>("intercepts")?;
+    let (coefficients, intercepts) = linear_parameters(node, targets, intercepts)?;
+    let op = linear::LinearRegressor { coefficients, intercepts };
+    let post_transform = post_transform(node)?;
+    Ok((expand(LinearRegressor { op, post_transform }), vec![]))
+}
+
+/// Coefficients as a `[rows, features]` matrix, and intercepts as a `[rows]` vector.
+fn linear_parameters(
+    node: &NodeProto,
+    rows: usize,
+    intercepts: Option<Vec<f32>>,
+) -> TractResult<(Arc<Tensor>, Arc<Tensor>)> {
+    let coefficients = node.get_attr_vec::<f32>("coefficients")?;
+    node.expect_attr("coefficients", rows > 0 && coefficients.len() % rows == 0, || {
+        format!("a multiple of {} values, got {}", rows, coefficients.len())
+    })?;
+    let features = coefficients.len() / rows;
+    let coefficients = tensor1(&coefficients).into_shape(&[rows, features])?.into_arc_tensor();
+    let intercepts = intercepts.unwrap_or_else(|| vec![0.0; rows]);
+    node.expect_attr("intercepts", intercepts.len() == rows, || {
+        format!("{} values, got {}", rows, intercepts.len())
+    })?;
+    Ok((coefficients, rctensor1(&intercepts)))
+}
+
+fn linear_rules<'r, 'p: 'r>(
+    s: &mut Solver<'r>,
+    input: &'p TensorProxy,
+    scores: &'p TensorProxy,
+    coefficients: &Arc<Tensor>,
+    columns: usize,
+) -> InferenceResult {
+    s.equals(&input.rank, 2)?;
+    s.equals(&input.shape[1], coefficients.shape()[1].to_dim())?;
+    s.equals(&scores.datum_type, f32::datum_type())?;
+    s.equals(&scores.rank, 2)?;
+    s.equals(&scores.shape[0], &input.shape[0])?;
+    s.equals(&scores.shape[1], columns.to_dim())?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct LinearClassifier {
+    pub op: linear::LinearClassifier,
+    pub class_labels: Arc<Tensor>,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(LinearClassifier);
+
+impl Expansion for LinearClassifier {
+    fn name(&self) -> Cow<str> {
+        "LinearClassifier".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 2)?;
+        s.equals(&outputs[0].datum_type, self.class_labels.datum_type())?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        linear_rules(s, &inputs[0], &outputs[1], &self.op.coefficients, self.op.n_scores())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let scores = model.wire_node(format!("{}.linear", prefix), self.op.clone(), inputs)?;
+        let scores = wire_post_transform(prefix, model, scores[0], self.post_transform)?;
+        let labels = wire_argmax_labels(prefix, model, scores, &self.class_labels)?;
+        Ok(tvec!(labels, scores))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct LinearRegressor {
+    pub op: linear::LinearRegressor,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(LinearRegressor);
+
+impl Expansion for LinearRegressor {
+    fn name(&self) -> Cow<str> {
+        "LinearRegressor".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        let coefficients = &self.op.coefficients;
+        linear_rules(s, &inputs[0], &outputs[0], coefficients, coefficients.shape()[0])
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let targets = model.wire_node(format!("{}.linear", prefix), self.op.clone(), inputs)?;
+        Ok(tvec!(wire_post_transform(prefix, model, targets[0], self.post_transform)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn binary_classifier() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [2, 2]))?;
+        let op = linear::LinearClassifier {
+            coefficients: rctensor2(&[[1f32, -1.]]),
+            intercepts: rctensor1(&[0.5f32]),
+            binary: true,
+        };
+        let op = LinearClassifier { op, class_labels: rctensor1(&[3i64, 7]), post_transform: None };
+        let outputs = model.wire_node("linear", expand(op), &[x])?;
+        model.set_output_outlets(&outputs)?;
+        let result =
+            SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor2(&[[2f32, 1.], [0., 2.]])))?;
+        assert_eq!(*result[0], tensor1(&[7i64, 3]));
+        assert_eq!(*result[1], tensor2(&[[-1.5f32, 1.5], [1.5, -1.5]]));
+        Ok(())
+    }
+}
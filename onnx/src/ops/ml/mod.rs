@@ -1,9 +1,128 @@
+mod array_feature_extractor;
 mod category_mapper;
+mod linear;
+mod one_hot_encoder;
+mod preprocessing;
+mod svm;
 mod tree_ensemble_classifier;
+mod tree_ensemble_regressor;
+mod zip_map;
 
 use crate::model::OnnxOpRegister;
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    array_feature_extractor::register_all_ops(reg);
     category_mapper::register_all_ops(reg);
+    linear::register_all_ops(reg);
+    one_hot_encoder::register_all_ops(reg);
+    preprocessing::register_all_ops(reg);
+    svm::register_all_ops(reg);
     tree_ensemble_classifier::register_all_ops(reg);
+    tree_ensemble_regressor::register_all_ops(reg);
+    zip_map::register_all_ops(reg);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PostTransform {
+    Softmax,
+    Logistic,
+    // SoftmaxZero,
+    // Probit, // probit, especially multinomial, is p.i.t.a. - so let's ignore it for now
+}
+
+pub fn parse_post_transform(s: &str) -> TractResult<Option<PostTransform>> {
+    match s {
+        "NONE" => Ok(None),
+        "SOFTMAX" => Ok(Some(PostTransform::Softmax)),
+        "LOGISTIC" => Ok(Some(PostTransform::Logistic)),
+        "PROBIT" | "SOFTMAX_ZERO" => bail!("PROBIT and SOFTMAX_ZERO unsupported"),
+        _ => bail!("Invalid post transform: {}", s),
+    }
+}
+
+fn post_transform(node: &NodeProto) -> TractResult<Option<PostTransform>> {
+    Ok(node.get_attr_opt("post_transform")?.map(parse_post_transform).transpose()?.flatten())
+}
+
+fn parse_class_data(node: &NodeProto) -> TractResult<Arc<Tensor>> {
+    // parse n_classes from protobuf
+    let ints = node.get_attr_opt_slice::<i64>("classlabels_int64s")?;
+    let strs = node.get_attr_opt_tvec::<&str>("classlabels_strings")?;
+    match (ints, strs) {
+        (Some(n), None) => Ok(rctensor1(n)),
+        (None, Some(n)) => Ok(rctensor1(&n.iter().map(|d| d.to_string()).collect::<Vec<_>>())),
+        (None, None) => {
+            bail!("cannot find neither 'classlabels_int64s' not 'classlabels_strings'")
+        }
+        (Some(_), Some(_)) => {
+            bail!("only one of 'classlabels_int64s' and 'classlabels_strings' can be set")
+        }
+    }
+}
+
+/// Applies the post transform to a `[N, C]` score matrix.
+fn wire_post_transform(
+    prefix: &str,
+    model: &mut TypedModel,
+    scores: OutletId,
+    post_transform: Option<PostTransform>,
+) -> TractResult<OutletId> {
+    match post_transform {
+        None => Ok(scores),
+        Some(PostTransform::Softmax) => Ok(tract_hir::ops::nn::LayerSoftmax::new(1, false).wire(
+            &format!("{}.softmax", prefix),
+            model,
+            &[scores],
+        )?[0]),
+        Some(PostTransform::Logistic) => Ok(model.wire_node(
+            format!("{}.logistic", prefix),
+            tract_core::ops::nn::sigmoid(),
+            &[scores],
+        )?[0]),
+    }
+}
+
+/// Maps `i32` class indices to their labels.
+fn wire_labels(
+    prefix: &str,
+    model: &mut TypedModel,
+    winners: OutletId,
+    class_labels: &Arc<Tensor>,
+) -> TractResult<OutletId> {
+    Ok(model.wire_node(
+        format!("{}.labels", prefix),
+        tract_onnx_opl::ml::DirectLookup::new(
+            class_labels.clone(),
+            Tensor::zero_dt(class_labels.datum_type(), &[])?.into_arc_tensor(),
+        )?,
+        &[winners],
+    )?[0])
+}
+
+/// Labels of the best scoring classes of a `[N, C]` score matrix.
+fn wire_argmax_labels(
+    prefix: &str,
+    model: &mut TypedModel,
+    scores: OutletId,
+    class_labels: &Arc<Tensor>,
+) -> TractResult<OutletId> {
+    use tract_core::ops::nn::{Reduce, Reducer};
+    let winners = model.wire_node(
+        format!("{}.argmax", prefix),
+        Reduce::new(tvec!(1), Reducer::ArgMax(false)),
+        &[scores],
+    )?;
+    let reduced = model.wire_node(
+        format!("{}.rm_axis", prefix),
+        tract_core::ops::change_axes::AxisOp::Rm(1),
+        &winners,
+    )?;
+    let casted = model.wire_node(
+        format!("{}.casted", prefix),
+        tract_core::ops::cast::cast(i32::datum_type()),
+        &reduced,
+    )?;
+    wire_labels(prefix, model, casted[0], class_labels)
 }
@@ -0,0 +1,81 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::one_hot_encoder;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("OneHotEncoder", one_hot_encoder);
+}
+
+fn one_hot_encoder(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let ints = node.get_attr_opt_slice::<i64>("cats_int64s")?;
+    let strs = node.get_attr_opt_tvec::<&str>("cats_strings")?;
+    let categories = match (ints, strs) {
+        (Some(n), None) => rctensor1(n),
+        (None, Some(n)) => rctensor1(&n.iter().map(|d| d.to_string()).collect::<Vec<_>>()),
+        _ => bail!("exactly one of 'cats_int64s' and 'cats_strings' must be set"),
+    };
+    Ok((expand(OneHotEncoder(one_hot_encoder::OneHotEncoder { categories })), vec![]))
+}
+
+/// Unknown values are encoded as a row of zeros, whatever the `zeros` attribute says.
+#[derive(Debug, Clone, Hash)]
+pub struct OneHotEncoder(pub one_hot_encoder::OneHotEncoder);
+
+impl_dyn_hash!(OneHotEncoder);
+
+impl Expansion for OneHotEncoder {
+    fn name(&self) -> Cow<str> {
+        "OneHotEncoder".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            for ix in 0..rank as usize {
+                s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+            }
+            s.equals(&outputs[0].shape[rank as usize], self.0.categories.len().to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unknown_category() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(i64::datum_type(), [3]))?;
+        let op =
+            OneHotEncoder(one_hot_encoder::OneHotEncoder { categories: rctensor1(&[4i64, 2]) });
+        let y = model.wire_node("encoder", expand(op), &[x])?;
+        model.set_output_outlets(&y)?;
+        let found = SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor1(&[2i64, 3, 4])))?;
+        assert_eq!(*found[0], tensor2(&[[0f32, 1.], [0., 0.], [1., 0.]]));
+        Ok(())
+    }
+}
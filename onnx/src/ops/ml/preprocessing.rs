@@ -0,0 +1,184 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::preprocessing::{self, Norm};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("Imputer", imputer);
+    reg.insert("Normalizer", normalizer);
+    reg.insert("Scaler", scaler);
+}
+
+fn normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let norm = Norm::parse(node.get_attr_opt("norm")?.unwrap_or("MAX"))?;
+    Ok((expand(Normalizer(preprocessing::Normalizer { norm })), vec![]))
+}
+
+fn scaler(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let offset = node.get_attr_opt_vec::<f32>("offset")?.unwrap_or_else(|| vec![0.0]);
+    let scale = node.get_attr_opt_vec::<f32>("scale")?.unwrap_or_else(|| vec![1.0]);
+    let op = preprocessing::Scaler { offset: rctensor1(&offset), scale: rctensor1(&scale) };
+    Ok((expand(Scaler(op)), vec![]))
+}
+
+fn imputer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let (imputed, replaced) =
+        if let Some(imputed) = node.get_attr_opt_vec::<f32>("imputed_value_floats")? {
+            let replaced = node.get_attr_opt::<f32>("replaced_value_float")?.unwrap_or(0.0);
+            (rctensor1(&imputed), rctensor0(replaced))
+        } else if let Some(imputed) = node.get_attr_opt_vec::<i64>("imputed_value_int64s")? {
+            let replaced = node.get_attr_opt::<i64>("replaced_value_int64")?.unwrap_or(0);
+            (rctensor1(&imputed), rctensor0(replaced))
+        } else {
+            bail!("Imputer needs one of 'imputed_value_floats' and 'imputed_value_int64s'")
+        };
+    Ok((expand(Imputer(preprocessing::Imputer { imputed, replaced })), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct Normalizer(pub preprocessing::Normalizer);
+
+impl_dyn_hash!(Normalizer);
+
+impl Expansion for Normalizer {
+    fn name(&self) -> Cow<str> {
+        "Normalizer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct Scaler(pub preprocessing::Scaler);
+
+impl_dyn_hash!(Scaler);
+
+impl Expansion for Scaler {
+    fn name(&self) -> Cow<str> {
+        "Scaler".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct Imputer(pub preprocessing::Imputer);
+
+impl_dyn_hash!(Imputer);
+
+impl Expansion for Imputer {
+    fn name(&self) -> Cow<str> {
+        "Imputer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(op: impl Expansion, input: Tensor) -> TractResult<Arc<Tensor>> {
+        let mut model = InferenceModel::default();
+        let x =
+            model.add_source("x", InferenceFact::dt_shape(input.datum_type(), input.shape()))?;
+        let y = model.wire_node("op", expand(op), &[x])?;
+        model.set_output_outlets(&y)?;
+        Ok(SimplePlan::new(model.into_typed()?)?.run(tvec!(input))?.remove(0))
+    }
+
+    #[test]
+    fn scaler() -> TractResult<()> {
+        let op =
+            preprocessing::Scaler { offset: rctensor1(&[1f32, 2.]), scale: rctensor1(&[2f32]) };
+        let found = run(Scaler(op), tensor2(&[[1i64, 4], [3, 2]]))?;
+        assert_eq!(*found, tensor2(&[[0f32, 4.], [4., 0.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn imputer_nan() -> TractResult<()> {
+        let op = preprocessing::Imputer {
+            imputed: rctensor1(&[-1f32, -2.]),
+            replaced: rctensor0(f32::NAN),
+        };
+        let found = run(Imputer(op), tensor2(&[[f32::NAN, 4.], [3., f32::NAN]]))?;
+        assert_eq!(*found, tensor2(&[[-1f32, 4.], [3., -2.]]));
+        Ok(())
+    }
+}
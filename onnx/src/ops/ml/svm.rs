@@ -0,0 +1,239 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::svm::{self, Kernel, KernelType};
+
+use super::{parse_class_data, post_transform, wire_labels, wire_post_transform, PostTransform};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("SVMClassifier", svm_classifier);
+    reg.insert("SVMRegressor", svm_regressor);
+}
+
+fn kernel(node: &NodeProto) -> TractResult<Kernel> {
+    let kernel_type = KernelType::parse(node.get_attr_opt("kernel_type")?.unwrap_or("LINEAR"))?;
+    let (gamma, coef0, degree) = match node.get_attr_opt_vec::<f32>("kernel_params")? {
+        Some(params) => {
+            node.expect_attr("kernel_params", params.len() == 3, "gamma, coef0 and degree")?;
+            (params[0], params[1], params[2] as i32)
+        }
+        None => (0.0, 0.0, 0),
+    };
+    Ok(Kernel { kernel_type, gamma, coef0, degree })
+}
+
+fn svm_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let class_labels = parse_class_data(node)?;
+    let n_classes = class_labels.len();
+    let coefficients = node.get_attr_vec::<f32>("coefficients")?;
+    let rho = node.get_attr_vec::<f32>("rho")?;
+    let vectors_per_class = node
+        .get_attr_opt_vec::<usize>("vectors_per_class")?
+        .filter(|v| !v.is_empty())
+        .map(TVec::from);
+    let (support_vectors, coefficients) = if let Some(vpc) = &vectors_per_class {
+        node.expect_attr("vectors_per_class", vpc.len() == n_classes, "one count per class")?;
+        let n_sv = vpc.iter().sum::<usize>();
+        node.expect_attr("coefficients", coefficients.len() == (n_classes - 1) * n_sv, || {
+            format!("{} values, got {}", (n_classes - 1) * n_sv, coefficients.len())
+        })?;
+        node.expect_attr("rho", rho.len() == n_classes * (n_classes - 1) / 2, "one per pair")?;
+        let support_vectors = node.get_attr_vec::<f32>("support_vectors")?;
+        node.expect_attr("support_vectors", n_sv > 0 && support_vectors.len() % n_sv == 0, || {
+            format!("a multiple of {} values, got {}", n_sv, support_vectors.len())
+        })?;
+        let features = support_vectors.len() / n_sv;
+        (
+            tensor1(&support_vectors).into_shape(&[n_sv, features])?.into_arc_tensor(),
+            tensor1(&coefficients).into_shape(&[n_classes - 1, n_sv])?.into_arc_tensor(),
+        )
+    } else {
+        node.expect_attr("rho", rho.len() == n_classes, "one per class")?;
+        node.expect_attr("coefficients", coefficients.len() % n_classes == 0, || {
+            format!("a multiple of {} values, got {}", n_classes, coefficients.len())
+        })?;
+        let features = coefficients.len() / n_classes;
+        let coefficients =
+            tensor1(&coefficients).into_shape(&[n_classes, features])?.into_arc_tensor();
+        (coefficients.clone(), coefficients)
+    };
+    let prob_a = node.get_attr_opt_vec::<f32>("prob_a")?.filter(|p| !p.is_empty());
+    let prob_b = node.get_attr_opt_vec::<f32>("prob_b")?.filter(|p| !p.is_empty());
+    let (prob_a, prob_b) = match (prob_a, prob_b) {
+        (Some(a), Some(b)) if vectors_per_class.is_some() => {
+            let pairs = n_classes * (n_classes - 1) / 2;
+            node.expect_attr("prob_a", a.len() == pairs && b.len() == pairs, "one per pair")?;
+            (Some(rctensor1(&a)), Some(rctensor1(&b)))
+        }
+        _ => (None, None),
+    };
+    let op = svm::SvmClassifier {
+        kernel: kernel(node)?,
+        n_classes,
+        support_vectors,
+        vectors_per_class,
+        coefficients,
+        rho: rctensor1(&rho),
+        prob_a,
+        prob_b,
+    };
+    let post_transform = post_transform(node)?;
+    Ok((expand(SvmClassifier { op, class_labels, post_transform }), vec![]))
+}
+
+fn svm_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let n_supports = node.get_attr_opt::<usize>("n_supports")?.unwrap_or(0);
+    let coefficients = node.get_attr_vec::<f32>("coefficients")?;
+    let support_vectors = if n_supports > 0 {
+        node.expect_attr("coefficients", coefficients.len() == n_supports, "one per support")?;
+        let support_vectors = node.get_attr_vec::<f32>("support_vectors")?;
+        node.expect_attr("support_vectors", support_vectors.len() % n_supports == 0, || {
+            format!("a multiple of {} values, got {}", n_supports, support_vectors.len())
+        })?;
+        let features = support_vectors.len() / n_supports;
+        tensor1(&support_vectors).into_shape(&[n_supports, features])?
+    } else {
+        Tensor::zero::<f32>(&[0, coefficients.len()])?
+    };
+    let rho = node.get_attr_vec::<f32>("rho")?;
+    node.expect_attr("rho", rho.len() == 1, "a single value")?;
+    let op = svm::SvmRegressor {
+        kernel: kernel(node)?,
+        support_vectors: support_vectors.into_arc_tensor(),
+        coefficients: rctensor1(&coefficients),
+        rho: rho[0],
+        one_class: node.get_attr_opt::<i64>("one_class")?.unwrap_or(0) != 0,
+    };
+    let post_transform = post_transform(node)?;
+    Ok((expand(SvmRegressor { op, post_transform }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct SvmClassifier {
+    pub op: svm::SvmClassifier,
+    pub class_labels: Arc<Tensor>,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(SvmClassifier);
+
+impl Expansion for SvmClassifier {
+    fn name(&self) -> Cow<str> {
+        "SVMClassifier".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 2)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].datum_type, self.class_labels.datum_type())?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[1].datum_type, f32::datum_type())?;
+        s.equals(&outputs[1].rank, 2)?;
+        s.equals(&outputs[1].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[1].shape[1], self.op.n_scores().to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let wires = model.wire_node(format!("{}.svm", prefix), self.op.clone(), inputs)?;
+        let scores = wire_post_transform(prefix, model, wires[1], self.post_transform)?;
+        let labels = wire_labels(prefix, model, wires[0], &self.class_labels)?;
+        Ok(tvec!(labels, scores))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct SvmRegressor {
+    pub op: svm::SvmRegressor,
+    pub post_transform: Option<PostTransform>,
+}
+
+impl_dyn_hash!(SvmRegressor);
+
+impl Expansion for SvmRegressor {
+    fn name(&self) -> Cow<str> {
+        "SVMRegressor".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], 1.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let wire = model.wire_node(format!("{}.svm", prefix), self.op.clone(), inputs)?;
+        Ok(tvec!(wire_post_transform(prefix, model, wire[0], self.post_transform)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn linear_classifier() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [2, 2]))?;
+        let coefficients = rctensor2(&[[1f32, 0.], [0., 1.]]);
+        let op = svm::SvmClassifier {
+            kernel: Kernel { kernel_type: KernelType::Linear, gamma: 0.0, coef0: 0.0, degree: 0 },
+            n_classes: 2,
+            support_vectors: coefficients.clone(),
+            vectors_per_class: None,
+            coefficients,
+            rho: rctensor1(&[0f32, 0.5]),
+            prob_a: None,
+            prob_b: None,
+        };
+        let op = SvmClassifier { op, class_labels: rctensor1(&[10i64, 20]), post_transform: None };
+        let outputs = model.wire_node("svm", expand(op), &[x])?;
+        model.set_output_outlets(&outputs)?;
+        let result =
+            SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor2(&[[2f32, 1.], [0., 3.]])))?;
+        assert_eq!(*result[0], tensor1(&[10i64, 20]));
+        assert_eq!(*result[1], tensor2(&[[2f32, 1.5], [0., 3.5]]));
+        Ok(())
+    }
+}
@@ -5,6 +5,10 @@ use std::iter;
 use tract_hir::internal::*;
 use tract_onnx_opl::ml::tree::*;
 
+use super::{
+    parse_class_data, post_transform, wire_argmax_labels, wire_post_transform, PostTransform,
+};
+
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("TreeEnsembleClassifier", tree_classifier);
 }
@@ -17,8 +21,7 @@ fn tree_classifier(
     let class_labels = parse_class_data(node)?;
     let base_class_score =
         get_vec_attr_opt::<f32>(node, "base_values", ensemble.n_classes())?.map(|t| rctensor1(&t));
-    let post_transform =
-        node.get_attr_opt("post_transform")?.map(parse_post_transform).transpose()?.unwrap_or(None);
+    let post_transform = post_transform(node)?;
 
     Ok((
         expand(TreeEnsembleClassifier { ensemble, class_labels, base_class_score, post_transform }),
@@ -26,24 +29,6 @@ fn tree_classifier(
     ))
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum PostTransform {
-    Softmax,
-    Logistic,
-    // SoftmaxZero,
-    // Probit, // probit, especially multinomial, is p.i.t.a. - so let's ignore it for now
-}
-
-pub fn parse_post_transform(s: &str) -> TractResult<Option<PostTransform>> {
-    match s {
-        "NONE" => Ok(None),
-        "SOFTMAX" => Ok(Some(PostTransform::Softmax)),
-        "LOGISTIC" => Ok(Some(PostTransform::Logistic)),
-        "PROBIT" | "SOFTMAX_ZERO" => bail!("PROBIT and SOFTMAX_ZERO unsupported"),
-        _ => bail!("Invalid post transform: {}", s),
-    }
-}
-
 fn parse_node_mode(s: &str) -> TractResult<Option<Cmp>> {
     match s {
         "BRANCH_LEQ" => Ok(Some(Cmp::LessEqual)),
@@ -57,7 +42,7 @@ fn parse_node_mode(s: &str) -> TractResult<Option<Cmp>> {
     }
 }
 
-fn get_vec_attr<'a, T>(node: &'a NodeProto, attr: &str, n: usize) -> TractResult<Vec<T>>
+pub(super) fn get_vec_attr<'a, T>(node: &'a NodeProto, attr: &str, n: usize) -> TractResult<Vec<T>>
 where
     T: AttrTVecType<'a>,
 {
@@ -66,7 +51,11 @@ where
     Ok(vec)
 }
 
-fn get_vec_attr_opt<'a, T>(node: &'a NodeProto, attr: &str, n: usize) -> TractResult<Option<Vec<T>>>
+pub(super) fn get_vec_attr_opt<'a, T>(
+    node: &'a NodeProto,
+    attr: &str,
+    n: usize,
+) -> TractResult<Option<Vec<T>>>
 where
     T: AttrTVecType<'a>,
 {
@@ -81,23 +70,7 @@ where
     }
 }
 
-fn parse_class_data(node: &NodeProto) -> TractResult<Arc<Tensor>> {
-    // parse n_classes from protobuf
-    let ints = node.get_attr_opt_slice::<i64>("classlabels_int64s")?;
-    let strs = node.get_attr_opt_tvec::<&str>("classlabels_strings")?;
-    match (ints, strs) {
-        (Some(n), None) => Ok(rctensor1(n)),
-        (None, Some(n)) => Ok(rctensor1(&n.iter().map(|d| d.to_string()).collect::<Vec<_>>())),
-        (None, None) => {
-            bail!("cannot find neither 'classlabels_int64s' not 'classlabels_strings'")
-        }
-        (Some(_), Some(_)) => {
-            bail!("only one of 'classlabels_int64s' and 'classlabels_strings' can be set")
-        }
-    }
-}
-
-fn parse_nodes_data(node: &NodeProto, is_classifier: bool) -> TractResult<TreeEnsemble> {
+pub(super) fn parse_nodes_data(node: &NodeProto, is_classifier: bool) -> TractResult<TreeEnsemble> {
     // parse n_classes from protobuf
     let n_classes = if is_classifier {
         let ints = node.get_attr_opt_slice::<i64>("classlabels_int64s")?;
@@ -140,7 +113,7 @@ fn parse_nodes_data(node: &NodeProto, is_classifier: bool) -> TractResult<TreeEn
     let aggregate_fn = parse_aggregate(if is_classifier {
         "SUM"
     } else {
-        node.get_attr_opt("aggregate")?.unwrap_or("SUM")
+        node.get_attr_opt("aggregate_function")?.unwrap_or("SUM")
     })?;
 
     // parse leaf data from protobuf
@@ -262,8 +235,6 @@ impl Expansion for TreeEnsembleClassifier {
         model: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        use tract_core::ops::nn::*;
-
         let mut scores = model.wire_node(
             format!("{}.classifier", prefix),
             tract_onnx_opl::ml::tree_ensemble_classifier::TreeEnsembleClassifier {
@@ -280,47 +251,9 @@ impl Expansion for TreeEnsembleClassifier {
                 &scores,
             )?;
         }
-        match self.post_transform {
-            None => (),
-            Some(PostTransform::Softmax) => {
-                scores = tract_hir::ops::nn::LayerSoftmax::new(1, false).wire(
-                    &format!("{}.softmax", prefix),
-                    model,
-                    &scores,
-                )?;
-            }
-            Some(PostTransform::Logistic) => {
-                scores = model.wire_node(
-                    &format!("{}.logistic", prefix),
-                    tract_core::ops::nn::sigmoid(),
-                    &scores,
-                )?;
-            }
-        }
-        let winners = model.wire_node(
-            format!("{}.argmax", prefix),
-            Reduce::new(tvec!(1), Reducer::ArgMax(false)),
-            &scores,
-        )?;
-        let reduced = model.wire_node(
-            format!("{}.rm_axis", prefix),
-            tract_core::ops::change_axes::AxisOp::Rm(1),
-            &winners,
-        )?;
-        let casted = model.wire_node(
-            format!("{}.casted", prefix),
-            tract_core::ops::cast::cast(i32::datum_type()),
-            &reduced,
-        )?;
-        let labels = model.wire_node(
-            format!("{}.labels", prefix),
-            tract_onnx_opl::ml::DirectLookup::new(
-                self.class_labels.clone(),
-                Tensor::zero_dt(self.class_labels.datum_type(), &[])?.into_arc_tensor(),
-            )?,
-            &casted,
-        )?[0];
-        Ok(tvec!(labels, scores[0]))
+        let scores = wire_post_transform(prefix, model, scores[0], self.post_transform)?;
+        let labels = wire_argmax_labels(prefix, model, scores, &self.class_labels)?;
+        Ok(tvec!(labels, scores))
     }
 
     fn nboutputs(&self) -> TractResult<usize> {
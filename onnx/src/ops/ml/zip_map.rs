@@ -0,0 +1,42 @@
+use crate::model::OnnxOpRegister;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::zip_map;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("ZipMap", |_, _| Ok((expand(ZipMap), vec![])));
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ZipMap;
+
+impl_dyn_hash!(ZipMap);
+
+impl Expansion for ZipMap {
+    fn name(&self) -> Cow<str> {
+        "ZipMap".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, zip_map::ZipMap, inputs)
+    }
+}
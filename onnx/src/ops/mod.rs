@@ -13,32 +13,42 @@ macro_rules! op_onnx {
 
 mod array;
 mod cast;
+mod control_flow;
+mod fft;
 mod logic;
 mod math;
 mod ml;
 mod nn;
 mod quant;
+mod random;
 pub mod rec;
 mod resize;
+mod text;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Cast", cast::cast);
     reg.insert("Constant", konst);
     reg.insert("Identity", |_, _| Ok((Box::new(ops::identity::Identity::default()), vec![])));
     reg.insert("Resize", resize::resize);
+    reg.insert("Upsample", resize::upsample);
     array::register_all_ops(reg);
+    control_flow::register_all_ops(reg);
+    fft::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
     ml::register_all_ops(reg);
     nn::register_all_ops(reg);
     quant::register_all_ops(reg);
+    random::register_all_ops(reg);
     rec::register_all_ops(reg);
+    text::register_all_ops(reg);
 }
 
 fn konst(
-    _ctx: &ParsingContext,
+    ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let v = node.get_attr::<Tensor>("value")?;
+    let v = node.get_attr::<&TensorProto>("value")?;
+    let v = crate::tensor::load_tensor(v, ctx.model_dir)?;
     Ok((Box::new(tract_hir::ops::konst::Const(v.into())), vec![]))
 }
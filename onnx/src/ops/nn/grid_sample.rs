@@ -0,0 +1,67 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_core::ops::nn::{GridPadding, Interpolation};
+use tract_hir::internal::*;
+
+pub fn grid_sample(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let interpolation = match node.get_attr_opt("mode")?.unwrap_or("bilinear") {
+        "bilinear" | "linear" => Interpolation::Bilinear,
+        "nearest" => Interpolation::Nearest,
+        mode => bail!("Unsupported GridSample mode: {}", mode),
+    };
+    let padding = match node.get_attr_opt("padding_mode")?.unwrap_or("zeros") {
+        "zeros" => GridPadding::Zeros,
+        "border" => GridPadding::Border,
+        "reflection" => GridPadding::Reflection,
+        mode => bail!("Unsupported GridSample padding mode: {}", mode),
+    };
+    let align_corners = node.get_attr_opt("align_corners")?.unwrap_or(false);
+    let op = tract_core::ops::nn::GridSample { interpolation, padding, align_corners };
+    Ok((expand(GridSample(op)), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct GridSample(tract_core::ops::nn::GridSample);
+
+impl_dyn_hash!(GridSample);
+
+impl Expansion for GridSample {
+    fn name(&self) -> Cow<str> {
+        "GridSample".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[1].shape[3], 2.to_dim())?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[2], &inputs[1].shape[1])?;
+        s.equals(&outputs[0].shape[3], &inputs[1].shape[2])?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
@@ -0,0 +1,365 @@
+use crate::model::ParsingContext;
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_hir::ops::nn::{Reduce, Reducer};
+
+pub fn layer_normalization(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(-1);
+    let epsilon = node.get_attr_opt("epsilon")?.unwrap_or(1e-5);
+    if node.output.iter().skip(1).any(|o| !o.is_empty()) {
+        bail!("LayerNormalization Mean and InvStdDev outputs are not supported")
+    }
+    let optional_bias_input = crate::model::optional_inputs(node).nth(2).unwrap();
+    Ok((expand(LayerNormalization::new(axis, epsilon, optional_bias_input)), vec![]))
+}
+
+pub fn mean_variance_normalization(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axes = node.get_attr_opt_vec("axes")?.unwrap_or_else(|| vec![0, 2, 3]);
+    Ok((expand(MeanVarianceNormalization::new(axes)), vec![]))
+}
+
+pub fn lp_normalization(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(-1);
+    let p = node.get_attr_opt("p")?.unwrap_or(2);
+    let reducer = match p {
+        1 => Reducer::L1,
+        2 => Reducer::L2,
+        _ => bail!("LpNormalization only supports p=1 or p=2, got {}", p),
+    };
+    Ok((expand(LpNormalization::new(axis, reducer)), vec![]))
+}
+
+fn wire_layer_norm(
+    prefix: &str,
+    model: &mut TypedModel,
+    axes: &[i64],
+    epsilon: f32,
+    input: OutletId,
+) -> TractResult<TVec<OutletId>> {
+    let rank = model.outlet_fact(input)?.rank();
+    let axes = axes
+        .iter()
+        .map(|&axis| if axis < 0 { axis + rank as i64 } else { axis } as usize)
+        .collect();
+    model.wire_node(prefix, tract_core::ops::nn::LayerNorm::new(axes, epsilon), &[input])
+}
+
+/// ONNX opset 17 `LayerNormalization`: normalizes over the axes from `axis` to the last one, then
+/// applies `Scale` and the optional `B`.
+#[derive(Debug, Clone, new, Educe)]
+#[educe(Hash)]
+pub struct LayerNormalization {
+    axis: i64,
+    #[educe(Hash(method = "hash_f32"))]
+    epsilon: f32,
+    optional_bias_input: Option<usize>,
+}
+
+impl_dyn_hash!(LayerNormalization);
+
+impl Expansion for LayerNormalization {
+    fn name(&self) -> Cow<str> {
+        "LayerNormalization".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2 + self.optional_bias_input.is_some() as usize)?;
+        check_output_arity(outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+        }
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = if self.axis < 0 { self.axis + rank } else { self.axis };
+        let axes: Vec<i64> = (axis..rank).collect();
+        let normed =
+            wire_layer_norm(&format!("{}.norm", prefix), model, &axes, self.epsilon, inputs[0])?;
+        let name = if self.optional_bias_input.is_some() {
+            format!("{}.scale", prefix)
+        } else {
+            prefix.to_string()
+        };
+        let mut wire = tract_core::ops::binary::wire_with_rank_broadcast(
+            &name,
+            model,
+            tract_hir::ops::math::mul::bin_typed(),
+            &[normed[0], inputs[1]],
+        )?;
+        if let Some(bias) = self.optional_bias_input {
+            wire = tract_core::ops::binary::wire_with_rank_broadcast(
+                prefix,
+                model,
+                tract_hir::ops::math::add::bin_typed(),
+                &[wire[0], inputs[bias]],
+            )?;
+        }
+        Ok(wire)
+    }
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct MeanVarianceNormalization {
+    axes: Vec<i64>,
+}
+
+impl_dyn_hash!(MeanVarianceNormalization);
+
+impl Expansion for MeanVarianceNormalization {
+    fn name(&self) -> Cow<str> {
+        "MeanVarianceNormalization".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        // the ONNX function body computes (X - mean) / (sqrt(variance) + 1e-9)
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let mean = Reduce::new(Some(self.axes.clone()), true, Reducer::Mean).wire(
+            &format!("{}.mean", prefix),
+            model,
+            inputs,
+        )?;
+        let diff = model.wire_node(
+            format!("{}.diff", prefix),
+            tract_hir::ops::math::sub::bin_typed(),
+            &[inputs[0], mean[0]],
+        )?;
+        let sqr_diff =
+            model.wire_node(format!("{}.sqr", prefix), tract_hir::ops::math::square(), &diff)?;
+        let variance = Reduce::new(Some(self.axes.clone()), true, Reducer::Mean).wire(
+            &format!("{}.variance", prefix),
+            model,
+            &sqr_diff,
+        )?;
+        let std_dev =
+            model.wire_node(format!("{}.sqrt", prefix), tract_hir::ops::math::sqrt(), &variance)?;
+        let epsilon = tensor0(1e-9f32).cast_to_dt(fact.datum_type)?.into_owned();
+        let std_dev_sane = model.wire_node(
+            format!("{}.epsilon", prefix),
+            tract_hir::ops::math::add::unary(
+                epsilon.broadcast_into_rank(fact.rank())?.into_arc_tensor(),
+            ),
+            &std_dev,
+        )?;
+        model.wire_node(prefix, tract_hir::ops::math::div::bin_typed(), &[diff[0], std_dev_sane[0]])
+    }
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct LpNormalization {
+    axis: i64,
+    reducer: Reducer,
+}
+
+impl_dyn_hash!(LpNormalization);
+
+impl Expansion for LpNormalization {
+    fn name(&self) -> Cow<str> {
+        "LpNormalization".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let norm = Reduce::new(Some(vec![self.axis]), true, self.reducer).wire(
+            &format!("{}.norm", prefix),
+            model,
+            inputs,
+        )?;
+        model.wire_node(prefix, tract_hir::ops::math::div::bin_typed(), &[inputs[0], norm[0]])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pb::tensor_proto::DataType;
+    use crate::pb::tensor_shape_proto::{dimension, Dimension};
+    use crate::pb::*;
+
+    fn node(op: &str, inputs: &[&str], output: &str) -> NodeProto {
+        NodeProto {
+            op_type: op.to_string(),
+            name: output.to_string(),
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: vec![output.to_string()],
+            ..NodeProto::default()
+        }
+    }
+
+    fn reduce_mean(input: &str, output: &str) -> NodeProto {
+        let mut node = node("ReduceMean", &[input], output);
+        node.attribute.push(AttributeProto {
+            name: "axes".to_string(),
+            r#type: attribute_proto::AttributeType::Ints as i32,
+            ints: vec![-1],
+            ..AttributeProto::default()
+        });
+        node
+    }
+
+    fn scalar(name: &str, value: f32) -> TensorProto {
+        TensorProto {
+            name: name.to_string(),
+            data_type: DataType::Float as i32,
+            float_data: vec![value],
+            ..TensorProto::default()
+        }
+    }
+
+    fn value_info(name: &str, shape: &[i64]) -> ValueInfoProto {
+        let dim = shape
+            .iter()
+            .map(|&d| Dimension {
+                value: Some(dimension::Value::DimValue(d)),
+                ..Dimension::default()
+            })
+            .collect();
+        let tensor = type_proto::Tensor {
+            elem_type: DataType::Float as i32,
+            shape: Some(TensorShapeProto { dim }),
+        };
+        ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(tensor)),
+                ..TypeProto::default()
+            }),
+            ..ValueInfoProto::default()
+        }
+    }
+
+    // layer normalization as exported by frameworks lacking the LayerNormalization operator
+    #[test]
+    fn expanded_layer_normalization_is_fused() -> TractResult<()> {
+        let graph = GraphProto {
+            node: vec![
+                reduce_mean("x", "mean"),
+                node("Sub", &["x", "mean"], "centered"),
+                node("Pow", &["centered", "two"], "square"),
+                reduce_mean("square", "var"),
+                node("Add", &["var", "eps"], "var_eps"),
+                node("Sqrt", &["var_eps"], "std"),
+                node("Div", &["centered", "std"], "y"),
+            ],
+            initializer: vec![scalar("two", 2.0), scalar("eps", 1e-5)],
+            input: vec![value_info("x", &[2, 4])],
+            output: vec![value_info("y", &[2, 4])],
+            ..GraphProto::default()
+        };
+        let proto = ModelProto { graph: Some(graph), ..ModelProto::default() };
+        let model = crate::onnx().model_for_proto_model(&proto)?.into_typed()?.declutter()?;
+        let op = model
+            .nodes()
+            .iter()
+            .find_map(|n| n.op_as::<tract_hir::tract_core::ops::nn::LayerNorm>())
+            .context("no LayerNorm after declutter")?;
+        assert_eq!(op.axes, tvec!(1));
+        assert_eq!(op.epsilon, 1e-5);
+        let input = tensor2(&[[1f32, 2., 3., 4.], [0., 0., 2., 2.]]);
+        let result = SimplePlan::new(model)?.run(tvec!(input))?;
+        let (a, b) = (1.341635f32, 0.447212f32);
+        result[0].close_enough(&tensor2(&[[-a, -b, b, a], [-1f32, -1., 1., 1.]]), true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn layer_normalization_scale_bias() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [2, 2]))?;
+        let scale = model.add_const("scale", tensor1(&[1f32, 2.]))?;
+        let bias = model.add_const("bias", tensor1(&[0f32, 1.]))?;
+        let op = LayerNormalization::new(-1, 1e-5, Some(2));
+        let y = model.wire_node("ln", expand(op), &[x, scale, bias])?;
+        model.set_output_outlets(&y)?;
+        let result =
+            SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor2(&[[1f32, 3.], [5., 5.]])))?;
+        result[0].close_enough(&tensor2(&[[-1f32, 3.], [0., 1.]]), true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn mean_variance_normalization_epsilon() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [1, 2]))?;
+        let y = model.wire_node("mvn", expand(MeanVarianceNormalization::new(vec![1])), &[x])?;
+        model.set_output_outlets(&y)?;
+        // std dev equals epsilon: the output is halved, not scaled down by sqrt(epsilon)
+        let result = SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor2(&[[0f32, 2e-9]])))?;
+        result[0].close_enough(&tensor2(&[[-0.5f32, 0.5]]), true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn lp_normalization() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [2, 2]))?;
+        let y = model.wire_node("lp", expand(LpNormalization::new(1, Reducer::L2)), &[x])?;
+        model.set_output_outlets(&y)?;
+        let result =
+            SimplePlan::new(model.into_typed()?)?.run(tvec!(tensor2(&[[3f32, 4.], [0., 2.]])))?;
+        result[0].close_enough(&tensor2(&[[0.6f32, 0.8], [0., 1.]]), true)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_hir::internal::*;
+
+pub fn max_unpool(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let kernel_shape: TVec<usize> = node.get_attr_tvec("kernel_shape")?;
+    let rank = kernel_shape.len();
+    let strides = node.get_attr_opt_tvec("strides")?.unwrap_or_else(|| tvec!(1; rank));
+    let pads: TVec<usize> = node.get_attr_opt_tvec("pads")?.unwrap_or_else(|| tvec!(0; 2 * rank));
+    let pads = (0..rank).map(|ix| (pads[ix], pads[rank + ix])).collect();
+    let op = tract_core::ops::cnn::MaxUnpool { kernel_shape, strides, pads };
+    Ok((expand(MaxUnpool { op, has_output_shape: node.input.len() == 3 }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct MaxUnpool {
+    op: tract_core::ops::cnn::MaxUnpool,
+    has_output_shape: bool,
+}
+
+impl_dyn_hash!(MaxUnpool);
+
+impl Expansion for MaxUnpool {
+    fn name(&self) -> Cow<str> {
+        "MaxUnpool".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2 + self.has_output_shape as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &inputs[1].shape)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].rank, self.op.kernel_shape.len() as i64 + 2)?;
+        if self.has_output_shape {
+            s.given(&inputs[2].value, move |s, shape| {
+                let shape = shape.cast_to::<TDim>()?;
+                s.equals(&outputs[0].shape, ShapeFactoid::from(shape.as_slice::<TDim>()?))
+            })
+        } else {
+            s.given(&inputs[0].shape, move |s, shape| {
+                let fact = TypedFact::dt_shape(f32::datum_type(), shape.clone());
+                let output = self.op.output_facts(&[&fact, &fact])?;
+                s.equals(&outputs[0].shape, ShapeFactoid::from(output[0].shape.to_tvec()))
+            })
+        }
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.op.clone(), inputs)
+    }
+}
@@ -9,9 +9,14 @@ use crate::pb_helpers::OptionExt;
 mod batch_norm;
 mod conv_transpose;
 mod dropout;
+mod grid_sample;
 mod instance_norm;
+mod layer_norm;
 mod lrn;
+mod max_unpool;
+mod non_max_suppression;
 mod reduce;
+mod roi_align;
 
 pub fn arg_max_min(
     _ctx: &ParsingContext,
@@ -41,13 +46,19 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("GlobalAveragePool", |_, _| Ok((expand(ops::nn::GlobalAvgPool), vec![])));
     reg.insert("GlobalLpPool", global_lp_pool);
     reg.insert("GlobalMaxPool", |_, _| Ok((expand(ops::nn::GlobalMaxPool), vec![])));
+    reg.insert("GridSample", grid_sample::grid_sample);
     reg.insert("Hardmax", layer_hard_max);
     reg.insert("HardSigmoid", hard_sigmoid);
     reg.insert("InstanceNormalization", instance_norm::instance_normalization);
+    reg.insert("LayerNormalization", layer_norm::layer_normalization);
     reg.insert("LeakyRelu", leaky_relu);
     reg.insert("LogSoftmax", layer_log_soft_max);
+    reg.insert("LpNormalization", layer_norm::lp_normalization);
     reg.insert("LRN", lrn::lrn);
     reg.insert("MaxPool", max_pool);
+    reg.insert("MaxUnpool", max_unpool::max_unpool);
+    reg.insert("MeanVarianceNormalization", layer_norm::mean_variance_normalization);
+    reg.insert("NonMaxSuppression", non_max_suppression::non_max_suppression);
     reg.insert("ParametricSoftplus", parametric_softplus);
     reg.insert("QLinearConv", conv_qlinear);
     reg.insert("PRelu", |_, _| Ok((expand(Prelu), vec![])));
@@ -62,6 +73,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("ReduceSum", |c, node| reduce::reduce(c, node, nn::Reducer::Sum));
     reg.insert("ReduceSumSquare", |c, node| reduce::reduce(c, node, nn::Reducer::SumSquare));
     reg.insert("Relu", |_, _| Ok((expand(ops::activations::Clip::new(Some(0.0), None)), vec![])));
+    reg.insert("RoiAlign", roi_align::roi_align);
     reg.insert("ScaledTanh", scaled_tanh);
     reg.insert("Shrink", shrink);
     reg.insert("ThresholdedRelu", thresholded_relu);
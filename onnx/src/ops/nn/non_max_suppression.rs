@@ -0,0 +1,119 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_core::ops::nn::BoxRepr;
+use tract_hir::internal::*;
+
+pub fn non_max_suppression(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let center_point_box = node.get_attr_opt("center_point_box")?.unwrap_or(0i64) != 0;
+    let mut options = crate::model::optional_inputs(node).skip(2);
+    let op = NonMaxSuppression {
+        center_point_box,
+        optional_max_output_boxes_per_class_input: options.next().unwrap(),
+        optional_iou_threshold_input: options.next().unwrap(),
+        optional_score_threshold_input: options.next().unwrap(),
+    };
+    Ok((expand(op), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct NonMaxSuppression {
+    center_point_box: bool,
+    optional_max_output_boxes_per_class_input: Option<usize>,
+    optional_iou_threshold_input: Option<usize>,
+    optional_score_threshold_input: Option<usize>,
+}
+
+impl_dyn_hash!(NonMaxSuppression);
+
+impl Expansion for NonMaxSuppression {
+    fn name(&self) -> Cow<str> {
+        "NonMaxSuppression".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(
+            inputs,
+            2 + self.optional_max_output_boxes_per_class_input.is_some() as usize
+                + self.optional_iou_threshold_input.is_some() as usize
+                + self.optional_score_threshold_input.is_some() as usize,
+        )?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[0].shape[2], 4.to_dim())?;
+        s.equals(&inputs[1].rank, 3)?;
+        s.equals(&inputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&inputs[0].shape[1], &inputs[1].shape[2])?;
+        s.equals(&outputs[0].datum_type, i64::datum_type())?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[1], 3.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut optional = |input: Option<usize>, name: &str, default: Tensor| {
+            if let Some(ix) = input {
+                Ok(inputs[ix])
+            } else {
+                model.add_const(format!("{}.{}", prefix, name), default)
+            }
+        };
+        let max = optional(
+            self.optional_max_output_boxes_per_class_input,
+            "max_output_boxes_per_class",
+            tensor0(0i64),
+        )?;
+        let iou = optional(self.optional_iou_threshold_input, "iou_threshold", tensor0(0f32))?;
+        let score = optional(
+            self.optional_score_threshold_input,
+            "score_threshold",
+            tensor0(f32::NEG_INFINITY),
+        )?;
+        let box_repr =
+            if self.center_point_box { BoxRepr::CenterWidthHeight } else { BoxRepr::TwoPoints };
+        let op = tract_core::ops::nn::NonMaxSuppression::new(box_repr);
+        model.wire_node(prefix, op, &[inputs[0], inputs[1], max, iou, score])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_thresholds() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let boxes =
+            model.add_source("boxes", InferenceFact::dt_shape(f32::datum_type(), [1, 3, 4]))?;
+        let scores =
+            model.add_source("scores", InferenceFact::dt_shape(f32::datum_type(), [1, 1, 3]))?;
+        let max = model.add_const("max", tensor1(&[2i64]))?;
+        let op = NonMaxSuppression {
+            center_point_box: false,
+            optional_max_output_boxes_per_class_input: Some(2),
+            optional_iou_threshold_input: None,
+            optional_score_threshold_input: None,
+        };
+        let selected = model.wire_node("nms", expand(op), &[boxes, scores, max])?;
+        model.set_output_outlets(&selected)?;
+        let boxes = tensor3(&[[[0f32, 0., 1., 1.], [2., 2., 3., 3.], [0., 0., 1., 1.]]]);
+        let scores = tensor3(&[[[0.1f32, 0.2, 0.3]]]);
+        let result = SimplePlan::new(model.into_typed()?)?.run(tvec!(boxes, scores))?;
+        assert_eq!(*result[0], tensor2(&[[0i64, 0, 2], [0, 0, 1]]));
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_core::ops::nn::RoiPooling;
+use tract_hir::internal::*;
+
+pub fn roi_align(
+    ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let pooling = match node.get_attr_opt("mode")?.unwrap_or("avg") {
+        "avg" => RoiPooling::Avg,
+        "max" => RoiPooling::Max,
+        mode => bail!("Unsupported RoiAlign mode: {}", mode),
+    };
+    // RoiAlign-10 behaves as "output_half_pixel"
+    let default_mode =
+        if ctx.onnx_operator_set_version < 16 { "output_half_pixel" } else { "half_pixel" };
+    let half_pixel =
+        match node.get_attr_opt("coordinate_transformation_mode")?.unwrap_or(default_mode) {
+            "half_pixel" => true,
+            "output_half_pixel" => false,
+            mode => bail!("Unsupported RoiAlign coordinate transformation mode: {}", mode),
+        };
+    let op = tract_core::ops::nn::RoiAlign {
+        pooling,
+        output_height: node.get_attr_opt("output_height")?.unwrap_or(1),
+        output_width: node.get_attr_opt("output_width")?.unwrap_or(1),
+        sampling_ratio: node.get_attr_opt("sampling_ratio")?.unwrap_or(0),
+        spatial_scale: node.get_attr_opt("spatial_scale")?.unwrap_or(1.0),
+        half_pixel,
+    };
+    Ok((expand(RoiAlign(op)), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct RoiAlign(tract_core::ops::nn::RoiAlign);
+
+impl_dyn_hash!(RoiAlign);
+
+impl Expansion for RoiAlign {
+    fn name(&self) -> Cow<str> {
+        "RoiAlign".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 3)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&inputs[2].rank, 1)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[1].shape[1], 4.to_dim())?;
+        s.equals(&inputs[1].shape[0], &inputs[2].shape[0])?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[2], self.0.output_height.to_dim())?;
+        s.equals(&outputs[0].shape[3], self.0.output_width.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
@@ -0,0 +1,344 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_core::ops::random::{stream_for_name, Distribution};
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("RandomNormal", random_normal);
+    reg.insert("RandomNormalLike", random_normal_like);
+    reg.insert("RandomUniform", random_uniform);
+    reg.insert("RandomUniformLike", random_uniform_like);
+    reg.insert("Bernoulli", bernoulli);
+    reg.insert("Multinomial", multinomial);
+}
+
+/// ONNX seeds are floats: their bits make the generator seed.
+fn seed(node: &NodeProto) -> TractResult<Option<(u64, u64)>> {
+    Ok(node.get_attr_opt::<f32>("seed")?.map(|s| (s.to_bits() as u64, 0)))
+}
+
+fn validation(seed: Option<(u64, u64)>) -> Validation {
+    if seed.is_some() {
+        Validation::Accurate
+    } else {
+        Validation::Random
+    }
+}
+
+fn normal(node: &NodeProto) -> TractResult<Distribution> {
+    let mean = node.get_attr_opt("mean")?.unwrap_or(0f32);
+    let dev = node.get_attr_opt("scale")?.unwrap_or(1f32);
+    Ok(Distribution::Normal { mean: rctensor0(mean), dev: rctensor0(dev) })
+}
+
+fn uniform(node: &NodeProto) -> TractResult<Distribution> {
+    let low = node.get_attr_opt("low")?.unwrap_or(0f32);
+    let high = node.get_attr_opt("high")?.unwrap_or(1f32);
+    Ok(Distribution::Uniform { low: rctensor0(low), high: rctensor0(high) })
+}
+
+fn random(node: &NodeProto, dist: Distribution) -> TractResult<Box<dyn InferenceOp>> {
+    let datum_type = node.get_attr_opt("dtype")?.unwrap_or(DatumType::F32);
+    let shape = node.get_attr_vec::<i64>("shape")?.into_iter().map(|d| d.to_dim()).collect();
+    let seed = seed(node)?;
+    let op = tract_core::ops::random::Random { datum_type, shape, dist, seed, stream: 0 };
+    Ok(expand(Random(op)))
+}
+
+fn random_like(node: &NodeProto, dist: Distribution) -> TractResult<Box<dyn InferenceOp>> {
+    let datum_type = node.get_attr_opt("dtype")?;
+    Ok(expand(RandomLike { datum_type, dist, seed: seed(node)? }))
+}
+
+fn random_normal(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    Ok((random(node, normal(node)?)?, vec![]))
+}
+
+fn random_uniform(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    Ok((random(node, uniform(node)?)?, vec![]))
+}
+
+fn random_normal_like(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    Ok((random_like(node, normal(node)?)?, vec![]))
+}
+
+fn random_uniform_like(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    Ok((random_like(node, uniform(node)?)?, vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Random(tract_core::ops::random::Random);
+
+impl_dyn_hash!(Random);
+
+impl Expansion for Random {
+    fn name(&self) -> Cow<str> {
+        "Random".into()
+    }
+
+    op_onnx!();
+
+    fn validation(&self) -> Validation {
+        validation(self.0.seed)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 0)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, self.0.datum_type)?;
+        s.equals(&outputs[0].shape, self.0.shape.iter().cloned().collect::<ShapeFactoid>())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        _inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let op =
+            tract_core::ops::random::Random { stream: stream_for_name(prefix), ..self.0.clone() };
+        model.wire_node(prefix, op, &[])
+    }
+}
+
+/// Random values with the shape, and by default the type, of the input.
+#[derive(Debug, Clone, Hash)]
+struct RandomLike {
+    datum_type: Option<DatumType>,
+    dist: Distribution,
+    seed: Option<(u64, u64)>,
+}
+
+impl_dyn_hash!(RandomLike);
+
+impl Expansion for RandomLike {
+    fn name(&self) -> Cow<str> {
+        "RandomLike".into()
+    }
+
+    op_onnx!();
+
+    fn validation(&self) -> Validation {
+        validation(self.seed)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        if let Some(dt) = self.datum_type {
+            s.equals(&outputs[0].datum_type, dt)?;
+        } else {
+            s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        }
+        s.equals(&outputs[0].shape, &inputs[0].shape)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let op = tract_core::ops::random::Random {
+            datum_type: self.datum_type.unwrap_or(fact.datum_type),
+            shape: fact.shape.to_tvec(),
+            dist: self.dist.clone(),
+            seed: self.seed,
+            stream: stream_for_name(prefix),
+        };
+        model.wire_node(prefix, op, inputs)
+    }
+}
+
+fn bernoulli(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let datum_type = node.get_attr_opt("dtype")?;
+    Ok((expand(Bernoulli { datum_type, seed: seed(node)? }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Bernoulli {
+    datum_type: Option<DatumType>,
+    seed: Option<(u64, u64)>,
+}
+
+impl_dyn_hash!(Bernoulli);
+
+impl Expansion for Bernoulli {
+    fn name(&self) -> Cow<str> {
+        "Bernoulli".into()
+    }
+
+    op_onnx!();
+
+    fn validation(&self) -> Validation {
+        validation(self.seed)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        if let Some(dt) = self.datum_type {
+            s.equals(&outputs[0].datum_type, dt)?;
+        } else {
+            s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        }
+        s.equals(&outputs[0].shape, &inputs[0].shape)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let datum_type = self.datum_type.unwrap_or(model.outlet_fact(inputs[0])?.datum_type);
+        let op = tract_core::ops::random::Bernoulli {
+            datum_type,
+            seed: self.seed,
+            stream: stream_for_name(prefix),
+        };
+        model.wire_node(prefix, op, inputs)
+    }
+}
+
+fn multinomial(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let datum_type = node.get_attr_opt("dtype")?.unwrap_or(DatumType::I32);
+    let sample_size = node.get_attr_opt("sample_size")?.unwrap_or(1usize);
+    let op = tract_core::ops::random::Multinomial {
+        datum_type,
+        sample_size,
+        seed: seed(node)?,
+        stream: 0,
+    };
+    Ok((expand(Multinomial(op)), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Multinomial(tract_core::ops::random::Multinomial);
+
+impl_dyn_hash!(Multinomial);
+
+impl Expansion for Multinomial {
+    fn name(&self) -> Cow<str> {
+        "Multinomial".into()
+    }
+
+    op_onnx!();
+
+    fn validation(&self) -> Validation {
+        validation(self.0.seed)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].datum_type, self.0.datum_type)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], self.0.sample_size.to_dim())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let op = tract_core::ops::random::Multinomial {
+            stream: stream_for_name(prefix),
+            ..self.0.clone()
+        };
+        model.wire_node(prefix, op, inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn random_normal_like_is_reproducible() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let input =
+            model.add_source("input", InferenceFact::dt_shape(f32::datum_type(), [2, 3]))?;
+        let op = RandomLike {
+            datum_type: None,
+            dist: Distribution::Normal { mean: rctensor0(0f32), dev: rctensor0(1f32) },
+            seed: None,
+        };
+        let random = model.wire_node("random", expand(op), &[input])?;
+        model.set_output_outlets(&random)?;
+        let plan = SimplePlan::new(model.into_typed()?)?;
+        let mut state = SimpleState::new(&plan)?;
+        state.set_random_seed(7)?;
+        let first = state.run(tvec!(Tensor::zero::<f32>(&[2, 3])?))?;
+        assert_eq!(first[0].shape(), &[2, 3]);
+        state.set_random_seed(7)?;
+        assert_eq!(state.run(tvec!(Tensor::zero::<f32>(&[2, 3])?))?, first);
+        Ok(())
+    }
+
+    #[test]
+    fn random_uniform_like_runtime_shape() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let bounds = ["start", "limit", "delta"]
+            .iter()
+            .map(|name| model.add_source(*name, TypedFact::dt_scalar(i64::datum_type())))
+            .collect::<TractResult<TVec<_>>>()?;
+        let range = model.wire_node("range", tract_core::ops::array::Range::default(), &bounds)?;
+        let op = RandomLike {
+            datum_type: Some(f32::datum_type()),
+            dist: Distribution::Uniform { low: rctensor0(0f32), high: rctensor0(1f32) },
+            seed: None,
+        };
+        let random = op.wire("random", &mut model, &range)?;
+        model.set_output_outlets(&random)?;
+        let result = SimplePlan::new(model.into_optimized()?)?.run(tvec!(
+            tensor0(0i64),
+            tensor0(5i64),
+            tensor0(1i64)
+        ))?;
+        assert_eq!(result[0].shape(), &[5]);
+        Ok(())
+    }
+}
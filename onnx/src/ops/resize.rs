@@ -1,83 +1,165 @@
-use crate::model::ParsingContext;
+use crate::model::{optional_inputs, ParsingContext};
 use crate::pb::*;
-use std::hash::Hash;
 use tract_hir::internal::*;
+use tract_ndarray::Axis;
 
 pub fn resize(
-    _ctx: &ParsingContext,
+    ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let interpolator = match node.get_attr_opt("mode")?.unwrap_or("nearest") {
+        "nearest" => Interpolator::Nearest,
+        "linear" => Interpolator::Linear,
+        "cubic" => Interpolator::Cubic,
+        s => bail!("Unsupported Resize mode: {}", s),
+    };
+    if ctx.onnx_operator_set_version < 11 {
+        // Resize-10 only takes scales, and behaves like Upsample
+        return Ok((Box::new(Resize::legacy(interpolator, None, Some(1))), vec![]));
+    }
     let coord_transformer =
         match node.get_attr_opt("coordinate_transformation_mode")?.unwrap_or("half_pixel") {
-            "align_corners" => CoordTransformer::AlignCorners,
             "half_pixel" => CoordTransformer::HalfPixel,
-            s => todo!("coordinate_transformation_mode: {}", s),
+            "pytorch_half_pixel" => CoordTransformer::PytorchHalfPixel,
+            "align_corners" => CoordTransformer::AlignCorners,
+            "asymmetric" => CoordTransformer::Asymmetric,
+            "tf_half_pixel_for_nn" => CoordTransformer::TfHalfPixelForNn,
+            "tf_crop_and_resize" => CoordTransformer::TfCropAndResize,
+            s => bail!("Unsupported Resize coordinate_transformation_mode: {}", s),
         };
-    let interpolator = match node.get_attr("mode")? {
-        "linear" => Interpolator::Linear,
-        s => todo!("mode: {}", s),
-    };
     let nearest = match node.get_attr_opt("nearest_mode")?.unwrap_or("round_prefer_floor") {
-        "floor" => Nearest::Floor,
         "round_prefer_floor" => Nearest::RoundPreferFloor,
-        s => todo!("nearest_mode: {}", s),
+        "round_prefer_ceil" => Nearest::RoundPreferCeil,
+        "floor" => Nearest::Floor,
+        "ceil" => Nearest::Ceil,
+        s => bail!("Unsupported Resize nearest_mode: {}", s),
     };
-    let mut options = crate::model::optional_inputs(node).skip(2);
+    let mut options = optional_inputs(node).skip(1);
     Ok((
         Box::new(Resize {
-            optional_scales_input: options.next().unwrap(),
-            optional_sizes_input: options.next().unwrap(),
             coord_transformer,
             interpolator,
             nearest,
+            cubic_coeff_a: node.get_attr_opt("cubic_coeff_a")?.unwrap_or(-0.75),
+            exclude_outside: node.get_attr_opt("exclude_outside")?.unwrap_or(false),
+            extrapolation_value: node.get_attr_opt("extrapolation_value")?.unwrap_or(0.0),
+            static_scales: None,
+            optional_roi_input: options.next().unwrap(),
+            optional_scales_input: options.next().unwrap(),
+            optional_sizes_input: options.next().unwrap(),
+            runtime_output_shape: tvec!(),
         }),
         vec![],
     ))
 }
 
-#[derive(Clone, Debug, Hash)]
+pub fn upsample(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let interpolator = match node.get_attr_opt("mode")?.unwrap_or("nearest") {
+        "nearest" => Interpolator::Nearest,
+        "linear" | "bilinear" => Interpolator::Linear,
+        s => bail!("Unsupported Upsample mode: {}", s),
+    };
+    // Upsample-7 has its scales as an attribute, later versions as an input
+    let op = if let Some(scales) = node.get_attr_opt_vec::<f32>("scales")? {
+        Resize::legacy(interpolator, Some(rctensor1(&scales)), None)
+    } else {
+        Resize::legacy(interpolator, None, Some(1))
+    };
+    Ok((Box::new(op), vec![]))
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
 enum CoordTransformer {
     HalfPixel,
+    PytorchHalfPixel,
     AlignCorners,
+    Asymmetric,
+    TfHalfPixelForNn,
+    TfCropAndResize,
 }
 
 impl CoordTransformer {
-    fn transform(&self, x_out: usize, scale: f32, len_in: usize, len_out: usize) -> f32 {
+    fn transform(
+        &self,
+        x_out: usize,
+        scale: f32,
+        len_in: usize,
+        len_out: usize,
+        roi: (f32, f32),
+    ) -> f32 {
+        let x = x_out as f32;
         match self {
-            CoordTransformer::HalfPixel => (x_out as f32 + 0.5) * scale - 0.5,
-            CoordTransformer::AlignCorners => {
-                (x_out as f32 * (len_in as f32 - 1.0)) / (len_out as f32 - 1.0)
+            CoordTransformer::HalfPixel => (x + 0.5) / scale - 0.5,
+            CoordTransformer::PytorchHalfPixel if len_out > 1 => (x + 0.5) / scale - 0.5,
+            CoordTransformer::PytorchHalfPixel => 0.0,
+            CoordTransformer::AlignCorners if len_out > 1 => {
+                x * (len_in as f32 - 1.0) / (len_out as f32 - 1.0)
+            }
+            CoordTransformer::AlignCorners => 0.0,
+            CoordTransformer::Asymmetric => x / scale,
+            CoordTransformer::TfHalfPixelForNn => (x + 0.5) / scale,
+            CoordTransformer::TfCropAndResize if len_out > 1 => {
+                roi.0 * (len_in as f32 - 1.0)
+                    + x * (roi.1 - roi.0) * (len_in as f32 - 1.0) / (len_out as f32 - 1.0)
             }
+            CoordTransformer::TfCropAndResize => 0.5 * (roi.0 + roi.1) * (len_in as f32 - 1.0),
         }
     }
 }
 
-#[derive(Clone, Debug, Hash)]
+#[derive(Clone, Debug, Hash, PartialEq)]
 enum Interpolator {
+    Nearest,
     Linear,
+    Cubic,
+}
+
+#[derive(Clone, Debug, Hash)]
+enum Nearest {
+    RoundPreferFloor,
+    RoundPreferCeil,
+    Floor,
+    Ceil,
 }
 
-impl Interpolator {
-    fn interpolate(&self, y_left: f32, y_right: f32, x_ratio: f32) -> f32 {
+impl Nearest {
+    fn round(&self, x: f32) -> f32 {
         match self {
-            Interpolator::Linear => y_left * (1.0 - x_ratio) + y_right * x_ratio,
+            Nearest::RoundPreferFloor if x == x.floor() + 0.5 => x.floor(),
+            Nearest::RoundPreferCeil if x == x.floor() + 0.5 => x.ceil(),
+            Nearest::RoundPreferFloor | Nearest::RoundPreferCeil => x.round(),
+            Nearest::Floor => x.floor(),
+            Nearest::Ceil => x.ceil(),
         }
     }
 }
 
-#[derive(Clone, Debug, Hash)]
-enum Nearest {
-    Floor,
-    RoundPreferFloor,
+fn cubic_coeffs(s: f32, a: f32) -> [f32; 4] {
+    let far = |x: f32| ((a * x - 5.0 * a) * x + 8.0 * a) * x - 4.0 * a;
+    let near = |x: f32| ((a + 2.0) * x - (a + 3.0)) * x * x + 1.0;
+    [far(s + 1.0), near(s), near(1.0 - s), far(2.0 - s)]
 }
 
-#[derive(Clone, new, Debug, Hash)]
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
 struct Resize {
     coord_transformer: CoordTransformer,
     interpolator: Interpolator,
     nearest: Nearest,
+    #[educe(Hash(method = "hash_f32"))]
+    cubic_coeff_a: f32,
+    exclude_outside: bool,
+    #[educe(Hash(method = "hash_f32"))]
+    extrapolation_value: f32,
+    static_scales: Option<Arc<Tensor>>,
+    optional_roi_input: Option<usize>,
     optional_scales_input: Option<usize>,
     optional_sizes_input: Option<usize>,
+    // output dimensions when neither scales nor sizes are known before running
+    runtime_output_shape: TVec<TDim>,
 }
 
 impl_dyn_hash!(Resize);
@@ -92,29 +174,163 @@ impl Op for Resize {
 }
 
 impl Resize {
+    fn legacy(
+        interpolator: Interpolator,
+        static_scales: Option<Arc<Tensor>>,
+        optional_scales_input: Option<usize>,
+    ) -> Resize {
+        Resize {
+            coord_transformer: CoordTransformer::Asymmetric,
+            interpolator,
+            nearest: Nearest::Floor,
+            cubic_coeff_a: -0.75,
+            exclude_outside: false,
+            extrapolation_value: 0.0,
+            static_scales,
+            optional_roi_input: None,
+            optional_scales_input,
+            optional_sizes_input: None,
+            runtime_output_shape: tvec!(),
+        }
+    }
+
+    /// The constant scales and sizes, if one of them is enough to compute the output shape.
+    fn known_output_shape_inputs<'a>(
+        &'a self,
+        inputs: &[&'a TypedFact],
+    ) -> Option<(Option<&'a Tensor>, Option<&'a Tensor>)> {
+        let konst =
+            |ix: Option<usize>| ix.and_then(|ix| inputs.get(ix)).and_then(|f| f.konst.as_deref());
+        let scales = self.static_scales.as_deref().or_else(|| konst(self.optional_scales_input));
+        let sizes = konst(self.optional_sizes_input);
+        let rank = inputs[0].rank();
+        if scales.map(|s| s.len() == rank).unwrap_or(false)
+            || sizes.map(|s| s.len() == rank).unwrap_or(false)
+        {
+            Some((scales, sizes))
+        } else {
+            None
+        }
+    }
+
     fn compute_output_shape(
         &self,
-        input_shape: &[usize],
+        input_shape: &[TDim],
         input_scale: Option<&Tensor>,
         input_sizes: Option<&Tensor>,
-    ) -> TractResult<TVec<usize>> {
-        if let Some(scale) = input_scale {
-            if scale.len() == input_shape.len() {
-                let scales = scale.cast_to::<f32>()?;
-                return Ok(input_shape
-                    .iter()
-                    .zip(scales.as_slice::<f32>()?.iter())
-                    .map(|(input, scale)| ((*input as f32) * scale) as usize)
-                    .collect());
-            }
+    ) -> TractResult<TVec<TDim>> {
+        if let Some(scale) = input_scale.filter(|s| s.len() == input_shape.len()) {
+            let scales = scale.cast_to::<f32>()?;
+            return input_shape
+                .iter()
+                .zip(scales.as_slice::<f32>()?.iter())
+                .map(|(input, scale)| {
+                    if let Ok(input) = input.to_i64() {
+                        Ok((((input as f32) * scale) as i64).to_dim())
+                    } else if scale.fract() == 0.0 {
+                        Ok(input.clone() * (*scale as i64))
+                    } else {
+                        bail!("Can not scale symbolic dimension {} by {}", input, scale)
+                    }
+                })
+                .collect();
+        }
+        if let Some(sizes) = input_sizes.filter(|s| s.len() == input_shape.len()) {
+            let sizes = sizes.cast_to::<TDim>()?;
+            return Ok(sizes.as_slice::<TDim>()?.iter().cloned().collect());
+        }
+        bail!(
+            "Neither shape not scale makes sense: input_shape: {:?}, scale: {:?}, sizes: {:?}",
+            input_shape,
+            input_scale,
+            input_sizes
+        )
+    }
+
+    /// For each output position along an axis, the input positions and their weights, or None
+    /// for extrapolated values.
+    fn taps(
+        &self,
+        len_in: usize,
+        len_out: usize,
+        scale: f32,
+        roi: (f32, f32),
+    ) -> Vec<Option<TVec<(usize, f32)>>> {
+        let last = len_in as f32 - 1.0;
+        let clamp = |x: isize| x.max(0).min(len_in as isize - 1) as usize;
+        (0..len_out)
+            .map(|x_out| {
+                let x = self.coord_transformer.transform(x_out, scale, len_in, len_out, roi);
+                if self.coord_transformer == CoordTransformer::TfCropAndResize
+                    && (x < 0.0 || x > last)
+                {
+                    return None;
+                }
+                Some(match self.interpolator {
+                    Interpolator::Nearest => tvec!((clamp(self.nearest.round(x) as isize), 1.0)),
+                    Interpolator::Linear => {
+                        let x = x.max(0.0).min(last);
+                        let left = x.floor();
+                        let frac = x - left;
+                        let left = left as usize;
+                        tvec!((left, 1.0 - frac), ((left + 1).min(len_in - 1), frac))
+                    }
+                    Interpolator::Cubic => {
+                        let base = x.floor();
+                        let coeffs = cubic_coeffs(x - base, self.cubic_coeff_a);
+                        let mut taps: TVec<(isize, f32)> =
+                            (0..4).map(|i| (base as isize - 1 + i, coeffs[i as usize])).collect();
+                        if self.exclude_outside {
+                            taps.retain(|(ix, _)| *ix >= 0 && *ix < len_in as isize);
+                            let sum: f32 = taps.iter().map(|(_, w)| w).sum();
+                            taps.iter_mut().for_each(|(_, w)| *w /= sum);
+                        }
+                        taps.into_iter().map(|(ix, w)| (clamp(ix), w)).collect()
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn resize_axis(
+        &self,
+        data: Tensor,
+        axis: usize,
+        len_out: usize,
+        scale: f32,
+        roi: (f32, f32),
+    ) -> TractResult<Tensor> {
+        let taps = self.taps(data.shape()[axis], len_out, scale, roi);
+        if self.interpolator == Interpolator::Nearest && taps.iter().all(|t| t.is_some()) {
+            let indices = taps.iter().map(|t| t.as_ref().unwrap()[0].0).collect::<Vec<_>>();
+            return dispatch_datum!(Self::gather_t(data.datum_type())(&data, axis, &indices));
         }
-        if let Some(sizes) = input_sizes {
-            if sizes.len() == input_shape.len() {
-                let size = sizes.cast_to::<i64>()?;
-                return Ok(size.as_slice::<i64>()?.iter().map(|i| *i as usize).collect());
+        let input = data.cast_to::<f32>()?;
+        let input = input.to_array_view::<f32>()?;
+        let mut shape = input.shape().to_vec();
+        shape[axis] = len_out;
+        let mut output = tract_ndarray::ArrayD::<f32>::zeros(shape);
+        for (x_out, taps) in taps.iter().enumerate() {
+            let mut slice = output.index_axis_mut(Axis(axis), x_out);
+            if let Some(taps) = taps {
+                for (x_in, weight) in taps {
+                    slice.scaled_add(*weight, &input.index_axis(Axis(axis), *x_in));
+                }
+            } else {
+                slice.fill(self.extrapolation_value);
             }
         }
-        bail!("Neither shape not scale makes sense: input_shape: {:?}, scale: {:?}, sizes: {:?}")
+        Ok(output.into_tensor().cast_to_dt(data.datum_type())?.into_owned())
+    }
+
+    fn gather_t<T: Datum>(data: &Tensor, axis: usize, indices: &[usize]) -> TractResult<Tensor> {
+        Ok(data.to_array_view::<T>()?.select(Axis(axis), indices).into_tensor())
+    }
+
+    fn scales<'a>(&'a self, inputs: &'a [Arc<Tensor>]) -> Option<&'a Tensor> {
+        self.static_scales
+            .as_deref()
+            .or_else(|| self.optional_scales_input.and_then(|ix| inputs.get(ix)).map(|t| &**t))
     }
 }
 
@@ -123,41 +339,46 @@ impl EvalOp for Resize {
         true
     }
 
-    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        let scales = self.optional_scales_input.and_then(|ix| inputs.get(ix));
-        let sizes = self.optional_sizes_input.and_then(|ix| inputs.get(ix));
-        let output_shape = self.compute_output_shape(
-            inputs[0].shape(),
-            scales.map(|t| &**t),
-            sizes.map(|t| &**t),
-        )?;
-        let mut data = inputs.remove(0).into_tensor().into_array::<f32>()?;
-        for axis in 0..data.ndim() {
-            if output_shape[axis] == data.shape()[axis] {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let scales = self.scales(&inputs);
+        let sizes = self.optional_sizes_input.and_then(|ix| inputs.get(ix)).map(|t| &**t);
+        let rank = inputs[0].rank();
+        let input_shape = inputs[0].shape().iter().map(|d| d.to_dim()).collect::<TVec<_>>();
+        let output_shape = self
+            .compute_output_shape(&input_shape, scales, sizes)?
+            .iter()
+            .map(|d| d.to_usize())
+            .collect::<TractResult<TVec<usize>>>()?;
+        let scales = if let Some(scales) = scales.filter(|s| s.len() == rank) {
+            scales.cast_to::<f32>()?.as_slice::<f32>()?.to_vec()
+        } else {
+            (0..rank)
+                .map(|ax| output_shape[ax] as f32 / input_shape[ax].to_usize().unwrap() as f32)
+                .collect()
+        };
+        let roi = if self.coord_transformer == CoordTransformer::TfCropAndResize {
+            let roi = self
+                .optional_roi_input
+                .and_then(|ix| inputs.get(ix))
+                .context("tf_crop_and_resize requires a roi input")?
+                .cast_to::<f32>()?
+                .into_owned();
+            if roi.len() != 2 * rank {
+                bail!("Expected roi of length {}, got {:?}", 2 * rank, roi)
+            }
+            let roi = roi.as_slice::<f32>()?;
+            (0..rank).map(|ax| (roi[ax], roi[rank + ax])).collect()
+        } else {
+            vec![(0.0, 1.0); rank]
+        };
+        let mut data = inputs[0].clone().into_tensor();
+        for axis in 0..rank {
+            if output_shape[axis] == data.shape()[axis]
+                && self.coord_transformer != CoordTransformer::TfCropAndResize
+            {
                 continue;
-            } else if output_shape[axis] > data.shape()[axis] {
-                let scale = output_shape[axis] as f32 / data.shape()[axis] as f32;
-                let mut new_shape: TVec<usize> = data.shape().into();
-                new_shape[axis] = output_shape[axis];
-                data = tract_ndarray::ArrayD::from_shape_fn(&*new_shape, |co_o| -> f32 {
-                    let x_out = co_o[axis];
-                    let x_in = self.coord_transformer.transform(
-                        x_out,
-                        scale,
-                        data.shape()[axis],
-                        new_shape[axis],
-                    );
-                    let mut co_i = co_o.clone();
-                    let x_left = (x_in as usize).min(data.shape()[axis] - 1).max(0);
-                    co_i[axis] = x_left;
-                    let y_left = data[&co_i];
-                    let x_right = (x_left + 1).min(data.shape()[axis] - 1);
-                    co_i[axis] = x_right;
-                    let y_right = data[&co_i];
-                    let x_frac = x_in - x_left as f32;
-                    self.interpolator.interpolate(y_left, y_right, x_frac)
-                })
             }
+            data = self.resize_axis(data, axis, output_shape[axis], scales[axis], roi[axis])?;
         }
         Ok(tvec!(data.into_arc_tensor()))
     }
@@ -173,12 +394,20 @@ impl InferenceRulesOp for Resize {
         check_output_arity(&outputs, 1)?;
         s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         s.equals(&inputs[0].rank, &outputs[0].rank)?;
-        if inputs.len() == 3 && self.optional_scales_input == Some(2) {
+        if let Some(scales) = &self.static_scales {
+            s.given(&inputs[0].shape, move |s, input_shape| {
+                let output_shape = self.compute_output_shape(&input_shape, Some(scales), None)?;
+                for (i, d) in output_shape.into_iter().enumerate() {
+                    s.equals(&outputs[0].shape[i], d)?;
+                }
+                Ok(())
+            })
+        } else if self.optional_sizes_input.is_none() {
             rules_with_scales(self, s, inputs, outputs)
-        } else if inputs.len() == 3 && self.optional_sizes_input == Some(2) {
+        } else if self.optional_scales_input.is_none() {
             rules_with_sizes(self, s, inputs, outputs)
         } else {
-            // bogus 4 inputs case
+            // both scales and sizes are wired, one of them is an empty placeholder
             s.given_2(
                 &inputs[0].rank,
                 &inputs[self.optional_scales_input.unwrap()].shape,
@@ -194,7 +423,26 @@ impl InferenceRulesOp for Resize {
     }
 
     as_op!();
-    to_typed!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|o| mapping[o]).collect::<TVec<_>>();
+        let mut op = self.clone();
+        {
+            let facts =
+                inputs.iter().map(|o| target.outlet_fact(*o)).collect::<TractResult<TVec<_>>>()?;
+            if op.known_output_shape_inputs(&facts).is_none() {
+                op.runtime_output_shape =
+                    (0..facts[0].rank()).map(|_| Symbol::new('r').into()).collect();
+            }
+        }
+        target.wire_node(&*node.name, op, &inputs)
+    }
 }
 
 fn rules_with_scales<'r, 'p: 'r, 's: 'r>(
@@ -207,20 +455,13 @@ fn rules_with_scales<'r, 'p: 'r, 's: 'r>(
     s.equals(&scales.datum_type, f32::datum_type())?;
     s.equals(&scales.rank, 1)?;
     s.equals(&scales.shape[0], inputs[0].rank.bex().to_dim())?;
-    s.given_2(
-        &inputs[0].shape,
-        &inputs[op.optional_scales_input.unwrap()].value,
-        move |s, input_shape, scales| {
-            let input_shape =
-                input_shape.iter().map(|d| d.to_usize()).collect::<TractResult<TVec<usize>>>()?;
-            let output_size = op.compute_output_shape(&input_shape, Some(scales.as_ref()), None)?;
-            let rank = input_shape.len();
-            for i in 0..rank {
-                s.equals(&outputs[0].shape[i], output_size[i].to_dim())?;
-            }
-            Ok(())
-        },
-    )
+    s.given_2(&inputs[0].shape, &scales.value, move |s, input_shape, scales| {
+        let output_shape = op.compute_output_shape(&input_shape, Some(scales.as_ref()), None)?;
+        for (i, d) in output_shape.into_iter().enumerate() {
+            s.equals(&outputs[0].shape[i], d)?;
+        }
+        Ok(())
+    })
 }
 
 fn rules_with_sizes<'r, 'p: 'r, 's: 'r>(
@@ -232,9 +473,10 @@ fn rules_with_sizes<'r, 'p: 'r, 's: 'r>(
     let sizes = &inputs[op.optional_sizes_input.unwrap()];
     s.equals(&sizes.rank, 1)?;
     s.equals(&sizes.shape[0], inputs[0].rank.bex().to_dim())?;
-    s.given(&inputs[0].rank, move |s, rank| {
-        for i in 0..(rank as usize) {
-            s.equals(&outputs[0].shape[i], sizes.value[i].bex().to_dim())?;
+    s.given(&sizes.value, move |s, sizes| {
+        let sizes = sizes.cast_to::<TDim>()?;
+        for (i, d) in sizes.as_slice::<TDim>()?.iter().enumerate() {
+            s.equals(&outputs[0].shape[i], d.clone())?;
         }
         Ok(())
     })
@@ -244,26 +486,179 @@ impl TypedOp for Resize {
     as_op!();
 
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        let input_shape = if let Some(s) = inputs[0].shape.as_concrete() {
-            s
+        let output_shape = if let Some((scales, sizes)) = self.known_output_shape_inputs(inputs) {
+            let input_shape = inputs[0].shape.iter().collect::<TVec<_>>();
+            self.compute_output_shape(&input_shape, scales, sizes)?
+        } else if self.runtime_output_shape.len() == inputs[0].rank() {
+            self.runtime_output_shape.clone()
         } else {
-            bail!("Only constant input shape are supported in Resize")
+            bail!("Resize with runtime scales and sizes has no output dimensions")
         };
-        let scales = self.optional_scales_input.and_then(|ix| inputs.get(ix));
-        let sizes = self.optional_sizes_input.and_then(|ix| inputs.get(ix));
-        let output_shape = self.compute_output_shape(
-            &*input_shape,
-            scales.and_then(|f| f.konst.as_deref()),
-            sizes.and_then(|f| f.konst.as_deref()),
-        )?;
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &output_shape)))
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, output_shape)))
     }
+}
 
-    fn declutter(
-        &self,
-        _model: &TypedModel,
-        _node: &TypedNode,
-    ) -> TractResult<Option<TypedModelPatch>> {
-        Ok(None)
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn op(coord_transformer: CoordTransformer, interpolator: Interpolator) -> Resize {
+        Resize {
+            coord_transformer,
+            interpolator,
+            nearest: Nearest::RoundPreferFloor,
+            cubic_coeff_a: -0.75,
+            exclude_outside: false,
+            extrapolation_value: 0.0,
+            static_scales: None,
+            optional_roi_input: None,
+            optional_scales_input: Some(1),
+            optional_sizes_input: None,
+            runtime_output_shape: tvec!(),
+        }
+    }
+
+    fn run(op: &Resize, input: Tensor, scales: &[f32]) -> TractResult<Tensor> {
+        let output = op.eval(tvec!(input.into_arc_tensor(), rctensor1(scales)))?;
+        Ok(output[0].clone().into_tensor())
+    }
+
+    fn close(found: Tensor, expected: Tensor) {
+        let found = found.into_array::<f32>().unwrap();
+        let expected = expected.into_array::<f32>().unwrap();
+        assert_eq!(found.shape(), expected.shape());
+        assert!(
+            found.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-5),
+            "{:?}",
+            found
+        );
+    }
+
+    // test cases from https://github.com/onnx/onnx/blob/master/docs/Operators.md#Resize
+    #[test]
+    fn upsample_nearest() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Nearest);
+        let input = tensor4(&[[[[1f32, 2.], [3., 4.]]]]);
+        let output = run(&op, input, &[1., 1., 2., 3.])?;
+        let expected = tensor4(&[[[
+            [1f32, 1., 1., 2., 2., 2.],
+            [1., 1., 1., 2., 2., 2.],
+            [3., 3., 3., 4., 4., 4.],
+            [3., 3., 3., 4., 4., 4.],
+        ]]]);
+        assert_eq!(output, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn upsample_nearest_integers() -> TractResult<()> {
+        let op = op(CoordTransformer::Asymmetric, Interpolator::Nearest);
+        let output = run(&op, tensor1(&[1i64, 2]), &[2.])?;
+        assert_eq!(output, tensor1(&[1i64, 1, 2, 2]));
+        Ok(())
+    }
+
+    #[test]
+    fn upsample_linear() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Linear);
+        let input = tensor4(&[[[[1f32, 2.], [3., 4.]]]]);
+        let output = run(&op, input, &[1., 1., 2., 2.])?;
+        let expected = tensor4(&[[[
+            [1f32, 1.25, 1.75, 2.],
+            [1.5, 1.75, 2.25, 2.5],
+            [2.5, 2.75, 3.25, 3.5],
+            [3., 3.25, 3.75, 4.],
+        ]]]);
+        close(output, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn upsample_linear_align_corners() -> TractResult<()> {
+        let op = op(CoordTransformer::AlignCorners, Interpolator::Linear);
+        let input = tensor4(&[[[[1f32, 2.], [3., 4.]]]]);
+        let output = run(&op, input, &[1., 1., 2., 2.])?;
+        let expected = tensor4(&[[[
+            [1f32, 1.3333333, 1.6666667, 2.],
+            [1.6666666, 2., 2.3333333, 2.6666667],
+            [2.3333333, 2.6666665, 3., 3.3333335],
+            [3., 3.3333333, 3.6666667, 4.],
+        ]]]);
+        close(output, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn downsample_linear() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Linear);
+        let input = tensor4(&[[[[1f32, 2., 3., 4.], [5., 6., 7., 8.]]]]);
+        let output = run(&op, input, &[1., 1., 0.6, 0.6])?;
+        close(output, tensor4(&[[[[2.6666665f32, 4.333333]]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn upsample_cubic() -> TractResult<()> {
+        let op = op(CoordTransformer::HalfPixel, Interpolator::Cubic);
+        let input =
+            tract_ndarray::Array1::range(1f32, 17., 1.).into_shape((1, 1, 4, 4))?.into_tensor();
+        let output = run(&op, input, &[1., 1., 2., 2.])?;
+        let first_row = output.slice(2, 0, 1)?.into_shape(&[8])?;
+        let expected = tensor1(&[
+            0.4726562f32,
+            0.7695312,
+            1.2460938,
+            1.875,
+            2.28125,
+            2.9101562,
+            3.3867188,
+            3.6835938,
+        ]);
+        close(first_row, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn upsample_cubic_reproduces_linear_data() -> TractResult<()> {
+        let mut op = op(CoordTransformer::Asymmetric, Interpolator::Cubic);
+        op.cubic_coeff_a = -0.5;
+        let input = tensor1(&[0f32, 1., 2., 3., 4., 5., 6., 7.]);
+        let output = run(&op, input, &[2.])?;
+        let output = output.as_slice::<f32>()?;
+        for (x, y) in output.iter().enumerate().skip(2).take(10) {
+            assert!((y - x as f32 / 2.).abs() < 1e-5, "{:?}", output);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_output_shape() -> TractResult<()> {
+        let op = op(CoordTransformer::Asymmetric, Interpolator::Nearest);
+        let n = Symbol::from('N');
+        let input_shape = tvec!(n.to_dim(), 3.to_dim());
+        let output_shape =
+            op.compute_output_shape(&input_shape, Some(&tensor1(&[2f32, 2.])), None)?;
+        assert_eq!(output_shape, tvec!(n.to_dim() * 2, 6.to_dim()));
+        let sizes = tensor1(&[n.to_dim() * 3, 4.to_dim()]);
+        let output_shape = op.compute_output_shape(&input_shape, None, Some(&sizes))?;
+        assert_eq!(output_shape, tvec!(n.to_dim() * 3, 4.to_dim()));
+        Ok(())
+    }
+
+    #[test]
+    fn runtime_sizes() -> TractResult<()> {
+        let mut op = op(CoordTransformer::Asymmetric, Interpolator::Nearest);
+        op.optional_scales_input = None;
+        op.optional_sizes_input = Some(1);
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), [2]))?;
+        let sizes = model.add_source("sizes", InferenceFact::dt_shape(i64::datum_type(), [1]))?;
+        let y = model.wire_node("resize", op, &[x, sizes])?;
+        model.set_output_outlets(&y)?;
+        let model = model.into_typed()?;
+        assert!(model.outlet_fact(y[0])?.shape[0].to_i64().is_err());
+        let output = SimplePlan::new(model)?.run(tvec!(tensor1(&[1f32, 2.]), tensor1(&[4i64])))?;
+        assert_eq!(*output[0], tensor1(&[1f32, 1., 2., 2.]));
+        Ok(())
     }
 }
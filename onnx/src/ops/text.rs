@@ -0,0 +1,227 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+use tract_onnx_opl::text::{string_normalizer, tfidf_vectorizer, tokenizer};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("StringNormalizer", string_normalizer);
+    reg.insert("TfIdfVectorizer", tfidf_vectorizer);
+    reg.insert("Tokenizer", tokenizer);
+}
+
+fn string_normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let case_change = node.get_attr_opt("case_change_action")?.unwrap_or("NONE");
+    let case_sensitive = node.get_attr_opt::<i64>("is_case_sensitive")?.unwrap_or(0) != 0;
+    let stopwords = node
+        .get_attr_opt_tvec::<&str>("stopwords")?
+        .map(|s| s.iter().map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+    let op = string_normalizer::StringNormalizer::new(
+        string_normalizer::CaseChange::parse(case_change)?,
+        case_sensitive,
+        stopwords,
+    );
+    Ok((expand(StringNormalizer(op)), vec![]))
+}
+
+fn tfidf_vectorizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let pool = if let Some(pool) = node.get_attr_opt_tvec::<&str>("pool_strings")? {
+        rctensor1(&pool.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    } else {
+        rctensor1(node.get_attr_slice::<i64>("pool_int64s")?)
+    };
+    let weights = node.get_attr_opt_vec::<f32>("weights")?.map(|w| rctensor1(&w));
+    let op = tfidf_vectorizer::TfIdfVectorizer::new(
+        tfidf_vectorizer::TfIdfMode::parse(node.get_attr("mode")?)?,
+        node.get_attr("min_gram_length")?,
+        node.get_attr("max_gram_length")?,
+        node.get_attr("max_skip_count")?,
+        pool,
+        node.get_attr_tvec("ngram_counts")?,
+        node.get_attr_tvec("ngram_indexes")?,
+        weights,
+    )?;
+    Ok((expand(TfIdfVectorizer(op)), vec![]))
+}
+
+fn tokenizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let separators = node.get_attr_opt_tvec::<&str>("separators")?.filter(|s| !s.is_empty());
+    let expression = node.get_attr_opt::<&str>("tokenexp")?.filter(|s| !s.is_empty());
+    let tokens = match (separators, expression) {
+        (Some(separators), None) => {
+            tokenizer::Tokens::separators(separators.iter().map(|s| s.to_string()).collect())?
+        }
+        (None, Some(expression)) => tokenizer::Tokens::expression(expression.to_string())?,
+        _ => bail!("exactly one of 'separators' and 'tokenexp' must be set"),
+    };
+    let op = tokenizer::Tokenizer::new(
+        tokens,
+        node.get_attr::<i64>("mark")? != 0,
+        node.get_attr("mincharnum")?,
+        node.get_attr::<&str>("pad_value")?.to_string(),
+    );
+    Ok((expand(Tokenizer(op)), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct StringNormalizer(string_normalizer::StringNormalizer);
+
+impl_dyn_hash!(StringNormalizer);
+
+impl Expansion for StringNormalizer {
+    fn name(&self) -> Cow<str> {
+        "StringNormalizer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, String::datum_type())?;
+        s.equals(&outputs[0].datum_type, String::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        if self.0.stopwords.is_empty() {
+            s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct TfIdfVectorizer(tfidf_vectorizer::TfIdfVectorizer);
+
+impl_dyn_hash!(TfIdfVectorizer);
+
+impl Expansion for TfIdfVectorizer {
+    fn name(&self) -> Cow<str> {
+        "TfIdfVectorizer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            if rank == 2 {
+                s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+            }
+            s.equals(&outputs[0].shape[rank as usize - 1], self.0.output_len().to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Tokenizer(tokenizer::Tokenizer);
+
+impl_dyn_hash!(Tokenizer);
+
+impl Expansion for Tokenizer {
+    fn name(&self) -> Cow<str> {
+        "Tokenizer".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, String::datum_type())?;
+        s.equals(&outputs[0].datum_type, String::datum_type())?;
+        s.equals(inputs[0].rank.bex() + 1, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            for axis in 0..rank as usize {
+                s.equals(&inputs[0].shape[axis], &outputs[0].shape[axis])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, self.0.clone(), inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn tokenize_and_count() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(String::datum_type(), [2]))?;
+        let tokens = tokenizer::Tokens::separators(tvec!(" ".to_string()))?;
+        let op = tokenizer::Tokenizer::new(tokens, false, 1, "#".to_string());
+        let wire = model.wire_node("tokenizer", expand(Tokenizer(op)), &[x])?;
+        let op = tfidf_vectorizer::TfIdfVectorizer::new(
+            tfidf_vectorizer::TfIdfMode::Tf,
+            1,
+            2,
+            0,
+            rctensor1(&strings(&["cat", "dog", "the", "dog"])),
+            tvec!(0, 2),
+            tvec!(0, 1, 2),
+            None,
+        )?;
+        let wire = model.wire_node("tfidf", expand(TfIdfVectorizer(op)), &wire)?;
+        model.set_output_outlets(&wire)?;
+        let input = tensor1(&strings(&["the dog and the cat", "dog"]));
+        let result = SimplePlan::new(model.into_typed()?)?.run(tvec!(input))?;
+        assert_eq!(*result[0], tensor2(&[[1f32, 1., 1.], [0., 1., 0.]]));
+        Ok(())
+    }
+}
@@ -114,6 +114,14 @@ impl<'a> AttrScalarType<'a> for Tensor {
     }
 }
 
+impl<'a> AttrScalarType<'a> for &'a TensorProto {
+    fn get_attr_opt_scalar(node: &'a NodeProto, name: &str) -> TractResult<Option<Self>> {
+        Ok(node
+            .get_attr_opt_with_type(name, AttributeType::Tensor)?
+            .map(|attr| attr.t.as_ref().unwrap()))
+    }
+}
+
 impl<'a> AttrScalarType<'a> for &'a [u8] {
     fn get_attr_opt_scalar(node: &'a NodeProto, name: &str) -> TractResult<Option<Self>> {
         Ok(node.get_attr_opt_with_type(name, AttributeType::String)?.map(|attr| &*attr.s))
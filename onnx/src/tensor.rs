@@ -2,6 +2,9 @@ use crate::pb::tensor_proto::DataType;
 use crate::pb::*;
 use prost::Message;
 use std::convert::{TryFrom, TryInto};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path};
 use tract_hir::internal::*;
 
 impl TryFrom<DataType> for DatumType {
@@ -58,32 +61,115 @@ impl TryFrom<type_proto::Tensor> for InferenceFact {
     }
 }
 
+fn tensor_from_raw(dt: DatumType, shape: &[usize], raw: &[u8]) -> TractResult<Tensor> {
+    unsafe {
+        match dt {
+            DatumType::U8 => Tensor::from_raw::<u8>(shape, raw),
+            DatumType::U16 => Tensor::from_raw::<u16>(shape, raw),
+            DatumType::U32 => Tensor::from_raw::<u32>(shape, raw),
+            DatumType::U64 => Tensor::from_raw::<u64>(shape, raw),
+            DatumType::I8 => Tensor::from_raw::<i8>(shape, raw),
+            DatumType::I16 => Tensor::from_raw::<i16>(shape, raw),
+            DatumType::I32 => Tensor::from_raw::<i32>(shape, raw),
+            DatumType::I64 => Tensor::from_raw::<i64>(shape, raw),
+            DatumType::F16 => Tensor::from_raw::<f16>(shape, raw),
+            DatumType::F32 => Tensor::from_raw::<f32>(shape, raw),
+            DatumType::F64 => Tensor::from_raw::<f64>(shape, raw),
+            DatumType::Bool => {
+                Ok(Tensor::from_raw::<u8>(shape, raw)?.into_array::<u8>()?.mapv(|x| x != 0).into())
+            }
+            _ => unimplemented!("FIXME, raw tensor loading"),
+        }
+    }
+}
+
+/// Load a tensor, resolving its external data relative to `model_dir`.
+pub fn load_tensor(t: &TensorProto, model_dir: Option<&Path>) -> TractResult<Tensor> {
+    if t.data_location == tensor_proto::DataLocation::External as i32 {
+        load_external_tensor(t, model_dir)
+            .with_context(|| format!("Loading external data for tensor {}", t.name))
+    } else {
+        t.try_into()
+    }
+}
+
+fn load_external_tensor(t: &TensorProto, model_dir: Option<&Path>) -> TractResult<Tensor> {
+    let dt: DatumType = DataType::from_i32(t.data_type).unwrap().try_into()?;
+    let shape: Vec<usize> = t.dims.iter().map(|&i| i as usize).collect();
+    let mut location = None;
+    let mut offset = 0usize;
+    let mut length = None;
+    for entry in &t.external_data {
+        match &*entry.key {
+            "location" => location = Some(&*entry.value),
+            "offset" => offset = entry.value.parse().context("Parsing external data offset")?,
+            "length" => length = Some(entry.value.parse().context("Parsing external data length")?),
+            _ => (),
+        }
+    }
+    let location = location.context("External data without location")?;
+    let model_dir = model_dir.with_context(|| {
+        format!("Model path is required to resolve external data file {:?}", location)
+    })?;
+    // external data must stay next to the model
+    let relative = Path::new(location);
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        bail!("External data location {:?} must be a relative path below the model", location);
+    }
+    let path = model_dir.join(relative);
+    if !dt.is_copy() {
+        bail!("Can not load {:?} tensor from external data", dt);
+    }
+    let expected = shape.iter().product::<usize>() * dt.size_of();
+    let length: usize = length.unwrap_or(expected);
+    if length != expected {
+        bail!(
+            "External data for {:?}{:?} is {} bytes long, expected {}",
+            dt,
+            shape,
+            length,
+            expected
+        );
+    }
+    let file = fs::File::open(&path).with_context(|| format!("Opening {:?}", path))?;
+    let file_len = file.metadata()?.len() as usize;
+    if offset + length > file_len {
+        bail!("{:?} is {} bytes long, can not read {} bytes at {}", path, file_len, length, offset);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // bool must be checked for 0/1 values, misaligned data must be copied
+        if dt != DatumType::Bool && offset % dt.alignment() == 0 && length > 0 {
+            // mapping offset must be a multiple of the page size (or of 64k on windows)
+            let start = offset - offset % 0x10000;
+            unsafe {
+                // copy-on-write mapping: tract may mutate a tensor it holds the only reference to
+                let mut mmap = mapr::MmapOptions::new()
+                    .offset(start as u64)
+                    .len(offset - start + length)
+                    .map_copy(&file)?;
+                let data = mmap.as_mut_ptr().add(offset - start);
+                return Tensor::from_raw_dt_with_owner(dt, &shape, data, Arc::new(mmap));
+            }
+        }
+    }
+    let mut file = file;
+    file.seek(SeekFrom::Start(offset as u64))?;
+    let mut raw = vec![0u8; length];
+    file.read_exact(&mut raw)?;
+    tensor_from_raw(dt, &shape, &raw)
+}
+
 impl<'a> TryFrom<&'a TensorProto> for Tensor {
     type Error = TractError;
     fn try_from(t: &TensorProto) -> TractResult<Tensor> {
         let dt = DataType::from_i32(t.data_type).unwrap().try_into()?;
         let shape: Vec<usize> = t.dims.iter().map(|&i| i as usize).collect();
+        if t.data_location == tensor_proto::DataLocation::External as i32 {
+            bail!("Tensor {} has external data, use load_tensor to load it", t.name);
+        }
         if t.raw_data.len() > 0 {
-            unsafe {
-                match dt {
-                    DatumType::U8 => Tensor::from_raw::<u8>(&*shape, &*t.raw_data),
-                    DatumType::U16 => Tensor::from_raw::<u16>(&*shape, &*t.raw_data),
-                    DatumType::U32 => Tensor::from_raw::<u32>(&*shape, &*t.raw_data),
-                    DatumType::U64 => Tensor::from_raw::<u64>(&*shape, &*t.raw_data),
-                    DatumType::I8 => Tensor::from_raw::<i8>(&*shape, &*t.raw_data),
-                    DatumType::I16 => Tensor::from_raw::<i16>(&*shape, &*t.raw_data),
-                    DatumType::I32 => Tensor::from_raw::<i32>(&*shape, &*t.raw_data),
-                    DatumType::I64 => Tensor::from_raw::<i64>(&*shape, &*t.raw_data),
-                    DatumType::F16 => Tensor::from_raw::<f16>(&*shape, &*t.raw_data),
-                    DatumType::F32 => Tensor::from_raw::<f32>(&*shape, &*t.raw_data),
-                    DatumType::F64 => Tensor::from_raw::<f64>(&*shape, &*t.raw_data),
-                    DatumType::Bool => Ok(Tensor::from_raw::<u8>(&*shape, &*t.raw_data)?
-                        .into_array::<u8>()?
-                        .mapv(|x| x != 0)
-                        .into()),
-                    _ => unimplemented!("FIXME, raw tensor loading"),
-                }
-            }
+            tensor_from_raw(dt, &shape, &t.raw_data)
         } else {
             use tract_ndarray::Array;
             let it = match dt {
@@ -155,3 +241,52 @@ pub fn proto_from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<TensorProt
 pub fn from_reader<R: ::std::io::Read>(r: R) -> TractResult<Tensor> {
     proto_from_reader(r)?.try_into()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn external(name: &str, location: &str, offset: usize, dims: &[i64]) -> TensorProto {
+        let entry = |key: &str, value: String| StringStringEntryProto { key: key.into(), value };
+        TensorProto {
+            name: name.into(),
+            dims: dims.into(),
+            data_type: DataType::Float as i32,
+            data_location: tensor_proto::DataLocation::External as i32,
+            external_data: vec![
+                entry("location", location.into()),
+                entry("offset", offset.to_string()),
+                entry("length", (dims.iter().product::<i64>() * 4).to_string()),
+            ],
+            ..TensorProto::default()
+        }
+    }
+
+    #[test]
+    fn external_data() -> TractResult<()> {
+        let dir = std::env::temp_dir();
+        let location = format!("tract-onnx-external-{}.bin", std::process::id());
+        let floats: Vec<u8> = [1f32, 2.0, 3.0, 4.0].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let data = [&[0u8; 8][..], &floats, &[0u8; 2], &floats].concat();
+        std::fs::write(dir.join(&location), &data)?;
+        let aligned = load_tensor(&external("a", &location, 8, &[2, 2]), Some(&dir));
+        let misaligned = load_tensor(&external("m", &location, 26, &[3]), Some(&dir));
+        let no_dir = load_tensor(&external("n", &location, 8, &[2, 2]), None);
+        std::fs::remove_file(dir.join(&location))?;
+        let aligned = aligned?;
+        #[cfg(not(target_arch = "wasm32"))]
+        assert!(aligned.is_foreign());
+        assert_eq!(aligned, tensor2(&[[1f32, 2.0], [3.0, 4.0]]));
+        assert_eq!(misaligned?, tensor1(&[1f32, 2.0, 3.0]));
+        assert!(no_dir.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn external_data_outside_model_dir() {
+        let dir = std::env::temp_dir().join("model");
+        for location in ["../secret.bin", "/etc/passwd", "data/../../secret.bin"] {
+            assert!(load_tensor(&external("t", location, 0, &[1]), Some(&dir)).is_err());
+        }
+    }
+}
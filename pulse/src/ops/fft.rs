@@ -0,0 +1,115 @@
+use crate::internal::*;
+use tract_core::ops::fft::{Dft, Stft};
+
+register_all!(Dft: pulsify_dft, Stft: pulsify_stft);
+
+fn pulsify_dft(
+    op: &Dft,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    if target.outlet_fact(input)?.axis == op.axis {
+        bail!("Can not pulsify Dft along the streaming axis")
+    }
+    target.wire_node(&*node.name, op.clone(), &[input])
+}
+
+impl PulsedOp for Dft {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape = self.output_facts(&[&inputs[0].to_pulse_fact()])?.remove(0).shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+fn pulsify_stft(
+    op: &Stft,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let mut wire = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(wire)?.clone();
+    if fact.axis == 1 {
+        let pulse = fact.pulse();
+        if pulse % op.frame_step != 0 {
+            bail!("Pulsificaton requires pulse to be a frame step multiple")
+        }
+        let overlap = op.frame_length.saturating_sub(op.frame_step);
+        let misalignment = fact.delay % op.frame_step;
+        if overlap > 0 || misalignment > 0 {
+            let align_to = (overlap + fact.delay).div_ceil(op.frame_step) * op.frame_step;
+            let delay = align_to - overlap - fact.delay;
+            wire = target.wire_node(
+                format!("{}.delay", node.name),
+                tract_pulse_opl::ops::Delay::new(fact.axis, &(&fact).into(), delay, overlap),
+                &[wire],
+            )?[0];
+        }
+    } else if fact.axis != 0 {
+        bail!("Can not pulsify Stft along axis {}", fact.axis)
+    }
+    target.wire_node(&*node.name, op.clone(), &[wire])
+}
+
+impl PulsedOp for Stft {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape = self.output_facts(&[&inputs[0].to_pulse_fact()])?.remove(0).shape;
+        if fact.axis == 1 {
+            fact.delay /= self.frame_step;
+            fact.dim = self.frames(&fact.dim);
+        }
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn streaming_stft() -> TractResult<()> {
+        let op = Stft { frame_step: 2, frame_length: 4, onesided: true, window: None };
+        let mut model = TypedModel::default();
+        let source = model.add_source(
+            "signal",
+            TypedFact::dt_shape(f32::datum_type(), [1.to_dim(), stream_dim(), 1.to_dim()]),
+        )?;
+        let stft = model.wire_node("stft", op.clone(), &[source])?;
+        model.set_output_outlets(&stft)?;
+        let pulsed = PulsedModel::new(&model, 4)?;
+        let output_fact = pulsed.output_fact(0)?.clone();
+        assert_eq!(output_fact.pulse(), 2);
+        assert_eq!(output_fact.delay, 1);
+
+        let signal: Vec<f32> = (0..12).map(|i| (i as f32).sin()).collect();
+        let expected =
+            op.eval(tvec!(tensor1(&signal).into_shape(&[1, 12, 1])?.into_arc_tensor()))?;
+        let plan = SimplePlan::new(pulsed.into_typed()?)?;
+        let mut state = tract_core::plan::SimpleState::new(plan)?;
+        let mut frames = vec![];
+        for chunk in signal.chunks(4) {
+            let output = state.run(tvec!(tensor1(chunk).into_shape(&[1, 4, 1])?))?;
+            frames.extend_from_slice(output[0].as_slice::<f32>()?);
+        }
+        let frame_size = op.bins() * 2;
+        let streamed = &frames[frame_size..][..expected[0].len()];
+        for (a, b) in streamed.iter().zip(expected[0].as_slice::<f32>()?) {
+            assert!((a - b).abs() < 1e-5, "{:?} != {:?}", streamed, expected[0]);
+        }
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ pub mod delay;
 pub mod downsample;
 pub mod dummy;
 pub mod element_wise;
+pub mod fft;
 pub mod matmul;
 pub mod nn;
 pub mod qmatmul;
@@ -22,6 +23,7 @@ register_all_mod!(
     cnn,
     downsample,
     element_wise,
+    fft,
     matmul,
     nn,
     qmatmul,
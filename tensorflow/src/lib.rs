@@ -61,7 +61,7 @@ pub use model::Tensorflow;
 pub fn tensorflow() -> Tensorflow {
     let mut ops = crate::model::TfOpRegister::default();
     ops::register_all_ops(&mut ops);
-    Tensorflow { op_register: ops }
+    Tensorflow { op_register: ops, share_tensors: false }
 }
 
 pub use tract_hir::tract_core;
@@ -25,6 +25,8 @@ impl TfOpRegister {
 
 pub struct Tensorflow {
     pub op_register: TfOpRegister,
+    /// Register the tensors of the loaded models in the process-wide `TensorStore`.
+    pub share_tensors: bool,
 }
 
 pub struct TfModelExtensions {
@@ -57,6 +59,13 @@ impl TfModelExtensions {
 pub struct TfModelAndExtensions(pub InferenceModel, pub TfModelExtensions);
 
 impl Tensorflow {
+    /// Let the loaded models share identical constants and pre-packed weights with the other
+    /// models of the process opting in.
+    pub fn with_shared_tensors(mut self) -> Self {
+        self.share_tensors = true;
+        self
+    }
+
     // From the node_def.proto documentation:
     // Each input is "node:src_output" with "node" being a string name and
     // "src_output" indicating which output tensor to use from "node". If
@@ -118,7 +127,8 @@ impl Tensorflow {
     pub fn parse_graph(&self, graph: &GraphDef) -> TractResult<TfModelAndExtensions> {
         use crate::ops::control_flow as cf;
 
-        let mut model = InferenceModel::default();
+        let mut model =
+            InferenceModel { share_tensors: self.share_tensors, ..InferenceModel::default() };
         let mut inputs = tvec!();
         let mut context = ParsingContext::default();
         let mut control_inputs = vec![];
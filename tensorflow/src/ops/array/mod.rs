@@ -13,6 +13,7 @@ mod pack;
 mod pad;
 mod range;
 mod squeeze;
+mod topk;
 mod transpose;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
@@ -30,6 +31,7 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Squeeze", squeeze::squeeze);
     reg.insert("StridedSlice", strided_slice);
     reg.insert("Tile", |_, _| Ok(expand(::tract_hir::ops::array::Tile)));
+    reg.insert("TopKV2", topk::topk_v2);
     reg.insert("Transpose", transpose::transpose);
 }
 
@@ -0,0 +1,70 @@
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+use tract_hir::internal::*;
+
+pub fn topk_v2(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let sorted = pb.get_attr_opt_bool("sorted")?.unwrap_or(true);
+    Ok(expand(TopKV2::new(sorted)))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct TopKV2 {
+    sorted: bool,
+}
+
+impl_dyn_hash!(TopKV2);
+
+impl Expansion for TopKV2 {
+    fn name(&self) -> Cow<str> {
+        "TopKV2".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 2)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].datum_type, i32::datum_type())?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[1].datum_type, i32::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].rank, &outputs[1].rank)?;
+        s.equals(&outputs[0].shape, &outputs[1].shape)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            let last = rank as usize - 1;
+            for ix in 0..last {
+                s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+            }
+            s.given(&inputs[1].value, move |s, k| {
+                s.equals(&outputs[0].shape[last], k.cast_to_scalar::<i64>()?.to_dim())
+            })
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let axis = target.outlet_fact(inputs[0])?.rank() - 1;
+        let op = tract_hir::tract_core::ops::array::TopK::new(axis, true, self.sorted);
+        let wires = target.wire_node(prefix, op, inputs)?;
+        let indices = target.wire_node(
+            format!("{}.indices", prefix),
+            tract_hir::tract_core::ops::cast::cast(i32::datum_type()),
+            &[wires[1]],
+        )?;
+        Ok(tvec!(wires[0], indices[0]))
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(2)
+    }
+}
@@ -15,6 +15,7 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("BiasAdd", |_, _| Ok(ops::math::Add.into_hir()));
     reg.insert("Ceil", |_, _| Ok(Box::new(ops::math::ceil())));
     reg.insert("Div", |_, _| Ok(ops::math::Div.into_hir()));
+    reg.insert("Einsum", einsum);
     reg.insert("FloorMod", |_, _| Ok(ops::math::Rem.into_hir()));
     reg.insert("MatMul", mat_mul);
     reg.insert("Max", reduce::max);
@@ -39,6 +40,11 @@ pub fn add_n(_ctx: &ParsingContext, _pb: &NodeDef) -> TractResult<Box<dyn Infere
     Ok(Box::new(ops::binary::Nary(Box::new(ops::math::Add), false)))
 }
 
+pub fn einsum(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let equation = pb.get_attr_str("equation")?;
+    Ok(expand(ops::einsum::EinSumInference::new(equation)))
+}
+
 pub fn mat_mul(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
     let trans_a = pb.get_attr_bool("transpose_a")?;
     let trans_b = pb.get_attr_bool("transpose_b")?;
@@ -8,6 +8,7 @@ use crate::tfpb::tensorflow::NodeDef;
 pub mod conv2d;
 pub mod dw_conv2d;
 pub mod fused_batch_norm;
+pub mod non_max_suppression;
 pub mod pools;
 pub mod s2b;
 
@@ -17,6 +18,8 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("DepthwiseConv2dNative", dw_conv2d::depthwise_conv2d);
     reg.insert("FusedBatchNorm", fused_batch_norm::fused_batch_norm);
     reg.insert("MaxPool", pools::maxpool);
+    reg.insert("NonMaxSuppressionV2", non_max_suppression::non_max_suppression_v2);
+    reg.insert("NonMaxSuppressionV3", non_max_suppression::non_max_suppression_v3);
     reg.insert("Relu", |_, _| Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), None))));
     reg.insert("Relu6", |_, _| {
         Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), Some(6.0))))
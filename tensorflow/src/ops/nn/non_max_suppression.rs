@@ -0,0 +1,78 @@
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::{array, cast, nn};
+
+pub fn non_max_suppression_v2(
+    _ctx: &ParsingContext,
+    _pb: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    Ok(expand(NonMaxSuppression::new(false)))
+}
+
+pub fn non_max_suppression_v3(
+    _ctx: &ParsingContext,
+    _pb: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    Ok(expand(NonMaxSuppression::new(true)))
+}
+
+/// Boxes are `[boxes, 4]` and scores `[boxes]`, the output is the 1D tensor of selected indices.
+#[derive(Debug, Clone, new, Hash)]
+pub struct NonMaxSuppression {
+    has_score_threshold: bool,
+}
+
+impl_dyn_hash!(NonMaxSuppression);
+
+impl Expansion for NonMaxSuppression {
+    fn name(&self) -> Cow<str> {
+        "NonMaxSuppression".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 4 + self.has_score_threshold as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], 4.to_dim())?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&inputs[2].datum_type, i32::datum_type())?;
+        s.equals(&outputs[0].datum_type, i32::datum_type())?;
+        s.equals(&outputs[0].rank, 1)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let boxes = target.wire_node(format!("{}.boxes", prefix), AxisOp::Add(0), &[inputs[0]])?;
+        let mut scores =
+            target.wire_node(format!("{}.scores", prefix), AxisOp::Add(0), &[inputs[1]])?;
+        scores = target.wire_node(format!("{}.scores_classes", prefix), AxisOp::Add(0), &scores)?;
+        let score_threshold = if self.has_score_threshold {
+            inputs[4]
+        } else {
+            target.add_const(format!("{}.score_threshold", prefix), tensor0(f32::NEG_INFINITY))?
+        };
+        let selected = target.wire_node(
+            format!("{}.nms", prefix),
+            nn::NonMaxSuppression::new(nn::BoxRepr::TwoPoints),
+            &[boxes[0], scores[0], inputs[2], inputs[3], score_threshold],
+        )?;
+        let selected =
+            target.wire_node(format!("{}.box", prefix), array::Slice::new(1, 2, 3), &selected)?;
+        let selected = target.wire_node(format!("{}.box_rm", prefix), AxisOp::Rm(1), &selected)?;
+        target.wire_node(prefix, cast::cast(i32::datum_type()), &selected)
+    }
+}
@@ -2,7 +2,7 @@ use crate::model::ParsingContext;
 use crate::tfpb::tensorflow::NodeDef;
 use tract_hir::internal::*;
 
-use super::philox::Philox4x32x10;
+use tract_hir::tract_core::ops::random::{stream_for_name, Distribution, Random};
 
 pub fn random_uniform(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
     let dtype = node.get_attr_datum_type("dtype")?;
@@ -53,12 +53,22 @@ impl EvalOp for RandomUniform {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        let shape: TVec<usize> =
-            inputs[0].cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&x| x as usize).collect();
-        match self.t {
-            DatumType::F32 => Ok(tvec!(make_f32(&*shape, self.seed1, self.seed2)?)),
-            dt => bail!("RandomUniform not implemented for {:?}", dt),
+        self.as_random(&inputs[0], 0)?.eval(tvec!())
+    }
+}
+
+impl RandomUniform {
+    fn as_random(&self, shape: &Tensor, stream: u64) -> TractResult<Random> {
+        if self.t != DatumType::F32 {
+            bail!("RandomUniform not implemented for {:?}", self.t)
         }
+        Ok(Random {
+            datum_type: self.t,
+            shape: shape.cast_to::<TDim>()?.as_slice::<TDim>()?.into(),
+            dist: Distribution::Uniform { low: rctensor0(0f32), high: rctensor0(1f32) },
+            seed: tf_seed(self.seed1, self.seed2),
+            stream,
+        })
     }
 }
 
@@ -92,79 +102,19 @@ impl InferenceRulesOp for RandomUniform {
         mapping: &HashMap<OutletId, OutletId>,
     ) -> TractResult<TVec<OutletId>> {
         if let Some(ref shape) = target.outlet_fact(mapping[&node.inputs[0]])?.konst {
-            let op = TypedRandomUniform::new(
-                self.t,
-                self.seed1,
-                self.seed2,
-                shape.cast_to::<TDim>()?.as_slice::<TDim>()?.into(),
-            );
-            target.wire_node(&*node.name, op, &[node.inputs[0]])
+            target.wire_node(&*node.name, self.as_random(shape, stream_for_name(&node.name))?, &[])
         } else {
             bail!("Dynamic shape")
         }
     }
 }
 
-#[derive(Debug, Clone, new, Hash)]
-pub struct TypedRandomUniform {
-    t: DatumType,
-    seed1: u64,
-    seed2: u64,
-    shape: TVec<TDim>,
-}
-
-impl_dyn_hash!(TypedRandomUniform);
-
-impl Op for TypedRandomUniform {
-    fn name(&self) -> Cow<str> {
-        "TypedRandomUniform".into()
-    }
-
-    fn validation(&self) -> Validation {
-        if self.seed1 == 0 && self.seed2 == 0 {
-            Validation::Random
-        } else {
-            Validation::Accurate
-        }
-    }
-
-    op_tf!();
-    op_as_typed_op!();
-}
-
-impl EvalOp for TypedRandomUniform {
-    fn is_stateless(&self) -> bool {
-        true
-    }
-
-    fn eval(&self, _inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        let shape = self.shape.iter().map(|d| d.to_usize()).collect::<TractResult<TVec<_>>>()?;
-        match self.t {
-            DatumType::F32 => Ok(tvec!(make_f32(&*shape, self.seed1, self.seed2)?)),
-            dt => bail!("RandomUniform not implemented for {:?}", dt),
-        }
-    }
-}
-
-impl TypedOp for TypedRandomUniform {
-    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(self.t, &self.shape)))
-    }
-
-    as_op!();
-}
-
-pub fn make_f32(shape: &[usize], seed1: u64, seed2: u64) -> TractResult<Arc<Tensor>> {
-    let mut rng = Philox4x32x10::weird_tf_constructor(seed1, seed2).u32_iter();
-    unsafe {
-        let mut tensor = Tensor::uninitialized::<f32>(&*shape)?;
-        tensor.as_slice_mut::<f32>()?.iter_mut().for_each(|x| {
-            let mantissa = rng.next().unwrap() & 0x7fffff;
-            let exp = 127 as u32;
-            let f = exp << 23 | mantissa;
-            *x = f32::from_bits(f) - 1.0
-        });
-        Ok(tensor.into_arc_tensor())
+/// TensorFlow picks a random seed when both seeds are zero.
+fn tf_seed(seed1: u64, seed2: u64) -> Option<(u64, u64)> {
+    if seed1 == 0 && seed2 == 0 {
+        None
+    } else {
+        Some((seed1, seed2))
     }
 }
 
@@ -178,18 +128,26 @@ pub struct RandomUniformInt {
 impl_dyn_hash!(RandomUniformInt);
 
 impl RandomUniformInt {
-    pub fn make_i32(&self, shape: &[usize], lo: i32, hi: i32) -> TractResult<Arc<Tensor>> {
-        let mut rng = Philox4x32x10::weird_tf_constructor(self.seed1, self.seed2).u32_iter();
-        unsafe {
-            let mut tensor = Tensor::uninitialized::<i32>(&*shape)?;
-            tensor.as_slice_mut::<i32>()?.iter_mut().for_each(|x| {
-                // reproduce TF casts, with no conviction
-                let lo = lo as u32;
-                let hi = hi as u32;
-                *x = (lo + rng.next().unwrap() % (hi - lo)) as i32;
-            });
-            Ok(tensor.into_arc_tensor())
+    fn as_random(
+        &self,
+        shape: &Tensor,
+        lo: &Tensor,
+        hi: &Tensor,
+        stream: u64,
+    ) -> TractResult<Random> {
+        if self.t != DatumType::I32 {
+            bail!("RandomUniformInt not implemented for {:?}", self.t)
         }
+        Ok(Random {
+            datum_type: self.t,
+            shape: shape.cast_to::<TDim>()?.as_slice::<TDim>()?.into(),
+            dist: Distribution::Uniform {
+                low: lo.clone().into_arc_tensor(),
+                high: hi.clone().into_arc_tensor(),
+            },
+            seed: tf_seed(self.seed1, self.seed2),
+            stream,
+        })
     }
 }
 
@@ -216,17 +174,7 @@ impl EvalOp for RandomUniformInt {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        let shape: TVec<usize> =
-            inputs[0].cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&x| x as usize).collect();
-        match self.t {
-            DatumType::I32 => Ok(tvec!(Self::make_i32(
-                self,
-                &*shape,
-                *inputs[1].to_scalar::<i32>()?,
-                *inputs[2].to_scalar::<i32>()?
-            )?)),
-            dt => bail!("RandomUniformInt not implemented for {:?}", dt),
-        }
+        self.as_random(&inputs[0], &inputs[1], &inputs[2], 0)?.eval(tvec!())
     }
 }
 
@@ -254,5 +202,25 @@ impl InferenceRulesOp for RandomUniformInt {
         Ok(())
     }
 
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let konst =
+            |ix: usize| target.outlet_fact(mapping[&node.inputs[ix]]).map(|f| f.konst.clone());
+        if let (Some(shape), Some(lo), Some(hi)) = (konst(0)?, konst(1)?, konst(2)?) {
+            target.wire_node(
+                &*node.name,
+                self.as_random(&shape, &lo, &hi, stream_for_name(&node.name))?,
+                &[],
+            )
+        } else {
+            bail!("Dynamic shape or range")
+        }
+    }
+
     as_op!();
 }